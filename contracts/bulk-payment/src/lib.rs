@@ -4,39 +4,93 @@
 // This contract enables batch payment processing with support for:
 // - Native NEAR tokens
 // - NEP-141 fungible tokens via NEAR Intents (intents.near)
+// - Mixed-token lists where each payment carries its own token
 // - Storage-based fee model with 10% revenue margin
 //
 // List IDs are SHA-256 hashes of the payment list contents, ensuring:
 // - Deterministic IDs (same list = same ID)
-// - Integrity verification (hash proves list contents)
+// - Integrity verification (hash proves list contents, recomputed on submit)
 // - No auto-incrementing counters needed
-use near_sdk::json_types::U128;
+//
+// State transitions are reported as NEP-297 events (standard "bulk-payment").
+use near_sdk::json_types::{U128, U64};
 use near_sdk::store::IterableMap;
-use near_sdk::{env, log, near, require, AccountId, Gas, NearToken, Promise, PromiseOrValue};
+use near_sdk::{
+    env, log, near, require, AccountId, Gas, NearToken, Promise, PromiseError, PromiseOrValue,
+    PromiseResult,
+};
 
 /// List ID is a hex-encoded SHA-256 hash (64 characters)
 /// Example: "a1b2c3d4e5f6..." (64 hex chars = 32 bytes)
 pub type ListId = String;
 
+/// List token_id of a mixed-token list, where each payment carries its own token_id
+pub const MIXED_TOKEN_ID: &str = "mixed";
+
+/// Maximum length of the salt a list_id may include
+const MAX_SALT_LENGTH: usize = 128;
+
+/// Multi-token contract that holds NEAR Intents balances ("nep141:" token IDs)
+const INTENTS_CONTRACT_ID: &str = "intents.near";
+
+/// Default time a list may stay Pending before it expires (30 days)
+const DEFAULT_PENDING_LIST_EXPIRY_NS: u64 = 30 * 24 * 60 * 60 * 1_000_000_000;
+
+/// Gas for the NEP-145 `storage_balance_of` / `storage_balance_bounds` views
+const GAS_FOR_STORAGE_VIEW: Gas = Gas::from_tgas(5);
+/// Gas for a NEP-145 `storage_deposit` registering a recipient
+const GAS_FOR_STORAGE_DEPOSIT: Gas = Gas::from_tgas(10);
+/// Gas for a NEP-141 `ft_transfer`
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(50);
+/// Gas for `on_recipient_storage_checked`, including the calls it schedules
+const GAS_FOR_STORAGE_CHECK_CALLBACK: Gas = Gas::from_tgas(70);
+/// Gas for `on_payment_complete`, including the refund of a failed payment
+const GAS_FOR_PAYMENT_CALLBACK: Gas = Gas::from_tgas(10);
+
+/// Storage key of the state layout version (see `StateVersion`)
+const STATE_VERSION_KEY: &[u8] = b"VERSION";
+/// Layout version of `BulkPaymentContract`, written by `new` and `migrate`
+const CURRENT_STATE_VERSION: u8 = 2;
+
 #[near(contract_state)]
 pub struct BulkPaymentContract {
     /// Payment lists indexed by their content hash (hex-encoded SHA-256)
     payment_lists: IterableMap<ListId, PaymentList>,
+    /// Lists stored by version 1 of the contract, not moved to `payment_lists` yet.
+    /// They move when first updated, or in batches through `migrate_lists`.
+    legacy_lists: IterableMap<ListId, PaymentListV1>,
     storage_credits: IterableMap<AccountId, NearToken>,
+    /// Receipts of paid lists whose payment rows were pruned by `close_list`
+    list_receipts: IterableMap<ListId, ListReceipt>,
+    /// Time after `created_at` when a Pending list expires (0 = never)
+    pending_list_expiry_ns: u64,
+    /// Account that manages the settings, guardians and upgrades of the contract
+    owner_id: AccountId,
+    /// Accounts that may pause the contract besides the owner
+    guardians: Vec<AccountId>,
+    /// While paused, lists can't be submitted, funded or paid out; refunds still work
+    paused: bool,
 }
 
 #[near(serializers = [json])]
 pub struct PaymentInput {
-    pub recipient: AccountId,
+    /// NEAR account, or the destination chain address for PoA (`.omft.near`) intents tokens
+    pub recipient: String,
     pub amount: U128,
+    /// Token of this payment; required in mixed lists, not allowed otherwise
+    #[serde(default)]
+    pub token_id: Option<String>,
 }
 
 #[near(serializers = [json, borsh])]
 #[derive(Clone)]
 pub struct PaymentRecord {
-    pub recipient: AccountId,
+    /// Borsh-compatible with the `AccountId` earlier versions stored
+    pub recipient: String,
     pub amount: U128,
     pub status: PaymentStatus,
+    /// Token of this payment in a mixed list (None = the list token)
+    pub token_id: Option<String>,
 }
 
 #[near(serializers = [json, borsh])]
@@ -48,6 +102,10 @@ pub enum PaymentStatus {
     Paid {
         block_height: u64,
     },
+    /// The transfer failed and the amount was refunded to the submitter
+    Failed {
+        error: String,
+    },
 }
 
 #[near(serializers = [json, borsh])]
@@ -58,6 +116,59 @@ pub struct PaymentList {
     pub status: ListStatus,
     pub payments: Vec<PaymentRecord>,
     pub created_at: u64,
+    /// NEAR available for registering FT recipients that have no storage on the token
+    /// contract (see `attach_storage_budget`). Unused budget is refunded to the submitter.
+    pub storage_budget: NearToken,
+    /// Recipient registration checks dispatched by `payout_batch` that haven't completed
+    pub storage_checks_in_flight: u32,
+    /// Tokens whose total has been deposited while the list is Pending
+    /// (native NEAR is recorded as "native")
+    pub funded_tokens: Vec<String>,
+}
+
+/// Amount of one token
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct TokenAmount {
+    pub token_id: String,
+    pub amount: U128,
+}
+
+/// How far a list's token total is covered by approval deposits
+#[near(serializers = [json])]
+pub struct TokenFunding {
+    pub token_id: String,
+    pub amount: U128,
+    pub funded: bool,
+}
+
+/// Compact record of a paid list, kept after `close_list` prunes its payment rows
+#[near(serializers = [json, borsh])]
+#[derive(Clone)]
+pub struct ListReceipt {
+    pub list_id: ListId,
+    pub token_id: String,
+    pub submitter: AccountId,
+    pub payment_count: u32,
+    /// Total paid per token
+    pub totals: Vec<TokenAmount>,
+    /// Block height of the last payment
+    pub completed_at_block: u64,
+    pub closed_at: u64,
+}
+
+/// NEP-145 storage balance of an account on a token contract
+#[near(serializers = [json])]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+/// NEP-145 storage balance bounds of a token contract
+#[near(serializers = [json])]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
 }
 
 #[near(serializers = [json, borsh])]
@@ -72,16 +183,73 @@ pub enum ListStatus {
 #[near(serializers = [json])]
 #[derive(Clone)]
 pub struct PaymentTransaction {
-    pub recipient: AccountId,
+    pub recipient: String,
     pub amount: U128,
     pub block_height: u64,
+    /// Token of the payment in a mixed list
+    pub token_id: Option<String>,
+}
+
+/// NEP-297 events, logged as `EVENT_JSON:{"standard":"bulk-payment","version":"1.0.0","event":..,"data":..}`
+#[near(event_json(standard = "bulk-payment"))]
+pub enum BulkPaymentEvent {
+    #[event_version("1.0.0")]
+    ListSubmitted {
+        list_id: ListId,
+        submitter: AccountId,
+        token_id: String,
+        payment_count: u32,
+    },
+    #[event_version("1.0.0")]
+    ListApproved {
+        list_id: ListId,
+        submitter: AccountId,
+    },
+    #[event_version("1.0.0")]
+    ListRejected {
+        list_id: ListId,
+        submitter: AccountId,
+    },
+    #[event_version("1.0.0")]
+    PaymentSent {
+        list_id: ListId,
+        index: u32,
+        recipient: String,
+        token_id: String,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    PaymentFailed {
+        list_id: ListId,
+        index: u32,
+        recipient: String,
+        token_id: String,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    StoragePurchased {
+        account_id: AccountId,
+        num_records: u64,
+        cost: NearToken,
+    },
+    #[event_version("1.0.0")]
+    ContractPaused { account_id: AccountId },
+    #[event_version("1.0.0")]
+    ContractUnpaused { account_id: AccountId },
 }
 
 impl Default for BulkPaymentContract {
     fn default() -> Self {
         Self {
-            payment_lists: IterableMap::new(b"p"),
+            payment_lists: IterableMap::new(b"l"),
+            // Where version 1 stored its lists
+            legacy_lists: IterableMap::new(b"p"),
             storage_credits: IterableMap::new(b"s"),
+            list_receipts: IterableMap::new(b"r"),
+            pending_list_expiry_ns: DEFAULT_PENDING_LIST_EXPIRY_NS,
+            owner_id: env::current_account_id(),
+            guardians: Vec::new(),
+            paused: false,
         }
     }
 }
@@ -101,13 +269,56 @@ pub trait MultiTokenReceiver {
 
 #[near]
 impl BulkPaymentContract {
-    /// Initialize the contract
+    /// Initialize the contract, owned by the contract account
     #[init]
     #[allow(clippy::use_self)]
     pub fn new() -> Self {
+        env::storage_write(STATE_VERSION_KEY, &[CURRENT_STATE_VERSION]);
         Self::default()
     }
 
+    /// Upgrade the stored state to the current layout after deploying new code.
+    /// Lists stored in an older layout are left in place and moved by `migrate_lists`, or
+    /// when first updated, so the upgrade fits in one call however many lists there are.
+    /// Other calls fail until this has run, so deploy new code with `migrate` as its init call.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let contract = match StateVersion::read() {
+            StateVersion::V1(old) => old.into(),
+            StateVersion::V2(current) => *current,
+        };
+        env::storage_write(STATE_VERSION_KEY, &[CURRENT_STATE_VERSION]);
+        log!(
+            "Contract state migrated to version {}",
+            CURRENT_STATE_VERSION
+        );
+        contract
+    }
+
+    /// Move up to `limit` lists stored in an older layout to the current one.
+    /// Returns the number of lists left to move; call again until it returns 0.
+    pub fn migrate_lists(&mut self, limit: u32) -> u32 {
+        self.assert_owner();
+        let list_ids: Vec<ListId> = self
+            .legacy_lists
+            .keys()
+            .take(limit as usize)
+            .cloned()
+            .collect();
+        for list_id in &list_ids {
+            self.load_list(list_id);
+        }
+
+        let remaining = self.legacy_lists.len();
+        log!(
+            "Migrated {} payment lists, {} remaining",
+            list_ids.len(),
+            remaining
+        );
+        remaining
+    }
+
     /// Calculate the required deposit for purchasing storage for a given number of records.
     /// This is a view function that does not modify state.
     ///
@@ -192,12 +403,12 @@ impl BulkPaymentContract {
         self.storage_credits
             .insert(beneficiary.clone(), new_credits);
 
-        log!(
-            "Storage purchased: {} records for {} (beneficiary: {})",
+        BulkPaymentEvent::StoragePurchased {
+            account_id: beneficiary,
             num_records,
-            total_cost,
-            beneficiary
-        );
+            cost: total_cost,
+        }
+        .emit();
 
         total_cost
     }
@@ -207,18 +418,421 @@ impl BulkPaymentContract {
         list_id.len() == 64 && list_id.chars().all(|c| c.is_ascii_hexdigit())
     }
 
+    /// Whether a list token_id refers to native NEAR
+    fn is_native_token(token_id: &str) -> bool {
+        matches!(token_id, "native" | "near" | "NEAR")
+    }
+
+    /// Whether a list token_id is a NEP-141 token contract paid out with `ft_transfer`
+    fn is_fungible_token(token_id: &str) -> bool {
+        !Self::is_native_token(token_id) && !token_id.starts_with("nep141:")
+    }
+
+    /// Whether a token_id is a PoA bridged token ("nep141:<..>.omft.near"), paid out by
+    /// withdrawing it to its origin chain
+    fn is_poa_token(token_id: &str) -> bool {
+        token_id
+            .strip_prefix("nep141:")
+            .is_some_and(|token| token.ends_with(".omft.near"))
+    }
+
+    /// Loose format check of an external chain address (the backend checks it per chain).
+    /// Addresses end up in the `WITHDRAW_TO:` memo, so only plain characters are allowed.
+    fn is_valid_chain_address(address: &str) -> bool {
+        (2..=128).contains(&address.len())
+            && address
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    }
+
+    /// Check a payment recipient: PoA tokens are withdrawn to a destination chain address,
+    /// all other tokens are paid to a NEAR account
+    fn assert_valid_recipient(token_id: &str, recipient: &str) {
+        if Self::is_poa_token(token_id) {
+            require!(
+                Self::is_valid_chain_address(recipient),
+                format!("Invalid withdrawal address: {}", recipient)
+            );
+        } else {
+            require!(
+                recipient.parse::<AccountId>().is_ok(),
+                format!("Invalid recipient account: {}", recipient)
+            );
+        }
+    }
+
+    /// NEAR account of a payment that isn't a PoA withdrawal (checked by `submit_list`)
+    fn recipient_account(recipient: &str) -> AccountId {
+        recipient.parse().expect("Invalid recipient account")
+    }
+
+    /// Token a payment is made in
+    fn payment_token<'a>(list: &'a PaymentList, payment: &'a PaymentRecord) -> &'a str {
+        payment.token_id.as_deref().unwrap_or(&list.token_id)
+    }
+
+    /// Key a token is funded under, so every native NEAR spelling is a single deposit
+    fn funding_key(token_id: &str) -> &str {
+        if Self::is_native_token(token_id) {
+            "native"
+        } else {
+            token_id
+        }
+    }
+
+    /// Total payment amount per token, in order of first appearance
+    fn token_totals(list: &PaymentList) -> Vec<TokenAmount> {
+        let mut totals: Vec<TokenAmount> = Vec::new();
+        for payment in &list.payments {
+            let key = Self::funding_key(Self::payment_token(list, payment));
+            match totals.iter_mut().find(|t| t.token_id == key) {
+                Some(total) => {
+                    total.amount = U128(
+                        total
+                            .amount
+                            .0
+                            .checked_add(payment.amount.0)
+                            .expect("Total payment amount overflow"),
+                    );
+                }
+                None => totals.push(TokenAmount {
+                    token_id: key.to_string(),
+                    amount: payment.amount,
+                }),
+            }
+        }
+        totals
+    }
+
+    /// Record an approval deposit of `amount` of `token_id` for a Pending list.
+    /// Each token must be deposited in its exact total; the list is Approved once
+    /// every token is covered.
+    fn fund_list(list_id: &ListId, list: &mut PaymentList, token_id: &str, amount: u128) {
+        let key = Self::funding_key(token_id);
+        let totals = Self::token_totals(list);
+
+        let required = totals
+            .iter()
+            .find(|t| t.token_id == key)
+            .unwrap_or_else(|| {
+                env::panic_str(&format!("Token {} is not paid out by this list", key))
+            })
+            .amount
+            .0;
+        require!(
+            !list.funded_tokens.iter().any(|t| t == key),
+            format!("Token {} is already funded for this list", key)
+        );
+        require!(
+            amount == required,
+            format!(
+                "Exact token amount required: {}, received: {}",
+                required, amount
+            )
+        );
+
+        list.funded_tokens.push(key.to_string());
+
+        if totals
+            .iter()
+            .all(|t| list.funded_tokens.contains(&t.token_id))
+        {
+            list.status = ListStatus::Approved;
+            list.funded_tokens.clear();
+            BulkPaymentEvent::ListApproved {
+                list_id: list_id.clone(),
+                submitter: list.submitter.clone(),
+            }
+            .emit();
+        } else {
+            log!(
+                "Payment list {} funded with {} {} ({} of {} tokens)",
+                list_id,
+                amount,
+                key,
+                list.funded_tokens.len(),
+                totals.len()
+            );
+        }
+    }
+
+    /// Send the deposits of a partially funded list back to the submitter
+    fn refund_funding(list_id: &ListId, list: &mut PaymentList) {
+        let totals = Self::token_totals(list);
+        for token_id in list.funded_tokens.drain(..) {
+            let amount = totals
+                .iter()
+                .find(|t| t.token_id == token_id)
+                .map(|t| t.amount)
+                .expect("Funded token is not part of the list");
+
+            Self::return_tokens(&token_id, &list.submitter, amount).detach();
+
+            log!(
+                "Refunded {} {} deposited for list {} to {}",
+                amount.0,
+                token_id,
+                list_id,
+                list.submitter
+            );
+        }
+    }
+
+    /// Send `amount` of `token_id` held by this contract back to `receiver_id`
+    /// (intents balances are moved with `mt_transfer` instead of being withdrawn)
+    fn return_tokens(token_id: &str, receiver_id: &AccountId, amount: U128) -> Promise {
+        if Self::is_native_token(token_id) {
+            Promise::new(receiver_id.clone()).transfer(NearToken::from_yoctonear(amount.0))
+        } else if token_id.starts_with("nep141:") {
+            let args = format!(
+                r#"{{"receiver_id":"{}","token_id":"{}","amount":"{}"}}"#,
+                receiver_id, token_id, amount.0
+            );
+            Promise::new(INTENTS_CONTRACT_ID.parse().unwrap()).function_call(
+                "mt_transfer".to_string(),
+                args.into_bytes(),
+                NearToken::from_yoctonear(1),
+                GAS_FOR_FT_TRANSFER,
+            )
+        } else {
+            let token_account: AccountId =
+                token_id.parse().expect("Invalid token contract address");
+            Self::ft_transfer(token_account, receiver_id, amount)
+        }
+    }
+
+    /// Whether a successful transfer receipt paid the recipient. Intents `ft_withdraw` resolves
+    /// to "0" when the withdrawal was refunded to this contract's intents balance.
+    fn transfer_succeeded(token_id: &str, data: &[u8]) -> bool {
+        if !token_id.starts_with("nep141:") {
+            return true;
+        }
+        near_sdk::serde_json::from_slice::<U128>(data).map_or(true, |withdrawn| withdrawn.0 > 0)
+    }
+
+    /// `on_payment_complete` callback for payment `index` of a list
+    fn payment_callback(
+        list_id: &ListId,
+        index: u32,
+        submitter: &AccountId,
+        token_id: &str,
+        payment: &PaymentRecord,
+    ) -> Promise {
+        Self::ext(env::current_account_id())
+            .with_static_gas(GAS_FOR_PAYMENT_CALLBACK)
+            .on_payment_complete(
+                list_id.clone(),
+                index,
+                submitter.clone(),
+                payment.recipient.clone(),
+                token_id.to_string(),
+                payment.amount,
+            )
+    }
+
+    /// Gas to reserve for dispatching one payment of `token_id`
+    fn gas_for_payment(token_id: &str, register_recipient: bool) -> Gas {
+        let transfer = if token_id.starts_with("nep141:") {
+            // NEAR Intents: ft_withdraw cross-contract call
+            Gas::from_tgas(50)
+        } else if Self::is_native_token(token_id) {
+            // Native NEAR: minimal gas per transfer
+            Gas::from_tgas(3)
+        } else if register_recipient {
+            // NEP-141 FT: storage views + callback that registers and transfers
+            Gas::from_gas(
+                2 * GAS_FOR_STORAGE_VIEW.as_gas() + GAS_FOR_STORAGE_CHECK_CALLBACK.as_gas(),
+            )
+        } else {
+            // NEP-141 FT: ft_transfer cross-contract call
+            GAS_FOR_FT_TRANSFER
+        };
+        // Every payment reports its outcome through on_payment_complete
+        transfer.saturating_add(GAS_FOR_PAYMENT_CALLBACK)
+    }
+
+    /// `ft_transfer` of `amount` from this contract to `receiver_id`
+    fn ft_transfer(token_account: AccountId, receiver_id: &AccountId, amount: U128) -> Promise {
+        let args = format!(
+            r#"{{"receiver_id":"{}","amount":"{}"}}"#,
+            receiver_id, amount.0
+        );
+
+        Promise::new(token_account).function_call(
+            "ft_transfer".to_string(),
+            args.into_bytes(),
+            NearToken::from_yoctonear(1),
+            GAS_FOR_FT_TRANSFER,
+        )
+    }
+
+    /// Refund the unused storage budget to the submitter once no more payments can use it:
+    /// the list was rejected, or every payment is paid and no registration check is pending.
+    fn settle_storage_budget(list_id: &ListId, list: &mut PaymentList) {
+        let finished = match list.status {
+            ListStatus::Rejected => true,
+            ListStatus::Approved => {
+                list.storage_checks_in_flight == 0
+                    && !list
+                        .payments
+                        .iter()
+                        .any(|p| matches!(p.status, PaymentStatus::Pending))
+            }
+            ListStatus::Pending => false,
+        };
+
+        if !finished || list.storage_budget.as_yoctonear() == 0 {
+            return;
+        }
+
+        let refund = list.storage_budget;
+        list.storage_budget = NearToken::from_yoctonear(0);
+        Promise::new(list.submitter.clone())
+            .transfer(refund)
+            .detach();
+
+        log!(
+            "Refunded unused storage budget {} of list {} to {}",
+            refund,
+            list_id,
+            list.submitter
+        );
+    }
+
+    /// Hex-encoded SHA-256 of the canonical list JSON:
+    /// `{"payments":[{"amount":"..","recipient":".."},..],"submitter":"..","token_id":".."}`
+    /// with payments sorted by recipient and no whitespace. Payments of mixed lists
+    /// also carry their `"token_id"` (after `"recipient"`). A salted list carries its
+    /// `"salt"` (after `"payments"`), so the same payments can be submitted again.
+    fn hash_list(
+        submitter: &AccountId,
+        token_id: &str,
+        payments: &[PaymentInput],
+        salt: Option<&str>,
+    ) -> ListId {
+        let mut sorted: Vec<&PaymentInput> = payments.iter().collect();
+        sorted.sort_by(|a, b| a.recipient.cmp(&b.recipient));
+
+        let payments: Vec<_> = sorted
+            .iter()
+            .map(|p| match &p.token_id {
+                Some(token_id) => near_sdk::serde_json::json!({
+                    "amount": p.amount.0.to_string(),
+                    "recipient": p.recipient,
+                    "token_id": token_id,
+                }),
+                None => near_sdk::serde_json::json!({
+                    "amount": p.amount.0.to_string(),
+                    "recipient": p.recipient,
+                }),
+            })
+            .collect();
+
+        // Keys in alphabetical order
+        let canonical = match salt {
+            Some(salt) => near_sdk::serde_json::json!({
+                "payments": payments,
+                "salt": salt,
+                "submitter": submitter,
+                "token_id": token_id,
+            }),
+            None => near_sdk::serde_json::json!({
+                "payments": payments,
+                "submitter": submitter,
+                "token_id": token_id,
+            }),
+        };
+
+        env::sha256(canonical.to_string().as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// Panic unless called by the owner
+    fn assert_owner(&self) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only the owner can call this method"
+        );
+    }
+
+    fn assert_not_paused(&self) {
+        require!(!self.paused, "Contract is paused");
+    }
+
+    /// Whether a Pending list is past the pending list expiry
+    fn is_expired(&self, list: &PaymentList) -> bool {
+        matches!(list.status, ListStatus::Pending)
+            && self.pending_list_expiry_ns > 0
+            && env::block_timestamp() >= list.created_at.saturating_add(self.pending_list_expiry_ns)
+    }
+
+    /// A list for reading; lists still stored in the version 1 layout are converted in place
+    fn list(&self, list_id: &ListId) -> PaymentList {
+        if let Some(list) = self.payment_lists.get(list_id) {
+            return list.clone();
+        }
+        self.legacy_lists
+            .get(list_id)
+            .expect("Payment list not found")
+            .clone()
+            .into()
+    }
+
+    /// A list about to be updated, moved out of the version 1 layout first if needed
+    fn load_list(&mut self, list_id: &ListId) -> PaymentList {
+        if let Some(list) = self.legacy_lists.remove(list_id) {
+            self.payment_lists.insert(list_id.clone(), list.into());
+        }
+        self.payment_lists
+            .get(list_id)
+            .expect("Payment list not found")
+            .clone()
+    }
+
+    /// Give back the storage credits held by a list's payment rows
+    fn return_storage_credits(&mut self, account_id: &AccountId, num_records: usize) {
+        let current_credits = self
+            .storage_credits
+            .get(account_id)
+            .copied()
+            .unwrap_or(NearToken::from_yoctonear(0));
+        let new_credits = NearToken::from_yoctonear(
+            current_credits
+                .as_yoctonear()
+                .checked_add(num_records as u128)
+                .expect("Storage credits overflow"),
+        );
+        self.storage_credits.insert(account_id.clone(), new_credits);
+    }
+
+    /// Compute the list_id that `submit_list` expects for the given list contents
+    pub fn compute_list_id(
+        &self,
+        submitter_id: AccountId,
+        token_id: String,
+        payments: Vec<PaymentInput>,
+        salt: Option<String>,
+    ) -> ListId {
+        Self::hash_list(&submitter_id, &token_id, &payments, salt.as_deref())
+    }
+
     /// Submit a payment list with pending status
     ///
     /// # Arguments
     /// * `list_id` - The SHA-256 hash of the payment list contents (hex-encoded, 64 chars).
-    ///               This hash should be calculated by the client and verified against
-    ///               a pending DAO proposal before submission.
-    /// * `token_id` - The token to use for payments ("native" for NEAR, or token contract ID)
+    ///               The contract recomputes it from (submitter, token_id, payments) and
+    ///               rejects the list on mismatch (see `compute_list_id`).
+    /// * `token_id` - The token to use for payments ("native" for NEAR, or token contract ID),
+    ///                or "mixed" when every payment names its own token_id
     /// * `payments` - List of payment records with recipient and amount
     /// * `submitter_id` - Optional submitter account ID. If provided, only the contract account
     ///                    can call this function to submit on behalf of another account (e.g., a DAO).
     ///                    The submitter must have sufficient storage credits.
     ///                    If not provided, the caller becomes the submitter.
+    /// * `salt` - Optional salt included in the list_id, so a list with the same payments
+    ///            (e.g. the next occurrence of a recurring payment) gets a new list_id
     ///
     /// # Returns
     /// The list_id that was passed in (for convenience in logging/tracking)
@@ -228,16 +842,27 @@ impl BulkPaymentContract {
         token_id: String,
         payments: Vec<PaymentInput>,
         submitter_id: Option<AccountId>,
+        salt: Option<String>,
     ) -> ListId {
+        self.assert_not_paused();
         require!(!payments.is_empty(), "Payment list cannot be empty");
+        require!(
+            salt.as_ref()
+                .is_none_or(|salt| salt.len() <= MAX_SALT_LENGTH),
+            "salt is too long"
+        );
         require!(
             Self::validate_list_id(&list_id),
             "Invalid list_id: must be a 64-character hex string (SHA-256 hash)"
         );
         require!(
-            self.payment_lists.get(&list_id).is_none(),
+            self.payment_lists.get(&list_id).is_none() && self.legacy_lists.get(&list_id).is_none(),
             "Payment list with this ID already exists"
         );
+        require!(
+            self.list_receipts.get(&list_id).is_none(),
+            "Payment list with this ID was already paid and closed"
+        );
 
         let caller = env::predecessor_account_id();
 
@@ -253,6 +878,37 @@ impl BulkPaymentContract {
             caller.clone()
         };
 
+        if token_id == MIXED_TOKEN_ID {
+            require!(
+                payments.iter().all(|p| p
+                    .token_id
+                    .as_deref()
+                    .is_some_and(|t| !t.is_empty() && t != MIXED_TOKEN_ID)),
+                "Every payment of a mixed list needs its own token_id"
+            );
+        } else {
+            require!(
+                payments.iter().all(|p| p.token_id.is_none()),
+                "Payment token_id is only allowed in mixed lists"
+            );
+        }
+
+        for payment in &payments {
+            let payment_token = payment.token_id.as_deref().unwrap_or(&token_id);
+            Self::assert_valid_recipient(payment_token, &payment.recipient);
+        }
+
+        // Bind the list_id to the list contents so a DAO proposal referencing it
+        // approves exactly these payments
+        let expected_list_id = Self::hash_list(&submitter, &token_id, &payments, salt.as_deref());
+        require!(
+            list_id == expected_list_id,
+            format!(
+                "list_id does not match the payment list contents. Expected: {}",
+                expected_list_id
+            )
+        );
+
         // Verify storage credits for the submitter
         let required_credits = payments.len() as u128;
         let current_credits = self
@@ -281,6 +937,7 @@ impl BulkPaymentContract {
                 recipient: input.recipient,
                 amount: input.amount,
                 status: PaymentStatus::Pending,
+                token_id: input.token_id,
             })
             .collect();
 
@@ -290,31 +947,32 @@ impl BulkPaymentContract {
             status: ListStatus::Pending,
             payments: payment_records,
             created_at: env::block_timestamp(),
+            storage_budget: NearToken::from_yoctonear(0),
+            storage_checks_in_flight: 0,
+            funded_tokens: Vec::new(),
         };
 
-        let num_payments = payment_list.payments.len();
-        self.payment_lists.insert(list_id.clone(), payment_list);
-
-        log!(
-            "Payment list {} submitted by {} with {} payments",
-            list_id,
+        BulkPaymentEvent::ListSubmitted {
+            list_id: list_id.clone(),
             submitter,
-            num_payments
-        );
+            token_id: payment_list.token_id.clone(),
+            payment_count: payment_list.payments.len() as u32,
+        }
+        .emit();
+        self.payment_lists.insert(list_id.clone(), payment_list);
 
         list_id
     }
 
-    /// Approve a payment list and attach the exact deposit amount
+    /// Approve a payment list and attach the exact deposit amount.
+    /// For mixed lists this funds the native NEAR payments; the list is Approved once
+    /// its token payments are funded as well.
     #[payable]
     pub fn approve_list(&mut self, list_id: ListId) {
+        self.assert_not_paused();
         let caller = env::predecessor_account_id();
 
-        let mut list = self
-            .payment_lists
-            .get(&list_id)
-            .expect("Payment list not found")
-            .clone();
+        let mut list = self.load_list(&list_id);
 
         require!(
             list.submitter == caller,
@@ -325,14 +983,17 @@ impl BulkPaymentContract {
             matches!(list.status, ListStatus::Pending),
             "List must be in Pending status"
         );
+        require!(!self.is_expired(&list), "Payment list has expired");
 
-        // Calculate total payment amount (with overflow check)
-        let total_amount: u128 = list
-            .payments
-            .iter()
-            .map(|p| p.amount.0)
-            .try_fold(0u128, |acc, x| acc.checked_add(x))
-            .expect("Total payment amount overflow");
+        // A NEAR deposit can only cover NEAR payments; token payments are funded by
+        // ft_transfer_call / mt_transfer_call from their token contract
+        let total_amount = Self::token_totals(&list)
+            .into_iter()
+            .find(|t| t.token_id == "native")
+            .map(|t| t.amount.0)
+            .unwrap_or_else(|| {
+                env::panic_str("Only lists paying native NEAR can be approved with a deposit")
+            });
 
         let attached = env::attached_deposit();
         let required = NearToken::from_yoctonear(total_amount);
@@ -345,15 +1006,10 @@ impl BulkPaymentContract {
             )
         );
 
-        // Update list status
-        list.status = ListStatus::Approved;
+        Self::fund_list(&list_id, &mut list, "native", total_amount);
         self.payment_lists.insert(list_id.clone(), list);
 
-        log!(
-            "Payment list {} approved with deposit {}",
-            list_id,
-            attached
-        );
+        log!("Payment list {} funded with deposit {}", list_id, attached);
     }
 
     /// Process payments in batches (public function, anyone can call)
@@ -365,6 +1021,7 @@ impl BulkPaymentContract {
     /// Gas costs per payment type:
     /// - Native NEAR: ~3 TGas per transfer
     /// - NEP-141 FT: ~50 TGas per ft_transfer
+    /// - NEP-141 FT with a storage budget: ~80 TGas per storage check, registration and ft_transfer
     /// - NEAR Intents: ~50 TGas per ft_withdraw
     ///
     /// Worker should call with 300 TGas for maximum throughput.
@@ -381,32 +1038,22 @@ impl BulkPaymentContract {
     /// are complete. The caller should keep calling until this returns 0.
     ///
     /// # Panics
+    /// - If the contract is paused
     /// - If the payment list is not found
     /// - If the list is not in Approved status
     /// - If there's not enough gas to process at least one payment
     pub fn payout_batch(&mut self, list_id: ListId) -> u64 {
-        let mut list = self
-            .payment_lists
-            .get(&list_id)
-            .expect("Payment list not found")
-            .clone();
+        self.assert_not_paused();
+        let mut list = self.load_list(&list_id);
 
         require!(
             matches!(list.status, ListStatus::Approved),
             "List must be Approved to process payments"
         );
 
-        // Determine gas needed per payment based on token type
-        let gas_per_payment: Gas = if list.token_id.starts_with("nep141:") {
-            // NEAR Intents: ft_withdraw cross-contract call
-            Gas::from_tgas(50)
-        } else if list.token_id == "native" || list.token_id == "near" || list.token_id == "NEAR" {
-            // Native NEAR: minimal gas per transfer
-            Gas::from_tgas(3)
-        } else {
-            // NEP-141 FT: ft_transfer cross-contract call
-            Gas::from_tgas(50)
-        };
+        // FT recipients are checked for token storage (and registered if needed)
+        // while the list still has a storage budget
+        let has_storage_budget = list.storage_budget.as_yoctonear() > 0;
 
         // Reserve gas for final operations (storing list, logging)
         let gas_reserve = Gas::from_tgas(15);
@@ -414,8 +1061,15 @@ impl BulkPaymentContract {
         let mut processed: u64 = 0;
         let mut first_pending_found = false;
 
-        for payment in list.payments.iter_mut() {
+        for (index, payment) in list.payments.iter_mut().enumerate() {
             if matches!(payment.status, PaymentStatus::Pending) {
+                let token_id = payment
+                    .token_id
+                    .clone()
+                    .unwrap_or_else(|| list.token_id.clone());
+                let register_recipient = has_storage_budget && Self::is_fungible_token(&token_id);
+                let gas_per_payment = Self::gas_for_payment(&token_id, register_recipient);
+
                 // Check if we have enough gas for this payment
                 let gas_remaining = env::prepaid_gas()
                     .as_gas()
@@ -437,14 +1091,23 @@ impl BulkPaymentContract {
 
                 first_pending_found = true;
 
-                if list.token_id.starts_with("nep141:") {
-                    // NEAR Intents - call ft_withdraw on intents.near
-                    let token_contract = list.token_id.strip_prefix("nep141:").unwrap();
+                let on_complete = || {
+                    Self::payment_callback(
+                        &list_id,
+                        index as u32,
+                        &list.submitter,
+                        &token_id,
+                        payment,
+                    )
+                };
 
-                    // PoA tokens require WITHDRAW_TO memo for external chain withdrawals
-                    let is_poa_token = token_contract.ends_with(".omft.near");
+                if token_id.starts_with("nep141:") {
+                    // NEAR Intents - call ft_withdraw on intents.near
+                    let token_contract = token_id.strip_prefix("nep141:").unwrap();
 
-                    let args_json = if is_poa_token {
+                    // PoA tokens are withdrawn to the recipient's address on the origin chain
+                    // through the WITHDRAW_TO memo
+                    let args_json = if Self::is_poa_token(&token_id) {
                         format!(
                             r#"{{"token":"{}","receiver_id":"{}","amount":"{}","memo":"WITHDRAW_TO:{}"}}"#,
                             token_contract, token_contract, payment.amount.0, payment.recipient
@@ -456,42 +1119,60 @@ impl BulkPaymentContract {
                         )
                     };
 
-                    Promise::new("intents.near".parse().unwrap())
+                    Promise::new(INTENTS_CONTRACT_ID.parse().unwrap())
                         .function_call(
                             "ft_withdraw".to_string(),
                             args_json.into_bytes(),
                             NearToken::from_yoctonear(1),
                             Gas::from_tgas(50),
                         )
+                        .then(on_complete())
                         .detach();
-                } else if list.token_id == "native"
-                    || list.token_id == "near"
-                    || list.token_id == "NEAR"
-                {
+                } else if Self::is_native_token(&token_id) {
                     // Native NEAR transfer
-                    Promise::new(payment.recipient.clone())
+                    Promise::new(Self::recipient_account(&payment.recipient))
                         .transfer(NearToken::from_yoctonear(payment.amount.0))
+                        .then(on_complete())
                         .detach();
-                } else {
-                    // NEP-141 fungible token transfer
-                    let token_account: AccountId = list
-                        .token_id
-                        .parse()
-                        .expect("Invalid token contract address");
-
-                    let args = format!(
-                        r#"{{"receiver_id":"{}","amount":"{}"}}"#,
-                        payment.recipient, payment.amount.0
-                    );
+                } else if register_recipient {
+                    // NEP-141 fungible token transfer, registering the recipient first
+                    // if it has no storage on the token contract
+                    let token_account: AccountId =
+                        token_id.parse().expect("Invalid token contract address");
 
-                    Promise::new(token_account)
+                    let balance_args =
+                        format!(r#"{{"account_id":"{}"}}"#, payment.recipient).into_bytes();
+
+                    Promise::new(token_account.clone())
                         .function_call(
-                            "ft_transfer".to_string(),
-                            args.into_bytes(),
-                            NearToken::from_yoctonear(1),
-                            Gas::from_tgas(50),
+                            "storage_balance_of".to_string(),
+                            balance_args,
+                            NearToken::from_yoctonear(0),
+                            GAS_FOR_STORAGE_VIEW,
+                        )
+                        .and(Promise::new(token_account).function_call(
+                            "storage_balance_bounds".to_string(),
+                            b"{}".to_vec(),
+                            NearToken::from_yoctonear(0),
+                            GAS_FOR_STORAGE_VIEW,
+                        ))
+                        .then(
+                            Self::ext(env::current_account_id())
+                                .with_static_gas(GAS_FOR_STORAGE_CHECK_CALLBACK)
+                                .on_recipient_storage_checked(list_id.clone(), index as u32),
                         )
                         .detach();
+
+                    list.storage_checks_in_flight += 1;
+                } else {
+                    // NEP-141 fungible token transfer
+                    let token_account: AccountId =
+                        token_id.parse().expect("Invalid token contract address");
+
+                    let receiver_id = Self::recipient_account(&payment.recipient);
+                    Self::ft_transfer(token_account, &receiver_id, payment.amount)
+                        .then(on_complete())
+                        .detach();
                 }
 
                 // Mark as Paid with current block height
@@ -502,6 +1183,8 @@ impl BulkPaymentContract {
             }
         }
 
+        Self::settle_storage_budget(&list_id, &mut list);
+
         // Update the list
         self.payment_lists.insert(list_id.clone(), list.clone());
 
@@ -522,15 +1205,199 @@ impl BulkPaymentContract {
         remaining_pending
     }
 
+    /// Attach NEAR for registering FT recipients that have no storage on the token contract.
+    ///
+    /// While a list has a storage budget, `payout_batch` checks each recipient with
+    /// `storage_balance_of` and calls `storage_deposit` (at the token's minimum bound)
+    /// for unregistered recipients before `ft_transfer`. Anyone may attach a budget once the
+    /// list is submitted (the backend's `submit-list` reports how much it needs); whatever is
+    /// not spent is refunded to the list submitter when the list completes or is rejected.
+    ///
+    /// # Returns
+    /// The total storage budget of the list
+    #[payable]
+    pub fn attach_storage_budget(&mut self, list_id: ListId) -> NearToken {
+        self.assert_not_paused();
+        let mut list = self.load_list(&list_id);
+
+        require!(
+            list.payments
+                .iter()
+                .any(|p| Self::is_fungible_token(Self::payment_token(&list, p))),
+            "Storage budget is only used for NEP-141 token lists"
+        );
+
+        let has_pending_payments = list
+            .payments
+            .iter()
+            .any(|p| matches!(p.status, PaymentStatus::Pending));
+        require!(
+            matches!(list.status, ListStatus::Pending)
+                || (matches!(list.status, ListStatus::Approved) && has_pending_payments),
+            "Storage budget can only be attached before all payments are made"
+        );
+
+        let attached = env::attached_deposit();
+        require!(
+            attached.as_yoctonear() > 0,
+            "Attach a deposit for the budget"
+        );
+
+        list.storage_budget = list
+            .storage_budget
+            .checked_add(attached)
+            .expect("Storage budget overflow");
+        let total = list.storage_budget;
+        self.payment_lists.insert(list_id.clone(), list);
+
+        log!(
+            "Storage budget of list {} increased by {} to {}",
+            list_id,
+            attached,
+            total
+        );
+
+        total
+    }
+
+    /// View the remaining storage budget of a list
+    pub fn view_storage_budget(&self, list_id: ListId) -> NearToken {
+        self.list(&list_id).storage_budget
+    }
+
+    /// Callback for the recipient storage check scheduled by `payout_batch`.
+    /// Registers the recipient from the list's storage budget if it has no storage
+    /// balance on the token contract, then transfers the payment.
+    #[private]
+    pub fn on_recipient_storage_checked(
+        &mut self,
+        list_id: ListId,
+        index: u32,
+        #[callback_result] storage_balance: Result<Option<StorageBalance>, PromiseError>,
+        #[callback_result] storage_bounds: Result<StorageBalanceBounds, PromiseError>,
+    ) {
+        let mut list = self.load_list(&list_id);
+        list.storage_checks_in_flight = list.storage_checks_in_flight.saturating_sub(1);
+
+        let payment = list.payments[index as usize].clone();
+        let token_id = Self::payment_token(&list, &payment).to_string();
+        let token_account: AccountId = token_id.parse().expect("Invalid token contract address");
+        let receiver_id = Self::recipient_account(&payment.recipient);
+        let transfer = Self::ft_transfer(token_account.clone(), &receiver_id, payment.amount);
+        let on_complete =
+            Self::payment_callback(&list_id, index, &list.submitter, &token_id, &payment);
+
+        // A failed view is treated as registered: the transfer behaves as without a budget
+        let needs_registration = matches!(storage_balance, Ok(None));
+
+        match storage_bounds {
+            Ok(bounds)
+                if needs_registration && list.storage_budget.as_yoctonear() >= bounds.min.0 =>
+            {
+                let deposit = NearToken::from_yoctonear(bounds.min.0);
+                list.storage_budget = list.storage_budget.saturating_sub(deposit);
+
+                let args = format!(
+                    r#"{{"account_id":"{}","registration_only":true}}"#,
+                    payment.recipient
+                );
+
+                Promise::new(token_account)
+                    .function_call(
+                        "storage_deposit".to_string(),
+                        args.into_bytes(),
+                        deposit,
+                        GAS_FOR_STORAGE_DEPOSIT,
+                    )
+                    .then(transfer)
+                    .then(on_complete)
+                    .detach();
+
+                log!(
+                    "Registered {} on {} for {} from the storage budget of list {}",
+                    payment.recipient,
+                    token_id,
+                    deposit,
+                    list_id
+                );
+            }
+            _ => {
+                if needs_registration {
+                    log!(
+                        "Storage budget of list {} cannot register {}",
+                        list_id,
+                        payment.recipient
+                    );
+                }
+                transfer.then(on_complete).detach();
+            }
+        }
+
+        Self::settle_storage_budget(&list_id, &mut list);
+        self.payment_lists.insert(list_id, list);
+    }
+
+    /// Callback for every payment dispatched by `payout_batch`.
+    ///
+    /// Emits `payment_sent` when the transfer succeeded. A failed transfer marks the payment
+    /// `Failed`, refunds its amount to the submitter and emits `payment_failed`.
+    ///
+    /// The transfer result is read raw: native and NEP-141 transfers return nothing while
+    /// intents `ft_withdraw` returns the withdrawn amount as a U128 string.
+    #[private]
+    pub fn on_payment_complete(
+        &mut self,
+        list_id: ListId,
+        index: u32,
+        submitter: AccountId,
+        recipient: String,
+        token_id: String,
+        amount: U128,
+    ) {
+        let transferred = match env::promise_result(0) {
+            PromiseResult::Successful(data) => Self::transfer_succeeded(&token_id, &data),
+            PromiseResult::Failed => false,
+        };
+
+        if transferred {
+            BulkPaymentEvent::PaymentSent {
+                list_id,
+                index,
+                recipient,
+                token_id,
+                amount,
+            }
+            .emit();
+            return;
+        }
+
+        // The amount is back with this contract: a failed native transfer is refunded to
+        // the sender, failed token transfers never leave the contract's balance
+        Self::return_tokens(&token_id, &submitter, amount).detach();
+
+        if let Some(list) = self.payment_lists.get_mut(&list_id) {
+            if let Some(payment) = list.payments.get_mut(index as usize) {
+                payment.status = PaymentStatus::Failed {
+                    error: format!("Transfer of {} {} failed", amount.0, token_id),
+                };
+            }
+        }
+
+        BulkPaymentEvent::PaymentFailed {
+            list_id,
+            index,
+            recipient,
+            token_id,
+            amount,
+        }
+        .emit();
+    }
+
     /// Reject a payment list (only allowed before approval)
     pub fn reject_list(&mut self, list_id: ListId) {
         let caller = env::predecessor_account_id();
 
-        let mut list = self
-            .payment_lists
-            .get(&list_id)
-            .expect("Payment list not found")
-            .clone();
+        let mut list = self.load_list(&list_id);
 
         require!(
             list.submitter == caller,
@@ -544,27 +1411,233 @@ impl BulkPaymentContract {
 
         // Update status
         list.status = ListStatus::Rejected;
-        self.payment_lists.insert(list_id.clone(), list);
+        Self::refund_funding(&list_id, &mut list);
+        Self::settle_storage_budget(&list_id, &mut list);
 
-        log!("Payment list {} rejected", list_id);
+        BulkPaymentEvent::ListRejected {
+            list_id: list_id.clone(),
+            submitter: list.submitter.clone(),
+        }
+        .emit();
+        self.payment_lists.insert(list_id, list);
     }
 
-    /// View a payment list with all details
-    pub fn view_list(&self, list_id: ListId) -> PaymentList {
-        self.payment_lists
+    /// Cancel a list that was never paid and return its storage credits to the submitter.
+    ///
+    /// Pending and Rejected lists can be cancelled by the submitter (or the contract account
+    /// acting for it). Once a Pending list has expired, anyone can cancel it.
+    /// Any storage budget and approval deposits are refunded to the submitter.
+    /// The list_id can be submitted again.
+    pub fn cancel_list(&mut self, list_id: ListId) {
+        let caller = env::predecessor_account_id();
+
+        let mut list = self.load_list(&list_id);
+
+        require!(
+            matches!(list.status, ListStatus::Pending | ListStatus::Rejected),
+            "Only pending or rejected lists can be cancelled"
+        );
+        require!(
+            list.submitter == caller
+                || caller == env::current_account_id()
+                || self.is_expired(&list),
+            "Only the submitter can cancel a list before it expires"
+        );
+
+        Self::refund_funding(&list_id, &mut list);
+
+        // Cancelled lists never start paying out, so the whole budget is unused
+        if list.storage_budget.as_yoctonear() > 0 {
+            Promise::new(list.submitter.clone())
+                .transfer(list.storage_budget)
+                .detach();
+            list.storage_budget = NearToken::from_yoctonear(0);
+        }
+
+        self.payment_lists.remove(&list_id);
+        self.return_storage_credits(&list.submitter, list.payments.len());
+
+        log!(
+            "Payment list {} cancelled, {} storage credits returned to {}",
+            list_id,
+            list.payments.len(),
+            list.submitter
+        );
+    }
+
+    /// Close a fully paid list: prune its payment rows to a compact `ListReceipt` and
+    /// return the freed storage credits to the submitter.
+    ///
+    /// Only the submitter (or the contract account acting for it) can close a list,
+    /// since per-payment block heights are no longer available afterwards.
+    pub fn close_list(&mut self, list_id: ListId) -> ListReceipt {
+        let caller = env::predecessor_account_id();
+
+        let list = self.load_list(&list_id);
+
+        require!(
+            list.submitter == caller || caller == env::current_account_id(),
+            "Only the submitter can close the list"
+        );
+        require!(
+            matches!(list.status, ListStatus::Approved),
+            "Only approved lists can be closed"
+        );
+        require!(
+            list.storage_checks_in_flight == 0,
+            "Payouts of this list are still in progress"
+        );
+
+        let mut completed_at_block: u64 = 0;
+        for payment in &list.payments {
+            match payment.status {
+                PaymentStatus::Paid { block_height } => {
+                    completed_at_block = completed_at_block.max(block_height);
+                }
+                PaymentStatus::Failed { .. } => {}
+                PaymentStatus::Pending => env::panic_str("All payments must be paid"),
+            }
+        }
+
+        // Failed payments were refunded, so they don't count towards the totals paid
+        let mut paid = list.clone();
+        paid.payments
+            .retain(|p| !matches!(p.status, PaymentStatus::Failed { .. }));
+
+        let receipt = ListReceipt {
+            list_id: list_id.clone(),
+            token_id: list.token_id.clone(),
+            submitter: list.submitter.clone(),
+            payment_count: list.payments.len() as u32,
+            totals: Self::token_totals(&paid),
+            completed_at_block,
+            closed_at: env::block_timestamp(),
+        };
+
+        self.payment_lists.remove(&list_id);
+        self.list_receipts.insert(list_id.clone(), receipt.clone());
+        self.return_storage_credits(&list.submitter, list.payments.len());
+
+        log!(
+            "Payment list {} closed, {} storage credits returned to {}",
+            list_id,
+            list.payments.len(),
+            list.submitter
+        );
+
+        receipt
+    }
+
+    /// View the receipt of a closed list
+    pub fn view_list_receipt(&self, list_id: ListId) -> ListReceipt {
+        self.list_receipts
             .get(&list_id)
-            .expect("Payment list not found")
+            .expect("List receipt not found")
             .clone()
     }
 
+    /// Set how long lists may stay Pending before they expire (0 disables expiry).
+    /// Only the owner can change it.
+    pub fn set_pending_list_expiry(&mut self, expiry_ns: U64) {
+        self.assert_owner();
+        self.pending_list_expiry_ns = expiry_ns.0;
+        log!("Pending list expiry set to {} ns", expiry_ns.0);
+    }
+
+    /// Time after submission when a Pending list expires, in nanoseconds (0 = never)
+    pub fn get_pending_list_expiry(&self) -> U64 {
+        U64(self.pending_list_expiry_ns)
+    }
+
+    /// Hand the contract over to a new owner (owner only)
+    pub fn set_owner(&mut self, owner_id: AccountId) {
+        self.assert_owner();
+        log!("Owner changed from {} to {}", self.owner_id, owner_id);
+        self.owner_id = owner_id;
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.owner_id.clone()
+    }
+
+    /// Allow an account to pause the contract (owner only)
+    pub fn add_guardian(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        require!(
+            !self.guardians.contains(&account_id),
+            "Account is already a guardian"
+        );
+        log!("Guardian {} added", account_id);
+        self.guardians.push(account_id);
+    }
+
+    /// Revoke an account's guardian role (owner only)
+    pub fn remove_guardian(&mut self, account_id: AccountId) {
+        self.assert_owner();
+        let len = self.guardians.len();
+        self.guardians.retain(|g| g != &account_id);
+        require!(self.guardians.len() < len, "Account is not a guardian");
+        log!("Guardian {} removed", account_id);
+    }
+
+    pub fn get_guardians(&self) -> Vec<AccountId> {
+        self.guardians.clone()
+    }
+
+    /// Stop list submissions, funding and payouts (owner or guardian).
+    /// Rejecting, cancelling and closing lists keep working, so deposits can be refunded,
+    /// and payments already dispatched still complete.
+    pub fn pause(&mut self) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.owner_id || self.guardians.contains(&caller),
+            "Only the owner or a guardian can pause the contract"
+        );
+        require!(!self.paused, "Contract is already paused");
+        self.paused = true;
+        BulkPaymentEvent::ContractPaused { account_id: caller }.emit();
+    }
+
+    /// Resume normal operation (owner only)
+    pub fn unpause(&mut self) {
+        self.assert_owner();
+        require!(self.paused, "Contract is not paused");
+        self.paused = false;
+        BulkPaymentEvent::ContractUnpaused {
+            account_id: env::predecessor_account_id(),
+        }
+        .emit();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// View a payment list with all details
+    pub fn view_list(&self, list_id: ListId) -> PaymentList {
+        self.list(&list_id)
+    }
+
+    /// Token totals of a list and whether each has been deposited
+    pub fn view_list_funding(&self, list_id: ListId) -> Vec<TokenFunding> {
+        let list = self.list(&list_id);
+        let approved = matches!(list.status, ListStatus::Approved);
+
+        Self::token_totals(&list)
+            .into_iter()
+            .map(|t| TokenFunding {
+                funded: approved || list.funded_tokens.contains(&t.token_id),
+                token_id: t.token_id,
+                amount: t.amount,
+            })
+            .collect()
+    }
+
     /// Get payment transactions for a list.
     /// Returns a list of recipients with their block heights where the payment was executed.
     /// The block height can be used to look up the transaction on a block explorer.
     pub fn get_payment_transactions(&self, list_id: ListId) -> Vec<PaymentTransaction> {
-        let list = self
-            .payment_lists
-            .get(&list_id)
-            .expect("Payment list not found");
+        let list = self.list(&list_id);
 
         list.payments
             .iter()
@@ -574,6 +1647,7 @@ impl BulkPaymentContract {
                         recipient: p.recipient.clone(),
                         amount: p.amount,
                         block_height: *block_height,
+                        token_id: p.token_id.clone(),
                     })
                 } else {
                     None
@@ -594,8 +1668,10 @@ impl BulkPaymentContract {
     /// This is called by the token contract after ft_transfer_call
     /// Returns the amount to refund (0 if all tokens are kept)
     ///
-    /// The `msg` parameter should be the list_id (hex-encoded SHA-256 hash)
+    /// The `msg` parameter should be the list_id (hex-encoded SHA-256 hash).
+    /// `amount` must be the list's total for the calling token.
     pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> U128 {
+        self.assert_not_paused();
         // msg is the list_id (hex-encoded hash)
         let list_id: ListId = msg;
 
@@ -605,11 +1681,7 @@ impl BulkPaymentContract {
         );
 
         // Get the list
-        let mut list = self
-            .payment_lists
-            .get(&list_id)
-            .expect("Payment list not found")
-            .clone();
+        let mut list = self.load_list(&list_id);
 
         // Validate that sender owns the list
         require!(
@@ -617,35 +1689,32 @@ impl BulkPaymentContract {
             "Only the submitter can approve the list via ft_transfer_call"
         );
 
-        // Validate list is in Pending status
+        // The caller is the token contract, so it must be an FT paid out by the list
+        let token_contract = env::predecessor_account_id();
         require!(
-            matches!(list.status, ListStatus::Pending),
-            "List must be in Pending status"
+            Self::is_fungible_token(token_contract.as_str())
+                && list
+                    .payments
+                    .iter()
+                    .any(|p| Self::payment_token(&list, p) == token_contract.as_str()),
+            format!(
+                "Token mismatch: list expects '{}', received tokens from '{}'",
+                list.token_id, token_contract
+            )
         );
 
-        // Calculate total payment amount
-        let total_amount: u128 = list
-            .payments
-            .iter()
-            .map(|p| p.amount.0)
-            .try_fold(0u128, |acc, x| acc.checked_add(x))
-            .expect("Total payment amount overflow");
-
-        // Validate amount matches total
+        // Validate list is in Pending status
         require!(
-            amount.0 == total_amount,
-            format!(
-                "Exact token amount required: {}, received: {}",
-                total_amount, amount.0
-            )
+            matches!(list.status, ListStatus::Pending),
+            "List must be in Pending status"
         );
+        require!(!self.is_expired(&list), "Payment list has expired");
 
-        // Approve the list
-        list.status = ListStatus::Approved;
+        Self::fund_list(&list_id, &mut list, token_contract.as_str(), amount.0);
         self.payment_lists.insert(list_id.clone(), list);
 
         log!(
-            "Payment list {} approved via ft_transfer_call with {} tokens",
+            "Payment list {} received {} tokens via ft_transfer_call",
             list_id,
             amount.0
         );
@@ -661,7 +1730,7 @@ impl MultiTokenReceiver for BulkPaymentContract {
     /// NEP-245 mt_on_transfer callback for multi-token approval flow
     /// This is called by the multi-token contract (like intents.near) after mt_transfer_call
     /// Returns the amount to refund (0 if all tokens are kept)
-    /// NEP-245 Multi-Token callback for `mt_transfer_call` and `mt_batch_transfer_call`
+    /// NEP-245 Multi-Token callback for `mt_transfer_call`
     ///
     /// This callback is invoked by the multi-token contract (e.g., intents.near) after
     /// transferring tokens to this contract via `mt_transfer_call`.
@@ -677,6 +1746,7 @@ impl MultiTokenReceiver for BulkPaymentContract {
     /// Array of unused token amounts to refund (as strings). Returns all zeros to keep all tokens.
     ///
     /// # Panics
+    /// - If the contract is paused
     /// - If msg is not a valid list_id (64-character hex string)
     /// - If payment list is not found
     /// - If list is not in Pending status
@@ -690,6 +1760,8 @@ impl MultiTokenReceiver for BulkPaymentContract {
         amounts: Vec<U128>,
         msg: String,
     ) -> PromiseOrValue<Vec<U128>> {
+        self.assert_not_paused();
+
         // Suppress unused variable warnings
         let _ = (previous_owner_ids, sender_id);
 
@@ -702,11 +1774,7 @@ impl MultiTokenReceiver for BulkPaymentContract {
         );
 
         // Get the list
-        let mut list = self
-            .payment_lists
-            .get(&list_id)
-            .expect("Payment list not found")
-            .clone();
+        let mut list = self.load_list(&list_id);
 
         // Validate that sender owns the list OR is the token holder approving the payment
         // The sender is the account calling mt_transfer_call (token owner)
@@ -718,6 +1786,7 @@ impl MultiTokenReceiver for BulkPaymentContract {
             matches!(list.status, ListStatus::Pending),
             "List must be in Pending status to approve via mt_transfer_call"
         );
+        require!(!self.is_expired(&list), "Payment list has expired");
 
         // For single token transfers, expect exactly one token
         require!(
@@ -725,41 +1794,36 @@ impl MultiTokenReceiver for BulkPaymentContract {
             "Expected exactly one token transfer"
         );
 
-        let token_id = &token_ids[0];
-        let amount = amounts[0];
-
-        // Validate token_id matches the list
+        // Only the intents contract can vouch for "nep141:" balances
         require!(
-            list.token_id == *token_id,
+            env::predecessor_account_id().as_str() == INTENTS_CONTRACT_ID,
             format!(
-                "Token ID mismatch: list expects '{}', received '{}'",
-                list.token_id, token_id
+                "Multi-token transfers are only accepted from {}",
+                INTENTS_CONTRACT_ID
             )
         );
 
-        // Calculate total payment amount
-        let total_amount: u128 = list
-            .payments
-            .iter()
-            .map(|p| p.amount.0)
-            .try_fold(0u128, |acc, x| acc.checked_add(x))
-            .expect("Total payment amount overflow");
+        let token_id = &token_ids[0];
+        let amount = amounts[0];
 
-        // Validate amount matches total
+        // Validate token_id is paid out by the list
         require!(
-            amount.0 == total_amount,
+            token_id.starts_with("nep141:")
+                && list
+                    .payments
+                    .iter()
+                    .any(|p| BulkPaymentContract::payment_token(&list, p) == token_id.as_str()),
             format!(
-                "Exact token amount required: {}, received: {}",
-                total_amount, amount.0
+                "Token ID mismatch: list expects '{}', received '{}'",
+                list.token_id, token_id
             )
         );
 
-        // Approve the list
-        list.status = ListStatus::Approved;
+        BulkPaymentContract::fund_list(&list_id, &mut list, token_id, amount.0);
         self.payment_lists.insert(list_id.clone(), list);
 
         log!(
-            "Payment list {} approved via mt_transfer_call with {} tokens ({})",
+            "Payment list {} received {} tokens via mt_transfer_call ({})",
             list_id,
             amount.0,
             token_id
@@ -770,6 +1834,92 @@ impl MultiTokenReceiver for BulkPaymentContract {
     }
 }
 
+/// Every layout the contract state has been stored in. `migrate` reads the stored layout
+/// and converts it to the current `BulkPaymentContract`.
+///
+/// To change the layout: keep a copy of the current structs as the next `...V<n>` types,
+/// add a variant for them, bump `CURRENT_STATE_VERSION` and convert in `migrate`.
+enum StateVersion {
+    /// Initial deployment, stored without a version key
+    V1(ContractV1),
+    /// Owner, guardians and pause; mixed lists, storage budgets and receipts
+    V2(Box<BulkPaymentContract>),
+}
+
+impl StateVersion {
+    fn read() -> Self {
+        let version = env::storage_read(STATE_VERSION_KEY).map(|v| v[0]);
+        match version {
+            None => Self::V1(env::state_read().expect("Contract is not initialized")),
+            Some(CURRENT_STATE_VERSION) => Self::V2(Box::new(
+                env::state_read().expect("Contract is not initialized"),
+            )),
+            Some(v) => env::panic_str(&format!("Unknown state version {}", v)),
+        }
+    }
+}
+
+#[near(serializers = [borsh])]
+struct ContractV1 {
+    payment_lists: IterableMap<ListId, PaymentListV1>,
+    storage_credits: IterableMap<AccountId, NearToken>,
+}
+
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+struct PaymentListV1 {
+    token_id: String,
+    submitter: AccountId,
+    status: ListStatus,
+    payments: Vec<PaymentRecordV1>,
+    created_at: u64,
+}
+
+/// `PaymentStatus` only gained variants since, so V1 statuses decode as is
+#[near(serializers = [borsh])]
+#[derive(Clone)]
+struct PaymentRecordV1 {
+    recipient: AccountId,
+    amount: U128,
+    status: PaymentStatus,
+}
+
+impl From<ContractV1> for BulkPaymentContract {
+    fn from(old: ContractV1) -> Self {
+        // The lists stay where they are as `legacy_lists`; the owner defaults to the contract
+        // account, which is calling `migrate`
+        Self {
+            legacy_lists: old.payment_lists,
+            storage_credits: old.storage_credits,
+            ..Self::default()
+        }
+    }
+}
+
+impl From<PaymentListV1> for PaymentList {
+    fn from(list: PaymentListV1) -> Self {
+        Self {
+            token_id: list.token_id,
+            submitter: list.submitter,
+            status: list.status,
+            payments: list
+                .payments
+                .into_iter()
+                .map(|p| PaymentRecord {
+                    recipient: p.recipient.into(),
+                    amount: p.amount,
+                    status: p.status,
+                    token_id: None,
+                })
+                .collect(),
+            created_at: list.created_at,
+            storage_budget: NearToken::from_yoctonear(0),
+            storage_checks_in_flight: 0,
+            funded_tokens: Vec::new(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -782,16 +1932,9 @@ mod tests {
         builder
     }
 
-    /// Generate a valid list_id (64-character hex string) for testing
-    fn test_list_id(suffix: &str) -> ListId {
-        // Create a valid 64-character hex string by hashing the suffix
-        // For testing, we just pad with 'a' (valid hex char) to make 64 characters
-        let hex_suffix = suffix
-            .bytes()
-            .map(|b| format!("{:02x}", b))
-            .collect::<String>();
-        let padded = format!("{:a>64}", hex_suffix);
-        padded[..64].to_string()
+    /// Compute the list_id the contract expects for a payment list
+    fn list_id_for(submitter: &AccountId, token_id: &str, payments: &[PaymentInput]) -> ListId {
+        BulkPaymentContract::hash_list(submitter, token_id, payments, None)
     }
 
     #[test]
@@ -853,18 +1996,20 @@ mod tests {
 
         let payments = vec![
             PaymentInput {
-                recipient: accounts(1),
+                recipient: accounts(1).to_string(),
                 amount: U128(1_000_000_000_000_000_000_000_000),
+                token_id: None,
             },
             PaymentInput {
-                recipient: accounts(2),
+                recipient: accounts(2).to_string(),
                 amount: U128(2_000_000_000_000_000_000_000_000),
+                token_id: None,
             },
         ];
 
-        let list_id = test_list_id("1");
+        let list_id = list_id_for(&accounts(0), "native", &payments);
         let returned_id =
-            contract.submit_list(list_id.clone(), "native".to_string(), payments, None);
+            contract.submit_list(list_id.clone(), "native".to_string(), payments, None, None);
 
         // Verify credits were deducted (10 - 2 = 8)
         let credits = contract.view_storage_credits(accounts(0));
@@ -886,12 +2031,14 @@ mod tests {
         let mut contract = BulkPaymentContract::default();
 
         let payments = vec![PaymentInput {
-            recipient: accounts(1),
+            recipient: accounts(1).to_string(),
             amount: U128(1_000_000_000_000_000_000_000_000),
+            token_id: None,
         }];
 
         // Should panic - no storage credits
-        contract.submit_list(test_list_id("1"), "native".to_string(), payments, None);
+        let list_id = list_id_for(&accounts(0), "native", &payments);
+        contract.submit_list(list_id, "native".to_string(), payments, None, None);
     }
 
     #[test]
@@ -911,17 +2058,19 @@ mod tests {
 
         let payments = vec![
             PaymentInput {
-                recipient: accounts(1),
+                recipient: accounts(1).to_string(),
                 amount: U128(1_000_000_000_000_000_000_000_000),
+                token_id: None,
             },
             PaymentInput {
-                recipient: accounts(2),
+                recipient: accounts(2).to_string(),
                 amount: U128(2_000_000_000_000_000_000_000_000),
+                token_id: None,
             },
         ];
 
-        let list_id = test_list_id("approve_test");
-        contract.submit_list(list_id.clone(), "native".to_string(), payments, None);
+        let list_id = list_id_for(&accounts(0), "native", &payments);
+        contract.submit_list(list_id.clone(), "native".to_string(), payments, None, None);
 
         // Approve with exact deposit (3 NEAR total)
         let total_deposit = NearToken::from_yoctonear(3_000_000_000_000_000_000_000_000);
@@ -952,12 +2101,13 @@ mod tests {
         testing_env!(context.build());
 
         let payments = vec![PaymentInput {
-            recipient: accounts(1),
+            recipient: accounts(1).to_string(),
             amount: U128(1_000_000_000_000_000_000_000_000),
+            token_id: None,
         }];
 
-        let list_id = test_list_id("wrong_deposit");
-        contract.submit_list(list_id.clone(), "native".to_string(), payments, None);
+        let list_id = list_id_for(&accounts(0), "native", &payments);
+        contract.submit_list(list_id.clone(), "native".to_string(), payments, None, None);
 
         // Try to approve with wrong deposit
         let wrong_deposit = NearToken::from_yoctonear(500_000_000_000_000_000_000_000);
@@ -984,12 +2134,13 @@ mod tests {
         testing_env!(context.build());
 
         let payments = vec![PaymentInput {
-            recipient: accounts(1),
+            recipient: accounts(1).to_string(),
             amount: U128(1_000_000_000_000_000_000_000_000),
+            token_id: None,
         }];
 
-        let list_id = test_list_id("unauthorized");
-        contract.submit_list(list_id.clone(), "native".to_string(), payments, None);
+        let list_id = list_id_for(&accounts(0), "native", &payments);
+        contract.submit_list(list_id.clone(), "native".to_string(), payments, None, None);
 
         // User 1 tries to approve (should fail)
         context = get_context(accounts(1));
@@ -1016,12 +2167,13 @@ mod tests {
         testing_env!(context.build());
 
         let payments = vec![PaymentInput {
-            recipient: accounts(1),
+            recipient: accounts(1).to_string(),
             amount: U128(1_000_000_000_000_000_000_000_000),
+            token_id: None,
         }];
 
-        let list_id = test_list_id("reject_test");
-        contract.submit_list(list_id.clone(), "native".to_string(), payments, None);
+        let list_id = list_id_for(&accounts(0), "native", &payments);
+        contract.submit_list(list_id.clone(), "native".to_string(), payments, None, None);
 
         // Reject without approval first
         contract.reject_list(list_id.clone());
@@ -1047,12 +2199,13 @@ mod tests {
         testing_env!(context.build());
 
         let payments = vec![PaymentInput {
-            recipient: accounts(1),
+            recipient: accounts(1).to_string(),
             amount: U128(1_000_000_000_000_000_000_000_000),
+            token_id: None,
         }];
 
-        let list_id = test_list_id("reject_approved");
-        contract.submit_list(list_id.clone(), "native".to_string(), payments, None);
+        let list_id = list_id_for(&accounts(0), "native", &payments);
+        contract.submit_list(list_id.clone(), "native".to_string(), payments, None, None);
 
         // Approve the list
         context.attached_deposit(NearToken::from_yoctonear(1_000_000_000_000_000_000_000_000));
@@ -1082,22 +2235,34 @@ mod tests {
 
         // Submit multiple lists
         let payments1 = vec![PaymentInput {
-            recipient: accounts(1),
+            recipient: accounts(1).to_string(),
             amount: U128(1_000_000_000_000_000_000_000_000),
+            token_id: None,
         }];
 
         let payments2 = vec![PaymentInput {
-            recipient: accounts(2),
+            recipient: accounts(2).to_string(),
             amount: U128(2_000_000_000_000_000_000_000_000),
+            token_id: None,
         }];
 
-        let list_id1 = test_list_id("multi_1");
-        let list_id2 = test_list_id("multi_2");
+        let list_id1 = list_id_for(&accounts(0), "native", &payments1);
+        let list_id2 = list_id_for(&accounts(0), "native", &payments2);
 
-        let returned_id1 =
-            contract.submit_list(list_id1.clone(), "native".to_string(), payments1, None);
-        let returned_id2 =
-            contract.submit_list(list_id2.clone(), "native".to_string(), payments2, None);
+        let returned_id1 = contract.submit_list(
+            list_id1.clone(),
+            "native".to_string(),
+            payments1,
+            None,
+            None,
+        );
+        let returned_id2 = contract.submit_list(
+            list_id2.clone(),
+            "native".to_string(),
+            payments2,
+            None,
+            None,
+        );
 
         assert_eq!(returned_id1, list_id1);
         assert_eq!(returned_id2, list_id2);
@@ -1201,18 +2366,20 @@ mod tests {
 
         let payments = vec![
             PaymentInput {
-                recipient: accounts(2),
+                recipient: accounts(2).to_string(),
                 amount: U128(1_000_000_000_000_000_000_000_000),
+                token_id: None,
             },
             PaymentInput {
-                recipient: accounts(3),
+                recipient: accounts(3).to_string(),
                 amount: U128(2_000_000_000_000_000_000_000_000),
+                token_id: None,
             },
         ];
 
-        let list_id = test_list_id("beneficiary_test");
+        let list_id = list_id_for(&accounts(1), "native", &payments);
         let returned_id =
-            contract.submit_list(list_id.clone(), "native".to_string(), payments, None);
+            contract.submit_list(list_id.clone(), "native".to_string(), payments, None, None);
 
         // Verify credits were deducted from User 1 (10 - 2 = 8)
         let credits = contract.view_storage_credits(accounts(1));
@@ -1225,6 +2392,1418 @@ mod tests {
         assert_eq!(list.submitter, accounts(1));
     }
 
+    /// Submit a two-payment list (3 tokens total) from accounts(0) and return its list_id
+    fn submit_pending_list(
+        contract: &mut BulkPaymentContract,
+        context: &mut VMContextBuilder,
+        token_id: &str,
+    ) -> ListId {
+        context.predecessor_account_id(accounts(0));
+        context.attached_deposit(NearToken::from_yoctonear(23_760_000_000_000_000_000_000));
+        testing_env!(context.build());
+        contract.buy_storage(10, None);
+
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+
+        let payments = vec![
+            PaymentInput {
+                recipient: accounts(1).to_string(),
+                amount: U128(1_000_000),
+                token_id: None,
+            },
+            PaymentInput {
+                recipient: accounts(2).to_string(),
+                amount: U128(2_000_000),
+                token_id: None,
+            },
+        ];
+        let list_id = list_id_for(&accounts(0), token_id, &payments);
+        contract.submit_list(list_id, token_id.to_string(), payments, None, None)
+    }
+
+    #[test]
+    fn test_list_id_matches_backend_hash() {
+        // Same canonical form as the treasury backend's compute_list_hash;
+        // input order must not matter
+        let payments = vec![
+            PaymentInput {
+                recipient: "bob.near".parse().unwrap(),
+                amount: U128(1_000_000_000_000_000_000_000_000),
+                token_id: None,
+            },
+            PaymentInput {
+                recipient: "alice.near".parse().unwrap(),
+                amount: U128(2_000_000_000_000_000_000_000_000),
+                token_id: None,
+            },
+        ];
+
+        // A salted list (one occurrence of a payment schedule) gets its own list_id
+        let salted_list_id = BulkPaymentContract::hash_list(
+            &"testdao.sputnik-dao.near".parse().unwrap(),
+            "native",
+            &payments,
+            Some("schedule:42:2026-03-01T00:00:00+00:00"),
+        );
+        assert_eq!(
+            salted_list_id,
+            "de53ec268fd3930ffb597a455f87b7a49a0af6da0be281a7a370fa92f918c955"
+        );
+
+        let contract = BulkPaymentContract::default();
+        let list_id = contract.compute_list_id(
+            "testdao.sputnik-dao.near".parse().unwrap(),
+            "native".to_string(),
+            payments,
+            None,
+        );
+
+        assert_eq!(
+            list_id,
+            "f05ca9436f5249224ab62d4c7819bffcf96cb9125a4b4f8fe477462b794c47b7"
+        );
+    }
+
+    #[test]
+    fn test_submit_salted_list() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        // The same payments can be submitted again under another salt
+        let payments = contract
+            .view_list(list_id.clone())
+            .payments
+            .into_iter()
+            .map(|payment| PaymentInput {
+                recipient: payment.recipient,
+                amount: payment.amount,
+                token_id: None,
+            })
+            .collect::<Vec<_>>();
+        let salt = "schedule:7:2026-04-01T00:00:00+00:00".to_string();
+        let salted_list_id =
+            BulkPaymentContract::hash_list(&accounts(0), "native", &payments, Some(salt.as_str()));
+        assert_ne!(salted_list_id, list_id);
+
+        contract.submit_list(
+            salted_list_id.clone(),
+            "native".to_string(),
+            payments,
+            None,
+            Some(salt),
+        );
+        assert_eq!(contract.view_list(salted_list_id).payments.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "list_id does not match the payment list contents")]
+    fn test_submit_list_rejects_mismatched_hash() {
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(NearToken::from_yoctonear(23_760_000_000_000_000_000_000));
+        testing_env!(context.build());
+
+        let mut contract = BulkPaymentContract::default();
+        contract.buy_storage(10, None);
+
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+
+        // Hash of the list the DAO approved
+        let approved = vec![PaymentInput {
+            recipient: accounts(1).to_string(),
+            amount: U128(1_000_000_000_000_000_000_000_000),
+            token_id: None,
+        }];
+        let list_id = list_id_for(&accounts(0), "native", &approved);
+
+        // Submitted under that hash with a different amount - should panic
+        let tampered = vec![PaymentInput {
+            recipient: accounts(1).to_string(),
+            amount: U128(100_000_000_000_000_000_000_000_000),
+            token_id: None,
+        }];
+        contract.submit_list(list_id, "native".to_string(), tampered, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "list_id does not match the payment list contents")]
+    fn test_submit_list_rejects_hash_of_other_token() {
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(NearToken::from_yoctonear(23_760_000_000_000_000_000_000));
+        testing_env!(context.build());
+
+        let mut contract = BulkPaymentContract::default();
+        contract.buy_storage(10, None);
+
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+
+        let payments = vec![PaymentInput {
+            recipient: accounts(1).to_string(),
+            amount: U128(1_000_000),
+            token_id: None,
+        }];
+        let list_id = list_id_for(&accounts(0), accounts(3).as_str(), &payments);
+
+        // Same payments, but the list is submitted with a different token - should panic
+        contract.submit_list(list_id, accounts(4).to_string(), payments, None, None);
+    }
+
+    /// Submit a list of 1_000_000 to each recipient from accounts(0)
+    fn submit_list_to(
+        contract: &mut BulkPaymentContract,
+        context: &mut VMContextBuilder,
+        token_id: &str,
+        recipients: &[&str],
+    ) -> ListId {
+        context.predecessor_account_id(accounts(0));
+        context.attached_deposit(NearToken::from_yoctonear(23_760_000_000_000_000_000_000));
+        testing_env!(context.build());
+        contract.buy_storage(10, None);
+
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+
+        let payments: Vec<PaymentInput> = recipients
+            .iter()
+            .map(|recipient| PaymentInput {
+                recipient: recipient.to_string(),
+                amount: U128(1_000_000),
+                token_id: None,
+            })
+            .collect();
+        let list_id = list_id_for(&accounts(0), token_id, &payments);
+        contract.submit_list(list_id, token_id.to_string(), payments, None, None)
+    }
+
+    #[test]
+    fn test_poa_list_pays_chain_addresses() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let token_id = "nep141:sol-5ce3bf3a31af18be40ba30f721101b4341690186.omft.near";
+        let list_id = submit_list_to(
+            &mut contract,
+            &mut context,
+            token_id,
+            &[
+                "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU",
+                "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+            ],
+        );
+
+        context.predecessor_account_id(INTENTS_CONTRACT_ID.parse().unwrap());
+        testing_env!(context.build());
+        let _ = contract.mt_on_transfer(
+            accounts(0),
+            vec![accounts(0)],
+            vec![token_id.to_string()],
+            vec![U128(2_000_000)],
+            list_id.clone(),
+        );
+
+        context.prepaid_gas(Gas::from_tgas(300));
+        testing_env!(context.build());
+        assert_eq!(contract.payout_batch(list_id.clone()), 0);
+
+        let transactions = contract.get_payment_transactions(list_id);
+        assert_eq!(
+            transactions[0].recipient,
+            "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid recipient account: 0xAbC0000000000000000000000000000000000001"
+    )]
+    fn test_chain_address_needs_poa_token() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+
+        // Only PoA tokens can be withdrawn to another chain
+        submit_list_to(
+            &mut contract,
+            &mut context,
+            "nep141:wrap.near",
+            &["0xAbC0000000000000000000000000000000000001"],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid withdrawal address")]
+    fn test_withdrawal_address_cannot_inject_memo() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+
+        submit_list_to(
+            &mut contract,
+            &mut context,
+            "nep141:btc.omft.near",
+            &[r#"bc1q","receiver_id":"attacker.near"#],
+        );
+    }
+
+    #[test]
+    fn test_ft_on_transfer_from_list_token() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, accounts(3).as_str());
+
+        // The list token contract forwards the submitter's ft_transfer_call
+        context.predecessor_account_id(accounts(3));
+        testing_env!(context.build());
+        let refund = contract.ft_on_transfer(accounts(0), U128(3_000_000), list_id.clone());
+
+        assert_eq!(refund, U128(0));
+        let list = contract.view_list(list_id);
+        assert!(matches!(list.status, ListStatus::Approved));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token mismatch")]
+    fn test_ft_on_transfer_rejects_forged_token() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, accounts(3).as_str());
+
+        // A worthless token contract calls ft_on_transfer claiming the submitter paid
+        context.predecessor_account_id(accounts(4));
+        testing_env!(context.build());
+        contract.ft_on_transfer(accounts(0), U128(3_000_000), list_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token mismatch")]
+    fn test_ft_on_transfer_rejects_native_list() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        // A NEAR list must never be approved with fungible tokens
+        context.predecessor_account_id(accounts(4));
+        testing_env!(context.build());
+        contract.ft_on_transfer(accounts(0), U128(3_000_000), list_id);
+    }
+
+    #[test]
+    fn test_mt_on_transfer_from_intents() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let token_id = "nep141:btc.omft.near";
+        let list_id = submit_pending_list(&mut contract, &mut context, token_id);
+
+        context.predecessor_account_id(INTENTS_CONTRACT_ID.parse().unwrap());
+        testing_env!(context.build());
+        let _ = contract.mt_on_transfer(
+            accounts(0),
+            vec![accounts(0)],
+            vec![token_id.to_string()],
+            vec![U128(3_000_000)],
+            list_id.clone(),
+        );
+
+        let list = contract.view_list(list_id);
+        assert!(matches!(list.status, ListStatus::Approved));
+    }
+
+    #[test]
+    #[should_panic(expected = "Multi-token transfers are only accepted from intents.near")]
+    fn test_mt_on_transfer_rejects_forged_caller() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let token_id = "nep141:btc.omft.near";
+        let list_id = submit_pending_list(&mut contract, &mut context, token_id);
+
+        // Any contract can claim to transfer "nep141:btc.omft.near" - only intents.near holds it
+        context.predecessor_account_id(accounts(4));
+        testing_env!(context.build());
+        let _ = contract.mt_on_transfer(
+            accounts(0),
+            vec![accounts(0)],
+            vec![token_id.to_string()],
+            vec![U128(3_000_000)],
+            list_id,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only lists paying native NEAR can be approved with a deposit")]
+    fn test_approve_token_list_with_near_fails() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, accounts(3).as_str());
+
+        // Attaching 3 yoctoNEAR must not approve a list paying out 3 units of an FT
+        context.attached_deposit(NearToken::from_yoctonear(3_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id);
+    }
+
+    /// wrap.near's storage_balance_bounds().min
+    const FT_STORAGE_MIN: u128 = 1_250_000_000_000_000_000_000;
+
+    /// Submit an FT list with a storage budget for one registration, approve it via
+    /// ft_on_transfer and dispatch its payouts
+    fn paid_out_list_with_budget(
+        contract: &mut BulkPaymentContract,
+        context: &mut VMContextBuilder,
+    ) -> ListId {
+        let list_id = submit_pending_list(contract, context, accounts(3).as_str());
+
+        context.attached_deposit(NearToken::from_yoctonear(FT_STORAGE_MIN));
+        testing_env!(context.build());
+        contract.attach_storage_budget(list_id.clone());
+
+        context.predecessor_account_id(accounts(3));
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+        contract.ft_on_transfer(accounts(0), U128(3_000_000), list_id.clone());
+
+        context.prepaid_gas(Gas::from_tgas(300));
+        testing_env!(context.build());
+        assert_eq!(contract.payout_batch(list_id.clone()), 0);
+
+        // Callbacks are called by the contract itself
+        context.predecessor_account_id(env::current_account_id());
+        testing_env!(context.build());
+
+        list_id
+    }
+
+    #[test]
+    fn test_attach_storage_budget() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, accounts(3).as_str());
+
+        // Anyone can top up the budget
+        context.predecessor_account_id(accounts(5));
+        context.attached_deposit(NearToken::from_millinear(10));
+        testing_env!(context.build());
+        contract.attach_storage_budget(list_id.clone());
+        let total = contract.attach_storage_budget(list_id.clone());
+
+        assert_eq!(total, NearToken::from_millinear(20));
+        assert_eq!(
+            contract.view_storage_budget(list_id),
+            NearToken::from_millinear(20)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Storage budget is only used for NEP-141 token lists")]
+    fn test_attach_storage_budget_native_list_fails() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        context.attached_deposit(NearToken::from_millinear(10));
+        testing_env!(context.build());
+        contract.attach_storage_budget(list_id);
+    }
+
+    #[test]
+    fn test_payout_registers_unregistered_recipient() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = paid_out_list_with_budget(&mut contract, &mut context);
+
+        // Both recipients are checked before their transfer
+        let list = contract.view_list(list_id.clone());
+        assert_eq!(list.storage_checks_in_flight, 2);
+
+        // First recipient has no storage on the token: registered from the budget
+        contract.on_recipient_storage_checked(
+            list_id.clone(),
+            0,
+            Ok(None),
+            Ok(StorageBalanceBounds {
+                min: U128(FT_STORAGE_MIN),
+                max: None,
+            }),
+        );
+
+        let list = contract.view_list(list_id);
+        assert_eq!(list.storage_checks_in_flight, 1);
+        assert_eq!(list.storage_budget.as_yoctonear(), 0);
+    }
+
+    #[test]
+    fn test_payout_keeps_budget_for_registered_recipient() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = paid_out_list_with_budget(&mut contract, &mut context);
+
+        contract.on_recipient_storage_checked(
+            list_id.clone(),
+            0,
+            Ok(Some(StorageBalance {
+                total: U128(FT_STORAGE_MIN),
+                available: U128(0),
+            })),
+            Ok(StorageBalanceBounds {
+                min: U128(FT_STORAGE_MIN),
+                max: None,
+            }),
+        );
+
+        let list = contract.view_list(list_id);
+        assert_eq!(list.storage_checks_in_flight, 1);
+        assert_eq!(list.storage_budget.as_yoctonear(), FT_STORAGE_MIN);
+    }
+
+    #[test]
+    fn test_storage_budget_refunded_when_list_completes() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = paid_out_list_with_budget(&mut contract, &mut context);
+
+        let registered = Ok(Some(StorageBalance {
+            total: U128(FT_STORAGE_MIN),
+            available: U128(0),
+        }));
+        let bounds = || {
+            Ok(StorageBalanceBounds {
+                min: U128(FT_STORAGE_MIN),
+                max: None,
+            })
+        };
+
+        contract.on_recipient_storage_checked(list_id.clone(), 0, registered, bounds());
+        assert_eq!(
+            contract.view_storage_budget(list_id.clone()).as_yoctonear(),
+            FT_STORAGE_MIN
+        );
+
+        // Last check completes the list: the unused budget goes back to the submitter
+        contract.on_recipient_storage_checked(
+            list_id.clone(),
+            1,
+            Ok(None),
+            Err(PromiseError::Failed),
+        );
+
+        let list = contract.view_list(list_id);
+        assert_eq!(list.storage_checks_in_flight, 0);
+        assert_eq!(list.storage_budget.as_yoctonear(), 0);
+    }
+
+    #[test]
+    fn test_reject_list_refunds_storage_budget() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, accounts(3).as_str());
+
+        context.attached_deposit(NearToken::from_yoctonear(FT_STORAGE_MIN));
+        testing_env!(context.build());
+        contract.attach_storage_budget(list_id.clone());
+
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+        contract.reject_list(list_id.clone());
+
+        assert_eq!(contract.view_storage_budget(list_id).as_yoctonear(), 0);
+    }
+
+    /// Default pending list expiry plus one second
+    const AFTER_EXPIRY_NS: u64 = DEFAULT_PENDING_LIST_EXPIRY_NS + 1_000_000_000;
+
+    /// Approve a native list from `submit_pending_list` and pay it out
+    fn approve_and_pay_out(
+        contract: &mut BulkPaymentContract,
+        context: &mut VMContextBuilder,
+        list_id: &ListId,
+    ) {
+        context.attached_deposit(NearToken::from_yoctonear(3_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id.clone());
+
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        context.prepaid_gas(Gas::from_tgas(300));
+        testing_env!(context.build());
+        assert_eq!(contract.payout_batch(list_id.clone()), 0);
+    }
+
+    #[test]
+    fn test_cancel_list_returns_credits() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+        assert_eq!(contract.view_storage_credits(accounts(0)).as_yoctonear(), 8);
+
+        contract.cancel_list(list_id.clone());
+
+        assert_eq!(
+            contract.view_storage_credits(accounts(0)).as_yoctonear(),
+            10
+        );
+        assert!(contract.payment_lists.get(&list_id).is_none());
+    }
+
+    #[test]
+    fn test_cancel_rejected_list() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        contract.reject_list(list_id.clone());
+        contract.cancel_list(list_id.clone());
+
+        assert_eq!(
+            contract.view_storage_credits(accounts(0)).as_yoctonear(),
+            10
+        );
+        assert!(contract.payment_lists.get(&list_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the submitter can cancel a list before it expires")]
+    fn test_cancel_list_unauthorized() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        context.predecessor_account_id(accounts(1));
+        testing_env!(context.build());
+        contract.cancel_list(list_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Payment list has expired")]
+    fn test_approve_expired_list_fails() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        context.block_timestamp(AFTER_EXPIRY_NS);
+        context.attached_deposit(NearToken::from_yoctonear(3_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Payment list has expired")]
+    fn test_ft_on_transfer_expired_list_fails() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, accounts(3).as_str());
+
+        context.predecessor_account_id(accounts(3));
+        context.block_timestamp(AFTER_EXPIRY_NS);
+        testing_env!(context.build());
+        contract.ft_on_transfer(accounts(0), U128(3_000_000), list_id);
+    }
+
+    #[test]
+    fn test_anyone_can_cancel_expired_list() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        context.predecessor_account_id(accounts(1));
+        context.block_timestamp(AFTER_EXPIRY_NS);
+        testing_env!(context.build());
+        contract.cancel_list(list_id.clone());
+
+        // Credits go back to the submitter, not the caller
+        assert_eq!(
+            contract.view_storage_credits(accounts(0)).as_yoctonear(),
+            10
+        );
+        assert_eq!(contract.view_storage_credits(accounts(1)).as_yoctonear(), 0);
+        assert!(contract.payment_lists.get(&list_id).is_none());
+    }
+
+    #[test]
+    fn test_pending_list_expiry_disabled() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        assert_eq!(
+            contract.get_pending_list_expiry(),
+            U64(DEFAULT_PENDING_LIST_EXPIRY_NS)
+        );
+        context.predecessor_account_id(contract.get_owner());
+        testing_env!(context.build());
+        contract.set_pending_list_expiry(U64(0));
+        context.predecessor_account_id(accounts(0));
+
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        context.block_timestamp(AFTER_EXPIRY_NS);
+        context.attached_deposit(NearToken::from_yoctonear(3_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id.clone());
+
+        let list = contract.view_list(list_id);
+        assert!(matches!(list.status, ListStatus::Approved));
+    }
+
+    #[test]
+    fn test_close_list() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        context.block_height(42);
+        approve_and_pay_out(&mut contract, &mut context, &list_id);
+
+        let receipt = contract.close_list(list_id.clone());
+
+        assert_eq!(receipt.list_id, list_id);
+        assert_eq!(receipt.submitter, accounts(0));
+        assert_eq!(receipt.payment_count, 2);
+        assert_eq!(receipt.totals.len(), 1);
+        assert_eq!(receipt.totals[0].token_id, "native");
+        assert_eq!(receipt.totals[0].amount, U128(3_000_000));
+        assert_eq!(receipt.completed_at_block, 42);
+
+        // Payment rows are gone and the credits can be reused
+        assert!(contract.payment_lists.get(&list_id).is_none());
+        assert_eq!(
+            contract.view_storage_credits(accounts(0)).as_yoctonear(),
+            10
+        );
+        assert_eq!(contract.view_list_receipt(list_id).payment_count, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "All payments must be paid")]
+    fn test_close_unpaid_list_fails() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        context.attached_deposit(NearToken::from_yoctonear(3_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id.clone());
+
+        contract.close_list(list_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Payment list with this ID was already paid and closed")]
+    fn test_resubmit_closed_list_fails() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+        approve_and_pay_out(&mut contract, &mut context, &list_id);
+        contract.close_list(list_id.clone());
+
+        // Same list again, e.g. a retried submission - must not be paid twice
+        let payments = vec![
+            PaymentInput {
+                recipient: accounts(1).to_string(),
+                amount: U128(1_000_000),
+                token_id: None,
+            },
+            PaymentInput {
+                recipient: accounts(2).to_string(),
+                amount: U128(2_000_000),
+                token_id: None,
+            },
+        ];
+        contract.submit_list(list_id, "native".to_string(), payments, None, None);
+    }
+
+    const BTC_INTENTS_TOKEN: &str = "nep141:btc.omft.near";
+
+    /// Submit a mixed list from accounts(0): 1_000_000 yoctoNEAR, 2_000_000 of the
+    /// accounts(3) FT and 5 intents BTC
+    fn submit_mixed_list(
+        contract: &mut BulkPaymentContract,
+        context: &mut VMContextBuilder,
+    ) -> ListId {
+        context.predecessor_account_id(accounts(0));
+        context.attached_deposit(NearToken::from_yoctonear(23_760_000_000_000_000_000_000));
+        testing_env!(context.build());
+        contract.buy_storage(10, None);
+
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+
+        let payments = vec![
+            PaymentInput {
+                recipient: accounts(1).to_string(),
+                amount: U128(1_000_000),
+                token_id: Some("native".to_string()),
+            },
+            PaymentInput {
+                recipient: accounts(2).to_string(),
+                amount: U128(2_000_000),
+                token_id: Some(accounts(3).to_string()),
+            },
+            PaymentInput {
+                recipient: accounts(1).to_string(),
+                amount: U128(5),
+                token_id: Some(BTC_INTENTS_TOKEN.to_string()),
+            },
+        ];
+        let list_id = list_id_for(&accounts(0), MIXED_TOKEN_ID, &payments);
+        contract.submit_list(list_id, MIXED_TOKEN_ID.to_string(), payments, None, None)
+    }
+
+    /// Fund every token of the list from `submit_mixed_list`
+    fn fund_mixed_list(
+        contract: &mut BulkPaymentContract,
+        context: &mut VMContextBuilder,
+        list_id: &ListId,
+    ) {
+        context.predecessor_account_id(accounts(0));
+        context.attached_deposit(NearToken::from_yoctonear(1_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id.clone());
+
+        context.predecessor_account_id(accounts(3));
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+        contract.ft_on_transfer(accounts(0), U128(2_000_000), list_id.clone());
+
+        context.predecessor_account_id(INTENTS_CONTRACT_ID.parse().unwrap());
+        testing_env!(context.build());
+        let _ = contract.mt_on_transfer(
+            accounts(0),
+            vec![accounts(0)],
+            vec![BTC_INTENTS_TOKEN.to_string()],
+            vec![U128(5)],
+            list_id.clone(),
+        );
+    }
+
+    #[test]
+    fn test_mixed_list_id_matches_backend_hash() {
+        let payments = vec![
+            PaymentInput {
+                recipient: "bob.near".parse().unwrap(),
+                amount: U128(1_000_000),
+                token_id: Some("usdt.tether-token.near".to_string()),
+            },
+            PaymentInput {
+                recipient: "alice.near".parse().unwrap(),
+                amount: U128(2_000_000_000_000_000_000_000_000),
+                token_id: Some("native".to_string()),
+            },
+        ];
+
+        let list_id = list_id_for(
+            &"testdao.sputnik-dao.near".parse().unwrap(),
+            MIXED_TOKEN_ID,
+            &payments,
+        );
+
+        assert_eq!(
+            list_id,
+            "9f0696c864b19ecce5244c52690369899f2cdf606849014763c00efc4d88fe1d"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Every payment of a mixed list needs its own token_id")]
+    fn test_mixed_list_requires_payment_tokens() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = BulkPaymentContract::default();
+
+        let payments = vec![PaymentInput {
+            recipient: accounts(1).to_string(),
+            amount: U128(1_000_000),
+            token_id: None,
+        }];
+        let list_id = list_id_for(&accounts(0), MIXED_TOKEN_ID, &payments);
+        contract.submit_list(list_id, MIXED_TOKEN_ID.to_string(), payments, None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Payment token_id is only allowed in mixed lists")]
+    fn test_payment_token_requires_mixed_list() {
+        let context = get_context(accounts(0));
+        testing_env!(context.build());
+        let mut contract = BulkPaymentContract::default();
+
+        let payments = vec![PaymentInput {
+            recipient: accounts(1).to_string(),
+            amount: U128(1_000_000),
+            token_id: Some(accounts(3).to_string()),
+        }];
+        let list_id = list_id_for(&accounts(0), "native", &payments);
+        contract.submit_list(list_id, "native".to_string(), payments, None, None);
+    }
+
+    #[test]
+    fn test_mixed_list_approved_once_every_token_is_funded() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_mixed_list(&mut contract, &mut context);
+
+        // NEAR leg only: still pending
+        context.attached_deposit(NearToken::from_yoctonear(1_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id.clone());
+
+        assert!(matches!(
+            contract.view_list(list_id.clone()).status,
+            ListStatus::Pending
+        ));
+        let funding = contract.view_list_funding(list_id.clone());
+        assert_eq!(funding.len(), 3);
+        assert_eq!(funding[0].token_id, "native");
+        assert!(funding[0].funded);
+        assert!(!funding[1].funded);
+        assert_eq!(funding[1].amount, U128(2_000_000));
+        assert!(!funding[2].funded);
+
+        // FT leg
+        context.predecessor_account_id(accounts(3));
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+        contract.ft_on_transfer(accounts(0), U128(2_000_000), list_id.clone());
+        assert!(matches!(
+            contract.view_list(list_id.clone()).status,
+            ListStatus::Pending
+        ));
+
+        // Intents leg completes the approval
+        context.predecessor_account_id(INTENTS_CONTRACT_ID.parse().unwrap());
+        testing_env!(context.build());
+        let _ = contract.mt_on_transfer(
+            accounts(0),
+            vec![accounts(0)],
+            vec![BTC_INTENTS_TOKEN.to_string()],
+            vec![U128(5)],
+            list_id.clone(),
+        );
+
+        assert!(matches!(
+            contract.view_list(list_id.clone()).status,
+            ListStatus::Approved
+        ));
+        assert!(contract.view_list_funding(list_id).iter().all(|f| f.funded));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token native is already funded for this list")]
+    fn test_mixed_list_rejects_double_funding() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_mixed_list(&mut contract, &mut context);
+
+        context.attached_deposit(NearToken::from_yoctonear(1_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id.clone());
+        contract.approve_list(list_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Exact token amount required: 2000000, received: 3000000")]
+    fn test_mixed_list_ft_leg_must_match_token_total() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_mixed_list(&mut contract, &mut context);
+
+        // The list total across tokens is not the FT total
+        context.predecessor_account_id(accounts(3));
+        testing_env!(context.build());
+        contract.ft_on_transfer(accounts(0), U128(3_000_000), list_id);
+    }
+
+    #[test]
+    fn test_mixed_list_payout() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_mixed_list(&mut contract, &mut context);
+        fund_mixed_list(&mut contract, &mut context, &list_id);
+
+        context.prepaid_gas(Gas::from_tgas(300));
+        testing_env!(context.build());
+        assert_eq!(contract.payout_batch(list_id.clone()), 0);
+
+        let transactions = contract.get_payment_transactions(list_id.clone());
+        assert_eq!(transactions.len(), 3);
+        assert_eq!(
+            transactions[1].token_id.as_deref(),
+            Some(accounts(3).as_str())
+        );
+
+        context.predecessor_account_id(accounts(0));
+        testing_env!(context.build());
+        let receipt = contract.close_list(list_id);
+        assert_eq!(receipt.totals.len(), 3);
+        assert_eq!(receipt.totals[2].token_id, BTC_INTENTS_TOKEN);
+        assert_eq!(receipt.totals[2].amount, U128(5));
+    }
+
+    #[test]
+    fn test_reject_partially_funded_mixed_list() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_mixed_list(&mut contract, &mut context);
+
+        context.attached_deposit(NearToken::from_yoctonear(1_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id.clone());
+
+        // The NEAR deposit is sent back to the submitter
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        testing_env!(context.build());
+        contract.reject_list(list_id.clone());
+
+        let list = contract.view_list(list_id.clone());
+        assert!(matches!(list.status, ListStatus::Rejected));
+        assert!(list.funded_tokens.is_empty());
+        assert!(contract
+            .view_list_funding(list_id)
+            .iter()
+            .all(|f| !f.funded));
+    }
+
+    /// NEP-297 events logged by the last call, as (event, data)
+    fn emitted_events() -> Vec<(String, near_sdk::serde_json::Value)> {
+        near_sdk::test_utils::get_logs()
+            .iter()
+            .filter_map(|log| log.strip_prefix("EVENT_JSON:"))
+            .map(|json| {
+                let event: near_sdk::serde_json::Value =
+                    near_sdk::serde_json::from_str(json).unwrap();
+                assert_eq!(event["standard"], "bulk-payment");
+                assert_eq!(event["version"], "1.0.0");
+                (
+                    event["event"].as_str().unwrap().to_string(),
+                    event["data"].clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_submit_list_emits_event() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+
+        let events = emitted_events();
+        let (event, data) = events.last().unwrap();
+        assert_eq!(event, "list_submitted");
+        assert_eq!(data["list_id"], list_id.as_str());
+        assert_eq!(data["submitter"], accounts(0).as_str());
+        assert_eq!(data["token_id"], "native");
+        assert_eq!(data["payment_count"], 2);
+    }
+
+    #[test]
+    fn test_buy_storage_emits_event() {
+        let mut context = get_context(accounts(0));
+        context.attached_deposit(NearToken::from_yoctonear(23_760_000_000_000_000_000_000));
+        testing_env!(context.build());
+
+        let mut contract = BulkPaymentContract::default();
+        contract.buy_storage(10, Some(accounts(1)));
+
+        let events = emitted_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "storage_purchased");
+        assert_eq!(events[0].1["account_id"], accounts(1).as_str());
+        assert_eq!(events[0].1["num_records"], 10);
+    }
+
+    #[test]
+    fn test_approve_and_reject_emit_events() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+        context.attached_deposit(NearToken::from_yoctonear(3_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id.clone());
+        let (event, data) = emitted_events().pop().unwrap();
+        assert_eq!(event, "list_approved");
+        assert_eq!(data["list_id"], list_id.as_str());
+
+        let list_id = submit_pending_list(&mut contract, &mut context, accounts(3).as_str());
+        contract.reject_list(list_id.clone());
+        let (event, data) = emitted_events().pop().unwrap();
+        assert_eq!(event, "list_rejected");
+        assert_eq!(data["list_id"], list_id.as_str());
+    }
+
+    /// Environment of `on_payment_complete` with the promise result of the transfer
+    fn callback_env(context: &mut VMContextBuilder, transfer_result: PromiseResult) {
+        context.predecessor_account_id(env::current_account_id());
+        testing_env!(
+            context.build(),
+            near_sdk::test_vm_config(),
+            near_sdk::RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![transfer_result],
+        );
+    }
+
+    #[test]
+    fn test_payment_sent_event() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+        approve_and_pay_out(&mut contract, &mut context, &list_id);
+
+        callback_env(&mut context, PromiseResult::Successful(vec![]));
+        contract.on_payment_complete(
+            list_id.clone(),
+            1,
+            accounts(0),
+            accounts(2).to_string(),
+            "native".to_string(),
+            U128(2_000_000),
+        );
+
+        let events = emitted_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "payment_sent");
+        assert_eq!(events[0].1["index"], 1);
+        assert_eq!(events[0].1["recipient"], accounts(2).as_str());
+        assert_eq!(events[0].1["amount"], "2000000");
+        assert!(matches!(
+            contract.view_list(list_id).payments[1].status,
+            PaymentStatus::Paid { .. }
+        ));
+    }
+
+    #[test]
+    fn test_failed_payment_is_refunded() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+        approve_and_pay_out(&mut contract, &mut context, &list_id);
+
+        callback_env(&mut context, PromiseResult::Failed);
+        contract.on_payment_complete(
+            list_id.clone(),
+            0,
+            accounts(0),
+            accounts(1).to_string(),
+            "native".to_string(),
+            U128(1_000_000),
+        );
+
+        let events = emitted_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, "payment_failed");
+        assert_eq!(events[0].1["index"], 0);
+        assert!(matches!(
+            contract.view_list(list_id.clone()).payments[0].status,
+            PaymentStatus::Failed { .. }
+        ));
+        assert_eq!(contract.get_payment_transactions(list_id.clone()).len(), 1);
+
+        // The list can still be closed; the receipt only counts what was paid
+        context.predecessor_account_id(accounts(0));
+        testing_env!(context.build());
+        let receipt = contract.close_list(list_id);
+        assert_eq!(receipt.payment_count, 2);
+        assert_eq!(receipt.totals[0].amount, U128(2_000_000));
+    }
+
+    #[test]
+    fn test_intents_withdrawal_result() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let token_id = "nep141:usdc.near";
+        let list_id = submit_list_to(
+            &mut contract,
+            &mut context,
+            token_id,
+            &[accounts(1).as_str(), accounts(2).as_str()],
+        );
+
+        context.predecessor_account_id(INTENTS_CONTRACT_ID.parse().unwrap());
+        testing_env!(context.build());
+        let _ = contract.mt_on_transfer(
+            accounts(0),
+            vec![accounts(0)],
+            vec![token_id.to_string()],
+            vec![U128(2_000_000)],
+            list_id.clone(),
+        );
+        context.prepaid_gas(Gas::from_tgas(300));
+        testing_env!(context.build());
+        assert_eq!(contract.payout_batch(list_id.clone()), 0);
+
+        // ft_withdraw resolves to the withdrawn amount
+        callback_env(
+            &mut context,
+            PromiseResult::Successful(b"\"1000000\"".to_vec()),
+        );
+        contract.on_payment_complete(
+            list_id.clone(),
+            0,
+            accounts(0),
+            accounts(1).to_string(),
+            token_id.to_string(),
+            U128(1_000_000),
+        );
+        let (event, _) = emitted_events().pop().unwrap();
+        assert_eq!(event, "payment_sent");
+
+        // "0" means the withdrawal was refunded to the contract's intents balance
+        callback_env(&mut context, PromiseResult::Successful(b"\"0\"".to_vec()));
+        contract.on_payment_complete(
+            list_id.clone(),
+            1,
+            accounts(0),
+            accounts(2).to_string(),
+            token_id.to_string(),
+            U128(1_000_000),
+        );
+        let (event, data) = emitted_events().pop().unwrap();
+        assert_eq!(event, "payment_failed");
+        assert_eq!(data["index"], 1);
+
+        let payments = contract.view_list(list_id).payments;
+        assert!(matches!(payments[0].status, PaymentStatus::Paid { .. }));
+        assert!(matches!(payments[1].status, PaymentStatus::Failed { .. }));
+    }
+
+    #[test]
+    fn test_owner_defaults_to_contract_account() {
+        let contract = BulkPaymentContract::default();
+        assert_eq!(contract.get_owner(), env::current_account_id());
+        assert!(contract.get_guardians().is_empty());
+        assert!(!contract.is_paused());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_set_pending_list_expiry_requires_owner() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = BulkPaymentContract::default();
+        contract.set_pending_list_expiry(U64(0));
+    }
+
+    #[test]
+    fn test_set_owner() {
+        let mut contract = BulkPaymentContract::default();
+        testing_env!(get_context(contract.get_owner()).build());
+        contract.set_owner(accounts(3));
+        assert_eq!(contract.get_owner(), accounts(3));
+
+        testing_env!(get_context(accounts(3)).build());
+        contract.add_guardian(accounts(4));
+        assert_eq!(contract.get_guardians(), vec![accounts(4)]);
+        contract.remove_guardian(accounts(4));
+        assert!(contract.get_guardians().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_add_guardian_requires_owner() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = BulkPaymentContract::default();
+        contract.add_guardian(accounts(0));
+    }
+
+    /// Contract paused by its owner
+    fn paused_contract(context: &mut VMContextBuilder) -> BulkPaymentContract {
+        let mut contract = BulkPaymentContract::default();
+        testing_env!(get_context(contract.get_owner()).build());
+        contract.pause();
+
+        testing_env!(context.build());
+        contract
+    }
+
+    #[test]
+    fn test_guardian_pauses_contract() {
+        let mut contract = BulkPaymentContract::default();
+        testing_env!(get_context(contract.get_owner()).build());
+        contract.add_guardian(accounts(4));
+
+        testing_env!(get_context(accounts(4)).build());
+        contract.pause();
+        assert!(contract.is_paused());
+        let events = emitted_events();
+        assert_eq!(events[0].0, "contract_paused");
+        assert_eq!(events[0].1["account_id"], accounts(4).as_str());
+
+        testing_env!(get_context(contract.get_owner()).build());
+        contract.unpause();
+        assert!(!contract.is_paused());
+        assert_eq!(emitted_events()[0].0, "contract_unpaused");
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner or a guardian can pause the contract")]
+    fn test_pause_requires_guardian() {
+        testing_env!(get_context(accounts(0)).build());
+        let mut contract = BulkPaymentContract::default();
+        contract.pause();
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_guardian_cannot_unpause() {
+        let mut context = get_context(accounts(4));
+        let mut contract = paused_contract(&mut context);
+        testing_env!(get_context(contract.get_owner()).build());
+        contract.add_guardian(accounts(4));
+
+        testing_env!(context.build());
+        contract.unpause();
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_submit_list_blocked_while_paused() {
+        let mut context = get_context(accounts(0));
+        let mut contract = paused_contract(&mut context);
+        submit_pending_list(&mut contract, &mut context, "native");
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is paused")]
+    fn test_payout_blocked_while_paused() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let list_id = submit_pending_list(&mut contract, &mut context, "native");
+        context.attached_deposit(NearToken::from_yoctonear(3_000_000));
+        testing_env!(context.build());
+        contract.approve_list(list_id.clone());
+
+        testing_env!(get_context(contract.get_owner()).build());
+        contract.pause();
+
+        context.attached_deposit(NearToken::from_yoctonear(0));
+        context.prepaid_gas(Gas::from_tgas(300));
+        testing_env!(context.build());
+        contract.payout_batch(list_id);
+    }
+
+    #[test]
+    fn test_refunds_allowed_while_paused() {
+        let mut context = get_context(accounts(0));
+        let mut contract = BulkPaymentContract::default();
+        let rejected = submit_pending_list(&mut contract, &mut context, "native");
+        let list_id = submit_pending_list(&mut contract, &mut context, "wrap.near");
+
+        testing_env!(get_context(contract.get_owner()).build());
+        contract.pause();
+
+        testing_env!(get_context(accounts(0)).build());
+        contract.reject_list(rejected.clone());
+        assert!(matches!(
+            contract.view_list(rejected).status,
+            ListStatus::Rejected
+        ));
+        contract.cancel_list(list_id.clone());
+        assert!(contract.payment_lists.get(&list_id).is_none());
+    }
+
+    /// State as stored by the initial deployment: no version key, lists under b"p"
+    fn write_v1_state(list_ids: &[String]) {
+        let mut old = ContractV1 {
+            payment_lists: IterableMap::new(b"p"),
+            storage_credits: IterableMap::new(b"s"),
+        };
+        for list_id in list_ids {
+            old.payment_lists.insert(
+                list_id.clone(),
+                PaymentListV1 {
+                    token_id: "native".to_string(),
+                    submitter: accounts(0),
+                    status: ListStatus::Approved,
+                    payments: vec![
+                        PaymentRecordV1 {
+                            recipient: accounts(1),
+                            amount: U128(1_000_000),
+                            status: PaymentStatus::Paid { block_height: 7 },
+                        },
+                        PaymentRecordV1 {
+                            recipient: accounts(2),
+                            amount: U128(2_000_000),
+                            status: PaymentStatus::Pending,
+                        },
+                    ],
+                    created_at: 1,
+                },
+            );
+        }
+        old.storage_credits
+            .insert(accounts(0), NearToken::from_yoctonear(8));
+        old.payment_lists.flush();
+        old.storage_credits.flush();
+        env::state_write(&old);
+    }
+
+    #[test]
+    fn test_migrate_from_v1() {
+        let mut context = get_context(env::current_account_id());
+        testing_env!(context.build());
+        let list_id = "a".repeat(64);
+        write_v1_state(std::slice::from_ref(&list_id));
+
+        let mut contract = BulkPaymentContract::migrate();
+        // The lists are left in place until they are moved
+        assert!(contract.payment_lists.is_empty());
+        assert_eq!(contract.legacy_lists.len(), 1);
+
+        assert_eq!(contract.get_owner(), env::current_account_id());
+        assert!(!contract.is_paused());
+        assert_eq!(
+            contract.get_pending_list_expiry(),
+            U64(DEFAULT_PENDING_LIST_EXPIRY_NS)
+        );
+        assert_eq!(contract.view_storage_credits(accounts(0)).as_yoctonear(), 8);
+
+        let list = contract.view_list(list_id.clone());
+        assert!(matches!(list.status, ListStatus::Approved));
+        assert_eq!(list.payments.len(), 2);
+        assert!(list.payments.iter().all(|p| p.token_id.is_none()));
+        assert_eq!(contract.get_payment_transactions(list_id.clone()).len(), 1);
+
+        // The list moves when first updated and pays out like any other
+        context.predecessor_account_id(accounts(0));
+        context.prepaid_gas(Gas::from_tgas(300));
+        testing_env!(context.build());
+        assert_eq!(contract.payout_batch(list_id), 0);
+        assert!(contract.legacy_lists.is_empty());
+        assert_eq!(contract.payment_lists.len(), 1);
+    }
+
+    #[test]
+    fn test_migrate_lists_in_batches() {
+        testing_env!(get_context(env::current_account_id()).build());
+        let list_ids: Vec<String> = ["a", "b", "c"].iter().map(|c| c.repeat(64)).collect();
+        write_v1_state(&list_ids);
+
+        let mut contract = BulkPaymentContract::migrate();
+        assert_eq!(contract.migrate_lists(2), 1);
+        assert_eq!(contract.payment_lists.len(), 2);
+        assert_eq!(contract.migrate_lists(2), 0);
+        assert_eq!(contract.payment_lists.len(), 3);
+        assert_eq!(contract.migrate_lists(2), 0);
+        assert!(contract.legacy_lists.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the owner can call this method")]
+    fn test_migrate_lists_owner_only() {
+        testing_env!(get_context(env::current_account_id()).build());
+        write_v1_state(&["a".repeat(64)]);
+        let mut contract = BulkPaymentContract::migrate();
+
+        testing_env!(get_context(accounts(1)).build());
+        contract.migrate_lists(10);
+    }
+
+    #[test]
+    fn test_migrate_current_state_is_noop() {
+        testing_env!(get_context(env::current_account_id()).build());
+        let mut contract = BulkPaymentContract::new();
+        contract.add_guardian(accounts(4));
+        env::state_write(&contract);
+
+        let contract = BulkPaymentContract::migrate();
+        assert_eq!(contract.get_guardians(), vec![accounts(4)]);
+    }
+
     // Note: Overflow protection tests are implicitly validated by the NEAR runtime environment.
     // The environment checks account balances and prevents unrealistic values before our
     // contract code executes, providing an additional layer of security. Our checked_*
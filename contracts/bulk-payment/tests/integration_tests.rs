@@ -4,11 +4,30 @@
 use base64::Engine;
 use near_sdk::{serde_json::json, AccountId, NearToken};
 
-/// Generate a valid list_id (64-character hex string) for testing
-/// Uses a simple deterministic approach: pads the suffix with 'a' characters
-fn test_list_id(suffix: &str) -> String {
-    let hex_suffix: String = suffix.bytes().map(|b| format!("{:02x}", b)).collect();
-    format!("{:a>64}", hex_suffix)[..64].to_string()
+/// Compute the list_id the contract expects for a payment list
+/// SHA-256 of the canonical JSON {"payments":[{"amount","recipient"}],"submitter","token_id"}
+/// with payments sorted by recipient (same scheme as the treasury backend)
+fn compute_list_id(
+    submitter: &AccountId,
+    token_id: &str,
+    payments: &[serde_json::Value],
+) -> String {
+    let mut sorted: Vec<serde_json::Value> = payments
+        .iter()
+        .map(|p| json!({ "amount": p["amount"], "recipient": p["recipient"] }))
+        .collect();
+    sorted.sort_by(|a, b| a["recipient"].as_str().cmp(&b["recipient"].as_str()));
+
+    let canonical = json!({
+        "payments": sorted,
+        "submitter": submitter,
+        "token_id": token_id,
+    });
+
+    near_sdk::env::sha256(canonical.to_string().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
 }
 
 fn get_genesis_signer() -> std::sync::Arc<near_api::Signer> {
@@ -91,8 +110,8 @@ async fn import_contract(
     let account_signer = get_genesis_signer();
 
     // Deploy the contract code to the sandbox account (which should already exist)
-    // For wrap.near, we need to initialize it since it's a fresh deployment
-    if mainnet_account_id == "wrap.near" {
+    // wrap.near and bulkpayment.near need to be initialized since it's a fresh deployment
+    if matches!(mainnet_account_id, "wrap.near" | "bulkpayment.near") {
         near_api::Contract::deploy(account_id.clone())
             .use_code(contract_code)
             .with_init_call("new", json!({}))
@@ -285,7 +304,7 @@ async fn test_submit_and_approve_list() -> Result<(), Box<dyn std::error::Error>
         }),
     ];
 
-    let list_id = test_list_id("submit_approve_test");
+    let list_id = compute_list_id(&user_id, "native", &payments);
     let submit_result = near_api::Contract(contract_id.clone())
         .call_function(
             "submit_list",
@@ -410,7 +429,7 @@ async fn test_batch_processing() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Submit large payment list
-    let list_id = test_list_id("batch_processing_test");
+    let list_id = compute_list_id(&user_id, "native", &payments);
     let submit_result = near_api::Contract(contract_id.clone())
         .call_function(
             "submit_list",
@@ -723,7 +742,7 @@ async fn test_fungible_token_payment() -> Result<(), Box<dyn std::error::Error>>
         }));
     }
 
-    let list_id = test_list_id("ft_payment_test");
+    let list_id = compute_list_id(&user_id, wrap_near_id.as_str(), &payments);
     let submit_result = near_api::Contract(contract_id.clone())
         .call_function(
             "submit_list",
@@ -992,7 +1011,7 @@ async fn test_reject_pending_list() -> Result<(), Box<dyn std::error::Error>> {
         "amount": "1000000000000000000000000"
     })];
 
-    let list_id = test_list_id("reject_pending_test");
+    let list_id = compute_list_id(&user_id, "native", &payments);
     near_api::Contract(contract_id.clone())
         .call_function(
             "submit_list",
@@ -1184,7 +1203,7 @@ async fn test_unauthorized_operations() -> Result<(), Box<dyn std::error::Error>
         "amount": "1000000000000000000000000"
     })];
 
-    let list_id = test_list_id("unauthorized_ops_test");
+    let list_id = compute_list_id(&user_id, "native", &payments);
     let submit_result = near_api::Contract(contract_id.clone())
         .call_function(
             "submit_list",
@@ -1658,7 +1677,7 @@ async fn test_bulk_btc_intents_payment() -> Result<(), Box<dyn std::error::Error
     // Token ID format for intents: full multi-token ID "nep141:btc.omft.near"
     let token_id = "nep141:btc.omft.near".to_string();
 
-    let list_id = test_list_id("btc_intents_payment_test");
+    let list_id = compute_list_id(&submitter_id, &token_id, &payments);
     let submit_result = near_api::Contract(contract_id.clone())
         .call_function(
             "submit_list",
@@ -2017,3 +2036,223 @@ async fn test_bulk_btc_intents_payment() -> Result<(), Box<dyn std::error::Error
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_upgrade_populated_contract() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = near_sandbox::Sandbox::start_sandbox().await?;
+    let network_config = near_api::NetworkConfig {
+        network_name: "sandbox".to_string(),
+        rpc_endpoints: vec![near_api::RPCEndpoint::new(
+            sandbox.rpc_addr.parse().unwrap(),
+        )],
+        linkdrop_account_id: None,
+        ..near_api::NetworkConfig::testnet()
+    };
+
+    // Deploy the code currently running on mainnet (initial state layout)
+    let contract_id: AccountId = format!(
+        "bulk-payment.{}",
+        near_sandbox::config::DEFAULT_GENESIS_ACCOUNT
+    )
+    .parse()
+    .unwrap();
+    let contract_signer =
+        create_account(&contract_id, NearToken::from_near(50), &network_config).await;
+    import_contract(&sandbox, &network_config, &contract_id, "bulkpayment.near").await?;
+
+    let user_id: AccountId = format!("user.{}", near_sandbox::config::DEFAULT_GENESIS_ACCOUNT)
+        .parse()
+        .unwrap();
+    let user_signer = create_account(&user_id, NearToken::from_near(50), &network_config).await;
+    let recipient: AccountId = format!(
+        "recipient.{}",
+        near_sandbox::config::DEFAULT_GENESIS_ACCOUNT
+    )
+    .parse()
+    .unwrap();
+    create_account(&recipient, NearToken::from_near(1), &network_config).await;
+
+    // Populate it: storage credits, an approved list and a pending list
+    near_api::Contract(contract_id.clone())
+        .call_function("buy_storage", json!({ "num_records": 5 }))?
+        .transaction()
+        .deposit(NearToken::from_yoctonear(11_880_000_000_000_000_000_000))
+        .with_signer(user_id.clone(), user_signer.clone())
+        .send_to(&network_config)
+        .await?
+        .assert_success();
+
+    let approved_payments = vec![json!({
+        "recipient": recipient.to_string(),
+        "amount": "1000000000000000000000000"
+    })];
+    let pending_payments = vec![json!({
+        "recipient": recipient.to_string(),
+        "amount": "2000000000000000000000000"
+    })];
+    let approved_id = compute_list_id(&user_id, "native", &approved_payments);
+    let pending_id = compute_list_id(&user_id, "native", &pending_payments);
+
+    for (list_id, payments) in [
+        (&approved_id, &approved_payments),
+        (&pending_id, &pending_payments),
+    ] {
+        near_api::Contract(contract_id.clone())
+            .call_function(
+                "submit_list",
+                json!({
+                    "list_id": list_id,
+                    "token_id": "native",
+                    "payments": payments
+                }),
+            )?
+            .transaction()
+            .with_signer(user_id.clone(), user_signer.clone())
+            .send_to(&network_config)
+            .await?
+            .assert_success();
+    }
+
+    near_api::Contract(contract_id.clone())
+        .call_function("approve_list", json!({ "list_id": approved_id }))?
+        .transaction()
+        .deposit(NearToken::from_near(1))
+        .with_signer(user_id.clone(), user_signer.clone())
+        .send_to(&network_config)
+        .await?
+        .assert_success();
+
+    // Upgrade to the current code and migrate the state
+    let contract_wasm_path = cargo_near_build::build_with_cli(Default::default())?;
+    near_api::Contract::deploy(contract_id.clone())
+        .use_code(std::fs::read(contract_wasm_path).unwrap())
+        .with_init_call("migrate", ())
+        .unwrap()
+        .with_signer(contract_signer.clone())
+        .send_to(&network_config)
+        .await?
+        .assert_success();
+
+    let owner: AccountId = near_api::Contract(contract_id.clone())
+        .call_function("get_owner", json!({}))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?
+        .data;
+    assert_eq!(owner, contract_id);
+
+    let credits: NearToken = near_api::Contract(contract_id.clone())
+        .call_function("view_storage_credits", json!({ "account_id": user_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?
+        .data;
+    assert_eq!(
+        credits.as_yoctonear(),
+        3,
+        "Storage credits should survive the upgrade"
+    );
+
+    let approved: serde_json::Value = near_api::Contract(contract_id.clone())
+        .call_function("view_list", json!({ "list_id": approved_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?
+        .data;
+    assert_eq!(approved["status"], "Approved");
+    assert_eq!(approved["payments"][0]["status"], "Pending");
+    assert_eq!(approved["payments"][0]["token_id"], serde_json::Value::Null);
+
+    let pending: serde_json::Value = near_api::Contract(contract_id.clone())
+        .call_function("view_list", json!({ "list_id": pending_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?
+        .data;
+    assert_eq!(pending["status"], "Pending");
+
+    // While paused the migrated list can't be paid out, but the pending one can be rejected
+    near_api::Contract(contract_id.clone())
+        .call_function("pause", json!({}))?
+        .transaction()
+        .with_signer(contract_id.clone(), contract_signer.clone())
+        .send_to(&network_config)
+        .await?
+        .assert_success();
+
+    let result = near_api::Contract(contract_id.clone())
+        .call_function("payout_batch", json!({ "list_id": approved_id }))?
+        .transaction()
+        .gas(near_sdk::Gas::from_tgas(300))
+        .with_signer(user_id.clone(), user_signer.clone())
+        .send_to(&network_config)
+        .await;
+    assert!(
+        result.is_err() || !result.unwrap().is_success(),
+        "payout_batch should fail while paused"
+    );
+
+    near_api::Contract(contract_id.clone())
+        .call_function("reject_list", json!({ "list_id": pending_id }))?
+        .transaction()
+        .with_signer(user_id.clone(), user_signer.clone())
+        .send_to(&network_config)
+        .await?
+        .assert_success();
+
+    near_api::Contract(contract_id.clone())
+        .call_function("unpause", json!({}))?
+        .transaction()
+        .with_signer(contract_id.clone(), contract_signer.clone())
+        .send_to(&network_config)
+        .await?
+        .assert_success();
+
+    // The rejected list moved when it was updated; the owner moves the rest
+    let remaining: u32 = near_api::Contract(contract_id.clone())
+        .call_function("migrate_lists", json!({ "limit": 10 }))?
+        .transaction()
+        .with_signer(contract_id.clone(), contract_signer.clone())
+        .send_to(&network_config)
+        .await?
+        .json()?;
+    assert_eq!(remaining, 0, "Every list should be migrated");
+
+    // The migrated list pays out with the new code
+    let recipient_before = near_api::Account(recipient.clone())
+        .view()
+        .fetch_from(&network_config)
+        .await?
+        .data
+        .amount;
+
+    near_api::Contract(contract_id.clone())
+        .call_function("payout_batch", json!({ "list_id": approved_id }))?
+        .transaction()
+        .gas(near_sdk::Gas::from_tgas(300))
+        .with_signer(user_id.clone(), user_signer.clone())
+        .send_to(&network_config)
+        .await?
+        .assert_success();
+
+    let recipient_after = near_api::Account(recipient.clone())
+        .view()
+        .fetch_from(&network_config)
+        .await?
+        .data
+        .amount;
+    assert_eq!(
+        recipient_after.as_yoctonear() - recipient_before.as_yoctonear(),
+        NearToken::from_near(1).as_yoctonear()
+    );
+
+    let approved: serde_json::Value = near_api::Contract(contract_id.clone())
+        .call_function("view_list", json!({ "list_id": approved_id }))?
+        .read_only()
+        .fetch_from(&network_config)
+        .await?
+        .data;
+    assert!(approved["payments"][0]["status"]["Paid"].is_object());
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+-- Recurring and scheduled payments
+-- A schedule describes a payment (one recipient) or bulk payment (several recipients)
+-- that the backend proposes to the DAO on a fixed cadence. Every generated proposal
+-- is recorded in payment_schedule_runs so the history links back to its schedule.
+
+CREATE TABLE payment_schedules (
+    id BIGSERIAL PRIMARY KEY,
+
+    -- Treasury the proposals are created in
+    dao_id VARCHAR(128) NOT NULL,
+    -- Account that created the schedule
+    created_by VARCHAR(128) NOT NULL,
+
+    title TEXT NOT NULL,
+    notes TEXT,
+
+    -- "native" for NEAR, a NEP-141 contract ID, or "nep141:<contract>" for intents tokens
+    token_id VARCHAR(256) NOT NULL,
+    -- JSON array of {"recipient": string, "amount": string} (amounts in smallest units)
+    payments JSONB NOT NULL,
+
+    -- once | daily | weekly | biweekly | monthly | quarterly | yearly
+    cadence VARCHAR(16) NOT NULL,
+    start_at TIMESTAMPTZ NOT NULL,
+    end_at TIMESTAMPTZ,
+    next_run_at TIMESTAMPTZ,
+
+    is_active BOOLEAN NOT NULL DEFAULT true,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT payment_schedules_cadence_check CHECK (
+        cadence IN ('once', 'daily', 'weekly', 'biweekly', 'monthly', 'quarterly', 'yearly')
+    )
+);
+
+CREATE INDEX idx_payment_schedules_dao ON payment_schedules(dao_id);
+CREATE INDEX idx_payment_schedules_due ON payment_schedules(next_run_at) WHERE is_active = true;
+
+CREATE TABLE payment_schedule_runs (
+    id BIGSERIAL PRIMARY KEY,
+    schedule_id BIGINT NOT NULL REFERENCES payment_schedules(id) ON DELETE CASCADE,
+
+    -- The occurrence this run was generated for
+    scheduled_for TIMESTAMPTZ NOT NULL,
+
+    -- pending | created | failed
+    status VARCHAR(16) NOT NULL,
+    proposal_id BIGINT,
+    -- Bulk payment list ID (only for schedules with several recipients)
+    list_id VARCHAR(64),
+    error TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_schedule_occurrence UNIQUE(schedule_id, scheduled_for)
+);
+
+CREATE INDEX idx_payment_schedule_runs_schedule ON payment_schedule_runs(schedule_id, scheduled_for DESC);
+
+COMMENT ON TABLE payment_schedules IS 'Recurring payments that are proposed to the DAO automatically when due';
+COMMENT ON COLUMN payment_schedules.next_run_at IS 'Next occurrence to propose, NULL once the schedule has finished';
+COMMENT ON TABLE payment_schedule_runs IS 'History of proposals generated from payment schedules';
@@ -0,0 +1,40 @@
+-- Fiat-denominated bulk payment lists
+-- A quote locks the token price used to convert fiat amounts into token base units,
+-- so the conversion can be shown and audited after the list is paid.
+
+CREATE TABLE bulk_payment_fiat_quotes (
+    id BIGSERIAL PRIMARY KEY,
+
+    -- Treasury the list will be paid from
+    dao_id VARCHAR(128) NOT NULL,
+    created_by VARCHAR(128) NOT NULL,
+
+    -- Token ID as used by the bulk payment contract ("native", FT contract or "nep141:...")
+    token_id VARCHAR(256) NOT NULL,
+    token_symbol VARCHAR(64) NOT NULL,
+    token_decimals SMALLINT NOT NULL,
+
+    -- Fiat currency of the entered amounts (ISO 4217)
+    currency VARCHAR(8) NOT NULL,
+    -- Price of one whole token in the fiat currency
+    rate NUMERIC(38, 18) NOT NULL,
+    -- Date of the historical price the rate was taken from
+    price_date DATE NOT NULL,
+    -- The locked quote time
+    quoted_at TIMESTAMPTZ NOT NULL,
+
+    -- JSON array of {"recipient", "fiatAmount", "amount"} (amount in token base units)
+    payments JSONB NOT NULL,
+    total_fiat NUMERIC(38, 2) NOT NULL,
+    total_amount NUMERIC(78, 0) NOT NULL,
+
+    -- Set once the list has been submitted to the bulk payment contract
+    list_id VARCHAR(64) UNIQUE,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_bulk_payment_fiat_quotes_dao ON bulk_payment_fiat_quotes(dao_id, created_at DESC);
+
+COMMENT ON TABLE bulk_payment_fiat_quotes IS 'Locked fiat-to-token conversions for bulk payment lists';
+COMMENT ON COLUMN bulk_payment_fiat_quotes.rate IS 'Fiat price of one whole token at price_date';
@@ -0,0 +1,26 @@
+-- NEP-297 events of the bulk payment contract
+-- Recorded from the outcomes of the payout transactions the worker sends, so each
+-- payment can be linked to its transaction without scanning blocks.
+
+CREATE TABLE bulk_payment_events (
+    id BIGSERIAL PRIMARY KEY,
+
+    list_id VARCHAR(64) NOT NULL,
+    -- Event name ("list_submitted", "payment_sent", "payment_failed", ...)
+    event VARCHAR(64) NOT NULL,
+    -- Index of the payment within the list (payment events only)
+    payment_index INTEGER,
+    recipient VARCHAR(128),
+
+    -- Full event data as emitted by the contract
+    data JSONB NOT NULL,
+    transaction_hash VARCHAR(64) NOT NULL,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_bulk_payment_events_list ON bulk_payment_events(list_id, payment_index);
+CREATE UNIQUE INDEX idx_bulk_payment_events_unique
+    ON bulk_payment_events(transaction_hash, list_id, event, COALESCE(payment_index, -1));
+
+COMMENT ON TABLE bulk_payment_events IS 'NEP-297 events emitted by the bulk payment contract';
@@ -0,0 +1,39 @@
+-- Payout runs: one logical bulk payment of any number of recipients
+-- The backend splits a run into contract lists of at most 25 payments ("chunks").
+-- Each chunk is linked to its DAO proposal once the list is submitted.
+
+CREATE TABLE bulk_payment_runs (
+    id BIGSERIAL PRIMARY KEY,
+
+    -- Treasury the run is paid from
+    dao_id VARCHAR(128) NOT NULL,
+    created_by VARCHAR(128) NOT NULL,
+    -- Token ID as used by the bulk payment contract ("native", FT contract, "nep141:..." or "mixed")
+    token_id VARCHAR(256) NOT NULL,
+    description TEXT,
+
+    payment_count INTEGER NOT NULL,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_bulk_payment_runs_dao ON bulk_payment_runs(dao_id, created_at DESC);
+
+CREATE TABLE bulk_payment_run_lists (
+    run_id BIGINT NOT NULL REFERENCES bulk_payment_runs(id) ON DELETE CASCADE,
+    -- Position of the chunk within the run, from 0
+    chunk_index INTEGER NOT NULL,
+
+    list_id VARCHAR(64) NOT NULL UNIQUE,
+    -- JSON array of {"recipient", "amount", "tokenId"?} as submitted to submit-list
+    payments JSONB NOT NULL,
+
+    -- Set by submit-list once the list is on chain
+    proposal_id BIGINT,
+    submitted_at TIMESTAMPTZ,
+
+    PRIMARY KEY (run_id, chunk_index)
+);
+
+COMMENT ON TABLE bulk_payment_runs IS 'Bulk payments split into several contract lists';
+COMMENT ON TABLE bulk_payment_run_lists IS 'Contract lists (chunks) of a bulk payment run';
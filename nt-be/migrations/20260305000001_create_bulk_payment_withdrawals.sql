@@ -0,0 +1,33 @@
+-- Bridge withdrawals of bulk payments in PoA tokens ("nep141:<..>.omft.near")
+-- Such payments are withdrawn from intents.near to an address on the token's origin chain.
+-- The payout worker records one row per sent payment and polls the bridge until the
+-- withdrawal completes or fails on the destination chain.
+
+CREATE TABLE bulk_payment_withdrawals (
+    list_id VARCHAR(64) NOT NULL,
+    -- Index of the payment within the list
+    payment_index INTEGER NOT NULL,
+    token_id VARCHAR(128) NOT NULL,
+    -- Origin chain of the token (intents chain name, e.g. "eth", "solana", "bitcoin")
+    chain VARCHAR(32) NOT NULL,
+    address VARCHAR(128) NOT NULL,
+    amount VARCHAR(64) NOT NULL,
+
+    -- Payout transaction on NEAR that started the withdrawal
+    transaction_hash VARCHAR(64) NOT NULL,
+    -- Bridge status: PENDING, COMPLETED or FAILED
+    status VARCHAR(16) NOT NULL DEFAULT 'PENDING',
+    -- Transfer transaction on the destination chain, once the bridge reports it
+    destination_tx_hash VARCHAR(128),
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (list_id, payment_index)
+);
+
+CREATE INDEX idx_bulk_payment_withdrawals_pending
+    ON bulk_payment_withdrawals(updated_at)
+    WHERE status = 'PENDING';
+
+COMMENT ON TABLE bulk_payment_withdrawals IS 'Bridge withdrawals of bulk payments to other chains';
@@ -0,0 +1,27 @@
+-- Traced executions of DAO proposals
+-- The finalizing act_proposal receipt of a proposal is found on the archival RPC and the
+-- receipt tree below it walked for transfers. A finalized proposal never changes, so each
+-- trace is stored once and served from here afterwards.
+
+CREATE TABLE proposal_executions (
+    dao_id VARCHAR(128) NOT NULL,
+    proposal_id BIGINT NOT NULL,
+    -- Finalizing action: VoteApprove, VoteReject, VoteRemove or Finalize
+    action VARCHAR(32) NOT NULL,
+
+    transaction_hash VARCHAR(64) NOT NULL,
+    signer_id VARCHAR(128) NOT NULL,
+    -- The act_proposal receipt
+    receipt_id VARCHAR(64) NOT NULL,
+    block_height BIGINT NOT NULL,
+    -- Block timestamp in nanoseconds
+    block_timestamp BIGINT NOT NULL,
+    -- Transfers made by the execution (NEAR, NEP-141 and NEP-245)
+    transfers JSONB NOT NULL,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (dao_id, proposal_id)
+);
+
+COMMENT ON TABLE proposal_executions IS 'Execution transactions and transfers of finalized proposals';
@@ -0,0 +1,38 @@
+-- Intents token and chain registry
+-- The bundled data/tokens.json is extended at runtime: tokens are synced from the 1Click API,
+-- operators edit overrides and chains here, and the registry is rebuilt on every sync.
+
+-- Tokens as last listed by the 1Click tokens endpoint
+CREATE TABLE intents_tokens (
+    defuse_asset_id VARCHAR(255) PRIMARY KEY,
+    symbol VARCHAR(64) NOT NULL,
+    decimals SMALLINT NOT NULL,
+    chain_name VARCHAR(64) NOT NULL,
+    contract_address VARCHAR(255),
+    synced_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Admin overrides of token metadata; NULL keeps the synced or bundled value
+CREATE TABLE intents_token_overrides (
+    defuse_asset_id VARCHAR(255) PRIMARY KEY,
+    symbol VARCHAR(64),
+    name VARCHAR(255),
+    icon TEXT,
+    -- Moves the token to another unified token (created if missing)
+    unified_asset_id VARCHAR(255),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Chains added to or replacing the bundled chain metadata
+CREATE TABLE intents_chains (
+    -- Lowercase intents chain name
+    chain_name VARCHAR(64) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    icon_dark TEXT NOT NULL,
+    icon_light TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE intents_tokens IS 'Intents tokens synced from the 1Click API';
+COMMENT ON TABLE intents_token_overrides IS 'Admin overrides applied to the intents token registry';
+COMMENT ON TABLE intents_chains IS 'Intents chain metadata added at runtime';
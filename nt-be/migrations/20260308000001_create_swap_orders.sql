@@ -0,0 +1,48 @@
+-- Swap orders of asset-exchange proposals
+-- Every live 1Click quote is recorded; the swap order worker links it to the exchange proposal
+-- that uses its deposit address, follows the swap through the 1Click status states and links
+-- the settled swap to its detected_swaps row. Quotes no proposal picks up within an hour are
+-- removed.
+
+CREATE TABLE swap_orders (
+    id BIGSERIAL PRIMARY KEY,
+    -- Treasury the quote was requested for (its refund address)
+    dao_id VARCHAR(128) NOT NULL,
+    -- Exchange proposal the quote was accepted into, NULL until the proposal is found
+    proposal_id BIGINT,
+
+    deposit_address VARCHAR(255) NOT NULL,
+    deposit_memo VARCHAR(255),
+    origin_asset VARCHAR(255) NOT NULL,
+    destination_asset VARCHAR(255) NOT NULL,
+    -- Amounts in base units
+    amount_in VARCHAR(78) NOT NULL,
+    amount_out VARCHAR(78) NOT NULL,
+    min_amount_out VARCHAR(78),
+    slippage_bps INTEGER,
+    deadline TIMESTAMPTZ NOT NULL,
+    -- Full 1Click quote response
+    quote JSONB NOT NULL,
+
+    -- 1Click swap status (PENDING_DEPOSIT, PROCESSING, SUCCESS, REFUNDED, FAILED, ...)
+    status VARCHAR(32) NOT NULL DEFAULT 'PENDING_DEPOSIT',
+    amount_out_settled VARCHAR(78),
+    refunded_amount VARCHAR(78),
+    refund_reason TEXT,
+    near_tx_hashes TEXT[] NOT NULL DEFAULT '{}',
+    detected_swap_id BIGINT REFERENCES detected_swaps(id),
+    -- Set when the deadline passed without a deposit
+    expired_at TIMESTAMPTZ,
+    settled_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_swap_order_deposit UNIQUE NULLS NOT DISTINCT (deposit_address, deposit_memo)
+);
+
+CREATE UNIQUE INDEX idx_swap_orders_proposal ON swap_orders(dao_id, proposal_id)
+    WHERE proposal_id IS NOT NULL;
+CREATE INDEX idx_swap_orders_unlinked ON swap_orders(dao_id) WHERE proposal_id IS NULL;
+
+COMMENT ON TABLE swap_orders IS 'Accepted 1Click quotes of exchange proposals and their swap status';
@@ -0,0 +1,84 @@
+-- Conditional exchange orders
+-- A limit order proposes a single exchange once the USD price of the sold token crosses its
+-- trigger price. A TWAP order splits the amount into equal slices proposed at even intervals
+-- between start_at and end_at. Every slice gets a fresh 1Click quote and its own
+-- asset-exchange proposal, recorded in exchange_order_slices.
+
+CREATE TABLE exchange_orders (
+    id BIGSERIAL PRIMARY KEY,
+
+    -- Treasury the proposals are created in
+    dao_id VARCHAR(128) NOT NULL,
+    -- Account that created the order
+    created_by VARCHAR(128) NOT NULL,
+
+    -- limit | twap
+    order_type VARCHAR(8) NOT NULL,
+    notes TEXT,
+
+    -- "near", a NEP-141 contract ID, or an intents asset ID ("nep141:..." / "nep245:...")
+    sell_token_id VARCHAR(255) NOT NULL,
+    receive_token_id VARCHAR(255) NOT NULL,
+    -- Total amount to sell in base units
+    amount VARCHAR(78) NOT NULL,
+    slippage_bps INTEGER NOT NULL,
+
+    -- USD price of the sell token that triggers a limit order
+    trigger_price_usd DOUBLE PRECISION,
+    -- above | below
+    trigger_direction VARCHAR(8),
+
+    slice_count INTEGER NOT NULL DEFAULT 1,
+    slices_created INTEGER NOT NULL DEFAULT 0,
+    start_at TIMESTAMPTZ NOT NULL,
+    -- TWAP: end of the slicing window; limit: expiry of the order
+    end_at TIMESTAMPTZ,
+    next_run_at TIMESTAMPTZ,
+
+    -- active | paused | completed | cancelled | expired
+    status VARCHAR(16) NOT NULL DEFAULT 'active',
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT exchange_orders_type_check CHECK (order_type IN ('limit', 'twap')),
+    CONSTRAINT exchange_orders_direction_check CHECK (
+        trigger_direction IS NULL OR trigger_direction IN ('above', 'below')
+    ),
+    CONSTRAINT exchange_orders_status_check CHECK (
+        status IN ('active', 'paused', 'completed', 'cancelled', 'expired')
+    )
+);
+
+CREATE INDEX idx_exchange_orders_dao ON exchange_orders(dao_id);
+CREATE INDEX idx_exchange_orders_due ON exchange_orders(next_run_at) WHERE status = 'active';
+
+CREATE TABLE exchange_order_slices (
+    id BIGSERIAL PRIMARY KEY,
+    order_id BIGINT NOT NULL REFERENCES exchange_orders(id) ON DELETE CASCADE,
+
+    -- Zero-based position of the slice within its order
+    slice_index INTEGER NOT NULL,
+    scheduled_for TIMESTAMPTZ NOT NULL,
+    -- Amount sold by this slice in base units
+    amount_in VARCHAR(78) NOT NULL,
+
+    -- pending | created | failed
+    status VARCHAR(16) NOT NULL,
+    -- Sell token price that triggered the slice (limit orders)
+    price_usd DOUBLE PRECISION,
+    -- 1Click deposit address of the quote, links the slice to its swap_orders row
+    deposit_address VARCHAR(255),
+    amount_out VARCHAR(78),
+    proposal_id BIGINT,
+    error TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_exchange_order_slice UNIQUE(order_id, slice_index)
+);
+
+COMMENT ON TABLE exchange_orders IS 'Limit and TWAP exchange orders that are proposed to the DAO automatically';
+COMMENT ON COLUMN exchange_orders.next_run_at IS 'When the next slice is due, NULL once the order has finished';
+COMMENT ON TABLE exchange_order_slices IS 'Exchange proposals generated from exchange orders';
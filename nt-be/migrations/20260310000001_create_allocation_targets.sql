@@ -0,0 +1,16 @@
+-- Target allocations of treasuries
+-- Each treasury stores a list of allocation buckets (e.g. stablecoins, NEAR, staked NEAR) with
+-- a target share and tolerance band. The rebalancing planner compares current holdings to
+-- these targets and drafts the exchange and staking proposals that bring them back on target.
+
+CREATE TABLE treasury_allocation_targets (
+    dao_id VARCHAR(128) PRIMARY KEY,
+    -- JSON array of allocation buckets, see handlers::rebalancing::targets::AllocationTarget
+    targets JSONB NOT NULL,
+    -- Account that last changed the targets
+    updated_by VARCHAR(128) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE treasury_allocation_targets IS 'Target asset allocations used by the rebalancing planner';
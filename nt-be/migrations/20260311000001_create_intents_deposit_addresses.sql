@@ -0,0 +1,34 @@
+-- Labeled intents deposit addresses
+-- Treasury members issue bridge deposit addresses with a purpose label (e.g. "Grant from
+-- Foundation"). Bridge deposit addresses are deterministic per account and chain, so several
+-- labels can share an address; an incoming deposit is attributed to the label of its chain that
+-- was issued most recently before the deposit landed.
+
+CREATE TABLE intents_deposit_addresses (
+    id BIGSERIAL PRIMARY KEY,
+
+    dao_id VARCHAR(128) NOT NULL,
+    -- Bridge chain ID, e.g. "eth:1" or "btc:mainnet"
+    chain VARCHAR(64) NOT NULL,
+    address VARCHAR(255) NOT NULL,
+    label VARCHAR(128) NOT NULL,
+
+    -- Account that last issued the address under this label
+    issued_by VARCHAR(128) NOT NULL,
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_intents_deposit_label UNIQUE(dao_id, chain, label)
+);
+
+CREATE INDEX idx_intents_deposit_addresses_dao ON intents_deposit_addresses(dao_id, chain, issued_at);
+
+CREATE TABLE deposit_attributions (
+    balance_change_id BIGINT PRIMARY KEY REFERENCES balance_changes(id) ON DELETE CASCADE,
+    deposit_address_id BIGINT NOT NULL REFERENCES intents_deposit_addresses(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_deposit_attributions_address ON deposit_attributions(deposit_address_id);
+
+COMMENT ON TABLE intents_deposit_addresses IS 'Bridge deposit addresses issued to treasuries with a purpose label';
+COMMENT ON TABLE deposit_attributions IS 'Incoming intents deposits matched to the labeled address they were sent to';
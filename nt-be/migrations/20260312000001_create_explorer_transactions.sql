@@ -0,0 +1,19 @@
+-- Transactions looked up in the explorer
+-- Final transactions never change, so the archival RPC response of each one is fetched once and
+-- decoded (receipt tree, actions, fees, balance deltas) on every read.
+
+CREATE TABLE explorer_transactions (
+    transaction_hash VARCHAR(64) PRIMARY KEY,
+    signer_id VARCHAR(128) NOT NULL,
+    receiver_id VARCHAR(128) NOT NULL,
+    -- Block the transaction was included in
+    block_hash VARCHAR(64) NOT NULL,
+    block_height BIGINT NOT NULL,
+    -- Block timestamp in nanoseconds
+    block_timestamp BIGINT NOT NULL,
+    -- EXPERIMENTAL_tx_status result in RPC JSON form
+    rpc_response JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE explorer_transactions IS 'Archival RPC responses of final transactions shown in the explorer';
@@ -0,0 +1,57 @@
+-- Gas and fee cost accounting
+-- Every transaction sent by the platform keys (relayed delegate actions, payouts, list
+-- submissions, relayer proposals) and every transaction a treasury or its members signed is
+-- queued in fee_transactions. The fee ledger worker looks each one up on the archival RPC and
+-- records the tokens burnt by the transaction and all its receipts against the signer, which
+-- is the account that paid for the gas.
+
+CREATE TABLE fee_transactions (
+    transaction_hash VARCHAR(64) PRIMARY KEY,
+    signer_id VARCHAR(128) NOT NULL,
+
+    -- Treasury the transaction was sent for, if known
+    dao_id VARCHAR(128),
+    -- Account a relayed delegate action was signed by
+    on_behalf_of VARCHAR(128),
+    -- Bulk payment list, used to find the treasury of payout transactions
+    list_id VARCHAR(64),
+    -- relay, payout, list_submission, relayer_proposal or treasury_activity
+    source VARCHAR(32) NOT NULL,
+
+    -- pending, recorded or failed
+    status VARCHAR(16) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_fee_transactions_pending ON fee_transactions(created_at) WHERE status = 'pending';
+CREATE INDEX idx_fee_transactions_list ON fee_transactions(list_id) WHERE list_id IS NOT NULL;
+
+CREATE TABLE fee_ledger (
+    transaction_hash VARCHAR(64) NOT NULL,
+    payer_id VARCHAR(128) NOT NULL,
+    -- treasury, member, relayer or other
+    payer_kind VARCHAR(16) NOT NULL,
+
+    dao_id VARCHAR(128),
+    on_behalf_of VARCHAR(128),
+    source VARCHAR(32) NOT NULL,
+
+    gas_burnt BIGINT NOT NULL,
+    -- Fees burnt by the transaction and all its receipts in yoctoNEAR
+    tokens_burnt NUMERIC(40, 0) NOT NULL,
+    block_timestamp TIMESTAMPTZ NOT NULL,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (transaction_hash, payer_id)
+);
+
+CREATE INDEX idx_fee_ledger_dao ON fee_ledger(dao_id, block_timestamp);
+CREATE INDEX idx_fee_ledger_payer ON fee_ledger(payer_kind, payer_id, block_timestamp);
+
+COMMENT ON TABLE fee_transactions IS 'Transactions queued for fee accounting';
+COMMENT ON TABLE fee_ledger IS 'Tokens burnt by each transaction, split by the account that paid them';
@@ -0,0 +1,14 @@
+-- Scan watermarks of the fee ledger worker
+-- The worker queues treasury transactions from balance_changes and proposal_executions rows
+-- created since its last scan. The watermark is stored here so a restart resumes from it
+-- instead of rescanning every row.
+
+CREATE TABLE fee_ledger_scans (
+    -- What was scanned, e.g. treasury_activity
+    scan VARCHAR(32) PRIMARY KEY,
+    -- Rows created at or after this time are scanned next
+    scanned_from TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE fee_ledger_scans IS 'Where the next fee ledger scan starts';
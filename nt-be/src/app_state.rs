@@ -1,12 +1,15 @@
 use axum::http::StatusCode;
 use chrono::{DateTime, Utc};
-use near_api::{AccountId, NetworkConfig, RPCEndpoint, Signer};
+use near_api::{AccountId, NearToken, NetworkConfig, RPCEndpoint, Signer};
 use sqlx::PgPool;
 use std::{sync::Arc, time::Duration};
 
 use crate::{
     handlers::balance_changes::transfer_hints::{TransferHintService, fastnear::FastNearProvider},
-    services::{DeFiLlamaClient, PriceLookupService},
+    services::{
+        DeFiLlamaClient, PriceLookupService,
+        signer_pool::{SignerPool, SignerRole},
+    },
     utils::{
         cache::{Cache, CacheKey, CacheTier},
         env::EnvVars,
@@ -17,9 +20,12 @@ use crate::{
 pub struct AppState {
     pub http_client: reqwest::Client,
     pub cache: Cache,
-    pub signer: Arc<Signer>,
-    pub bulk_payment_signer: Arc<Signer>,
+    /// Primary relayer account, which holds the DAO roles of relayer proposals
     pub signer_id: AccountId,
+    /// Relayer accounts, with `signer_id` as the primary account
+    pub relayer_pool: SignerPool,
+    /// Keys of the bulk payment contract account
+    pub bulk_payment_pool: SignerPool,
     pub network: NetworkConfig,
     pub archival_network: NetworkConfig,
     pub env_vars: EnvVars,
@@ -165,6 +171,7 @@ impl AppStateBuilder {
     /// - cache: Cache::new()
     /// - signer: Test signer from env or default test key
     /// - signer_id: "test.near"
+    /// - relayer_pool / bulk_payment_pool: The signers with the extra keys and accounts from env
     /// - network: Mainnet with fastnear API (from env)
     /// - archival_network: Archival mainnet with fastnear API (from env)
     /// - env_vars: EnvVars::default()
@@ -265,6 +272,27 @@ impl AppStateBuilder {
             .bulk_payment_contract_id
             .unwrap_or_else(|| env_vars.bulk_payment_contract_id.clone());
 
+        // Signer pools around the primary signers, with the extra keys and accounts from env
+        let low_balance_threshold = NearToken::from_near(env_vars.signer_low_balance_near);
+        let relayer_pool = SignerPool::new(
+            SignerRole::Relayer,
+            signer_id.clone(),
+            signer,
+            &env_vars.signer_extra_keys,
+            &env_vars.relayer_pool_accounts,
+            low_balance_threshold,
+        )
+        .await?;
+        let bulk_payment_pool = SignerPool::new(
+            SignerRole::BulkPayment,
+            bulk_payment_contract_id.clone(),
+            bulk_payment_signer,
+            &env_vars.bulk_payment_signer_extra_keys,
+            &[],
+            low_balance_threshold,
+        )
+        .await?;
+
         // Create transfer hint service if enabled (and not explicitly provided)
         let transfer_hint_service = if let Some(service) = self.transfer_hint_service {
             Some(service)
@@ -283,9 +311,9 @@ impl AppStateBuilder {
         Ok(AppState {
             http_client: self.http_client.unwrap_or_default(),
             cache: self.cache.unwrap_or_default(),
-            signer,
-            bulk_payment_signer,
             signer_id,
+            relayer_pool,
+            bulk_payment_pool,
             network,
             telegram_client: self.telegram_client.unwrap_or_default(),
             archival_network,
@@ -16,6 +16,7 @@ pub enum AuthError {
     TokenExpired,
     MissingToken,
     RevokedToken,
+    NotAdmin,
     DatabaseError(String),
     InternalError(String),
 }
@@ -32,6 +33,7 @@ impl std::fmt::Display for AuthError {
             AuthError::TokenExpired => write!(f, "Token has expired"),
             AuthError::MissingToken => write!(f, "Missing authentication token"),
             AuthError::RevokedToken => write!(f, "Token has been revoked or expired"),
+            AuthError::NotAdmin => write!(f, "Admin access required"),
             AuthError::DatabaseError(msg) => write!(f, "Database error: {}", msg),
             AuthError::InternalError(msg) => write!(f, "Internal error: {}", msg),
         }
@@ -52,6 +54,7 @@ impl IntoResponse for AuthError {
             AuthError::RevokedToken => (StatusCode::UNAUTHORIZED, self.to_string()),
             AuthError::TokenExpired => (StatusCode::UNAUTHORIZED, self.to_string()),
             AuthError::MissingToken => (StatusCode::UNAUTHORIZED, self.to_string()),
+            AuthError::NotAdmin => (StatusCode::FORBIDDEN, self.to_string()),
             AuthError::DatabaseError(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Database error".to_string(),
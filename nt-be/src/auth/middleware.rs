@@ -1,7 +1,13 @@
 use crate::AppState;
 use crate::auth::{AuthError, jwt::hash_token, verify_jwt};
-use axum::{extract::FromRequestParts, http::request::Parts};
+use crate::handlers::proposals::forecast::is_policy_member;
+use crate::handlers::proposals::scraper::fetch_policy;
+use axum::{
+    extract::FromRequestParts,
+    http::{StatusCode, request::Parts},
+};
 use axum_extra::extract::CookieJar;
+use near_api::AccountId;
 use std::sync::Arc;
 
 /// The name of the auth cookie
@@ -57,6 +63,60 @@ impl FromRequestParts<Arc<AppState>> for AuthUser {
     }
 }
 
+/// Authenticated user listed in `ADMIN_ACCOUNT_IDS`
+#[derive(Debug, Clone)]
+pub struct AdminUser {
+    pub account_id: String,
+}
+
+impl FromRequestParts<Arc<AppState>> for AdminUser {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+
+        if !state.env_vars.admin_account_ids.contains(&user.account_id) {
+            return Err(AuthError::NotAdmin);
+        }
+
+        Ok(AdminUser {
+            account_id: user.account_id,
+        })
+    }
+}
+
+/// Reject a request unless the account is a member of the DAO's policy
+pub async fn require_policy_member(
+    state: &AppState,
+    dao_id: &str,
+    account_id: &str,
+) -> Result<(), (StatusCode, String)> {
+    let dao: AccountId = dao_id.parse().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid DAO ID: {}", dao_id),
+        )
+    })?;
+    let policy = fetch_policy(&state.network, &dao).await.map_err(|e| {
+        log::error!("Error fetching policy for {}: {}", dao_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to fetch DAO policy".to_string(),
+        )
+    })?;
+
+    if !is_policy_member(&policy, account_id) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!("{} is not a member of {}", account_id, dao_id),
+        ));
+    }
+    Ok(())
+}
+
 /// Optional auth user - doesn't fail if no token is present
 #[derive(Debug, Clone)]
 pub struct OptionalAuthUser(pub Option<AuthUser>);
@@ -5,4 +5,4 @@ pub mod middleware;
 
 pub use error::AuthError;
 pub use jwt::{Claims, JwtCreateResult, create_jwt, verify_jwt};
-pub use middleware::AuthUser;
+pub use middleware::{AdminUser, AuthUser, require_policy_member};
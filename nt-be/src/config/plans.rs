@@ -322,6 +322,8 @@ pub fn get_volume_limit(plan_type: PlanType) -> Option<u64> {
 }
 
 #[cfg(test)]
+// Amounts are written in cents as dollars_cents
+#[allow(clippy::inconsistent_digit_grouping)]
 mod tests {
     use super::*;
 
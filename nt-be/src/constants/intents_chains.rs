@@ -2,7 +2,10 @@ use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-const ICON_PREFIX: &str = "https://near-intents.org/static/icons/network/";
+use super::intents_tokens::token_registry;
+
+/// Base URL of the network icons of NEAR Intents
+pub const ICON_PREFIX: &str = "https://near-intents.org/static/icons/network/";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChainIcons {
@@ -34,6 +37,7 @@ impl ChainMetadata {
     }
 }
 
+/// Bundled chain metadata, the defaults of the token registry's chains
 pub static CHAIN_METADATA: Lazy<HashMap<String, ChainMetadata>> = Lazy::new(|| {
     let mut metadata = HashMap::new();
 
@@ -160,5 +164,161 @@ pub static CHAIN_METADATA: Lazy<HashMap<String, ChainMetadata>> = Lazy::new(|| {
 /// Get chain metadata by chain name (returns name and both dark/light icon variants)
 pub fn get_chain_metadata_by_name(chain_name: &str) -> Option<ChainMetadata> {
     let normalized_name = chain_name.to_lowercase();
-    CHAIN_METADATA.get(&normalized_name).cloned()
+    token_registry().chains.get(&normalized_name).cloned()
+}
+
+/// Address format of a chain that intents tokens can be withdrawn to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressFormat {
+    /// `0x` + 20 bytes hex
+    Evm,
+    /// `0x` + 32 bytes hex (Move chains)
+    Move,
+    Solana,
+    Bitcoin,
+    Base58 {
+        prefixes: &'static [char],
+    },
+    Bech32 {
+        hrp: &'static str,
+    },
+    Stellar,
+    Near,
+}
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BECH32_ALPHABET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Address format of a chain (by `CHAIN_METADATA` key), if withdrawals to it are supported
+pub fn chain_address_format(chain_name: &str) -> Option<AddressFormat> {
+    let format = match chain_name.to_lowercase().as_str() {
+        "eth" | "base" | "arbitrum" | "gnosis" | "berachain" | "polygon" | "bsc" | "optimism"
+        | "avalanche" | "aurora" | "hyperliquid" | "turbochain" | "tuxappchain" | "vertex"
+        | "optima" | "easychain" => AddressFormat::Evm,
+        "sui" | "aptos" => AddressFormat::Move,
+        "solana" => AddressFormat::Solana,
+        "bitcoin" => AddressFormat::Bitcoin,
+        "dogecoin" => AddressFormat::Base58 {
+            prefixes: &['D', 'A', '9'],
+        },
+        "litecoin" => AddressFormat::Base58 {
+            prefixes: &['L', 'M', '3'],
+        },
+        "tron" => AddressFormat::Base58 { prefixes: &['T'] },
+        "xrpledger" => AddressFormat::Base58 { prefixes: &['r'] },
+        "zcash" => AddressFormat::Base58 { prefixes: &['t'] },
+        "cardano" => AddressFormat::Bech32 { hrp: "addr" },
+        "stellar" => AddressFormat::Stellar,
+        "near" => AddressFormat::Near,
+        _ => return None,
+    };
+    Some(format)
+}
+
+fn is_hex(s: &str, len: usize) -> bool {
+    s.len() == len && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_base58(s: &str, lengths: std::ops::RangeInclusive<usize>) -> bool {
+    lengths.contains(&s.len()) && s.chars().all(|c| BASE58_ALPHABET.contains(c))
+}
+
+fn is_bech32(s: &str, hrp: &str) -> bool {
+    let lower = s.to_lowercase();
+    // Bech32 strings are either all lowercase or all uppercase
+    if s != lower && s != s.to_uppercase() {
+        return false;
+    }
+    lower
+        .strip_prefix(hrp)
+        .and_then(|rest| rest.strip_prefix('1'))
+        .is_some_and(|data| {
+            (6..=100).contains(&data.len()) && data.chars().all(|c| BECH32_ALPHABET.contains(c))
+        })
+}
+
+/// Check the format of an address on a chain (by `CHAIN_METADATA` key)
+///
+/// Checks the encoding and length only, not checksums. Unsupported chains are never valid.
+pub fn is_valid_chain_address(chain_name: &str, address: &str) -> bool {
+    let Some(format) = chain_address_format(chain_name) else {
+        return false;
+    };
+
+    match format {
+        AddressFormat::Evm => address
+            .strip_prefix("0x")
+            .is_some_and(|hex| is_hex(hex, 40)),
+        AddressFormat::Move => address
+            .strip_prefix("0x")
+            .is_some_and(|hex| is_hex(hex, 64)),
+        AddressFormat::Solana => is_base58(address, 32..=44),
+        AddressFormat::Bitcoin => {
+            is_bech32(address, "bc")
+                || (address.starts_with(['1', '3']) && is_base58(address, 26..=35))
+        }
+        AddressFormat::Base58 { prefixes } => {
+            address.starts_with(prefixes) && is_base58(address, 25..=35)
+        }
+        AddressFormat::Bech32 { hrp } => is_bech32(address, hrp),
+        AddressFormat::Stellar => {
+            address.len() == 56
+                && address.starts_with('G')
+                && address
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || ('2'..='7').contains(&c))
+        }
+        AddressFormat::Near => address.parse::<near_api::AccountId>().is_ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_chain_address() {
+        assert!(is_valid_chain_address(
+            "eth",
+            "0x71C7656EC7ab88b098defB751B7401B5f6d8976F"
+        ));
+        assert!(!is_valid_chain_address(
+            "eth",
+            "0x71C7656EC7ab88b098defB751B7401B5f6d897"
+        ));
+        assert!(!is_valid_chain_address("base", "alice.near"));
+
+        assert!(is_valid_chain_address(
+            "solana",
+            "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"
+        ));
+        // 0 and O are not base58
+        assert!(!is_valid_chain_address(
+            "solana",
+            "0xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"
+        ));
+
+        assert!(is_valid_chain_address(
+            "bitcoin",
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+        ));
+        assert!(is_valid_chain_address(
+            "bitcoin",
+            "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"
+        ));
+        assert!(!is_valid_chain_address(
+            "bitcoin",
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdb"
+        ));
+        assert!(!is_valid_chain_address(
+            "bitcoin",
+            "bc1Qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+        ));
+
+        assert!(is_valid_chain_address(
+            "tron",
+            "TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU7"
+        ));
+        assert!(!is_valid_chain_address("starknet", "0x1234"));
+    }
 }
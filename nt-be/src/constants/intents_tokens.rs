@@ -1,6 +1,9 @@
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::sync::{Arc, RwLock};
+
+use super::intents_chains::{CHAIN_METADATA, ChainMetadata};
 
 /// Represents the root of the tokens.json file
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -67,59 +70,86 @@ pub enum TokenDeployment {
     },
 }
 
-/// Static map of unified tokens loaded from data/tokens.json for fast lookup
-static TOKENS_MAP_CELL: OnceLock<HashMap<String, UnifiedTokenInfo>> = OnceLock::new();
+/// Lookup maps of the intents token and chain registry
+///
+/// Built from the bundled data/tokens.json at startup and replaced at runtime by the token
+/// registry service (synced tokens, admin overrides and chains from the database).
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    /// Unified tokens by lowercase unifiedAssetId
+    pub tokens: Arc<HashMap<String, UnifiedTokenInfo>>,
+    /// Base tokens by defuseAssetId
+    pub defuse_tokens: Arc<HashMap<String, BaseTokenInfo>>,
+    /// Unified tokens by lowercase symbol; the first unified token of a symbol wins, so bundled
+    /// tokens keep their ticker over synced tokens that share it
+    pub symbol_tokens: Arc<HashMap<String, UnifiedTokenInfo>>,
+    /// Chain metadata by lowercase chain name
+    pub chains: Arc<HashMap<String, ChainMetadata>>,
+}
 
-/// Static map of base tokens by defuseAssetId for fast lookup
-static DEFUSE_TOKENS_MAP_CELL: OnceLock<HashMap<String, BaseTokenInfo>> = OnceLock::new();
+impl TokenRegistry {
+    pub fn new(tokens: Vec<UnifiedTokenInfo>, chains: HashMap<String, ChainMetadata>) -> Self {
+        let mut defuse_tokens = HashMap::new();
+        for unified_token in &tokens {
+            for base_token in &unified_token.grouped_tokens {
+                defuse_tokens.insert(base_token.defuse_asset_id.clone(), base_token.clone());
+            }
+        }
+        let mut symbol_tokens = HashMap::new();
+        for unified_token in &tokens {
+            symbol_tokens
+                .entry(unified_token.symbol.to_lowercase())
+                .or_insert_with(|| unified_token.clone());
+        }
+        let tokens = tokens
+            .into_iter()
+            .map(|t| (t.unified_asset_id.to_lowercase(), t))
+            .collect();
 
-/// Static map of unified tokens by lowercase symbol for fast lookup
-static SYMBOL_TOKENS_MAP_CELL: OnceLock<HashMap<String, UnifiedTokenInfo>> = OnceLock::new();
+        Self {
+            tokens: Arc::new(tokens),
+            defuse_tokens: Arc::new(defuse_tokens),
+            symbol_tokens: Arc::new(symbol_tokens),
+            chains: Arc::new(chains),
+        }
+    }
 
-/// Get the map of unified tokens, loading from JSON if not already loaded
-pub fn get_tokens_map() -> &'static HashMap<String, UnifiedTokenInfo> {
-    TOKENS_MAP_CELL.get_or_init(|| {
+    /// Registry of the bundled data/tokens.json and chain metadata
+    pub fn bundled() -> Self {
         let tokens = load_tokens_from_json().unwrap_or_else(|e| {
             eprintln!("Failed to load tokens from JSON: {}", e);
             vec![]
         });
-        tokens
-            .into_iter()
-            .map(|t| (t.unified_asset_id.to_lowercase(), t))
-            .collect()
-    })
+        Self::new(tokens, CHAIN_METADATA.clone())
+    }
 }
 
-/// Get the map of base tokens by defuseAssetId, loading from JSON if not already loaded
-pub fn get_defuse_tokens_map() -> &'static HashMap<String, BaseTokenInfo> {
-    DEFUSE_TOKENS_MAP_CELL.get_or_init(|| {
-        let tokens = load_tokens_from_json().unwrap_or_else(|e| {
-            eprintln!("Failed to load tokens from JSON: {}", e);
-            vec![]
-        });
+static REGISTRY: Lazy<RwLock<Arc<TokenRegistry>>> =
+    Lazy::new(|| RwLock::new(Arc::new(TokenRegistry::bundled())));
 
-        let mut map = HashMap::new();
-        for unified_token in tokens {
-            for base_token in unified_token.grouped_tokens {
-                map.insert(base_token.defuse_asset_id.clone(), base_token);
-            }
-        }
-        map
-    })
+/// Current token registry
+pub fn token_registry() -> Arc<TokenRegistry> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner()).clone()
 }
 
-/// Get the map of unified tokens by lowercase symbol, loading from JSON if not already loaded
-pub fn get_symbol_tokens_map() -> &'static HashMap<String, UnifiedTokenInfo> {
-    SYMBOL_TOKENS_MAP_CELL.get_or_init(|| {
-        let tokens = load_tokens_from_json().unwrap_or_else(|e| {
-            eprintln!("Failed to load tokens from JSON: {}", e);
-            vec![]
-        });
-        tokens
-            .into_iter()
-            .map(|t| (t.symbol.to_lowercase(), t))
-            .collect()
-    })
+/// Replace the token registry used by all lookups
+pub fn install_token_registry(registry: TokenRegistry) {
+    *REGISTRY.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(registry);
+}
+
+/// Get the map of unified tokens
+pub fn get_tokens_map() -> Arc<HashMap<String, UnifiedTokenInfo>> {
+    token_registry().tokens.clone()
+}
+
+/// Get the map of base tokens by defuseAssetId
+pub fn get_defuse_tokens_map() -> Arc<HashMap<String, BaseTokenInfo>> {
+    token_registry().defuse_tokens.clone()
+}
+
+/// Get the map of unified tokens by lowercase symbol
+pub fn get_symbol_tokens_map() -> Arc<HashMap<String, UnifiedTokenInfo>> {
+    token_registry().symbol_tokens.clone()
 }
 
 /// Find a token by its defuse_asset_id
@@ -135,12 +165,12 @@ pub fn find_token_by_symbol(symbol: &str) -> Option<UnifiedTokenInfo> {
 }
 
 /// Find a base token by its defuseAssetId (e.g., "nep141:wrap.near" or "nep245:v2_1.omni.hot.tg:137_...")
-pub fn find_token_by_defuse_asset_id(defuse_asset_id: &str) -> Option<&'static BaseTokenInfo> {
-    get_defuse_tokens_map().get(defuse_asset_id)
+pub fn find_token_by_defuse_asset_id(defuse_asset_id: &str) -> Option<BaseTokenInfo> {
+    get_defuse_tokens_map().get(defuse_asset_id).cloned()
 }
 
-/// Load tokens from the JSON file as unified tokens
-fn load_tokens_from_json() -> Result<Vec<UnifiedTokenInfo>, Box<dyn std::error::Error>> {
+/// Load tokens from the bundled JSON file as unified tokens
+pub fn load_tokens_from_json() -> Result<Vec<UnifiedTokenInfo>, Box<dyn std::error::Error>> {
     let json_str = include_str!("../../data/tokens.json");
     let tokens_json: TokensJson = serde_json::from_str(json_str)?;
 
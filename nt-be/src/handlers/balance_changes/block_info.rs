@@ -280,6 +280,28 @@ pub async fn get_transaction(
     Ok(response)
 }
 
+/// Get the height and timestamp of a block by its hash
+///
+/// # Returns
+/// The block height and its timestamp in nanoseconds since Unix epoch
+pub async fn get_block_by_hash(
+    network: &NetworkConfig,
+    block_hash: &str,
+) -> Result<(u64, i64), Box<dyn std::error::Error + Send + Sync>> {
+    let client = create_rpc_client(network)?;
+    let hash: near_primitives::hash::CryptoHash = block_hash.parse()?;
+
+    let block = with_transport_retry("block_by_hash", || {
+        let req = methods::block::RpcBlockRequest {
+            block_reference: BlockReference::BlockId(BlockId::Hash(hash)),
+        };
+        client.call(req)
+    })
+    .await?;
+
+    Ok((block.header.height, block.header.timestamp as i64))
+}
+
 /// Create a new block timestamp cache
 pub fn new_cache() -> BlockTimestampCache {
     Arc::new(RwLock::new(HashMap::new()))
@@ -192,6 +192,15 @@ pub fn convert_raw_to_decimal(
     Ok(decimal.normalized())
 }
 
+/// Format a decimal without exponent notation (normalized integers like 8E+2 print as 800)
+pub fn format_decimal(value: &bigdecimal::BigDecimal) -> String {
+    if value.fractional_digit_count() < 0 {
+        value.with_scale(0).to_string()
+    } else {
+        value.to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,4 +265,13 @@ mod tests {
             BigDecimal::from_str("100").unwrap()
         );
     }
+
+    #[test]
+    fn test_format_decimal() {
+        let amount = convert_raw_to_decimal("800000000000000000000000000", 24).unwrap();
+        assert_eq!(format_decimal(&amount), "800");
+
+        let amount = convert_raw_to_decimal("2500000", 6).unwrap();
+        assert_eq!(format_decimal(&amount), "2.5");
+    }
 }
@@ -2,7 +2,7 @@
 //!
 //! Provides endpoints for querying historical balance data:
 //! - Chart API: Returns balance snapshots at specified intervals
-//! - CSV Export: Returns raw balance changes as downloadable CSV
+//! - CSV Export: Returns raw balance changes, or the monthly swap summary, as downloadable CSV
 
 use axum::{
     Json,
@@ -18,6 +18,8 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::AppState;
+use crate::handlers::intents::deposit_labels::load_deposit_labels_in_range;
+use crate::handlers::intents::swap_analytics::{SwapAnalyticsQuery, analyze_swaps, monthly_csv};
 
 /// Deserializer for comma-separated values
 /// Accepts either a comma-separated string or None
@@ -133,31 +135,56 @@ pub struct CsvRequest {
     pub end_time: DateTime<Utc>,
     #[serde(default, deserialize_with = "comma_separated")]
     pub token_ids: Option<Vec<String>>, // Comma-separated list
+    /// "changes" (default) or "swaps", the monthly summary of the swaps settled in the period
+    #[serde(default)]
+    pub view: Option<String>,
 }
 
 /// CSV Export API - returns balance changes as CSV
 ///
-/// Excludes SNAPSHOT and NOT_REGISTERED records
+/// Excludes SNAPSHOT and NOT_REGISTERED records. With `view=swaps`, returns the monthly swap
+/// execution summary instead.
 pub async fn export_balance_csv(
     State(state): State<Arc<AppState>>,
     Query(params): Query<CsvRequest>,
 ) -> Result<Response, (StatusCode, String)> {
-    // Query balance changes
-    let csv_data = generate_csv(
-        &state.db_pool,
-        &state.price_service,
-        &params.account_id,
-        params.start_time,
-        params.end_time,
-        params.token_ids.as_ref(),
-    )
-    .await
-    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let (view, csv_data) = match params.view.as_deref().unwrap_or("changes") {
+        // Query balance changes
+        "changes" => (
+            "balance_changes",
+            generate_csv(
+                &state.db_pool,
+                &state.price_service,
+                &params.account_id,
+                params.start_time,
+                params.end_time,
+                params.token_ids.as_ref(),
+            )
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?,
+        ),
+        "swaps" => {
+            let query = SwapAnalyticsQuery {
+                account_id: params.account_id.clone(),
+                start_time: params.start_time,
+                end_time: params.end_time,
+                view: None,
+            };
+            let analytics = analyze_swaps(&state, &query).await?;
+            ("swap_summary", monthly_csv(&analytics.monthly))
+        }
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported view: {}", other),
+            ));
+        }
+    };
 
     // Return as downloadable CSV
     let filename = format!(
-        "balance_changes_{}_{}_to_{}.csv",
-        params.account_id, params.start_time, params.end_time
+        "{}_{}_{}_to_{}.csv",
+        view, params.account_id, params.start_time, params.end_time
     );
 
     Ok((
@@ -461,6 +488,15 @@ async fn enrich_snapshots_with_prices<P: crate::services::PriceProvider>(
     }
 }
 
+/// Quote a free-text CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 /// Generate CSV from balance changes
 async fn generate_csv<P: crate::services::PriceProvider>(
     pool: &PgPool,
@@ -471,6 +507,8 @@ async fn generate_csv<P: crate::services::PriceProvider>(
     token_ids: Option<&Vec<String>>,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     let changes = load_balance_changes(pool, account_id, start_date, end_date, token_ids).await?;
+    let deposit_labels =
+        load_deposit_labels_in_range(pool, account_id, start_date, end_date).await?;
 
     // Pre-fetch prices for all token/date combinations to avoid per-row API calls
     let mut prices_cache: HashMap<(String, NaiveDate), f64> = HashMap::new();
@@ -508,7 +546,7 @@ async fn generate_csv<P: crate::services::PriceProvider>(
     let mut csv = String::new();
 
     // Header (with price columns)
-    csv.push_str("block_height,block_time,token_id,token_symbol,counterparty,amount,balance_before,balance_after,price_usd,value_usd,transaction_hashes,receipt_id\n");
+    csv.push_str("block_height,block_time,token_id,token_symbol,counterparty,amount,balance_before,balance_after,price_usd,value_usd,transaction_hashes,receipt_id,deposit_label\n");
 
     // Rows (exclude SNAPSHOT, NOT_REGISTERED, and STAKING_SNAPSHOT)
     for change in changes {
@@ -522,6 +560,10 @@ async fn generate_csv<P: crate::services::PriceProvider>(
         let tx_hashes = change.transaction_hashes.join(",");
         let receipt_id = change.receipt_id.first().map(|s| s.as_str()).unwrap_or("");
         let token_symbol = change.token_symbol.as_deref().unwrap_or("");
+        let deposit_label = deposit_labels
+            .get(&(change.token_id.clone(), change.block_height))
+            .map(|label| csv_field(label))
+            .unwrap_or_default();
 
         // Look up price from pre-fetched cache
         let date = change.block_time.date_naive();
@@ -537,7 +579,7 @@ async fn generate_csv<P: crate::services::PriceProvider>(
         let value_str = value_usd.map(|v| format!("{}", v)).unwrap_or_default();
 
         csv.push_str(&format!(
-            "{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
             change.block_height,
             change.block_time.to_rfc3339(),
             change.token_id,
@@ -549,7 +591,8 @@ async fn generate_csv<P: crate::services::PriceProvider>(
             price_str,
             value_str,
             tx_hashes,
-            receipt_id
+            receipt_id,
+            deposit_label
         ));
     }
 
@@ -561,6 +604,13 @@ mod tests {
     use super::*;
     use chrono::TimeZone;
 
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("Grant from Foundation"), "Grant from Foundation");
+        assert_eq!(csv_field("Grants, Q1"), "\"Grants, Q1\"");
+        assert_eq!(csv_field("The \"big\" grant"), "\"The \"\"big\"\" grant\"");
+    }
+
     #[test]
     fn test_interval_increment_hourly() {
         let dt = Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap();
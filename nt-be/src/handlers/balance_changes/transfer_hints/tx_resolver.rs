@@ -20,6 +20,10 @@
 //! 2. Scan the block's chunks for transactions signed by that signer
 //! 3. For each candidate transaction, check if it produced our receipt
 //! 4. Return the matching transaction hash
+//!
+//! ## Transaction → Receipt tree
+//! `fetch_transaction_receipts` returns a transaction with all its receipts and outcomes, for
+//! walking the receipts a given receipt caused (e.g. the transfers of a proposal execution).
 
 use crate::handlers::balance_changes::utils::with_transport_retry;
 use crate::utils::jsonrpc::create_rpc_client;
@@ -459,6 +463,45 @@ pub async fn get_all_receipt_tx_mappings(
         .collect())
 }
 
+/// Fetch a transaction with all its receipts and their outcomes
+///
+/// Uses `EXPERIMENTAL_tx_status`, which unlike `tx` includes the receipts themselves (actions,
+/// predecessor) and not only their outcomes. The result is returned in its RPC JSON form
+/// (`receipts`, `receipts_outcome`, `transaction`, ...) so callers can walk the receipt tree
+/// the same way for live responses and recorded fixtures.
+pub async fn fetch_transaction_receipts(
+    network: &NetworkConfig,
+    tx_hash: &str,
+    sender_account_id: &str,
+) -> Result<serde_json::Value, Box<dyn Error + Send + Sync>> {
+    let client = create_rpc_client(network)?;
+
+    let parsed_tx_hash: near_primitives::hash::CryptoHash = tx_hash.parse()?;
+    let parsed_sender: near_primitives::types::AccountId = sender_account_id.parse()?;
+
+    let tx_response = with_transport_retry("experimental_tx_status", || {
+        let req = methods::EXPERIMENTAL_tx_status::RpcTransactionStatusRequest {
+            transaction_info: methods::EXPERIMENTAL_tx_status::TransactionInfo::TransactionId {
+                tx_hash: parsed_tx_hash,
+                sender_account_id: parsed_sender.clone(),
+            },
+            wait_until: near_primitives::views::TxExecutionStatus::Final,
+        };
+        client.call(req)
+    })
+    .await?;
+
+    match tx_response.final_execution_outcome {
+        Some(FinalExecutionOutcomeViewEnum::FinalExecutionOutcomeWithReceipt(outcome)) => {
+            Ok(serde_json::to_value(outcome)?)
+        }
+        Some(FinalExecutionOutcomeViewEnum::FinalExecutionOutcome(_)) => {
+            Err(format!("Transaction {} was returned without its receipts", tx_hash).into())
+        }
+        None => Err("No final execution outcome in transaction".into()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,144 @@
+//! NEP-297 events emitted by the bulk payment contract
+//!
+//! The contract logs `EVENT_JSON:{"standard":"bulk-payment",...}` for list submission,
+//! approval and rejection, every payment outcome and storage purchases. The payout worker
+//! records the events of its transactions, which the transactions view uses to link
+//! payments to their transaction hash.
+
+use serde::{Deserialize, Serialize};
+
+const EVENT_LOG_PREFIX: &str = "EVENT_JSON:";
+const EVENT_STANDARD: &str = "bulk-payment";
+
+/// A `bulk-payment` event parsed from a contract log
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContractEvent {
+    pub standard: String,
+    pub version: String,
+    pub event: String,
+    pub data: serde_json::Value,
+}
+
+impl ContractEvent {
+    /// List the event refers to (every bulk payment event except `storage_purchased`)
+    pub fn list_id(&self) -> Option<&str> {
+        self.data.get("list_id").and_then(|v| v.as_str())
+    }
+
+    /// Index of the payment within the list (`payment_sent` / `payment_failed`)
+    pub fn payment_index(&self) -> Option<i32> {
+        self.data
+            .get("index")
+            .and_then(|v| v.as_u64())
+            .and_then(|i| i32::try_from(i).ok())
+    }
+
+    pub fn recipient(&self) -> Option<&str> {
+        self.data.get("recipient").and_then(|v| v.as_str())
+    }
+}
+
+/// Parse the bulk payment events out of a transaction's logs, ignoring other logs
+pub fn parse_events<'a>(logs: impl IntoIterator<Item = &'a str>) -> Vec<ContractEvent> {
+    logs.into_iter()
+        .filter_map(|log| log.strip_prefix(EVENT_LOG_PREFIX))
+        .filter_map(|json| serde_json::from_str::<ContractEvent>(json).ok())
+        .filter(|event| event.standard == EVENT_STANDARD)
+        .collect()
+}
+
+/// Store the list events of a transaction
+///
+/// Failures are logged only - the events are an index, the contract stays the source of truth.
+pub async fn record_events(pool: &sqlx::PgPool, transaction_hash: &str, events: &[ContractEvent]) {
+    for event in events {
+        let Some(list_id) = event.list_id() else {
+            continue;
+        };
+
+        if let Err(e) = sqlx::query(
+            r#"
+            INSERT INTO bulk_payment_events
+                (list_id, event, payment_index, recipient, data, transaction_hash)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(list_id)
+        .bind(&event.event)
+        .bind(event.payment_index())
+        .bind(event.recipient())
+        .bind(&event.data)
+        .bind(transaction_hash)
+        .execute(pool)
+        .await
+        {
+            log::error!(
+                "Failed to record {} event of list {}: {}",
+                event.event,
+                list_id,
+                e
+            );
+        }
+    }
+}
+
+/// Outcome of a payment as reported by its `payment_sent` / `payment_failed` event
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentEvent {
+    pub event: String,
+    pub payment_index: i32,
+    pub recipient: String,
+    pub transaction_hash: String,
+}
+
+/// Payment events recorded for a list, by payment index
+pub async fn fetch_payment_events(
+    pool: &sqlx::PgPool,
+    list_id: &str,
+) -> Result<Vec<PaymentEvent>, sqlx::Error> {
+    sqlx::query_as::<_, PaymentEvent>(
+        r#"
+        SELECT event, payment_index, recipient, transaction_hash
+        FROM bulk_payment_events
+        WHERE list_id = $1
+          AND event IN ('payment_sent', 'payment_failed')
+          AND payment_index IS NOT NULL
+          AND recipient IS NOT NULL
+        ORDER BY payment_index, created_at
+        "#,
+    )
+    .bind(list_id)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_events() {
+        let logs = [
+            "Processed 1 payments for list abc, 0 remaining",
+            r#"EVENT_JSON:{"standard":"bulk-payment","version":"1.0.0","event":"payment_sent","data":{"list_id":"abc","index":1,"recipient":"bob.near","token_id":"native","amount":"1000"}}"#,
+            r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{"old_owner_id":"a.near","new_owner_id":"b.near","amount":"1"}]}"#,
+            r#"EVENT_JSON:{"standard":"bulk-payment","version":"1.0.0","event":"storage_purchased","data":{"account_id":"dao.near","num_records":10,"cost":"1"}}"#,
+            "EVENT_JSON:not json",
+        ];
+
+        let events = parse_events(logs);
+        assert_eq!(events.len(), 2);
+
+        assert_eq!(events[0].event, "payment_sent");
+        assert_eq!(events[0].list_id(), Some("abc"));
+        assert_eq!(events[0].payment_index(), Some(1));
+        assert_eq!(events[0].recipient(), Some("bob.near"));
+
+        // Storage purchases aren't tied to a list
+        assert_eq!(events[1].event, "storage_purchased");
+        assert_eq!(events[1].list_id(), None);
+        assert_eq!(events[1].payment_index(), None);
+    }
+}
@@ -0,0 +1,580 @@
+//! Fiat-denominated bulk payment lists
+//!
+//! Amounts entered in a fiat currency are converted into token base units with the
+//! cached historical price at a locked quote time. The quote (rate, price date and both
+//! amounts per line) is stored and linked to the list once it is submitted.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use bigdecimal::{BigDecimal, FromPrimitive, Zero};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use near_api::AccountId;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json as SqlJson;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::submit::{MAX_RECIPIENTS_PER_BULK_PAYMENT, PaymentInput, is_native, metadata_contract};
+use crate::AppState;
+use crate::auth::{AuthUser, require_policy_member};
+use crate::handlers::balance_changes::counterparty::{convert_raw_to_decimal, format_decimal};
+use crate::handlers::proposals::scraper::fetch_ft_metadata;
+
+/// Fiat currencies prices are available in
+const SUPPORTED_CURRENCIES: &[&str] = &["USD"];
+
+/// How many days back to look for a cached price when the quote date has none yet
+const PRICE_LOOKBACK_DAYS: u64 = 3;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FiatPaymentInput {
+    pub recipient: String,
+    /// Decimal amount in the quote currency, e.g. "2000.00"
+    pub fiat_amount: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FiatPayment {
+    pub recipient: String,
+    pub fiat_amount: String,
+    /// Token amount in base units
+    pub amount: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FiatQuoteRequest {
+    pub dao_id: String,
+    pub token_id: String,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    pub payments: Vec<FiatPaymentInput>,
+}
+
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct FiatQuote {
+    pub id: i64,
+    pub dao_id: String,
+    pub created_by: String,
+    pub token_id: String,
+    pub token_symbol: String,
+    pub token_decimals: i16,
+    pub currency: String,
+    pub rate: BigDecimal,
+    pub price_date: NaiveDate,
+    pub quoted_at: DateTime<Utc>,
+    pub payments: SqlJson<Vec<FiatPayment>>,
+    pub total_fiat: BigDecimal,
+    pub total_amount: BigDecimal,
+    pub list_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FiatQuoteResponse {
+    #[serde(flatten)]
+    pub quote: FiatQuote,
+    /// Human-readable conversion summary to attach to the proposal description
+    pub report: String,
+}
+
+impl From<FiatQuote> for FiatQuoteResponse {
+    fn from(quote: FiatQuote) -> Self {
+        let report = conversion_report(&quote);
+        Self { quote, report }
+    }
+}
+
+const QUOTE_COLUMNS: &str = "id, dao_id, created_by, token_id, token_symbol, token_decimals, \
+     currency, rate, price_date, quoted_at, payments, total_fiat, total_amount, list_id, created_at";
+
+/// Token ID understood by the price lookup service
+fn price_token_id(token_id: &str) -> String {
+    if is_native(token_id) {
+        "near".to_string()
+    } else if token_id.starts_with("nep141:") {
+        format!("intents.near:{}", token_id)
+    } else {
+        token_id.to_string()
+    }
+}
+
+/// Convert fiat amounts into token base units at `rate` (fiat per whole token)
+///
+/// Token amounts are rounded down so a list never pays more than its fiat value.
+pub fn convert_fiat_payments(
+    payments: &[FiatPaymentInput],
+    rate: &BigDecimal,
+    decimals: u8,
+) -> Result<(Vec<FiatPayment>, BigDecimal, u128), String> {
+    if rate <= &BigDecimal::zero() {
+        return Err("Token price must be positive".to_string());
+    }
+
+    let unit = BigDecimal::new(1.into(), -(decimals as i64));
+    let mut converted = Vec::with_capacity(payments.len());
+    let mut total_fiat = BigDecimal::zero();
+    let mut total_amount: u128 = 0;
+
+    for payment in payments {
+        let fiat = BigDecimal::from_str(payment.fiat_amount.trim())
+            .map_err(|_| format!("Invalid fiat amount for {}", payment.recipient))?;
+        if fiat <= BigDecimal::zero() || fiat.fractional_digit_count() > 2 {
+            return Err(format!("Invalid fiat amount for {}", payment.recipient));
+        }
+
+        let amount = (&fiat / rate * &unit).with_scale(0);
+        let amount: u128 = amount
+            .to_string()
+            .parse()
+            .map_err(|_| format!("Token amount overflow for {}", payment.recipient))?;
+        if amount == 0 {
+            return Err(format!(
+                "Fiat amount for {} is below the smallest token unit",
+                payment.recipient
+            ));
+        }
+
+        total_amount = total_amount
+            .checked_add(amount)
+            .ok_or_else(|| "Total token amount overflow".to_string())?;
+        total_fiat += &fiat;
+
+        converted.push(FiatPayment {
+            recipient: payment.recipient.clone(),
+            fiat_amount: fiat.with_scale(2).to_string(),
+            amount: amount.to_string(),
+        });
+    }
+
+    Ok((converted, total_fiat.with_scale(2), total_amount))
+}
+
+/// Conversion summary for the proposal description
+pub fn conversion_report(quote: &FiatQuote) -> String {
+    let total_tokens =
+        convert_raw_to_decimal(&quote.total_amount.to_string(), quote.token_decimals as u8)
+            .map(|d| format_decimal(&d))
+            .unwrap_or_else(|_| quote.total_amount.to_string());
+    format!(
+        "{} {} converted to {} {} at 1 {} = {} {} (price date {}, quoted at {})",
+        quote.total_fiat,
+        quote.currency,
+        total_tokens,
+        quote.token_symbol,
+        quote.token_symbol,
+        format_decimal(&quote.rate.normalized()),
+        quote.currency,
+        quote.price_date,
+        quote.quoted_at.format("%Y-%m-%d %H:%M UTC")
+    )
+}
+
+/// Latest cached USD price of a bulk payment token at or before a date
+pub async fn lookup_rate(
+    state: &AppState,
+    token_id: &str,
+    quote_date: NaiveDate,
+) -> Result<Option<(NaiveDate, BigDecimal)>, (StatusCode, String)> {
+    let dates: Vec<NaiveDate> = (0..=PRICE_LOOKBACK_DAYS)
+        .filter_map(|days| quote_date.checked_sub_days(Days::new(days)))
+        .collect();
+
+    let prices = state
+        .price_service
+        .get_prices_batch(&price_token_id(token_id), &dates)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to look up price for {}: {}", token_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to look up token price".to_string(),
+            )
+        })?;
+
+    Ok(dates.into_iter().find_map(|date| {
+        prices
+            .get(&date)
+            .and_then(|price| BigDecimal::from_f64(*price))
+            .map(|price| (date, price.round(8)))
+    }))
+}
+
+/// Lock a fiat-to-token conversion for a bulk payment list
+pub async fn create_fiat_quote(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<FiatQuoteRequest>,
+) -> Result<Json<FiatQuoteResponse>, (StatusCode, String)> {
+    require_policy_member(&state, &request.dao_id, &auth_user.account_id).await?;
+
+    let currency = request.currency.to_uppercase();
+    if !SUPPORTED_CURRENCIES.contains(&currency.as_str()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Unsupported currency {}. Supported: {}",
+                request.currency,
+                SUPPORTED_CURRENCIES.join(", ")
+            ),
+        ));
+    }
+    if request.payments.is_empty() || request.payments.len() > MAX_RECIPIENTS_PER_BULK_PAYMENT {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "A list must have between 1 and {} recipients",
+                MAX_RECIPIENTS_PER_BULK_PAYMENT
+            ),
+        ));
+    }
+
+    // The price is always locked at the time of the request, never backdated
+    let quoted_at = Utc::now();
+
+    let token_contract: AccountId = metadata_contract(&request.token_id).parse().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid token ID: {}", request.token_id),
+        )
+    })?;
+    let metadata = fetch_ft_metadata(&state.cache, &state.network, &token_contract)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to fetch metadata for {}: {}", token_contract, e);
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to fetch token metadata for {}", request.token_id),
+            )
+        })?;
+
+    let (price_date, rate) = lookup_rate(&state, &request.token_id, quoted_at.date_naive())
+        .await?
+        .ok_or_else(|| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!(
+                    "No {} price available for {} around {}",
+                    currency,
+                    metadata.symbol,
+                    quoted_at.date_naive()
+                ),
+            )
+        })?;
+
+    let (payments, total_fiat, total_amount) =
+        convert_fiat_payments(&request.payments, &rate, metadata.decimals)
+            .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let quote = sqlx::query_as::<_, FiatQuote>(&format!(
+        r#"
+        INSERT INTO bulk_payment_fiat_quotes
+            (dao_id, created_by, token_id, token_symbol, token_decimals, currency, rate,
+             price_date, quoted_at, payments, total_fiat, total_amount)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        RETURNING {}
+        "#,
+        QUOTE_COLUMNS
+    ))
+    .bind(&request.dao_id)
+    .bind(&auth_user.account_id)
+    .bind(&request.token_id)
+    .bind(&metadata.symbol)
+    .bind(metadata.decimals as i16)
+    .bind(&currency)
+    .bind(&rate)
+    .bind(price_date)
+    .bind(quoted_at)
+    .bind(SqlJson(&payments))
+    .bind(&total_fiat)
+    .bind(BigDecimal::from(total_amount))
+    .fetch_one(&state.db_pool)
+    .await
+    .map_err(|e| {
+        log::error!("Failed to store fiat quote: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to store fiat quote".to_string(),
+        )
+    })?;
+
+    Ok(Json(quote.into()))
+}
+
+async fn fetch_quote_by_id(state: &AppState, id: i64) -> Result<Option<FiatQuote>, sqlx::Error> {
+    sqlx::query_as::<_, FiatQuote>(&format!(
+        "SELECT {} FROM bulk_payment_fiat_quotes WHERE id = $1",
+        QUOTE_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&state.db_pool)
+    .await
+}
+
+fn not_found_or_error(
+    result: Result<Option<FiatQuote>, sqlx::Error>,
+    what: String,
+) -> Result<Json<FiatQuoteResponse>, (StatusCode, String)> {
+    match result {
+        Ok(Some(quote)) => Ok(Json(quote.into())),
+        Ok(None) => Err((StatusCode::NOT_FOUND, format!("{} not found", what))),
+        Err(e) => {
+            log::error!("Failed to fetch fiat quote: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to fetch fiat quote".to_string(),
+            ))
+        }
+    }
+}
+
+/// Get a fiat quote by ID
+pub async fn get_fiat_quote(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<FiatQuoteResponse>, (StatusCode, String)> {
+    let result = fetch_quote_by_id(&state, id).await;
+    not_found_or_error(result, format!("Fiat quote {}", id))
+}
+
+/// Get the fiat conversion of a submitted list (fiat and token amounts per recipient)
+pub async fn get_list_conversion(
+    State(state): State<Arc<AppState>>,
+    Path(list_id): Path<String>,
+) -> Result<Json<FiatQuoteResponse>, (StatusCode, String)> {
+    let result = sqlx::query_as::<_, FiatQuote>(&format!(
+        "SELECT {} FROM bulk_payment_fiat_quotes WHERE list_id = $1",
+        QUOTE_COLUMNS
+    ))
+    .bind(&list_id)
+    .fetch_optional(&state.db_pool)
+    .await;
+    not_found_or_error(result, format!("Fiat conversion for list {}", list_id))
+}
+
+/// Check a quote can back the given list
+///
+/// The list must pay exactly the converted amounts from the same treasury and token.
+pub fn validate_quote_for_list(
+    quote: &FiatQuote,
+    dao_id: &str,
+    token_id: &str,
+    payments: &[PaymentInput],
+) -> Result<(), String> {
+    if quote.list_id.is_some() {
+        return Err(format!("Fiat quote {} is already used by a list", quote.id));
+    }
+    if quote.dao_id != dao_id || quote.token_id != token_id {
+        return Err(format!(
+            "Fiat quote {} was created for a different treasury or token",
+            quote.id
+        ));
+    }
+
+    let mut quoted: Vec<(&str, &str)> = quote
+        .payments
+        .0
+        .iter()
+        .map(|p| (p.recipient.as_str(), p.amount.as_str()))
+        .collect();
+    let mut listed: Vec<(&str, &str)> = payments
+        .iter()
+        .map(|p| (p.recipient.as_str(), p.amount.as_str()))
+        .collect();
+    quoted.sort_unstable();
+    listed.sort_unstable();
+
+    if quoted != listed {
+        return Err(format!(
+            "Payments do not match the amounts of fiat quote {}",
+            quote.id
+        ));
+    }
+    Ok(())
+}
+
+/// Load a quote for submission and make sure it matches the list
+pub async fn load_quote_for_list(
+    state: &AppState,
+    quote_id: i64,
+    dao_id: &str,
+    token_id: &str,
+    payments: &[PaymentInput],
+) -> Result<(), (StatusCode, String)> {
+    let quote = fetch_quote_by_id(state, quote_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to fetch fiat quote {}: {}", quote_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to fetch fiat quote".to_string(),
+            )
+        })?
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            format!("Fiat quote {} not found", quote_id),
+        ))?;
+
+    validate_quote_for_list(&quote, dao_id, token_id, payments)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))
+}
+
+/// Record which list a quote was used for
+pub async fn link_quote_to_list(pool: &sqlx::PgPool, quote_id: i64, list_id: &str) {
+    if let Err(e) = sqlx::query(
+        r#"
+        UPDATE bulk_payment_fiat_quotes
+        SET list_id = $2
+        WHERE id = $1 AND list_id IS NULL
+        "#,
+    )
+    .bind(quote_id)
+    .bind(list_id)
+    .execute(pool)
+    .await
+    {
+        log::error!(
+            "Failed to link fiat quote {} to list {}: {}",
+            quote_id,
+            list_id,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(recipient: &str, fiat_amount: &str) -> FiatPaymentInput {
+        FiatPaymentInput {
+            recipient: recipient.to_string(),
+            fiat_amount: fiat_amount.to_string(),
+        }
+    }
+
+    fn quote(payments: Vec<FiatPayment>) -> FiatQuote {
+        FiatQuote {
+            id: 1,
+            dao_id: "testdao.sputnik-dao.near".to_string(),
+            created_by: "alice.near".to_string(),
+            token_id: "native".to_string(),
+            token_symbol: "NEAR".to_string(),
+            token_decimals: 24,
+            currency: "USD".to_string(),
+            rate: BigDecimal::from_str("2.5").unwrap(),
+            price_date: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            quoted_at: DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            payments: SqlJson(payments),
+            total_fiat: BigDecimal::from_str("2000.00").unwrap(),
+            total_amount: BigDecimal::from_str("800000000000000000000000000").unwrap(),
+            list_id: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_price_token_id() {
+        assert_eq!(price_token_id("native"), "near");
+        assert_eq!(
+            price_token_id("nep141:btc.omft.near"),
+            "intents.near:nep141:btc.omft.near"
+        );
+        assert_eq!(
+            price_token_id("usdt.tether-token.near"),
+            "usdt.tether-token.near"
+        );
+    }
+
+    #[test]
+    fn test_convert_fiat_payments() {
+        let rate = BigDecimal::from_str("2.5").unwrap();
+        let (payments, total_fiat, total_amount) = convert_fiat_payments(
+            &[input("alice.near", "2000"), input("bob.near", "0.10")],
+            &rate,
+            24,
+        )
+        .unwrap();
+
+        assert_eq!(payments[0].amount, "800000000000000000000000000");
+        assert_eq!(payments[0].fiat_amount, "2000.00");
+        assert_eq!(payments[1].amount, "40000000000000000000000");
+        assert_eq!(total_fiat.to_string(), "2000.10");
+        assert_eq!(total_amount, 800_040_000_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn test_convert_rounds_down() {
+        // 1 USD at 3 USD per token with 6 decimals = 333333.33.. base units
+        let rate = BigDecimal::from_str("3").unwrap();
+        let (payments, _, _) =
+            convert_fiat_payments(&[input("alice.near", "1")], &rate, 6).unwrap();
+        assert_eq!(payments[0].amount, "333333");
+    }
+
+    #[test]
+    fn test_convert_rejects_invalid_amounts() {
+        let rate = BigDecimal::from_str("1").unwrap();
+        assert!(convert_fiat_payments(&[input("alice.near", "0")], &rate, 6).is_err());
+        assert!(convert_fiat_payments(&[input("alice.near", "-5")], &rate, 6).is_err());
+        assert!(convert_fiat_payments(&[input("alice.near", "1.005")], &rate, 6).is_err());
+        assert!(convert_fiat_payments(&[input("alice.near", "abc")], &rate, 6).is_err());
+        assert!(
+            convert_fiat_payments(&[input("alice.near", "1")], &BigDecimal::zero(), 6).is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_quote_for_list() {
+        let quote = quote(vec![FiatPayment {
+            recipient: "alice.near".to_string(),
+            fiat_amount: "2000.00".to_string(),
+            amount: "800000000000000000000000000".to_string(),
+        }]);
+        let payments = vec![PaymentInput {
+            recipient: "alice.near".to_string(),
+            amount: "800000000000000000000000000".to_string(),
+            token_id: None,
+        }];
+
+        assert!(
+            validate_quote_for_list(&quote, "testdao.sputnik-dao.near", "native", &payments)
+                .is_ok()
+        );
+        assert!(
+            validate_quote_for_list(&quote, "other.sputnik-dao.near", "native", &payments).is_err()
+        );
+
+        let tampered = vec![PaymentInput {
+            recipient: "alice.near".to_string(),
+            amount: "900000000000000000000000000".to_string(),
+            token_id: None,
+        }];
+        assert!(
+            validate_quote_for_list(&quote, "testdao.sputnik-dao.near", "native", &tampered)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_conversion_report() {
+        let quote = quote(vec![]);
+        assert_eq!(
+            conversion_report(&quote),
+            "2000.00 USD converted to 800 NEAR at 1 NEAR = 2.5 USD (price date 2026-03-01, quoted at 2026-03-01 12:00 UTC)"
+        );
+    }
+}
@@ -0,0 +1,680 @@
+//! Spreadsheet import of bulk payment lists
+//!
+//! A CSV or XLSX sheet with recipient, amount (in whole tokens) and an optional memo is
+//! checked row by row before anything is submitted: account ID and existence, token storage
+//! registration, duplicate recipients, amount precision against the token decimals, and the
+//! total against the treasury balance. Valid rows are split into lists of at most
+//! `MAX_RECIPIENTS_PER_BULK_PAYMENT` payments with their list IDs, ready for `submit-list`.
+
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use bigdecimal::{BigDecimal, Zero};
+use futures::{StreamExt, stream};
+use near_api::{AccountId, Contract, NetworkConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::submit::{
+    MAX_RECIPIENTS_PER_BULK_PAYMENT, MIXED_TOKEN_ID, PaymentInput, compute_list_hash, is_native,
+    metadata_contract,
+};
+use super::withdrawals::{is_poa_token, validate_recipient};
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::handlers::proposals::scraper::fetch_ft_metadata;
+use crate::handlers::user::balance::{fetch_ft_balance, fetch_intents_balance, fetch_near_balance};
+use crate::handlers::user::check_account_exists::account_exists;
+
+/// Most rows a single import may contain
+const MAX_IMPORT_ROWS: usize = 1000;
+
+/// Rows checked against the chain at the same time
+pub const LOOKUP_CONCURRENCY: usize = 8;
+
+/// XLSX files are zip archives
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+const RECIPIENT_HEADERS: &[&str] = &["recipient", "account", "account_id", "account id"];
+const AMOUNT_HEADERS: &[&str] = &["amount"];
+const MEMO_HEADERS: &[&str] = &["memo", "note", "description"];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportQuery {
+    pub dao_id: AccountId,
+    pub token_id: String,
+}
+
+/// A payment row as read from the sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetRow {
+    /// 1-based row number in the sheet
+    pub row: usize,
+    pub recipient: String,
+    /// Decimal amount in whole tokens, as entered
+    pub amount: String,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRowReport {
+    pub row: usize,
+    pub recipient: String,
+    pub amount: String,
+    pub memo: Option<String>,
+    /// Amount in token base units (when the amount is valid)
+    pub raw_amount: Option<String>,
+    pub account_exists: Option<bool>,
+    /// Whether the recipient has storage on the token contract (token lists only)
+    pub storage_registered: Option<bool>,
+    /// Problems that keep the row out of the lists
+    pub errors: Vec<String>,
+    /// Problems that don't block the payment but may need action
+    pub warnings: Vec<String>,
+}
+
+impl ImportRowReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// One list of valid rows, in the shape `submit-list` expects
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedList {
+    pub list_id: String,
+    pub token_id: String,
+    /// Total in token base units
+    pub total: String,
+    /// Sheet rows of the payments
+    pub rows: Vec<usize>,
+    pub payments: Vec<PaymentInput>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub dao_id: String,
+    pub token_id: String,
+    pub token_symbol: String,
+    pub token_decimals: u8,
+    pub rows: Vec<ImportRowReport>,
+    pub valid_rows: usize,
+    pub invalid_rows: usize,
+    /// Total of the valid rows in token base units
+    pub total: String,
+    /// Treasury balance of the token (None if it couldn't be fetched)
+    pub treasury_balance: Option<String>,
+    pub sufficient_balance: Option<bool>,
+    pub lists: Vec<ImportedList>,
+}
+
+/// Read the payment rows of a CSV or XLSX sheet (first worksheet)
+///
+/// A header row naming `recipient` and `amount` (and optionally `memo`) columns is used when
+/// present; otherwise the columns are recipient, amount, memo in that order. Blank rows are
+/// skipped.
+pub fn parse_sheet(body: &[u8]) -> Result<Vec<SheetRow>, String> {
+    let records = if body.starts_with(ZIP_MAGIC) {
+        read_xlsx(body)?
+    } else {
+        read_csv(body)?
+    };
+    rows_from_records(records)
+}
+
+/// Sheet records as (1-based row number, cells)
+type Records = Vec<(usize, Vec<String>)>;
+
+fn read_csv(body: &[u8]) -> Result<Records, String> {
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(body)
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| format!("Invalid CSV: {}", e))?;
+            // The record position includes the blank lines skipped before it
+            let line = record.position().map_or(0, |p| {
+                let start = (p.byte() as usize).min(body.len());
+                let blank = body[start..]
+                    .iter()
+                    .take_while(|b| matches!(b, b'\r' | b'\n'))
+                    .count();
+                body[..start + blank]
+                    .iter()
+                    .filter(|&&b| b == b'\n')
+                    .count()
+                    + 1
+            });
+            Ok((line, record.iter().map(str::to_string).collect()))
+        })
+        .collect()
+}
+
+fn read_xlsx(body: &[u8]) -> Result<Records, String> {
+    use calamine::{Reader, Xlsx};
+
+    let mut workbook = Xlsx::new(std::io::Cursor::new(body))
+        .map_err(|e: calamine::XlsxError| format!("Invalid XLSX: {}", e))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| "XLSX file has no worksheet".to_string())?
+        .map_err(|e| format!("Invalid XLSX: {}", e))?;
+
+    // Numeric cells are formatted without precision loss for typical amounts; amounts
+    // stored as text are taken as is
+    let first_row = range.start().map_or(0, |(row, _)| row as usize);
+    Ok(range
+        .rows()
+        .enumerate()
+        .map(|(index, row)| {
+            let cells = row
+                .iter()
+                .map(|cell| cell.to_string().trim().to_string())
+                .collect();
+            (first_row + index + 1, cells)
+        })
+        .collect())
+}
+
+struct Columns {
+    recipient: usize,
+    amount: usize,
+    memo: Option<usize>,
+}
+
+const POSITIONAL_COLUMNS: Columns = Columns {
+    recipient: 0,
+    amount: 1,
+    memo: Some(2),
+};
+
+fn find_column(record: &[String], names: &[&str]) -> Option<usize> {
+    record
+        .iter()
+        .position(|cell| names.contains(&cell.to_lowercase().as_str()))
+}
+
+/// Columns named by a header row, or None if the record isn't a header
+fn header_columns(record: &[String]) -> Option<Result<Columns, String>> {
+    let recipient = find_column(record, RECIPIENT_HEADERS);
+    let amount = find_column(record, AMOUNT_HEADERS);
+    if recipient.is_none() && amount.is_none() {
+        return None;
+    }
+
+    Some(match (recipient, amount) {
+        (Some(recipient), Some(amount)) => Ok(Columns {
+            recipient,
+            amount,
+            memo: find_column(record, MEMO_HEADERS),
+        }),
+        _ => Err("Header row needs both a recipient and an amount column".to_string()),
+    })
+}
+
+fn rows_from_records(records: Records) -> Result<Vec<SheetRow>, String> {
+    let mut records = records
+        .into_iter()
+        .filter(|(_, record)| record.iter().any(|cell| !cell.is_empty()))
+        .peekable();
+
+    let columns = match records.peek().and_then(|(_, first)| header_columns(first)) {
+        Some(columns) => {
+            records.next();
+            columns?
+        }
+        None => POSITIONAL_COLUMNS,
+    };
+
+    let cell = |record: &[String], index: usize| record.get(index).cloned().unwrap_or_default();
+    let rows: Vec<SheetRow> = records
+        .map(|(row, record)| SheetRow {
+            row,
+            recipient: cell(&record, columns.recipient),
+            amount: cell(&record, columns.amount),
+            memo: columns
+                .memo
+                .map(|memo| cell(&record, memo))
+                .filter(|memo| !memo.is_empty()),
+        })
+        .collect();
+
+    if rows.is_empty() {
+        return Err("The sheet has no payment rows".to_string());
+    }
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(format!(
+            "The sheet has {} payment rows, at most {} can be imported at once",
+            rows.len(),
+            MAX_IMPORT_ROWS
+        ));
+    }
+    Ok(rows)
+}
+
+/// Convert a decimal amount in whole tokens into base units
+pub fn parse_amount(amount: &str, decimals: u8) -> Result<u128, String> {
+    let value = BigDecimal::from_str(amount).map_err(|_| format!("Invalid amount '{}'", amount))?;
+    if value <= BigDecimal::zero() {
+        return Err("Amount must be positive".to_string());
+    }
+    if value.normalized().fractional_digit_count() > decimals as i64 {
+        return Err(format!(
+            "Amount has more than {} decimal places, the token's precision",
+            decimals
+        ));
+    }
+
+    let unit = BigDecimal::new(1.into(), -(decimals as i64));
+    (value * unit)
+        .with_scale(0)
+        .to_string()
+        .parse()
+        .map_err(|_| "Amount is too large for the token".to_string())
+}
+
+/// Checks that don't need the chain: recipient format (account ID, or origin chain address
+/// for PoA tokens), amount and duplicate recipients
+pub fn validate_rows(rows: &[SheetRow], token_id: &str, decimals: u8) -> Vec<ImportRowReport> {
+    let mut first_rows: HashMap<&str, usize> = HashMap::new();
+
+    rows.iter()
+        .map(|row| {
+            let mut report = ImportRowReport {
+                row: row.row,
+                recipient: row.recipient.clone(),
+                amount: row.amount.clone(),
+                memo: row.memo.clone(),
+                raw_amount: None,
+                account_exists: None,
+                storage_registered: None,
+                errors: Vec::new(),
+                warnings: Vec::new(),
+            };
+
+            if row.recipient.is_empty() {
+                report.errors.push("Recipient is missing".to_string());
+            } else if let Err(e) = validate_recipient(token_id, &row.recipient) {
+                report.errors.push(e);
+            } else if let Some(first) = first_rows.get(row.recipient.as_str()) {
+                report
+                    .errors
+                    .push(format!("Duplicate recipient, also on row {}", first));
+            } else {
+                first_rows.insert(&row.recipient, row.row);
+            }
+
+            match parse_amount(&row.amount, decimals) {
+                Ok(raw) => report.raw_amount = Some(raw.to_string()),
+                Err(e) => report.errors.push(e),
+            }
+
+            report
+        })
+        .collect()
+}
+
+/// NEP-145 registration of an account on a token contract
+pub async fn storage_registered(
+    network: &NetworkConfig,
+    token_contract: &AccountId,
+    account_id: &AccountId,
+) -> Result<bool, String> {
+    Contract(token_contract.clone())
+        .call_function(
+            "storage_balance_of",
+            serde_json::json!({ "account_id": account_id }),
+        )
+        .read_only::<Option<serde_json::Value>>()
+        .fetch_from(network)
+        .await
+        .map(|r| r.data.is_some())
+        .map_err(|e| format!("Failed to check storage registration: {}", e))
+}
+
+/// Account existence and token storage checks of a row
+async fn check_on_chain(
+    network: &NetworkConfig,
+    token_id: &str,
+    token_contract: &AccountId,
+    mut report: ImportRowReport,
+) -> ImportRowReport {
+    // Payments of PoA tokens go to an address on another chain
+    if is_poa_token(token_id) {
+        return report;
+    }
+    let Ok(account_id) = AccountId::from_str(&report.recipient) else {
+        return report;
+    };
+    let implicit = account_id.get_account_type().is_implicit();
+
+    match account_exists(network, &account_id).await {
+        Ok(exists) => {
+            report.account_exists = Some(exists);
+            if !exists && implicit {
+                report
+                    .warnings
+                    .push("Implicit account has not been used yet".to_string());
+            } else if !exists {
+                report.errors.push("Account does not exist".to_string());
+            }
+        }
+        Err(e) => {
+            log::warn!("Import: {}", e);
+            report
+                .warnings
+                .push("Could not check whether the account exists".to_string());
+        }
+    }
+
+    if is_native(token_id) {
+        return report;
+    }
+
+    match storage_registered(network, token_contract, &account_id).await {
+        Ok(registered) => {
+            report.storage_registered = Some(registered);
+            if !registered && token_id.starts_with("nep141:") {
+                report.warnings.push(format!(
+                    "Not registered with {}; the withdrawal will fail and be refunded",
+                    token_contract
+                ));
+            } else if !registered {
+                report.warnings.push(format!(
+                    "Not registered with {}; attach a storage budget to the list to register it",
+                    token_contract
+                ));
+            }
+        }
+        Err(e) => {
+            log::warn!("Import: {}", e);
+            report
+                .warnings
+                .push("Could not check token storage registration".to_string());
+        }
+    }
+
+    report
+}
+
+/// Split the valid rows into lists of at most `MAX_RECIPIENTS_PER_BULK_PAYMENT` payments
+pub fn split_into_lists(
+    dao_id: &str,
+    token_id: &str,
+    rows: &[ImportRowReport],
+) -> Vec<ImportedList> {
+    let valid: Vec<(usize, PaymentInput, u128)> = rows
+        .iter()
+        .filter(|row| row.is_valid())
+        .filter_map(|row| {
+            let raw = row.raw_amount.as_ref()?;
+            Some((
+                row.row,
+                PaymentInput {
+                    recipient: row.recipient.clone(),
+                    amount: raw.clone(),
+                    token_id: None,
+                },
+                raw.parse::<u128>().ok()?,
+            ))
+        })
+        .collect();
+
+    valid
+        .chunks(MAX_RECIPIENTS_PER_BULK_PAYMENT)
+        .map(|chunk| {
+            let payments: Vec<PaymentInput> = chunk.iter().map(|(_, p, _)| p.clone()).collect();
+            let total: u128 = chunk.iter().map(|(_, _, amount)| amount).sum();
+            ImportedList {
+                list_id: compute_list_hash(dao_id, token_id, &payments),
+                token_id: token_id.to_string(),
+                total: total.to_string(),
+                rows: chunk.iter().map(|(row, _, _)| *row).collect(),
+                payments,
+            }
+        })
+        .collect()
+}
+
+/// Treasury balance of the list token in base units
+async fn treasury_balance(
+    state: &Arc<AppState>,
+    dao_id: &AccountId,
+    token_id: &str,
+) -> Result<u128, String> {
+    let balance = if is_native(token_id) {
+        fetch_near_balance(state, dao_id.clone()).await?
+    } else if token_id.starts_with("nep141:") {
+        fetch_intents_balance(state, dao_id.clone(), token_id.to_string()).await?
+    } else {
+        let token: AccountId = token_id
+            .parse()
+            .map_err(|_| format!("Invalid token ID: {}", token_id))?;
+        fetch_ft_balance(state, dao_id.clone(), token).await?
+    };
+    Ok(balance.balance.0)
+}
+
+/// Validate a CSV or XLSX payment sheet and split it into bulk payment lists
+///
+/// The request body is the file itself; nothing is submitted.
+pub async fn import_list(
+    State(state): State<Arc<AppState>>,
+    _auth_user: AuthUser,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<ImportReport>, (StatusCode, String)> {
+    if query.token_id == MIXED_TOKEN_ID {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Imports pay a single token; choose the token of the sheet".to_string(),
+        ));
+    }
+
+    let sheet_rows = parse_sheet(&body).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let token_contract: AccountId = metadata_contract(&query.token_id).parse().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid token ID: {}", query.token_id),
+        )
+    })?;
+    let metadata = fetch_ft_metadata(&state.cache, &state.network, &token_contract)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to fetch metadata for {}: {}", token_contract, e);
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to fetch token metadata for {}", query.token_id),
+            )
+        })?;
+
+    let reports = validate_rows(&sheet_rows, &query.token_id, metadata.decimals);
+    let rows: Vec<ImportRowReport> = stream::iter(reports)
+        .map(|report| check_on_chain(&state.network, &query.token_id, &token_contract, report))
+        .buffered(LOOKUP_CONCURRENCY)
+        .collect()
+        .await;
+
+    let lists = split_into_lists(query.dao_id.as_str(), &query.token_id, &rows);
+    let total: u128 = lists
+        .iter()
+        .filter_map(|list| list.total.parse::<u128>().ok())
+        .sum();
+
+    let balance = treasury_balance(&state, &query.dao_id, &query.token_id)
+        .await
+        .inspect_err(|e| log::warn!("Import: {}", e))
+        .ok();
+
+    let valid_rows = rows.iter().filter(|row| row.is_valid()).count();
+    Ok(Json(ImportReport {
+        dao_id: query.dao_id.to_string(),
+        token_id: query.token_id,
+        token_symbol: metadata.symbol,
+        token_decimals: metadata.decimals,
+        invalid_rows: rows.len() - valid_rows,
+        valid_rows,
+        rows,
+        total: total.to_string(),
+        treasury_balance: balance.map(|b| b.to_string()),
+        sufficient_balance: balance.map(|b| total <= b),
+        lists,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sheet_row(row: usize, recipient: &str, amount: &str) -> SheetRow {
+        SheetRow {
+            row,
+            recipient: recipient.to_string(),
+            amount: amount.to_string(),
+            memo: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_csv_with_header() {
+        let csv = "Amount,Recipient,Memo\n1.5,alice.near,March salary\n\n\"2\", bob.near ,\n";
+        let rows = parse_sheet(csv.as_bytes()).unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                SheetRow {
+                    row: 2,
+                    recipient: "alice.near".to_string(),
+                    amount: "1.5".to_string(),
+                    memo: Some("March salary".to_string()),
+                },
+                sheet_row(4, "bob.near", "2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_csv_without_header() {
+        let rows = parse_sheet(b"alice.near,1\nbob.near,2,bonus").unwrap();
+        assert_eq!(rows[0], sheet_row(1, "alice.near", "1"));
+        assert_eq!(rows[1].memo.as_deref(), Some("bonus"));
+    }
+
+    #[test]
+    fn test_parse_xlsx() {
+        let xlsx = include_bytes!("../../../tests/test_data/bulk_payment_import/payments.xlsx");
+        let rows = parse_sheet(xlsx).unwrap();
+
+        // Numeric and text amount cells, with the blank row skipped
+        assert_eq!(
+            rows,
+            vec![
+                SheetRow {
+                    row: 2,
+                    recipient: "alice.near".to_string(),
+                    amount: "2.5".to_string(),
+                    memo: Some("March salary".to_string()),
+                },
+                sheet_row(4, "bob.near", "1.25"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_xlsx_errors() {
+        let mut truncated = ZIP_MAGIC.to_vec();
+        truncated.extend_from_slice(b"not a workbook");
+        assert!(
+            parse_sheet(&truncated)
+                .unwrap_err()
+                .starts_with("Invalid XLSX")
+        );
+    }
+
+    #[test]
+    fn test_parse_sheet_errors() {
+        assert!(parse_sheet(b"recipient,memo\nalice.near,x").is_err());
+        assert!(parse_sheet(b"recipient,amount\n").is_err());
+
+        let too_many = "alice.near,1\n".repeat(MAX_IMPORT_ROWS + 1);
+        assert!(parse_sheet(too_many.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1.5", 6), Ok(1_500_000));
+        assert_eq!(parse_amount("0.000001", 6), Ok(1));
+        assert_eq!(parse_amount("2.500000000", 6), Ok(2_500_000));
+        assert_eq!(parse_amount("1", 24), Ok(10u128.pow(24)));
+
+        assert!(parse_amount("0.0000001", 6).is_err());
+        assert!(parse_amount("0", 6).is_err());
+        assert!(parse_amount("-1", 6).is_err());
+        assert!(parse_amount("1,000", 6).is_err());
+        // u128::MAX is ~3.4e38
+        assert!(parse_amount("1000000000000000", 24).is_err());
+    }
+
+    #[test]
+    fn test_validate_rows() {
+        let rows = vec![
+            sheet_row(2, "alice.near", "1"),
+            sheet_row(3, "Not An Account", "1"),
+            sheet_row(4, "alice.near", "2"),
+            sheet_row(5, "bob.near", "1.2345678"),
+            sheet_row(6, "", "1"),
+        ];
+        let reports = validate_rows(&rows, "usdc.near", 6);
+
+        assert!(reports[0].is_valid());
+        assert_eq!(reports[0].raw_amount.as_deref(), Some("1000000"));
+        assert_eq!(
+            reports[1].errors,
+            vec!["Invalid account ID: Not An Account"]
+        );
+        assert_eq!(
+            reports[2].errors,
+            vec!["Duplicate recipient, also on row 2"]
+        );
+        assert_eq!(reports[3].errors.len(), 1);
+        assert!(reports[3].raw_amount.is_none());
+        assert_eq!(reports[4].errors, vec!["Recipient is missing"]);
+    }
+
+    #[test]
+    fn test_split_into_lists() {
+        let rows: Vec<SheetRow> = (0..60)
+            .map(|i| sheet_row(i + 2, &format!("user{}.near", i), "1"))
+            .collect();
+        let mut reports = validate_rows(&rows, "usdc.near", 6);
+        reports[10]
+            .errors
+            .push("Account does not exist".to_string());
+
+        let lists = split_into_lists("dao.sputnik-dao.near", "usdc.near", &reports);
+
+        assert_eq!(
+            lists.iter().map(|l| l.payments.len()).collect::<Vec<_>>(),
+            vec![25, 25, 9]
+        );
+        assert_eq!(lists[0].total, "25000000");
+        assert!(!lists[0].rows.contains(&12));
+        assert_eq!(
+            lists[2].list_id,
+            compute_list_hash("dao.sputnik-dao.near", "usdc.near", &lists[2].payments)
+        );
+    }
+}
@@ -1,5 +1,10 @@
+pub mod events;
+pub mod fiat;
 pub mod get;
+pub mod import;
+pub mod runs;
 pub mod storage_credits;
 pub mod submit;
 pub mod transactions;
+pub mod withdrawals;
 pub mod worker;
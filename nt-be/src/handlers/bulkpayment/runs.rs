@@ -0,0 +1,691 @@
+//! Payout runs
+//!
+//! A run is one logical bulk payment of up to `MAX_PAYMENTS_PER_RUN` recipients. The backend
+//! splits it into contract lists ("chunks") of at most `MAX_RECIPIENTS_PER_BULK_PAYMENT`
+//! payments, each funded by its own DAO proposal. `submit-list` links a chunk to its proposal
+//! once the list is on chain, and the run view tracks the amounts of all chunks from the
+//! contract.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use futures::{FutureExt, StreamExt};
+use near_api::Contract;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json as SqlJson;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use super::submit::{
+    MAX_RECIPIENTS_PER_BULK_PAYMENT, PaymentInput, compute_list_hash, validate_payment_tokens,
+    validate_recipients,
+};
+use crate::AppState;
+use crate::auth::{AuthUser, require_policy_member};
+use crate::handlers::proposals::scraper::{BatchPaymentResponse, fetch_batch_payment_list};
+
+/// Most payments a single run may contain
+pub const MAX_PAYMENTS_PER_RUN: usize = 5000;
+
+/// Number of chunks queried concurrently when loading a run
+const CHUNK_STATE_CONCURRENCY: usize = 5;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRunRequest {
+    pub dao_id: String,
+    pub token_id: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub payments: Vec<PaymentInput>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct RunRow {
+    id: i64,
+    dao_id: String,
+    created_by: String,
+    token_id: String,
+    description: Option<String>,
+    payment_count: i32,
+    created_at: DateTime<Utc>,
+}
+
+const RUN_COLUMNS: &str =
+    "id, dao_id, created_by, token_id, description, payment_count, created_at";
+
+#[derive(Debug, sqlx::FromRow)]
+struct RunListRow {
+    chunk_index: i32,
+    list_id: String,
+    payments: SqlJson<Vec<PaymentInput>>,
+    proposal_id: Option<i64>,
+    submitted_at: Option<DateTime<Utc>>,
+}
+
+/// Amounts of one token in base units
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenAmounts {
+    pub total: u128,
+    pub paid: u128,
+    /// Not paid yet: chunks not submitted or approved, and payments waiting for payout
+    pub pending: u128,
+    /// Payments whose transfer failed (refunded to the treasury)
+    pub failed: u128,
+    /// Payments of rejected or cancelled lists
+    pub cancelled: u128,
+}
+
+impl TokenAmounts {
+    fn add(&mut self, other: &TokenAmounts) {
+        self.total += other.total;
+        self.paid += other.paid;
+        self.pending += other.pending;
+        self.failed += other.failed;
+        self.cancelled += other.cancelled;
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunAmounts {
+    pub token_id: String,
+    pub total: String,
+    pub paid: String,
+    pub pending: String,
+    pub failed: String,
+    pub cancelled: String,
+}
+
+fn run_amounts(amounts: &BTreeMap<String, TokenAmounts>) -> Vec<RunAmounts> {
+    amounts
+        .iter()
+        .map(|(token_id, a)| RunAmounts {
+            token_id: token_id.clone(),
+            total: a.total.to_string(),
+            paid: a.paid.to_string(),
+            pending: a.pending.to_string(),
+            failed: a.failed.to_string(),
+            cancelled: a.cancelled.to_string(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunChunk {
+    pub chunk_index: i32,
+    pub list_id: String,
+    pub proposal_id: Option<i64>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    /// "NotSubmitted", the contract list status ("Pending", "Approved", "Rejected"),
+    /// "Closed", "Cancelled" or "Unknown" when the contract couldn't be queried
+    pub status: String,
+    pub payments: Vec<PaymentInput>,
+    pub amounts: Vec<RunAmounts>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunResponse {
+    pub id: i64,
+    pub dao_id: String,
+    pub created_by: String,
+    pub token_id: String,
+    pub description: Option<String>,
+    pub payment_count: i32,
+    pub created_at: DateTime<Utc>,
+    /// "Draft" (no chunk submitted), "InProgress", "Completed" or "Cancelled"
+    pub status: String,
+    /// Totals across all chunks, per token
+    pub amounts: Vec<RunAmounts>,
+    pub chunks: Vec<RunChunk>,
+}
+
+/// Receipt of a list pruned by `close_list` (contract response, snake_case)
+#[derive(Debug, Deserialize)]
+pub struct ListReceiptResponse {
+    pub token_id: String,
+    pub submitter: String,
+    pub payment_count: u32,
+    pub totals: Vec<ReceiptTotal>,
+    /// Block height of the last payment
+    pub completed_at_block: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReceiptTotal {
+    pub token_id: String,
+    pub amount: String,
+}
+
+/// On-chain state of a chunk
+#[derive(Debug)]
+pub enum ChunkState {
+    NotSubmitted,
+    Listed(BatchPaymentResponse),
+    Closed(ListReceiptResponse),
+    /// Submitted, but the list was cancelled
+    Cancelled,
+    /// The contract couldn't be queried
+    Unknown,
+}
+
+impl ChunkState {
+    fn status(&self) -> &str {
+        match self {
+            ChunkState::NotSubmitted => "NotSubmitted",
+            ChunkState::Listed(list) => list.status.as_str(),
+            ChunkState::Closed(_) => "Closed",
+            ChunkState::Cancelled => "Cancelled",
+            ChunkState::Unknown => "Unknown",
+        }
+    }
+}
+
+fn parse_amount(amount: &str) -> u128 {
+    amount.parse().unwrap_or(0)
+}
+
+/// Amounts of a chunk per token, from its payments and on-chain state
+pub fn chunk_amounts(
+    run_token_id: &str,
+    payments: &[PaymentInput],
+    state: &ChunkState,
+) -> BTreeMap<String, TokenAmounts> {
+    let mut amounts: BTreeMap<String, TokenAmounts> = BTreeMap::new();
+    for payment in payments {
+        let token_id = payment.token_id.as_deref().unwrap_or(run_token_id);
+        amounts.entry(token_id.to_string()).or_default().total += parse_amount(&payment.amount);
+    }
+
+    match state {
+        ChunkState::NotSubmitted | ChunkState::Unknown => {
+            amounts.values_mut().for_each(|a| a.pending = a.total);
+        }
+        ChunkState::Cancelled => {
+            amounts.values_mut().for_each(|a| a.cancelled = a.total);
+        }
+        ChunkState::Listed(list) if list.status == "Rejected" => {
+            amounts.values_mut().for_each(|a| a.cancelled = a.total);
+        }
+        ChunkState::Listed(list) => {
+            for payment in &list.payments {
+                let token_id = payment.token_id.as_deref().unwrap_or(&list.token_id);
+                let entry = amounts.entry(token_id.to_string()).or_default();
+                let amount = parse_amount(&payment.amount);
+                if payment.status.get("Paid").is_some() {
+                    entry.paid += amount;
+                } else if payment.status.get("Failed").is_some() {
+                    entry.failed += amount;
+                } else {
+                    entry.pending += amount;
+                }
+            }
+        }
+        ChunkState::Closed(receipt) => {
+            for total in &receipt.totals {
+                amounts.entry(total.token_id.clone()).or_default().paid +=
+                    parse_amount(&total.amount);
+            }
+            // Receipts only count what was paid; the rest of a closed list failed
+            amounts
+                .values_mut()
+                .for_each(|a| a.failed = a.total.saturating_sub(a.paid));
+        }
+    }
+
+    amounts
+}
+
+/// Overall status of a run from its chunk states and totals
+fn run_status(states: &[ChunkState], amounts: &BTreeMap<String, TokenAmounts>) -> &'static str {
+    if states.iter().all(|s| matches!(s, ChunkState::NotSubmitted)) {
+        "Draft"
+    } else if amounts.values().any(|a| a.pending > 0) {
+        "InProgress"
+    } else if amounts.values().all(|a| a.paid == 0 && a.failed == 0) {
+        "Cancelled"
+    } else {
+        "Completed"
+    }
+}
+
+/// Check the payments of a run
+pub fn validate_run_payments(token_id: &str, payments: &[PaymentInput]) -> Result<(), String> {
+    if payments.is_empty() {
+        return Err("A run needs at least one payment".to_string());
+    }
+    if payments.len() > MAX_PAYMENTS_PER_RUN {
+        return Err(format!(
+            "A run can have at most {} payments",
+            MAX_PAYMENTS_PER_RUN
+        ));
+    }
+    validate_payment_tokens(token_id, payments)?;
+    validate_recipients(token_id, payments)?;
+
+    for payment in payments {
+        if !payment
+            .amount
+            .parse::<u128>()
+            .is_ok_and(|amount| amount > 0)
+        {
+            return Err(format!("Invalid amount for {}", payment.recipient));
+        }
+    }
+    Ok(())
+}
+
+/// Split run payments into lists of at most `MAX_RECIPIENTS_PER_BULK_PAYMENT`, with the
+/// list_id each chunk is submitted under
+pub fn split_run(
+    dao_id: &str,
+    token_id: &str,
+    payments: &[PaymentInput],
+) -> Result<Vec<(String, Vec<PaymentInput>)>, String> {
+    let chunks: Vec<(String, Vec<PaymentInput>)> = payments
+        .chunks(MAX_RECIPIENTS_PER_BULK_PAYMENT)
+        .map(|chunk| (compute_list_hash(dao_id, token_id, chunk), chunk.to_vec()))
+        .collect();
+
+    let mut list_ids = HashSet::new();
+    if !chunks.iter().all(|(list_id, _)| list_ids.insert(list_id)) {
+        return Err("The run contains identical chunks of payments".to_string());
+    }
+    Ok(chunks)
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    log::error!("Bulk payment run database error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Database error".to_string(),
+    )
+}
+
+/// Query the contract for the state of a submitted chunk
+async fn fetch_chunk_state(state: &AppState, chunk: &RunListRow) -> ChunkState {
+    if chunk.submitted_at.is_none() {
+        return ChunkState::NotSubmitted;
+    }
+
+    match fetch_batch_payment_list(
+        &state.network,
+        &chunk.list_id,
+        &state.bulk_payment_contract_id,
+    )
+    .await
+    {
+        Ok(list) => return ChunkState::Listed(list),
+        Err(e) if !e.to_string().contains("Payment list not found") => {
+            log::warn!("Failed to fetch list {}: {}", chunk.list_id, e);
+            return ChunkState::Unknown;
+        }
+        Err(_) => {}
+    }
+
+    // Lists disappear when they are closed (leaving a receipt) or cancelled
+    match fetch_list_receipt(state, &chunk.list_id).await {
+        Ok(Some(receipt)) => ChunkState::Closed(receipt),
+        Ok(None) => ChunkState::Cancelled,
+        Err(e) => {
+            log::warn!("{}", e);
+            ChunkState::Unknown
+        }
+    }
+}
+
+/// Receipt of a list pruned by `close_list`, `None` if the contract has no receipt for it
+pub async fn fetch_list_receipt(
+    state: &AppState,
+    list_id: &str,
+) -> Result<Option<ListReceiptResponse>, String> {
+    match Contract(state.bulk_payment_contract_id.clone())
+        .call_function(
+            "view_list_receipt",
+            serde_json::json!({ "list_id": list_id }),
+        )
+        .read_only::<ListReceiptResponse>()
+        .fetch_from(&state.network)
+        .await
+    {
+        Ok(receipt) => Ok(Some(receipt.data)),
+        Err(e) if e.to_string().contains("List receipt not found") => Ok(None),
+        Err(e) => Err(format!(
+            "Failed to fetch receipt of list {}: {}",
+            list_id, e
+        )),
+    }
+}
+
+fn build_run_response(
+    run: RunRow,
+    chunks: Vec<RunListRow>,
+    states: Vec<ChunkState>,
+) -> RunResponse {
+    let mut totals: BTreeMap<String, TokenAmounts> = BTreeMap::new();
+    let mut run_chunks = Vec::with_capacity(chunks.len());
+
+    for (chunk, chunk_state) in chunks.into_iter().zip(&states) {
+        let amounts = chunk_amounts(&run.token_id, &chunk.payments.0, chunk_state);
+        for (token_id, amount) in &amounts {
+            totals.entry(token_id.clone()).or_default().add(amount);
+        }
+        run_chunks.push(RunChunk {
+            chunk_index: chunk.chunk_index,
+            list_id: chunk.list_id,
+            proposal_id: chunk.proposal_id,
+            submitted_at: chunk.submitted_at,
+            status: chunk_state.status().to_string(),
+            payments: chunk.payments.0,
+            amounts: run_amounts(&amounts),
+        });
+    }
+
+    RunResponse {
+        status: run_status(&states, &totals).to_string(),
+        amounts: run_amounts(&totals),
+        id: run.id,
+        dao_id: run.dao_id,
+        created_by: run.created_by,
+        token_id: run.token_id,
+        description: run.description,
+        payment_count: run.payment_count,
+        created_at: run.created_at,
+        chunks: run_chunks,
+    }
+}
+
+/// Create a payout run and split it into contract lists
+///
+/// Each chunk in the response carries the list_id and payments for its DAO proposal and
+/// `submit-list` call.
+pub async fn create_run(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<CreateRunRequest>,
+) -> Result<Json<RunResponse>, (StatusCode, String)> {
+    require_policy_member(&state, &request.dao_id, &auth_user.account_id).await?;
+    validate_run_payments(&request.token_id, &request.payments)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let chunks = split_run(&request.dao_id, &request.token_id, &request.payments)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let mut tx = state.db_pool.begin().await.map_err(db_error)?;
+
+    let run = sqlx::query_as::<_, RunRow>(&format!(
+        r#"
+        INSERT INTO bulk_payment_runs (dao_id, created_by, token_id, description, payment_count)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {}
+        "#,
+        RUN_COLUMNS
+    ))
+    .bind(&request.dao_id)
+    .bind(&auth_user.account_id)
+    .bind(&request.token_id)
+    .bind(&request.description)
+    .bind(request.payments.len() as i32)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(db_error)?;
+
+    let mut rows = Vec::with_capacity(chunks.len());
+    for (index, (list_id, payments)) in chunks.into_iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO bulk_payment_run_lists (run_id, chunk_index, list_id, payments)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(run.id)
+        .bind(index as i32)
+        .bind(&list_id)
+        .bind(SqlJson(&payments))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            if e.as_database_error()
+                .is_some_and(|db| db.is_unique_violation())
+            {
+                (
+                    StatusCode::CONFLICT,
+                    format!("List {} is already part of another run", list_id),
+                )
+            } else {
+                db_error(e)
+            }
+        })?;
+
+        rows.push(RunListRow {
+            chunk_index: index as i32,
+            list_id,
+            payments: SqlJson(payments),
+            proposal_id: None,
+            submitted_at: None,
+        });
+    }
+
+    tx.commit().await.map_err(db_error)?;
+
+    let states = rows.iter().map(|_| ChunkState::NotSubmitted).collect();
+    Ok(Json(build_run_response(run, rows, states)))
+}
+
+/// Get a payout run with the status of all its chunks (DAO members only)
+pub async fn get_run(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<RunResponse>, (StatusCode, String)> {
+    let run = sqlx::query_as::<_, RunRow>(&format!(
+        "SELECT {} FROM bulk_payment_runs WHERE id = $1",
+        RUN_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(db_error)?
+    .ok_or((
+        StatusCode::NOT_FOUND,
+        format!("Payout run {} not found", id),
+    ))?;
+    require_policy_member(&state, &run.dao_id, &auth_user.account_id).await?;
+
+    let chunks = sqlx::query_as::<_, RunListRow>(
+        r#"
+        SELECT chunk_index, list_id, payments, proposal_id, submitted_at
+        FROM bulk_payment_run_lists
+        WHERE run_id = $1
+        ORDER BY chunk_index
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    // Queried a few at a time, then put back in chunk order
+    let requests: Vec<_> = chunks
+        .iter()
+        .enumerate()
+        .map(|(index, chunk)| fetch_chunk_state(&state, chunk).map(move |s| (index, s)))
+        .collect();
+    let mut states: Vec<(usize, ChunkState)> = futures::stream::iter(requests)
+        .buffer_unordered(CHUNK_STATE_CONCURRENCY)
+        .collect()
+        .await;
+    states.sort_by_key(|(index, _)| *index);
+    let states = states.into_iter().map(|(_, state)| state).collect();
+    Ok(Json(build_run_response(run, chunks, states)))
+}
+
+/// Link a submitted list to its run chunk (if it belongs to a run) and funding proposal
+///
+/// Failures are logged only - the list is already on-chain at this point.
+pub async fn link_submitted_list(pool: &sqlx::PgPool, list_id: &str, proposal_id: u64) {
+    if let Err(e) = sqlx::query(
+        r#"
+        UPDATE bulk_payment_run_lists
+        SET proposal_id = $2, submitted_at = NOW()
+        WHERE list_id = $1
+        "#,
+    )
+    .bind(list_id)
+    .bind(proposal_id as i64)
+    .execute(pool)
+    .await
+    {
+        log::error!("Failed to link list {} to its run: {}", list_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::proposals::scraper::BatchPayment;
+
+    fn payment(recipient: &str, amount: &str) -> PaymentInput {
+        PaymentInput {
+            recipient: recipient.to_string(),
+            amount: amount.to_string(),
+            token_id: None,
+        }
+    }
+
+    fn run_payments(count: usize) -> Vec<PaymentInput> {
+        (0..count)
+            .map(|i| payment(&format!("user{}.near", i), "100"))
+            .collect()
+    }
+
+    #[test]
+    fn test_split_run() {
+        let payments = run_payments(60);
+        let chunks = split_run("dao.sputnik-dao.near", "native", &payments).unwrap();
+
+        assert_eq!(
+            chunks.iter().map(|(_, p)| p.len()).collect::<Vec<_>>(),
+            vec![25, 25, 10]
+        );
+        assert_eq!(
+            chunks[1].0,
+            compute_list_hash("dao.sputnik-dao.near", "native", &payments[25..50])
+        );
+    }
+
+    #[test]
+    fn test_split_run_rejects_identical_chunks() {
+        let payments: Vec<PaymentInput> = run_payments(25).into_iter().cycle().take(50).collect();
+        assert!(split_run("dao.sputnik-dao.near", "native", &payments).is_err());
+    }
+
+    #[test]
+    fn test_validate_run_payments() {
+        assert!(validate_run_payments("native", &run_payments(1000)).is_ok());
+        assert!(validate_run_payments("native", &[]).is_err());
+        assert!(validate_run_payments("native", &run_payments(MAX_PAYMENTS_PER_RUN + 1)).is_err());
+        assert!(validate_run_payments("native", &[payment("user.near", "0")]).is_err());
+        assert!(validate_run_payments("native", &[payment("Bad Account", "1")]).is_err());
+    }
+
+    fn listed(status: &str, statuses: &[serde_json::Value]) -> ChunkState {
+        ChunkState::Listed(BatchPaymentResponse {
+            token_id: "native".to_string(),
+            submitter: "dao.sputnik-dao.near".parse().unwrap(),
+            status: status.to_string(),
+            payments: statuses
+                .iter()
+                .enumerate()
+                .map(|(i, status)| BatchPayment {
+                    recipient: format!("user{}.near", i),
+                    amount: "100".to_string(),
+                    status: status.clone(),
+                    token_id: None,
+                })
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn test_chunk_amounts() {
+        let payments = run_payments(3);
+
+        let amounts = chunk_amounts("native", &payments, &ChunkState::NotSubmitted);
+        assert_eq!(amounts["native"].pending, 300);
+
+        let state = listed(
+            "Approved",
+            &[
+                serde_json::json!({ "Paid": { "block_height": 1 } }),
+                serde_json::json!({ "Failed": { "error": "Transfer failed" } }),
+                serde_json::json!("Pending"),
+            ],
+        );
+        let amounts = chunk_amounts("native", &payments, &state);
+        assert_eq!(
+            amounts["native"],
+            TokenAmounts {
+                total: 300,
+                paid: 100,
+                pending: 100,
+                failed: 100,
+                cancelled: 0,
+            }
+        );
+
+        let amounts = chunk_amounts("native", &payments, &listed("Rejected", &[]));
+        assert_eq!(amounts["native"].cancelled, 300);
+
+        let receipt = ChunkState::Closed(ListReceiptResponse {
+            token_id: "native".to_string(),
+            submitter: "dao.sputnik-dao.near".to_string(),
+            payment_count: 3,
+            totals: vec![ReceiptTotal {
+                token_id: "native".to_string(),
+                amount: "200".to_string(),
+            }],
+            completed_at_block: 150_000_000,
+        });
+        let amounts = chunk_amounts("native", &payments, &receipt);
+        assert_eq!(
+            (amounts["native"].paid, amounts["native"].failed),
+            (200, 100)
+        );
+    }
+
+    #[test]
+    fn test_run_status() {
+        let pending = BTreeMap::from([(
+            "native".to_string(),
+            TokenAmounts {
+                total: 100,
+                pending: 100,
+                ..Default::default()
+            },
+        )]);
+        assert_eq!(run_status(&[ChunkState::NotSubmitted], &pending), "Draft");
+        assert_eq!(
+            run_status(&[ChunkState::NotSubmitted, ChunkState::Unknown], &pending),
+            "InProgress"
+        );
+
+        let paid = BTreeMap::from([(
+            "native".to_string(),
+            TokenAmounts {
+                total: 100,
+                paid: 100,
+                ..Default::default()
+            },
+        )]);
+        assert_eq!(run_status(&[ChunkState::Unknown], &paid), "Completed");
+        assert_eq!(
+            run_status(&[ChunkState::Cancelled], &BTreeMap::new()),
+            "Cancelled"
+        );
+    }
+}
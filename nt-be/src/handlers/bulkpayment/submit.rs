@@ -1,23 +1,34 @@
 use axum::{Json, extract::State, http::StatusCode};
+use futures::{StreamExt, stream};
 use near_api::{
-    NearGas, NearToken, Transaction,
+    AccountId, NearGas, NearToken, Transaction,
     types::{Action, tokens::STORAGE_COST_PER_BYTE, transaction::actions::FunctionCallAction},
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use super::import::{LOOKUP_CONCURRENCY, storage_registered};
+use super::withdrawals::validate_recipient;
+use crate::handlers::fees::ledger::{FeeSource, PlatformTransaction, record_platform_transaction};
 use crate::handlers::subscription::plans::get_account_plan_info;
 use crate::{AppState, auth::AuthUser};
 
-const MAX_RECIPIENTS_PER_BULK_PAYMENT: usize = 25;
+pub const MAX_RECIPIENTS_PER_BULK_PAYMENT: usize = 25;
 const BYTES_PER_RECORD: u128 = 216;
 
-#[derive(Debug, Deserialize)]
+/// List token of a mixed-token list, where every payment names its own token
+pub const MIXED_TOKEN_ID: &str = "mixed";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PaymentInput {
     pub recipient: String,
     pub amount: String,
+    /// Token of this payment (mixed-token lists only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -28,6 +39,12 @@ pub struct SubmitListRequest {
     pub dao_contract_id: String,
     pub token_id: String,
     pub payments: Vec<PaymentInput>,
+    /// Fiat quote the amounts were converted with (fiat-denominated lists)
+    #[serde(default)]
+    pub fiat_quote_id: Option<i64>,
+    /// Proposal funding the list; checked directly instead of scanning the last 100 proposals
+    #[serde(default)]
+    pub proposal_id: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -37,20 +54,90 @@ pub struct SubmitListResponse {
     pub list_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// yoctoNEAR to attach with `attach_storage_budget` so the payout can register the
+    /// FT recipients that have no storage on their token contract
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_budget: Option<String>,
+}
+
+/// Whether a bulk payment token ID refers to native NEAR
+pub fn is_native(token_id: &str) -> bool {
+    token_id == "native" || token_id == "near"
+}
+
+/// Contract that holds the metadata of a bulk payment token
+/// ("native" -> "near", "nep141:usdc.omft.near" -> "usdc.omft.near")
+pub fn metadata_contract(token_id: &str) -> &str {
+    if is_native(token_id) {
+        "near"
+    } else {
+        token_id.strip_prefix("nep141:").unwrap_or(token_id)
+    }
+}
+
+/// Check per-payment tokens against the list token
+///
+/// Mixed lists need a token on every payment, other lists must not have any.
+pub fn validate_payment_tokens(token_id: &str, payments: &[PaymentInput]) -> Result<(), String> {
+    let mixed = token_id == MIXED_TOKEN_ID;
+    for payment in payments {
+        match (&payment.token_id, mixed) {
+            (None, true) => {
+                return Err(format!(
+                    "Payment to {} needs a token_id in a mixed list",
+                    payment.recipient
+                ));
+            }
+            (Some(_), false) => {
+                return Err("Payment token_id is only allowed in mixed lists".to_string());
+            }
+            (Some(payment_token), true) if payment_token == MIXED_TOKEN_ID => {
+                return Err(format!("Invalid token for {}", payment.recipient));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Check every recipient for the token of its payment: an origin chain address for PoA
+/// tokens, a NEAR account otherwise
+pub fn validate_recipients(token_id: &str, payments: &[PaymentInput]) -> Result<(), String> {
+    payments.iter().try_for_each(|payment| {
+        validate_recipient(
+            payment.token_id.as_deref().unwrap_or(token_id),
+            &payment.recipient,
+        )
+    })
+}
+
+/// Contract JSON of a payment (`token_id` only for mixed lists)
+fn payment_json(payment: &PaymentInput) -> serde_json::Value {
+    let mut value = serde_json::json!({
+        "amount": payment.amount,
+        "recipient": payment.recipient,
+    });
+    if let Some(token_id) = &payment.token_id {
+        value["token_id"] = serde_json::Value::String(token_id.clone());
+    }
+    value
 }
 
 /// Compute the SHA-256 hash of the payment list for verification
-fn compute_list_hash(submitter_id: &str, token_id: &str, payments: &[PaymentInput]) -> String {
+pub fn compute_list_hash(submitter_id: &str, token_id: &str, payments: &[PaymentInput]) -> String {
+    compute_salted_list_hash(submitter_id, token_id, payments, None)
+}
+
+/// Compute the list hash including a salt, which gives a list with the same payments
+/// (e.g. the next occurrence of a payment schedule) a new list ID
+pub fn compute_salted_list_hash(
+    submitter_id: &str,
+    token_id: &str,
+    payments: &[PaymentInput],
+    salt: Option<&str>,
+) -> String {
     // Sort payments by recipient for deterministic hashing
-    let mut sorted_payments: Vec<_> = payments
-        .iter()
-        .map(|p| {
-            serde_json::json!({
-                "amount": p.amount,
-                "recipient": p.recipient,
-            })
-        })
-        .collect();
+    let mut sorted_payments: Vec<_> = payments.iter().map(payment_json).collect();
     sorted_payments.sort_by(|a, b| {
         a["recipient"]
             .as_str()
@@ -58,11 +145,20 @@ fn compute_list_hash(submitter_id: &str, token_id: &str, payments: &[PaymentInpu
             .cmp(b["recipient"].as_str().unwrap())
     });
 
-    let canonical = serde_json::json!({
-        "payments": sorted_payments,
-        "submitter": submitter_id,
-        "token_id": token_id,
-    });
+    // Keys in alphabetical order, like the contract's canonical form
+    let canonical = match salt {
+        Some(salt) => serde_json::json!({
+            "payments": sorted_payments,
+            "salt": salt,
+            "submitter": submitter_id,
+            "token_id": token_id,
+        }),
+        None => serde_json::json!({
+            "payments": sorted_payments,
+            "submitter": submitter_id,
+            "token_id": token_id,
+        }),
+    };
 
     let canonical_str = serde_json::to_string(&canonical).unwrap();
     let mut hasher = Sha256::new();
@@ -108,12 +204,48 @@ struct ActionCall {
     gas: String,
 }
 
+/// Fetch a single DAO proposal (None if the DAO has no proposal with this ID)
+async fn fetch_proposal(
+    state: &AppState,
+    dao_contract_id: &str,
+    proposal_id: u64,
+) -> Option<Proposal> {
+    near_api::Contract(dao_contract_id.parse().ok()?)
+        .call_function("get_proposal", serde_json::json!({ "id": proposal_id }))
+        .read_only::<Proposal>()
+        .fetch_from(&state.network)
+        .await
+        .inspect_err(|e| {
+            log::warn!(
+                "Failed to fetch proposal {} of {}: {}",
+                proposal_id,
+                dao_contract_id,
+                e
+            )
+        })
+        .ok()
+        .map(|r| r.data)
+}
+
 /// Verify that a pending DAO proposal exists with the given list_id
+///
+/// With a `proposal_id` only that proposal is checked, otherwise the last 100 proposals
+/// are scanned. Returns the ID of the matching proposal.
 async fn verify_dao_proposal(
     state: &AppState,
     dao_contract_id: &str,
     list_id: &str,
-) -> Result<bool, (StatusCode, String)> {
+    proposal_id: Option<u64>,
+) -> Result<Option<u64>, (StatusCode, String)> {
+    if let Some(proposal_id) = proposal_id {
+        return Ok(fetch_proposal(state, dao_contract_id, proposal_id)
+            .await
+            .filter(|proposal| {
+                proposal_funds_list(state.bulk_payment_contract_id.as_str(), proposal, list_id)
+            })
+            .map(|proposal| proposal.id));
+    }
+
     // Get the last proposal ID to know the total number of proposals
     let last_proposal_id: u64 = near_api::Contract(dao_contract_id.parse().unwrap())
         .call_function("get_last_proposal_id", ())
@@ -155,73 +287,73 @@ async fn verify_dao_proposal(
         })?
         .data;
 
-    // Look for a pending proposal with this list_id
-    for proposal in proposals {
-        if proposal.status != "InProgress" {
-            continue;
-        }
+    Ok(proposals
+        .iter()
+        .find(|proposal| {
+            proposal_funds_list(state.bulk_payment_contract_id.as_str(), proposal, list_id)
+        })
+        .map(|proposal| proposal.id))
+}
 
-        // Check if this is a FunctionCall proposal
-        if let ProposalKind::FunctionCall { function_call } = &proposal.kind {
-            // First, check the description for the list_id (fastest check)
-            if proposal.description.contains(list_id) {
-                return Ok(true);
-            }
+/// Whether a pending proposal funds the list: its description names the list, or one of its
+/// actions is a funding leg for it
+///
+/// A mixed-token list is funded by one leg per token, possibly spread over several actions
+/// and proposals, so any single leg is enough to recognise a funding proposal.
+fn proposal_funds_list(bulk_payment_contract_id: &str, proposal: &Proposal, list_id: &str) -> bool {
+    if proposal.status != "InProgress" {
+        return false;
+    }
 
-            // Check each action for bulk payment related methods
-            for action in &function_call.actions {
-                // Case 1: Direct approve_list call (NEAR tokens)
-                if action.method_name == "approve_list"
-                    && function_call.receiver_id == state.bulk_payment_contract_id.as_str()
-                {
-                    // Decode the base64 args and check for matching list_id
-                    if let Ok(decoded) = base64::Engine::decode(
-                        &base64::engine::general_purpose::STANDARD,
-                        &action.args,
-                    ) && let Ok(args) = serde_json::from_slice::<serde_json::Value>(&decoded)
-                        && let Some(proposal_list_id) = args.get("list_id").and_then(|v| v.as_str())
-                        && proposal_list_id == list_id
-                    {
-                        return Ok(true);
-                    }
-                }
-
-                // Case 2: ft_transfer_call (FT tokens)
-                if action.method_name == "ft_transfer_call" {
-                    // Decode the base64 args and check for matching list_id in msg field
-                    if let Ok(decoded) = base64::Engine::decode(
-                        &base64::engine::general_purpose::STANDARD,
-                        &action.args,
-                    ) && let Ok(args) = serde_json::from_slice::<serde_json::Value>(&decoded)
-                        && let Some(receiver_id) = args.get("receiver_id").and_then(|v| v.as_str())
-                        && receiver_id == state.bulk_payment_contract_id.as_str()
-                        && let Some(msg) = args.get("msg").and_then(|v| v.as_str())
-                        && msg == list_id
-                    {
-                        return Ok(true);
-                    }
-                }
-
-                // Case 3: mt_transfer_call (MT tokens / Intents)
-                if action.method_name == "mt_transfer_call" {
-                    // Decode the base64 args and check for matching list_id in msg field
-                    if let Ok(decoded) = base64::Engine::decode(
-                        &base64::engine::general_purpose::STANDARD,
-                        &action.args,
-                    ) && let Ok(args) = serde_json::from_slice::<serde_json::Value>(&decoded)
-                        && let Some(receiver_id) = args.get("receiver_id").and_then(|v| v.as_str())
-                        && receiver_id == state.bulk_payment_contract_id.as_str()
-                        && let Some(msg) = args.get("msg").and_then(|v| v.as_str())
-                        && msg == list_id
-                    {
-                        return Ok(true);
-                    }
-                }
-            }
-        }
+    // Check if this is a FunctionCall proposal
+    let ProposalKind::FunctionCall { function_call } = &proposal.kind else {
+        return false;
+    };
+
+    // First, check the description for the list_id (fastest check)
+    if proposal.description.contains(list_id) {
+        return true;
     }
 
-    Ok(false)
+    function_call.actions.iter().any(|action| {
+        is_funding_leg(
+            bulk_payment_contract_id,
+            &function_call.receiver_id,
+            action,
+            list_id,
+        )
+    })
+}
+
+/// Whether an action deposits funds for the list on the bulk payment contract:
+/// `approve_list` (NEAR), `ft_transfer_call` (NEP-141 tokens) or `mt_transfer_call`
+/// (intents tokens). The contract takes one token per transfer, so `mt_batch_transfer_call`
+/// does not count.
+fn is_funding_leg(
+    bulk_payment_contract_id: &str,
+    receiver_id: &str,
+    action: &ActionCall,
+    list_id: &str,
+) -> bool {
+    let Some(args) =
+        base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &action.args)
+            .ok()
+            .and_then(|decoded| serde_json::from_slice::<serde_json::Value>(&decoded).ok())
+    else {
+        return false;
+    };
+
+    match action.method_name.as_str() {
+        "approve_list" => {
+            receiver_id == bulk_payment_contract_id
+                && args.get("list_id").and_then(|v| v.as_str()) == Some(list_id)
+        }
+        "ft_transfer_call" | "mt_transfer_call" => {
+            args.get("receiver_id").and_then(|v| v.as_str()) == Some(bulk_payment_contract_id)
+                && args.get("msg").and_then(|v| v.as_str()) == Some(list_id)
+        }
+        _ => false,
+    }
 }
 
 fn calculate_storage_cost(num_records: u128) -> NearToken {
@@ -232,19 +364,8 @@ fn calculate_storage_cost(num_records: u128) -> NearToken {
         .saturating_div(10)
 }
 
-fn serialize_args(
-    args: &serde_json::Value,
-) -> Result<Vec<u8>, (StatusCode, Json<SubmitListResponse>)> {
-    serde_json::to_vec(args).map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(SubmitListResponse {
-                success: false,
-                list_id: None,
-                error: Some(format!("Failed to serialize args: {}", e)),
-            }),
-        )
-    })
+fn serialize_args(args: &serde_json::Value) -> Result<Vec<u8>, String> {
+    serde_json::to_vec(args).map_err(|e| format!("Failed to serialize args: {}", e))
 }
 
 /// Submit a payment list to the bulk payment contract
@@ -270,6 +391,21 @@ pub async fn submit_list(
                     "Maximum number of recipients per bulk payment is {}",
                     MAX_RECIPIENTS_PER_BULK_PAYMENT
                 )),
+                storage_budget: None,
+            }),
+        ));
+    }
+
+    if let Err(msg) = validate_payment_tokens(&request.token_id, &request.payments)
+        .and_then(|_| validate_recipients(&request.token_id, &request.payments))
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(SubmitListResponse {
+                success: false,
+                list_id: None,
+                error: Some(msg),
+                storage_budget: None,
             }),
         ));
     }
@@ -288,6 +424,29 @@ pub async fn submit_list(
                     "Provided list_id ({}) does not match computed hash ({})",
                     request.list_id, computed_hash
                 )),
+                storage_budget: None,
+            }),
+        ));
+    }
+
+    // Step 1b: Fiat-denominated lists must pay exactly the quoted amounts
+    if let Some(quote_id) = request.fiat_quote_id
+        && let Err((status, msg)) = super::fiat::load_quote_for_list(
+            &state,
+            quote_id,
+            &request.dao_contract_id,
+            &request.token_id,
+            &request.payments,
+        )
+        .await
+    {
+        return Err((
+            status,
+            Json(SubmitListResponse {
+                success: false,
+                list_id: None,
+                error: Some(msg),
+                storage_budget: None,
             }),
         ));
     }
@@ -307,6 +466,7 @@ pub async fn submit_list(
                     success: false,
                     list_id: None,
                     error: Some(format!("Failed to check subscription status: {}", e)),
+                    storage_budget: None,
                 }),
             )
         })?;
@@ -324,6 +484,7 @@ pub async fn submit_list(
                             "Insufficient batch payment credits. Your treasury has {} credits remaining. Please upgrade your plan or wait for the monthly reset.",
                             plan.batch_payment_credits
                         )),
+                        storage_budget: None,
                     }),
                 ));
             }
@@ -342,11 +503,16 @@ pub async fn submit_list(
     }
 
     // Step 3: Verify that a pending DAO proposal exists with this list_id
-    match verify_dao_proposal(&state, &request.dao_contract_id, &request.list_id).await {
-        Ok(true) => {
-            // Proposal exists, proceed
-        }
-        Ok(false) => {
+    let proposal_id = match verify_dao_proposal(
+        &state,
+        &request.dao_contract_id,
+        &request.list_id,
+        request.proposal_id,
+    )
+    .await
+    {
+        Ok(Some(proposal_id)) => proposal_id,
+        Ok(None) => {
             return Err((
                 StatusCode::BAD_REQUEST,
                 Json(SubmitListResponse {
@@ -356,6 +522,7 @@ pub async fn submit_list(
                         "No pending DAO proposal found with list_id: {}",
                         request.list_id
                     )),
+                    storage_budget: None,
                 }),
             ));
         }
@@ -366,132 +533,270 @@ pub async fn submit_list(
                     success: false,
                     list_id: None,
                     error: Some(msg),
+                    storage_budget: None,
                 }),
             ));
         }
-    }
+    };
 
     // Step 4: Submit the list to the contract
-    let payments: Vec<serde_json::Value> = request
-        .payments
+    if let Err(e) = submit_list_to_contract(
+        &state,
+        &request.list_id,
+        &request.submitter_id,
+        &request.dao_contract_id,
+        &request.token_id,
+        &request.payments,
+        None,
+    )
+    .await
+    {
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(SubmitListResponse {
+                success: false,
+                list_id: None,
+                error: Some(e),
+                storage_budget: None,
+            }),
+        ));
+    }
+
+    // Step 5: Decrement credits using shared subscription function
+    log::info!(
+        "Bulk payment submitted successfully for treasury {}. Decrementing credits...",
+        request.dao_contract_id
+    );
+    decrement_batch_payment_credits(&state.db_pool, &request.dao_contract_id).await;
+
+    if let Some(quote_id) = request.fiat_quote_id {
+        super::fiat::link_quote_to_list(&state.db_pool, quote_id, &request.list_id).await;
+    }
+    super::runs::link_submitted_list(&state.db_pool, &request.list_id, proposal_id).await;
+
+    // Step 6: Add list to the payout worker queue for processing
+    // This ensures the worker will poll this list and process payments once approved
+    super::worker::add_pending_list(request.list_id.clone()).await;
+
+    // Step 7: Report the storage budget the list needs; the list is submitted either way
+    let storage_budget =
+        match required_storage_budget(&state, &request.token_id, &request.payments).await {
+            Ok(budget) if !budget.is_zero() => Some(budget.as_yoctonear().to_string()),
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!(
+                    "Failed to compute storage budget of list {}: {}",
+                    request.list_id,
+                    e
+                );
+                None
+            }
+        };
+
+    Ok(Json(SubmitListResponse {
+        success: true,
+        list_id: Some(request.list_id),
+        error: None,
+        storage_budget,
+    }))
+}
+
+/// Minimum NEP-145 storage deposit of a token contract
+async fn storage_balance_min(state: &AppState, token_contract: &AccountId) -> Result<u128, String> {
+    let bounds = near_api::Contract(token_contract.clone())
+        .call_function("storage_balance_bounds", ())
+        .read_only::<serde_json::Value>()
+        .fetch_from(&state.network)
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to fetch storage bounds of {}: {}",
+                token_contract, e
+            )
+        })?
+        .data;
+
+    bounds["min"]
+        .as_str()
+        .and_then(|min| min.parse().ok())
+        .ok_or_else(|| format!("Invalid storage bounds of {}: {}", token_contract, bounds))
+}
+
+/// NEAR `attach_storage_budget` needs so `payout_batch` can register every FT recipient of
+/// the list that has no storage on its token contract
+///
+/// Only plain NEP-141 payments are checked, like the contract does; NEAR and intents
+/// payments need no registration.
+pub async fn required_storage_budget(
+    state: &AppState,
+    token_id: &str,
+    payments: &[PaymentInput],
+) -> Result<NearToken, String> {
+    let mut recipients: Vec<(AccountId, AccountId)> = Vec::new();
+    for payment in payments {
+        let token = payment.token_id.as_deref().unwrap_or(token_id);
+        if is_native(token) || token.starts_with("nep141:") {
+            continue;
+        }
+        let token_contract: AccountId = token
+            .parse()
+            .map_err(|_| format!("Invalid token ID: {}", token))?;
+        let recipient: AccountId = payment
+            .recipient
+            .parse()
+            .map_err(|_| format!("Invalid recipient: {}", payment.recipient))?;
+        if !recipients.contains(&(token_contract.clone(), recipient.clone())) {
+            recipients.push((token_contract, recipient));
+        }
+    }
+
+    let checks: Vec<_> = recipients
         .iter()
-        .map(|p| {
-            serde_json::json!({
-                "recipient": p.recipient,
-                "amount": p.amount,
-            })
+        .map(|(token_contract, recipient)| {
+            storage_registered(&state.network, token_contract, recipient)
         })
         .collect();
+    let registered: Vec<Result<bool, String>> = stream::iter(checks)
+        .buffered(LOOKUP_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut minimums: HashMap<&AccountId, u128> = HashMap::new();
+    let mut budget: u128 = 0;
+    for ((token_contract, _), registered) in recipients.iter().zip(registered) {
+        if registered? {
+            continue;
+        }
+        let min = match minimums.get(token_contract) {
+            Some(min) => *min,
+            None => {
+                let min = storage_balance_min(state, token_contract).await?;
+                minimums.insert(token_contract, min);
+                min
+            }
+        };
+        budget = budget.saturating_add(min);
+    }
+
+    Ok(NearToken::from_yoctonear(budget))
+}
 
-    let execution_result = Transaction::construct(
-        state.bulk_payment_contract_id.clone(),
-        state.bulk_payment_contract_id.clone(),
+/// Buy storage for the DAO and submit the payment list to the bulk payment contract
+///
+/// Signed by the bulk payment contract key so the list can be submitted on behalf of the DAO.
+pub async fn submit_list_to_contract(
+    state: &AppState,
+    list_id: &str,
+    submitter_id: &str,
+    dao_contract_id: &str,
+    token_id: &str,
+    payments: &[PaymentInput],
+    salt: Option<&str>,
+) -> Result<(), String> {
+    let payments: Vec<serde_json::Value> = payments.iter().map(payment_json).collect();
+
+    let buy_storage_args = serialize_args(&serde_json::json!({
+        "num_records": payments.len() as u64,
+        "beneficiary_account_id": dao_contract_id,
+    }))?;
+    let storage_cost = calculate_storage_cost(payments.len() as u128);
+    let submit_list_args = serialize_args(&serde_json::json!({
+        "list_id": list_id,
+        "token_id": token_id,
+        "payments": payments,
+        "submitter_id": submitter_id,
+        "salt": salt,
+    }))?;
+
+    // The contract submits the list to itself, so only its own keys can sign
+    let network = &state.network;
+    let execution_result = state
+        .bulk_payment_pool
+        .send_primary(move |contract| {
+            let transaction =
+                Transaction::construct(contract.account_id.clone(), contract.account_id)
+                    .add_action(Action::FunctionCall(Box::new(FunctionCallAction {
+                        method_name: "buy_storage".to_string(),
+                        args: buy_storage_args.clone(),
+                        gas: NearGas::from_tgas(100),
+                        deposit: storage_cost,
+                    })))
+                    .add_action(Action::FunctionCall(Box::new(FunctionCallAction {
+                        method_name: "submit_list".to_string(),
+                        args: submit_list_args.clone(),
+                        gas: NearGas::from_tgas(200),
+                        deposit: NearToken::from_yoctonear(0),
+                    })))
+                    .with_signer(contract.signer);
+            async move { transaction.send_to(network).await }
+        })
+        .await;
+
+    if let Ok(result) = &execution_result {
+        record_platform_transaction(
+            &state.db_pool,
+            PlatformTransaction {
+                transaction_hash: &result.outcome().transaction_hash.to_string(),
+                signer_id: state.bulk_payment_contract_id.as_str(),
+                source: FeeSource::ListSubmission,
+                dao_id: Some(dao_contract_id),
+                on_behalf_of: None,
+                list_id: Some(list_id),
+            },
+        )
+        .await;
+    }
+
+    match execution_result {
+        // Check if the transaction execution succeeded
+        Ok(result) => result.into_result().map(|_| ()).map_err(|e| {
+            log::error!("Contract execution failed: {:?}", e);
+            format!("Contract execution failed: {}", e)
+        }),
+        Err(e) => {
+            log::error!("Failed to submit list to contract: {:?}", e);
+            Err(format!("Failed to submit list: {}", e))
+        }
+    }
+}
+
+/// Decrement the treasury's batch payment credits after a successful submission
+///
+/// Failures are logged only - the list is already on-chain at this point.
+pub async fn decrement_batch_payment_credits(pool: &sqlx::PgPool, dao_contract_id: &str) {
+    let db_result = sqlx::query_as::<_, (i32,)>(
+        r#"
+        UPDATE monitored_accounts
+        SET batch_payment_credits = GREATEST(batch_payment_credits - 1, 0),
+            updated_at = NOW()
+        WHERE account_id = $1
+        RETURNING batch_payment_credits
+        "#,
     )
-    .add_action(Action::FunctionCall(Box::new(FunctionCallAction {
-        method_name: "buy_storage".to_string(),
-        args: serialize_args(&serde_json::json!({
-            "num_records": payments.len() as u64,
-            "beneficiary_account_id": request.dao_contract_id.clone(),
-        }))?,
-        gas: NearGas::from_tgas(100),
-        deposit: calculate_storage_cost(payments.len() as u128),
-    })))
-    .add_action(Action::FunctionCall(Box::new(FunctionCallAction {
-        method_name: "submit_list".to_string(),
-        args: serialize_args(&serde_json::json!({
-            "list_id": request.list_id,
-            "token_id": request.token_id,
-            "payments": payments,
-            "submitter_id": request.submitter_id,
-        }))?,
-        gas: NearGas::from_tgas(200),
-        deposit: NearToken::from_yoctonear(0),
-    })))
-    .with_signer(state.bulk_payment_signer.clone())
-    .send_to(&state.network)
+    .bind(dao_contract_id)
+    .fetch_optional(pool)
     .await;
 
-    match execution_result {
-        Ok(result) => {
-            // Check if the transaction execution succeeded
-            match result.into_result() {
-                Ok(_) => {
-                    // Step 5: Decrement credits using shared subscription function
-                    log::info!(
-                        "Bulk payment submitted successfully for treasury {}. Decrementing credits...",
-                        request.dao_contract_id
-                    );
-
-                    let db_result = sqlx::query_as::<_, (i32,)>(
-                        r#"
-                UPDATE monitored_accounts
-                SET batch_payment_credits = GREATEST(batch_payment_credits - 1, 0),
-                    updated_at = NOW()
-                WHERE account_id = $1
-                RETURNING batch_payment_credits
-                "#,
-                    )
-                    .bind(&request.dao_contract_id)
-                    .fetch_optional(&state.db_pool)
-                    .await;
-
-                    match db_result {
-                        Ok(Some((new_credits,))) => {
-                            log::info!(
-                                "Successfully decremented credits for treasury {}. New balance: {}",
-                                request.dao_contract_id,
-                                new_credits
-                            );
-                        }
-                        Ok(None) => {
-                            log::warn!(
-                                "Treasury {} not found in monitored_accounts, credits not decremented",
-                                request.dao_contract_id
-                            );
-                        }
-                        Err(e) => {
-                            log::error!(
-                                "Failed to decrement batch payment credits for {}: {}",
-                                request.dao_contract_id,
-                                e
-                            );
-                            // Don't fail the request if DB update fails - contract submission succeeded
-                        }
-                    }
-
-                    // Step 5: Add list to the payout worker queue for processing
-                    // This ensures the worker will poll this list and process payments once approved
-                    super::worker::add_pending_list(request.list_id.clone()).await;
-
-                    Ok(Json(SubmitListResponse {
-                        success: true,
-                        list_id: Some(request.list_id),
-                        error: None,
-                    }))
-                }
-                Err(e) => {
-                    log::error!("Contract execution failed: {:?}", e);
-                    Err((
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(SubmitListResponse {
-                            success: false,
-                            list_id: None,
-                            error: Some(format!("Contract execution failed: {}", e)),
-                        }),
-                    ))
-                }
-            }
+    match db_result {
+        Ok(Some((new_credits,))) => {
+            log::info!(
+                "Successfully decremented credits for treasury {}. New balance: {}",
+                dao_contract_id,
+                new_credits
+            );
+        }
+        Ok(None) => {
+            log::warn!(
+                "Treasury {} not found in monitored_accounts, credits not decremented",
+                dao_contract_id
+            );
         }
         Err(e) => {
-            log::error!("Failed to submit list to contract: {:?}", e);
-            Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(SubmitListResponse {
-                    success: false,
-                    list_id: None,
-                    error: Some(format!("Failed to submit list: {}", e)),
-                }),
-            ))
+            log::error!(
+                "Failed to decrement batch payment credits for {}: {}",
+                dao_contract_id,
+                e
+            );
         }
     }
 }
@@ -506,10 +811,12 @@ mod tests {
             PaymentInput {
                 recipient: "bob.near".to_string(),
                 amount: "1000000000000000000000000".to_string(),
+                token_id: None,
             },
             PaymentInput {
                 recipient: "alice.near".to_string(),
                 amount: "2000000000000000000000000".to_string(),
+                token_id: None,
             },
         ];
 
@@ -519,15 +826,23 @@ mod tests {
         let hash2 = compute_list_hash("testdao.sputnik-dao.near", "native", &payments);
         assert_eq!(hash1, hash2);
 
+        // The bulk payment contract recomputes the hash on submit and checks the same vector
+        assert_eq!(
+            hash1,
+            "f05ca9436f5249224ab62d4c7819bffcf96cb9125a4b4f8fe477462b794c47b7"
+        );
+
         // Different order should produce the same hash (sorted by recipient)
         let payments_reversed = vec![
             PaymentInput {
                 recipient: "alice.near".to_string(),
                 amount: "2000000000000000000000000".to_string(),
+                token_id: None,
             },
             PaymentInput {
                 recipient: "bob.near".to_string(),
                 amount: "1000000000000000000000000".to_string(),
+                token_id: None,
             },
         ];
         let hash3 = compute_list_hash("testdao.sputnik-dao.near", "native", &payments_reversed);
@@ -535,5 +850,155 @@ mod tests {
 
         // Hash should be 64 characters (SHA-256 hex)
         assert_eq!(hash1.len(), 64);
+
+        // Same vector as the contract's salted list test
+        assert_eq!(
+            compute_salted_list_hash(
+                "testdao.sputnik-dao.near",
+                "native",
+                &payments,
+                Some("schedule:42:2026-03-01T00:00:00+00:00"),
+            ),
+            "de53ec268fd3930ffb597a455f87b7a49a0af6da0be281a7a370fa92f918c955"
+        );
+    }
+
+    #[test]
+    fn test_compute_mixed_list_hash() {
+        let payments = vec![
+            PaymentInput {
+                recipient: "bob.near".to_string(),
+                amount: "1000000".to_string(),
+                token_id: Some("usdt.tether-token.near".to_string()),
+            },
+            PaymentInput {
+                recipient: "alice.near".to_string(),
+                amount: "2000000000000000000000000".to_string(),
+                token_id: Some("native".to_string()),
+            },
+        ];
+
+        // Same vector as the contract's mixed list test
+        assert_eq!(
+            compute_list_hash("testdao.sputnik-dao.near", MIXED_TOKEN_ID, &payments),
+            "9f0696c864b19ecce5244c52690369899f2cdf606849014763c00efc4d88fe1d"
+        );
+        assert!(validate_payment_tokens(MIXED_TOKEN_ID, &payments).is_ok());
+        assert!(validate_payment_tokens("native", &payments).is_err());
+
+        let untokened = vec![PaymentInput {
+            token_id: None,
+            ..payments[0].clone()
+        }];
+        assert!(validate_payment_tokens(MIXED_TOKEN_ID, &untokened).is_err());
+        assert!(validate_payment_tokens("native", &untokened).is_ok());
+    }
+
+    fn funding_proposal(receiver_id: &str, actions: &[(&str, serde_json::Value)]) -> Proposal {
+        let actions: Vec<serde_json::Value> = actions
+            .iter()
+            .map(|(method_name, args)| {
+                serde_json::json!({
+                    "method_name": method_name,
+                    "args": base64::Engine::encode(
+                        &base64::engine::general_purpose::STANDARD,
+                        args.to_string(),
+                    ),
+                    "deposit": "1",
+                    "gas": "150000000000000",
+                })
+            })
+            .collect();
+
+        serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "proposer": "alice.near",
+            "description": "Fund payments",
+            "kind": { "FunctionCall": { "receiver_id": receiver_id, "actions": actions } },
+            "status": "InProgress",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_proposal_funds_mixed_list() {
+        let bulk = "bulkpayment.near";
+        let list_id = "ab".repeat(32);
+        let transfer_call = serde_json::json!({
+            "receiver_id": bulk,
+            "amount": "1000000",
+            "msg": list_id,
+        });
+
+        // One leg per token of a mixed list, each in its own proposal
+        let near_leg = funding_proposal(
+            bulk,
+            &[("approve_list", serde_json::json!({ "list_id": list_id }))],
+        );
+        let ft_leg = funding_proposal(
+            "usdt.tether-token.near",
+            &[
+                (
+                    "storage_deposit",
+                    serde_json::json!({ "account_id": bulk, "registration_only": true }),
+                ),
+                ("ft_transfer_call", transfer_call.clone()),
+            ],
+        );
+        let intents_leg = funding_proposal(
+            "intents.near",
+            &[(
+                "mt_transfer_call",
+                serde_json::json!({
+                    "receiver_id": bulk,
+                    "token_id": "nep141:btc.omft.near",
+                    "amount": "1000",
+                    "msg": list_id,
+                }),
+            )],
+        );
+        for proposal in [&near_leg, &ft_leg, &intents_leg] {
+            assert!(proposal_funds_list(bulk, proposal, &list_id));
+        }
+
+        // mt_on_transfer rejects batches, so a batch transfer would fail on execution
+        let batch_leg = funding_proposal(
+            "intents.near",
+            &[(
+                "mt_batch_transfer_call",
+                serde_json::json!({
+                    "receiver_id": bulk,
+                    "token_ids": ["nep141:btc.omft.near", "nep141:eth.omft.near"],
+                    "amounts": ["1000", "2000"],
+                    "msg": list_id,
+                }),
+            )],
+        );
+        assert!(!proposal_funds_list(bulk, &batch_leg, &list_id));
+
+        // approve_list only counts on the bulk payment contract, transfers only to it
+        let wrong_contract = funding_proposal(
+            "other.near",
+            &[("approve_list", serde_json::json!({ "list_id": list_id }))],
+        );
+        assert!(!proposal_funds_list(bulk, &wrong_contract, &list_id));
+        let wrong_receiver = funding_proposal(
+            "usdt.tether-token.near",
+            &[(
+                "ft_transfer_call",
+                serde_json::json!({ "receiver_id": "other.near", "amount": "1", "msg": list_id }),
+            )],
+        );
+        assert!(!proposal_funds_list(bulk, &wrong_receiver, &list_id));
+        assert!(!proposal_funds_list(bulk, &ft_leg, &"cd".repeat(32)));
+
+        let decided = Proposal {
+            status: "Approved".to_string(),
+            ..funding_proposal(
+                bulk,
+                &[("approve_list", serde_json::json!({ "list_id": list_id }))],
+            )
+        };
+        assert!(!proposal_funds_list(bulk, &decided, &list_id));
     }
 }
@@ -8,6 +8,8 @@ use near_jsonrpc_client::methods;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use super::events::{PaymentEvent, fetch_payment_events};
+use super::runs::{ListReceiptResponse, fetch_list_receipt};
 use crate::{
     AppState,
     utils::cache::{CacheKey, CacheTier},
@@ -20,6 +22,12 @@ pub struct PaymentTransaction {
     pub recipient: String,
     pub amount: String,
     pub block_height: u64,
+    /// Token of the payment in a mixed-token list
+    #[serde(default, alias = "token_id", skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
+    /// Transaction of the payment, from its recorded `payment_sent` event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -40,6 +48,8 @@ pub struct ListStatus {
     pub total_payments: u32,
     pub processed_payments: u32,
     pub pending_payments: u32,
+    /// Processed payments whose transfer failed (refunded to the treasury)
+    pub failed_payments: u32,
 }
 
 #[derive(Debug, Serialize)]
@@ -110,6 +120,9 @@ struct ContractPaymentRecord {
     recipient: String,
     amount: String,
     status: ContractPaymentStatus,
+    /// Token of the payment in a mixed-token list
+    #[serde(default)]
+    token_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -118,6 +131,7 @@ struct ContractPaymentRecord {
 enum ContractPaymentStatus {
     Pending(String),
     Paid { Paid: PaidStatus },
+    Failed { Failed: serde_json::Value },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -126,8 +140,12 @@ struct PaidStatus {
 }
 
 impl ContractPaymentStatus {
-    fn is_paid(&self) -> bool {
-        matches!(self, ContractPaymentStatus::Paid { .. })
+    fn is_pending(&self) -> bool {
+        matches!(self, ContractPaymentStatus::Pending(_))
+    }
+
+    fn is_failed(&self) -> bool {
+        matches!(self, ContractPaymentStatus::Failed { .. })
     }
 
     fn block_height(&self) -> Option<u64> {
@@ -166,8 +184,17 @@ pub async fn get_list_status(
     match result {
         Ok(list) => {
             let total = list.payments.len() as u32;
-            let processed = list.payments.iter().filter(|p| p.status.is_paid()).count() as u32;
-            let pending = total - processed;
+            let pending = list
+                .payments
+                .iter()
+                .filter(|p| p.status.is_pending())
+                .count() as u32;
+            let failed = list
+                .payments
+                .iter()
+                .filter(|p| p.status.is_failed())
+                .count() as u32;
+            let processed = total - pending;
 
             Ok(Json(ListStatusResponse {
                 success: true,
@@ -177,10 +204,28 @@ pub async fn get_list_status(
                     total_payments: total,
                     processed_payments: processed,
                     pending_payments: pending,
+                    failed_payments: failed,
                 }),
                 error: None,
             }))
         }
+        Err((_, msg)) if msg.contains("Payment list not found") => {
+            match closed_list_status(&state, &list_id).await {
+                Ok(list) => Ok(Json(ListStatusResponse {
+                    success: true,
+                    list: Some(list),
+                    error: None,
+                })),
+                Err((status, msg)) => Err((
+                    status,
+                    Json(ListStatusResponse {
+                        success: false,
+                        list: None,
+                        error: Some(msg),
+                    }),
+                )),
+            }
+        }
         Err((status, msg)) => Err((
             status,
             Json(ListStatusResponse {
@@ -192,6 +237,45 @@ pub async fn get_list_status(
     }
 }
 
+/// Receipt of a list that is no longer stored, or NOT_FOUND if it was never closed
+async fn closed_list_receipt(
+    state: &AppState,
+    list_id: &str,
+) -> Result<ListReceiptResponse, (StatusCode, String)> {
+    fetch_list_receipt(state, list_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                format!("Payment list {} not found", list_id),
+            )
+        })
+}
+
+/// Status of a list pruned by `close_list`; its failed payments are counted from the
+/// recorded `payment_failed` events
+async fn closed_list_status(
+    state: &AppState,
+    list_id: &str,
+) -> Result<ListStatus, (StatusCode, String)> {
+    let receipt = closed_list_receipt(state, list_id).await?;
+    let failed = load_payment_events(state, list_id)
+        .await
+        .iter()
+        .filter(|e| e.event == "payment_failed")
+        .count() as u32;
+
+    Ok(ListStatus {
+        list_id: list_id.to_string(),
+        status: "Closed".to_string(),
+        total_payments: receipt.payment_count,
+        processed_payments: receipt.payment_count,
+        pending_payments: 0,
+        failed_payments: failed,
+    })
+}
+
 /// Get all payment transactions for a list
 pub async fn get_transactions(
     State(state): State<Arc<AppState>>,
@@ -220,11 +304,16 @@ pub async fn get_transactions(
         .await;
 
     match result {
-        Ok(transactions) => Ok(Json(TransactionsResponse {
-            success: true,
-            transactions: Some(transactions),
-            error: None,
-        })),
+        Ok(mut transactions) => {
+            let events = load_payment_events(&state, &list_id).await;
+            link_transaction_hashes(&mut transactions, &events);
+
+            Ok(Json(TransactionsResponse {
+                success: true,
+                transactions: Some(transactions),
+                error: None,
+            }))
+        }
         Err((status, msg)) => Err((
             status,
             Json(TransactionsResponse {
@@ -236,15 +325,53 @@ pub async fn get_transactions(
     }
 }
 
+/// A paid bulk payment with the transaction it was sent in
+#[derive(Debug, Clone)]
+pub struct ResolvedPayment {
+    /// Submitter of the list (the paying DAO)
+    pub submitter: String,
+    pub recipient: String,
+    /// Token of the payment (the list token unless the list is mixed)
+    pub token_id: String,
+    pub amount: String,
+    pub block_height: u64,
+    pub transaction_hash: String,
+}
+
 /// Look up the transaction hash for a specific payment recipient
 pub async fn get_transaction_hash(
     State(state): State<Arc<AppState>>,
     Path((list_id, recipient)): Path<(String, String)>,
 ) -> Result<Json<TransactionHashResponse>, (StatusCode, Json<TransactionHashResponse>)> {
+    match resolve_payment(&state, &list_id, &recipient).await {
+        Ok(payment) => Ok(Json(TransactionHashResponse {
+            success: true,
+            transaction_hash: Some(payment.transaction_hash),
+            block_height: Some(payment.block_height),
+            error: None,
+        })),
+        Err((status, msg)) => Err((
+            status,
+            Json(TransactionHashResponse {
+                success: false,
+                transaction_hash: None,
+                block_height: None,
+                error: Some(msg),
+            }),
+        )),
+    }
+}
+
+/// Find the paid payment to a recipient in a list and the transaction it was sent in
+pub async fn resolve_payment(
+    state: &AppState,
+    list_id: &str,
+    recipient: &str,
+) -> Result<ResolvedPayment, (StatusCode, String)> {
     // First get the list to find the block height
-    let list_cache_key = CacheKey::new("bulk-payment-list").with(&list_id).build();
+    let list_cache_key = CacheKey::new("bulk-payment-list").with(list_id).build();
 
-    let list_result = state
+    let list = state
         .cache
         .clone()
         .cached_contract_call(CacheTier::LongTerm, list_cache_key, async {
@@ -261,70 +388,58 @@ pub async fn get_transaction_hash(
                 .map(|r| r.data)
         })
         .await;
-
-    let list = match list_result {
-        Ok(l) => l,
-        Err((status, msg)) => {
-            return Err((
-                status,
-                Json(TransactionHashResponse {
-                    success: false,
-                    transaction_hash: None,
-                    block_height: None,
-                    error: Some(msg),
-                }),
-            ));
+    let list = match list {
+        Ok(list) => list,
+        Err((_, msg)) if msg.contains("Payment list not found") => {
+            return resolve_closed_payment(state, list_id, recipient).await;
         }
+        Err(e) => return Err(e),
     };
 
     // Find the payment for this recipient
-    let payment = list.payments.iter().find(|p| p.recipient == recipient);
-
-    let payment = match payment {
-        Some(p) => p,
-        None => {
-            return Err((
+    let payment = list
+        .payments
+        .iter()
+        .find(|p| p.recipient == recipient)
+        .ok_or_else(|| {
+            (
                 StatusCode::NOT_FOUND,
-                Json(TransactionHashResponse {
-                    success: false,
-                    transaction_hash: None,
-                    block_height: None,
-                    error: Some(format!(
-                        "Recipient {} not found in list {}",
-                        recipient, list_id
-                    )),
-                }),
-            ));
-        }
+                format!("Recipient {} not found in list {}", recipient, list_id),
+            )
+        })?;
+
+    let block_height = payment.status.block_height().ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Payment to {} has not been processed yet", recipient),
+        )
+    })?;
+
+    let resolved = |transaction_hash: String| ResolvedPayment {
+        submitter: list.submitter.clone(),
+        recipient: recipient.to_string(),
+        token_id: payment
+            .token_id
+            .clone()
+            .unwrap_or_else(|| list.token_id.clone()),
+        amount: payment.amount.clone(),
+        block_height,
+        transaction_hash,
     };
 
-    let block_height = match payment.status.block_height() {
-        Some(h) => h,
-        None => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(TransactionHashResponse {
-                    success: false,
-                    transaction_hash: None,
-                    block_height: None,
-                    error: Some(format!(
-                        "Payment to {} has not been processed yet",
-                        recipient
-                    )),
-                }),
-            ));
-        }
-    };
+    // Payouts sent by the worker have their payment_sent event recorded
+    let events = load_payment_events(state, list_id).await;
+    if let Some(event) = events
+        .iter()
+        .find(|e| e.event == "payment_sent" && e.recipient == recipient)
+    {
+        return Ok(resolved(event.transaction_hash.clone()));
+    }
 
-    // Look up the transaction hash by querying the block
+    // Otherwise (e.g. payout_batch called by someone else) look it up by querying the block
     let contract_id = state.bulk_payment_contract_id.to_string();
     match lookup_transaction_hash(&state.archival_network, block_height, &contract_id).await {
-        Ok(tx_hash) => Ok(Json(TransactionHashResponse {
-            success: true,
-            transaction_hash: Some(tx_hash),
-            block_height: Some(block_height),
-            error: None,
-        })),
+        Ok(tx_hash) => Ok(resolved(tx_hash)),
         Err(e) => {
             log::error!(
                 "Failed to lookup transaction hash for recipient {} in block {}: {}",
@@ -334,17 +449,101 @@ pub async fn get_transaction_hash(
             );
             Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TransactionHashResponse {
-                    success: false,
-                    transaction_hash: None,
-                    block_height: Some(block_height),
-                    error: Some(format!("Failed to lookup transaction hash: {}", e)),
-                }),
+                format!("Failed to lookup transaction hash: {}", e),
             ))
         }
     }
 }
 
+/// Paid payment of a list pruned by `close_list`, from its recorded `payment_sent` event
+///
+/// The receipt only keeps the block of the last payment, which stands in for the block of
+/// each payment of the list.
+async fn resolve_closed_payment(
+    state: &AppState,
+    list_id: &str,
+    recipient: &str,
+) -> Result<ResolvedPayment, (StatusCode, String)> {
+    let receipt = closed_list_receipt(state, list_id).await?;
+
+    let sent: Option<(serde_json::Value, String)> = sqlx::query_as(
+        r#"
+        SELECT data, transaction_hash
+        FROM bulk_payment_events
+        WHERE list_id = $1 AND event = 'payment_sent' AND recipient = $2
+        ORDER BY payment_index, created_at
+        LIMIT 1
+        "#,
+    )
+    .bind(list_id)
+    .bind(recipient)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| {
+        log::error!("Failed to load payment events for list {}: {}", list_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to load payment events".to_string(),
+        )
+    })?;
+
+    let (data, transaction_hash) = sent.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("No recorded payment to {} in list {}", recipient, list_id),
+        )
+    })?;
+    let amount = data["amount"].as_str().ok_or_else(|| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!(
+                "Invalid payment_sent event for {} in list {}",
+                recipient, list_id
+            ),
+        )
+    })?;
+
+    Ok(ResolvedPayment {
+        submitter: receipt.submitter,
+        recipient: recipient.to_string(),
+        token_id: data["token_id"]
+            .as_str()
+            .map_or(receipt.token_id, str::to_string),
+        amount: amount.to_string(),
+        block_height: receipt.completed_at_block,
+        transaction_hash,
+    })
+}
+
+/// Recorded payment events of a list (empty if they can't be loaded)
+async fn load_payment_events(state: &AppState, list_id: &str) -> Vec<PaymentEvent> {
+    fetch_payment_events(&state.db_pool, list_id)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load payment events for list {}: {}", list_id, e);
+            Vec::new()
+        })
+}
+
+/// Attach the transaction hash of each paid payment from its `payment_sent` event
+///
+/// Both are in list order, so a recipient paid more than once is matched in order.
+fn link_transaction_hashes(transactions: &mut [PaymentTransaction], events: &[PaymentEvent]) {
+    let mut sent: Vec<&PaymentEvent> = events
+        .iter()
+        .filter(|e| e.event == "payment_sent")
+        .collect();
+
+    for transaction in transactions.iter_mut() {
+        if let Some(pos) = sent
+            .iter()
+            .position(|e| e.recipient == transaction.recipient)
+        {
+            transaction.transaction_hash = Some(sent.remove(pos).transaction_hash.clone());
+        }
+    }
+}
+
 /// Look up the transaction hash by querying the block and finding the transaction
 /// to the bulk payment contract.
 async fn lookup_transaction_hash(
@@ -393,3 +592,51 @@ async fn lookup_transaction_hash(
     )
     .into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(recipient: &str) -> PaymentTransaction {
+        PaymentTransaction {
+            recipient: recipient.to_string(),
+            amount: "1".to_string(),
+            block_height: 100,
+            token_id: None,
+            transaction_hash: None,
+        }
+    }
+
+    fn event(event: &str, payment_index: i32, recipient: &str, hash: &str) -> PaymentEvent {
+        PaymentEvent {
+            event: event.to_string(),
+            payment_index,
+            recipient: recipient.to_string(),
+            transaction_hash: hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_link_transaction_hashes() {
+        let mut transactions = vec![
+            transaction("alice.near"),
+            transaction("bob.near"),
+            transaction("alice.near"),
+            transaction("carol.near"),
+        ];
+        let events = vec![
+            event("payment_sent", 0, "alice.near", "tx1"),
+            event("payment_sent", 1, "bob.near", "tx1"),
+            event("payment_sent", 2, "alice.near", "tx2"),
+            event("payment_failed", 3, "carol.near", "tx2"),
+        ];
+
+        link_transaction_hashes(&mut transactions, &events);
+
+        let hashes: Vec<Option<&str>> = transactions
+            .iter()
+            .map(|t| t.transaction_hash.as_deref())
+            .collect();
+        assert_eq!(hashes, vec![Some("tx1"), Some("tx1"), Some("tx2"), None]);
+    }
+}
@@ -0,0 +1,358 @@
+//! Bulk payments to other chains
+//!
+//! Payments in PoA bridged tokens ("nep141:<..>.omft.near") are withdrawn from intents.near
+//! to an address on the token's origin chain (EVM, Solana, Bitcoin, ...). Recipients of such
+//! payments are checked against the chain's address format; once the payout worker sees a
+//! `payment_sent` event for one, the withdrawal is tracked on the bridge until it completes.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use near_api::AccountId;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::events::ContractEvent;
+use crate::AppState;
+use crate::constants::intents_chains::{chain_address_format, is_valid_chain_address};
+use crate::constants::intents_tokens::find_token_by_defuse_asset_id;
+use crate::utils::jsonrpc::{JsonRpcRequest, JsonRpcResponse};
+
+/// Pending withdrawals checked on the bridge per worker tick
+const WITHDRAWALS_PER_REFRESH: i64 = 20;
+
+/// Whether a bulk payment token is a PoA bridged token, paid out by withdrawing it to its
+/// origin chain
+pub fn is_poa_token(token_id: &str) -> bool {
+    token_id
+        .strip_prefix("nep141:")
+        .is_some_and(|token| token.ends_with(".omft.near"))
+}
+
+/// Chain a PoA token is withdrawn to (intents chain name), if the token and chain are known
+pub fn withdrawal_chain(token_id: &str) -> Option<String> {
+    if !is_poa_token(token_id) {
+        return None;
+    }
+    find_token_by_defuse_asset_id(token_id)
+        .map(|token| token.origin_chain_name)
+        .filter(|chain| chain_address_format(chain).is_some())
+}
+
+/// Check a payment recipient for its token: an address on the origin chain for PoA tokens,
+/// a NEAR account otherwise
+pub fn validate_recipient(token_id: &str, recipient: &str) -> Result<(), String> {
+    if !is_poa_token(token_id) {
+        return AccountId::from_str(recipient)
+            .map(|_| ())
+            .map_err(|_| format!("Invalid account ID: {}", recipient));
+    }
+
+    let chain = withdrawal_chain(token_id)
+        .ok_or_else(|| format!("Withdrawals of {} are not supported", token_id))?;
+    if is_valid_chain_address(&chain, recipient) {
+        Ok(())
+    } else {
+        Err(format!("Invalid {} address: {}", chain, recipient))
+    }
+}
+
+/// Withdrawal status of a bulk payment, as returned by the withdrawals view
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentWithdrawal {
+    pub payment_index: i32,
+    pub token_id: String,
+    pub chain: String,
+    pub address: String,
+    pub amount: String,
+    pub transaction_hash: String,
+    /// "PENDING", "COMPLETED" or "FAILED"
+    pub status: String,
+    pub destination_tx_hash: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Start tracking the withdrawals of the PoA payments sent in a payout transaction
+///
+/// Failures are logged only, like the events the withdrawals are recorded from.
+pub async fn record_withdrawals(
+    pool: &sqlx::PgPool,
+    transaction_hash: &str,
+    events: &[ContractEvent],
+) {
+    for event in events.iter().filter(|e| e.event == "payment_sent") {
+        let (Some(list_id), Some(index), Some(address)) =
+            (event.list_id(), event.payment_index(), event.recipient())
+        else {
+            continue;
+        };
+        let token_id = event.data["token_id"].as_str().unwrap_or_default();
+        let Some(chain) = withdrawal_chain(token_id) else {
+            continue;
+        };
+        let amount = event.data["amount"].as_str().unwrap_or_default();
+
+        if let Err(e) = sqlx::query(
+            r#"
+            INSERT INTO bulk_payment_withdrawals
+                (list_id, payment_index, token_id, chain, address, amount, transaction_hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(list_id)
+        .bind(index)
+        .bind(token_id)
+        .bind(&chain)
+        .bind(address)
+        .bind(amount)
+        .bind(transaction_hash)
+        .execute(pool)
+        .await
+        {
+            log::error!(
+                "Failed to record withdrawal of payment {} in list {}: {}",
+                index,
+                list_id,
+                e
+            );
+        }
+    }
+}
+
+/// Bridge `withdrawal_status` result (snake_case: bridge RPC response)
+#[derive(Debug, Deserialize)]
+struct WithdrawalStatusResult {
+    #[serde(default)]
+    withdrawals: Vec<BridgeWithdrawal>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeWithdrawal {
+    status: String,
+    #[serde(default)]
+    data: Option<BridgeWithdrawalData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeWithdrawalData {
+    #[serde(default)]
+    address: Option<String>,
+    #[serde(default)]
+    transfer_tx_hash: Option<String>,
+}
+
+/// Withdrawals a NEAR transaction started, as reported by the bridge
+async fn fetch_bridge_withdrawals(
+    state: &AppState,
+    transaction_hash: &str,
+) -> Result<Vec<BridgeWithdrawal>, String> {
+    let rpc_request = JsonRpcRequest::new(
+        "withdrawalStatus",
+        "withdrawal_status",
+        vec![serde_json::json!({ "withdrawal_hash": transaction_hash })],
+    );
+
+    let response = state
+        .http_client
+        .post(&state.env_vars.bridge_rpc_url)
+        .header("content-type", "application/json")
+        .json(&rpc_request)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch withdrawal status: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("HTTP error! status: {}", response.status()));
+    }
+
+    let data = response
+        .json::<JsonRpcResponse<WithdrawalStatusResult>>()
+        .await
+        .map_err(|e| format!("Failed to parse bridge response: {}", e))?;
+
+    if let Some(error) = data.error {
+        return Err(error.message);
+    }
+    Ok(data.result.map(|r| r.withdrawals).unwrap_or_default())
+}
+
+/// Bridge status of one withdrawal: its status and destination transaction, matched by address
+fn match_withdrawal(
+    withdrawals: &[BridgeWithdrawal],
+    address: &str,
+) -> Option<(&'static str, Option<String>)> {
+    let withdrawal = withdrawals.iter().find(|w| {
+        w.data
+            .as_ref()
+            .and_then(|d| d.address.as_deref())
+            .is_some_and(|a| a.eq_ignore_ascii_case(address))
+    })?;
+
+    let status = match withdrawal.status.to_uppercase().as_str() {
+        "COMPLETED" => "COMPLETED",
+        "FAILED" => "FAILED",
+        _ => "PENDING",
+    };
+    let destination_tx_hash = withdrawal
+        .data
+        .as_ref()
+        .and_then(|d| d.transfer_tx_hash.clone());
+    Some((status, destination_tx_hash))
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct PendingWithdrawal {
+    list_id: String,
+    payment_index: i32,
+    address: String,
+    transaction_hash: String,
+}
+
+/// Poll the bridge for the least recently checked pending withdrawals
+///
+/// Returns the number of withdrawals that completed or failed.
+pub async fn refresh_pending_withdrawals(state: &Arc<AppState>) -> Result<usize, sqlx::Error> {
+    let pending = sqlx::query_as::<_, PendingWithdrawal>(
+        r#"
+        SELECT list_id, payment_index, address, transaction_hash
+        FROM bulk_payment_withdrawals
+        WHERE status = 'PENDING'
+        ORDER BY updated_at
+        LIMIT $1
+        "#,
+    )
+    .bind(WITHDRAWALS_PER_REFRESH)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let mut settled = 0;
+    for withdrawal in pending {
+        let (status, destination_tx_hash) =
+            match fetch_bridge_withdrawals(state, &withdrawal.transaction_hash).await {
+                Ok(withdrawals) => {
+                    match_withdrawal(&withdrawals, &withdrawal.address).unwrap_or(("PENDING", None))
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Bridge status of withdrawal {} ({}) unavailable: {}",
+                        withdrawal.transaction_hash,
+                        withdrawal.address,
+                        e
+                    );
+                    ("PENDING", None)
+                }
+            };
+
+        // Bumping updated_at of still pending rows moves them to the back of the queue
+        sqlx::query(
+            r#"
+            UPDATE bulk_payment_withdrawals
+            SET status = $3,
+                destination_tx_hash = COALESCE($4, destination_tx_hash),
+                updated_at = NOW()
+            WHERE list_id = $1 AND payment_index = $2
+            "#,
+        )
+        .bind(&withdrawal.list_id)
+        .bind(withdrawal.payment_index)
+        .bind(status)
+        .bind(destination_tx_hash)
+        .execute(&state.db_pool)
+        .await?;
+
+        if status != "PENDING" {
+            settled += 1;
+        }
+    }
+
+    Ok(settled)
+}
+
+/// Get the bridge withdrawals of a list's payments to other chains
+pub async fn get_withdrawals(
+    State(state): State<Arc<AppState>>,
+    Path(list_id): Path<String>,
+) -> Result<Json<Vec<PaymentWithdrawal>>, (StatusCode, String)> {
+    sqlx::query_as::<_, PaymentWithdrawal>(
+        r#"
+        SELECT payment_index, token_id, chain, address, amount, transaction_hash,
+               status, destination_tx_hash, updated_at
+        FROM bulk_payment_withdrawals
+        WHERE list_id = $1
+        ORDER BY payment_index
+        "#,
+    )
+    .bind(&list_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map(Json)
+    .map_err(|e| {
+        log::error!("Failed to load withdrawals of list {}: {}", list_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error".to_string(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC_ETH: &str = "nep141:eth-0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48.omft.near";
+    const USDC_SOL: &str = "nep141:sol-5ce3bf3a31af18be40ba30f721101b4341690186.omft.near";
+
+    #[test]
+    fn test_validate_recipient() {
+        assert!(validate_recipient("native", "alice.near").is_ok());
+        assert!(
+            validate_recipient("native", "0x71C7656EC7ab88b098defB751B7401B5f6d8976F").is_err()
+        );
+        assert!(validate_recipient("nep141:wrap.near", "alice.near").is_ok());
+
+        assert_eq!(withdrawal_chain(USDC_ETH).as_deref(), Some("eth"));
+        assert!(validate_recipient(USDC_ETH, "0x71C7656EC7ab88b098defB751B7401B5f6d8976F").is_ok());
+        assert!(validate_recipient(USDC_ETH, "alice.near").is_err());
+        assert!(
+            validate_recipient(USDC_SOL, "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU").is_ok()
+        );
+        assert!(
+            validate_recipient(USDC_SOL, "0x71C7656EC7ab88b098defB751B7401B5f6d8976F").is_err()
+        );
+        assert!(validate_recipient("nep141:unknown.omft.near", "bc1qxyz").is_err());
+    }
+
+    #[test]
+    fn test_match_withdrawal() {
+        let withdrawals: Vec<BridgeWithdrawal> = serde_json::from_value(serde_json::json!([
+            {
+                "status": "COMPLETED",
+                "data": {
+                    "address": "0x71c7656ec7ab88b098defb751b7401b5f6d8976f",
+                    "transfer_tx_hash": "0xabc"
+                }
+            },
+            {
+                "status": "PENDING",
+                "data": { "address": "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU" }
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            match_withdrawal(&withdrawals, "0x71C7656EC7ab88b098defB751B7401B5f6d8976F"),
+            Some(("COMPLETED", Some("0xabc".to_string())))
+        );
+        assert_eq!(
+            match_withdrawal(&withdrawals, "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU"),
+            Some(("PENDING", None))
+        );
+        assert_eq!(match_withdrawal(&withdrawals, "bc1qxyz"), None);
+    }
+}
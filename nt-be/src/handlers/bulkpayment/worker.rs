@@ -1,4 +1,5 @@
 use crate::app_state::AppState;
+use crate::handlers::fees::ledger::{FeeSource, PlatformTransaction, record_platform_transaction};
 use near_api::Contract;
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -51,23 +52,49 @@ pub async fn query_and_process_pending_lists(
         // The contract will handle the logic of checking if the list is ready
         log::info!("Processing payout batch for list {}", list_id);
 
-        let call_result = Contract(state.bulk_payment_contract_id.clone())
-            .call_function(
-                "payout_batch",
-                serde_json::json!({
-                    "caller_id": state.bulk_payment_contract_id.to_string(),
-                    "list_id": list_id
-                }),
-            )
-            .transaction()
-            .with_signer(state.signer_id.clone(), state.signer.clone())
-            .send_to(&state.network)
+        let network = &state.network;
+        let contract_id = &state.bulk_payment_contract_id;
+        let call_result = state
+            .relayer_pool
+            .send(move |relayer| async move {
+                Contract(contract_id.clone())
+                    .call_function(
+                        "payout_batch",
+                        serde_json::json!({
+                            "caller_id": contract_id.to_string(),
+                            "list_id": list_id
+                        }),
+                    )
+                    .transaction()
+                    .with_signer(relayer.account_id, relayer.signer)
+                    .send_to(network)
+                    .await
+            })
             .await;
 
         match call_result {
-            Ok(_) => {
+            Ok((relayer_id, result)) => {
                 processed_count += 1;
                 log::info!("Successfully processed batch for list {}", list_id);
+
+                // Keep the payment events so payments can be linked to this transaction
+                let events = super::events::parse_events(result.logs());
+                let transaction_hash = result.outcome().transaction_hash.to_string();
+                super::events::record_events(&state.db_pool, &transaction_hash, &events).await;
+                super::withdrawals::record_withdrawals(&state.db_pool, &transaction_hash, &events)
+                    .await;
+                record_platform_transaction(
+                    &state.db_pool,
+                    PlatformTransaction {
+                        transaction_hash: &transaction_hash,
+                        signer_id: relayer_id.as_str(),
+                        source: FeeSource::Payout,
+                        dao_id: None,
+                        on_behalf_of: None,
+                        list_id: Some(list_id),
+                    },
+                )
+                .await;
             }
             Err(e) => {
                 let err_str = e.to_string();
@@ -0,0 +1,4 @@
+//! Limit and TWAP exchange orders that are proposed to the DAO automatically
+
+pub mod orders;
+pub mod worker;
@@ -0,0 +1,574 @@
+//! Exchange order management endpoints.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use near_api::AccountId;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::auth::{AuthUser, require_policy_member};
+use crate::handlers::proposals::forecast::can_add_proposal;
+use crate::handlers::proposals::scraper::fetch_policy;
+
+/// Maximum number of slices of a TWAP order
+pub const MAX_TWAP_SLICES: u32 = 100;
+
+/// Maximum slippage tolerance of an order in basis points
+pub const MAX_SLIPPAGE_BPS: u32 = 5000;
+
+/// Sputnik proposal kind label of the exchange proposals
+const EXCHANGE_PROPOSAL_KIND: &str = "call";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderType {
+    /// A single exchange proposed once the price condition is met
+    Limit,
+    /// Equal slices proposed at even intervals
+    Twap,
+}
+
+impl OrderType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Limit => "limit",
+            OrderType::Twap => "twap",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TriggerDirection {
+    /// Triggers when the price is at or above the trigger price
+    Above,
+    /// Triggers when the price is at or below the trigger price
+    Below,
+}
+
+impl TriggerDirection {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "above" => Some(TriggerDirection::Above),
+            "below" => Some(TriggerDirection::Below),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TriggerDirection::Above => "above",
+            TriggerDirection::Below => "below",
+        }
+    }
+
+    /// Whether `price` satisfies the condition
+    pub fn is_met(&self, price: f64, trigger_price: f64) -> bool {
+        match self {
+            TriggerDirection::Above => price >= trigger_price,
+            TriggerDirection::Below => price <= trigger_price,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeOrder {
+    pub id: i64,
+    pub dao_id: String,
+    pub created_by: String,
+    pub order_type: String,
+    pub notes: Option<String>,
+    pub sell_token_id: String,
+    pub receive_token_id: String,
+    pub amount: String,
+    pub slippage_bps: i32,
+    pub trigger_price_usd: Option<f64>,
+    pub trigger_direction: Option<String>,
+    pub slice_count: i32,
+    pub slices_created: i32,
+    pub start_at: DateTime<Utc>,
+    pub end_at: Option<DateTime<Utc>>,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An exchange proposal generated from an order
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeOrderSlice {
+    pub id: i64,
+    pub order_id: i64,
+    pub slice_index: i32,
+    pub scheduled_for: DateTime<Utc>,
+    pub amount_in: String,
+    pub status: String,
+    pub price_usd: Option<f64>,
+    pub deposit_address: Option<String>,
+    pub amount_out: Option<String>,
+    pub proposal_id: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeOrderDetails {
+    #[serde(flatten)]
+    pub order: ExchangeOrder,
+    pub slices: Vec<ExchangeOrderSlice>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrderRequest {
+    pub dao_id: String,
+    pub order_type: OrderType,
+    pub notes: Option<String>,
+    pub sell_token_id: String,
+    pub receive_token_id: String,
+    /// Total amount to sell in base units
+    pub amount: String,
+    pub slippage_bps: u32,
+    pub trigger_price_usd: Option<f64>,
+    pub trigger_direction: Option<TriggerDirection>,
+    /// Number of slices of a TWAP order
+    pub slice_count: Option<u32>,
+    /// Defaults to now
+    pub start_at: Option<DateTime<Utc>>,
+    /// End of a TWAP order, or expiry of a limit order
+    pub end_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateOrderRequest {
+    /// "active" to resume, "paused" to pause or "cancelled" to cancel the order
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListOrdersQuery {
+    pub dao_id: String,
+}
+
+const ORDER_COLUMNS: &str = "id, dao_id, created_by, order_type, notes, sell_token_id, \
+     receive_token_id, amount, slippage_bps, trigger_price_usd, trigger_direction, slice_count, \
+     slices_created, start_at, end_at, next_run_at, status, created_at, updated_at";
+
+fn bad_request(message: impl Into<String>) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, message.into())
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    log::error!("Exchange order database error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Database error".to_string(),
+    )
+}
+
+/// Amount sold by a slice; the last slice also sells the remainder of the division
+pub fn slice_amount(total: u128, slice_count: u32, index: u32) -> u128 {
+    let count = u128::from(slice_count.max(1));
+    let base = total / count;
+    if u128::from(index) + 1 == count {
+        base + total % count
+    } else {
+        base
+    }
+}
+
+/// When a slice is due: slices are spread evenly from `start_at`, the first one at `start_at`
+pub fn slice_time(
+    start_at: DateTime<Utc>,
+    end_at: Option<DateTime<Utc>>,
+    slice_count: u32,
+    index: u32,
+) -> DateTime<Utc> {
+    match end_at {
+        Some(end_at) if slice_count > 1 => {
+            let interval = (end_at - start_at) / slice_count as i32;
+            start_at + interval * index as i32
+        }
+        _ => start_at,
+    }
+}
+
+/// Validate a new order, returning its slice count
+pub fn validate_order(request: &CreateOrderRequest, now: DateTime<Utc>) -> Result<u32, String> {
+    if request.sell_token_id.is_empty() || request.receive_token_id.is_empty() {
+        return Err("Sell and receive tokens are required".to_string());
+    }
+    if request.sell_token_id == request.receive_token_id {
+        return Err("Sell and receive tokens must differ".to_string());
+    }
+    let amount = match request.amount.parse::<u128>() {
+        Ok(amount) if amount > 0 => amount,
+        _ => return Err(format!("Invalid amount: {}", request.amount)),
+    };
+    if request.slippage_bps == 0 || request.slippage_bps > MAX_SLIPPAGE_BPS {
+        return Err(format!(
+            "Slippage must be between 1 and {} basis points",
+            MAX_SLIPPAGE_BPS
+        ));
+    }
+    let start_at = request.start_at.unwrap_or(now);
+    if request.end_at.is_some_and(|end| end <= start_at.max(now)) {
+        return Err("End date must be in the future and after the start date".to_string());
+    }
+
+    match request.order_type {
+        OrderType::Limit => {
+            match request.trigger_price_usd {
+                Some(price) if price.is_finite() && price > 0.0 => {}
+                _ => return Err("Limit orders need a positive trigger price".to_string()),
+            }
+            if request.trigger_direction.is_none() {
+                return Err("Limit orders need a trigger direction".to_string());
+            }
+            if request.slice_count.is_some_and(|count| count != 1) {
+                return Err("Limit orders are proposed as a single exchange".to_string());
+            }
+            Ok(1)
+        }
+        OrderType::Twap => {
+            if request.trigger_price_usd.is_some() || request.trigger_direction.is_some() {
+                return Err("TWAP orders don't take a trigger price".to_string());
+            }
+            if request.end_at.is_none() {
+                return Err("TWAP orders need an end date".to_string());
+            }
+            let slice_count = request.slice_count.unwrap_or(0);
+            if !(2..=MAX_TWAP_SLICES).contains(&slice_count) {
+                return Err(format!(
+                    "TWAP orders need between 2 and {} slices",
+                    MAX_TWAP_SLICES
+                ));
+            }
+            if amount < u128::from(slice_count) {
+                return Err("Amount is too small to split into the requested slices".to_string());
+            }
+            Ok(slice_count)
+        }
+    }
+}
+
+/// Check that both the user and the relayer may add the exchange proposals of an order
+async fn authorize_order(
+    state: &AppState,
+    account_id: &str,
+    dao_id: &str,
+) -> Result<(), (StatusCode, String)> {
+    let dao: AccountId = dao_id
+        .parse()
+        .map_err(|_| bad_request(format!("Invalid DAO ID: {}", dao_id)))?;
+    let policy = fetch_policy(&state.network, &dao).await.map_err(|e| {
+        log::error!("Error fetching policy for {}: {}", dao_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to fetch DAO policy".to_string(),
+        )
+    })?;
+
+    if !can_add_proposal(&policy, EXCHANGE_PROPOSAL_KIND, account_id) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!(
+                "{} is not allowed to create {} proposals in {}",
+                account_id, EXCHANGE_PROPOSAL_KIND, dao_id
+            ),
+        ));
+    }
+    if !can_add_proposal(&policy, EXCHANGE_PROPOSAL_KIND, state.signer_id.as_str()) {
+        return Err(bad_request(format!(
+            "The DAO policy must allow {} to create {} proposals for exchange orders",
+            state.signer_id, EXCHANGE_PROPOSAL_KIND
+        )));
+    }
+
+    Ok(())
+}
+
+async fn fetch_order(state: &AppState, id: i64) -> Result<ExchangeOrder, (StatusCode, String)> {
+    sqlx::query_as::<_, ExchangeOrder>(&format!(
+        "SELECT {} FROM exchange_orders WHERE id = $1",
+        ORDER_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(db_error)?
+    .ok_or((
+        StatusCode::NOT_FOUND,
+        format!("Exchange order {} not found", id),
+    ))
+}
+
+/// Create a limit or TWAP exchange order
+pub async fn create_order(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<CreateOrderRequest>,
+) -> Result<Json<ExchangeOrder>, (StatusCode, String)> {
+    let now = Utc::now();
+    let slice_count = validate_order(&request, now).map_err(bad_request)?;
+
+    authorize_order(&state, &auth_user.account_id, &request.dao_id).await?;
+
+    let start_at = request.start_at.unwrap_or(now);
+
+    let order = sqlx::query_as::<_, ExchangeOrder>(&format!(
+        r#"
+        INSERT INTO exchange_orders
+            (dao_id, created_by, order_type, notes, sell_token_id, receive_token_id, amount,
+             slippage_bps, trigger_price_usd, trigger_direction, slice_count, start_at, end_at,
+             next_run_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $12)
+        RETURNING {}
+        "#,
+        ORDER_COLUMNS
+    ))
+    .bind(&request.dao_id)
+    .bind(&auth_user.account_id)
+    .bind(request.order_type.as_str())
+    .bind(&request.notes)
+    .bind(&request.sell_token_id)
+    .bind(&request.receive_token_id)
+    .bind(&request.amount)
+    .bind(request.slippage_bps as i32)
+    .bind(request.trigger_price_usd)
+    .bind(request.trigger_direction.map(|d| d.as_str()))
+    .bind(slice_count as i32)
+    .bind(start_at)
+    .bind(request.end_at)
+    .fetch_one(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    log::info!(
+        "Exchange order {} ({}) created for {} by {}",
+        order.id,
+        order.order_type,
+        order.dao_id,
+        order.created_by
+    );
+
+    Ok(Json(order))
+}
+
+/// List the exchange orders of a treasury. Members only, since pending orders reveal
+/// upcoming trades.
+pub async fn list_orders(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<ListOrdersQuery>,
+) -> Result<Json<Vec<ExchangeOrder>>, (StatusCode, String)> {
+    require_policy_member(&state, &query.dao_id, &auth_user.account_id).await?;
+
+    let orders = sqlx::query_as::<_, ExchangeOrder>(&format!(
+        "SELECT {} FROM exchange_orders WHERE dao_id = $1 ORDER BY created_at DESC",
+        ORDER_COLUMNS
+    ))
+    .bind(&query.dao_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(orders))
+}
+
+/// Get an exchange order together with the proposals generated from it. Members only.
+pub async fn get_order(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<ExchangeOrderDetails>, (StatusCode, String)> {
+    let order = fetch_order(&state, id).await?;
+    require_policy_member(&state, &order.dao_id, &auth_user.account_id).await?;
+
+    let slices = sqlx::query_as::<_, ExchangeOrderSlice>(
+        r#"
+        SELECT id, order_id, slice_index, scheduled_for, amount_in, status, price_usd,
+               deposit_address, amount_out, proposal_id, error, created_at, updated_at
+        FROM exchange_order_slices
+        WHERE order_id = $1
+        ORDER BY slice_index DESC
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(ExchangeOrderDetails { order, slices }))
+}
+
+/// Pause, resume or cancel an exchange order
+///
+/// Resuming an order whose last slice failed retries that slice.
+pub async fn update_order(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Json(request): Json<UpdateOrderRequest>,
+) -> Result<Json<ExchangeOrder>, (StatusCode, String)> {
+    let order = fetch_order(&state, id).await?;
+
+    authorize_order(&state, &auth_user.account_id, &order.dao_id).await?;
+
+    if !matches!(order.status.as_str(), "active" | "paused") {
+        return Err(bad_request(format!(
+            "Exchange order {} is {} and can no longer change",
+            id, order.status
+        )));
+    }
+    if !matches!(request.status.as_str(), "active" | "paused" | "cancelled") {
+        return Err(bad_request(format!("Invalid status: {}", request.status)));
+    }
+    if request.status == "active"
+        && order.order_type == OrderType::Limit.as_str()
+        && order.end_at.is_some_and(|end| end <= Utc::now())
+    {
+        return Err(bad_request("Limit order has expired"));
+    }
+
+    let updated = sqlx::query_as::<_, ExchangeOrder>(&format!(
+        r#"
+        UPDATE exchange_orders
+        SET status = $2,
+            next_run_at = CASE WHEN $2 = 'cancelled' THEN NULL ELSE next_run_at END,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        ORDER_COLUMNS
+    ))
+    .bind(id)
+    .bind(&request.status)
+    .fetch_one(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn request(order_type: OrderType) -> CreateOrderRequest {
+        CreateOrderRequest {
+            dao_id: "testdao.sputnik-dao.near".to_string(),
+            order_type,
+            notes: None,
+            sell_token_id: "near".to_string(),
+            receive_token_id: "nep141:usdc.omft.near".to_string(),
+            amount: "10000".to_string(),
+            slippage_bps: 100,
+            trigger_price_usd: None,
+            trigger_direction: None,
+            slice_count: None,
+            start_at: None,
+            end_at: None,
+        }
+    }
+
+    #[test]
+    fn test_slice_amount_gives_remainder_to_last_slice() {
+        assert_eq!(slice_amount(100, 3, 0), 33);
+        assert_eq!(slice_amount(100, 3, 1), 33);
+        assert_eq!(slice_amount(100, 3, 2), 34);
+        assert_eq!(slice_amount(100, 1, 0), 100);
+        assert_eq!(
+            (0..7).map(|i| slice_amount(1_000_003, 7, i)).sum::<u128>(),
+            1_000_003
+        );
+    }
+
+    #[test]
+    fn test_slice_time_spreads_slices_evenly() {
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+        let end = start + Duration::days(10);
+
+        assert_eq!(slice_time(start, Some(end), 10, 0), start);
+        assert_eq!(
+            slice_time(start, Some(end), 10, 3),
+            start + Duration::days(3)
+        );
+        assert_eq!(
+            slice_time(start, Some(end), 10, 9),
+            start + Duration::days(9)
+        );
+        // Limit orders run at their start, the end date is their expiry
+        assert_eq!(slice_time(start, Some(end), 1, 0), start);
+        assert_eq!(slice_time(start, None, 1, 0), start);
+    }
+
+    #[test]
+    fn test_trigger_direction() {
+        assert!(TriggerDirection::Above.is_met(5.0, 5.0));
+        assert!(TriggerDirection::Above.is_met(5.1, 5.0));
+        assert!(!TriggerDirection::Above.is_met(4.9, 5.0));
+        assert!(TriggerDirection::Below.is_met(4.9, 5.0));
+        assert!(!TriggerDirection::Below.is_met(5.1, 5.0));
+    }
+
+    #[test]
+    fn test_validate_limit_order() {
+        let now = Utc::now();
+        let mut limit = request(OrderType::Limit);
+        assert!(validate_order(&limit, now).is_err());
+
+        limit.trigger_price_usd = Some(5.0);
+        limit.trigger_direction = Some(TriggerDirection::Above);
+        assert_eq!(validate_order(&limit, now), Ok(1));
+
+        limit.slice_count = Some(4);
+        assert!(validate_order(&limit, now).is_err());
+        limit.slice_count = None;
+
+        limit.end_at = Some(now - Duration::hours(1));
+        assert!(validate_order(&limit, now).is_err());
+
+        limit.end_at = None;
+        limit.slippage_bps = 0;
+        assert!(validate_order(&limit, now).is_err());
+    }
+
+    #[test]
+    fn test_validate_twap_order() {
+        let now = Utc::now();
+        let mut twap = request(OrderType::Twap);
+        twap.slice_count = Some(10);
+        assert!(validate_order(&twap, now).is_err());
+
+        twap.end_at = Some(now + Duration::days(10));
+        assert_eq!(validate_order(&twap, now), Ok(10));
+
+        twap.slice_count = Some(MAX_TWAP_SLICES + 1);
+        assert!(validate_order(&twap, now).is_err());
+
+        twap.slice_count = Some(10);
+        twap.trigger_price_usd = Some(5.0);
+        assert!(validate_order(&twap, now).is_err());
+
+        twap.trigger_price_usd = None;
+        twap.amount = "5".to_string();
+        assert!(validate_order(&twap, now).is_err());
+
+        twap.amount = "10000".to_string();
+        twap.receive_token_id = "near".to_string();
+        assert!(validate_order(&twap, now).is_err());
+    }
+}
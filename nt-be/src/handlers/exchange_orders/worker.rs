@@ -0,0 +1,759 @@
+//! Background job that turns due exchange order slices into asset-exchange proposals.
+//!
+//! A limit order is due once the 1Click quote for its slice prices the sell token across the
+//! trigger price; a TWAP slice is due at its scheduled time. Each due slice gets a live 1Click
+//! quote for the treasury and is proposed the same way the frontend builds exchange proposals, so
+//! the DAO approves it like any other exchange. The quote is recorded as a swap order and
+//! linked to the proposal, which lets the swap order worker follow its settlement.
+//!
+//! A failed slice pauses its order; resuming the order retries the slice.
+
+use chrono::{DateTime, Duration, Utc};
+use near_api::{AccountId, Contract, NearGas, NearToken};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use super::orders::{ExchangeOrder, OrderType, TriggerDirection, slice_amount, slice_time};
+use crate::AppState;
+use crate::constants::INTENTS_CONTRACT_ID;
+use crate::handlers::intents::quote::{QuoteRequest, is_intents_token, request_quote};
+use crate::handlers::payment_schedules::worker::{
+    add_relayer_proposal, base64_args, check_credits, encode_description,
+};
+
+/// Validity of the quotes requested for slices, matching the exchange page
+const QUOTE_VALIDITY_HOURS: i64 = 24;
+
+/// Storage deposit registering the 1Click deposit address on a NEAR token contract
+pub const STORAGE_DEPOSIT_YOCTO: u128 = 1_250_000_000_000_000_000_000;
+
+/// An asset-exchange proposal built from a live quote
+#[derive(Debug, Clone)]
+pub struct ExchangeProposal {
+    pub description: String,
+    pub kind: Value,
+    pub deposit_address: String,
+    pub amount_out: String,
+}
+
+/// Result of proposing one slice
+struct ProposedSlice {
+    proposal_id: Option<u64>,
+    deposit_address: String,
+    amount_out: String,
+}
+
+/// Live quote request selling `amount` of the order's sell token for the treasury
+pub fn slice_quote_request(
+    order: &ExchangeOrder,
+    amount: u128,
+    now: DateTime<Utc>,
+) -> QuoteRequest {
+    QuoteRequest::treasury_exchange(
+        &order.dao_id,
+        &order.sell_token_id,
+        &order.receive_token_id,
+        amount,
+        order.slippage_bps as u32,
+        false,
+        now + Duration::hours(QUOTE_VALIDITY_HOURS),
+    )
+}
+
+/// Build the exchange proposal of a slice from its live quote
+pub fn build_exchange_proposal(
+    order: &ExchangeOrder,
+    slice_index: i32,
+    body: &Value,
+) -> Result<ExchangeProposal, String> {
+    exchange_proposal(
+        &order.sell_token_id,
+        &order.receive_token_id,
+        order.slippage_bps as u32,
+        body,
+        (
+            "Exchange Order",
+            format!(
+                "{} (slice {} of {})",
+                order.id,
+                slice_index + 1,
+                order.slice_count
+            ),
+        ),
+    )
+}
+
+/// NEAR token contract the 1Click deposit address must be registered on before an
+/// `ft_transfer` of the token to it (`None` for intents tokens)
+pub fn deposit_token_contract(sell_token_id: &str) -> Option<&str> {
+    if is_intents_token(sell_token_id) {
+        None
+    } else if sell_token_id == "near" {
+        Some("wrap.near")
+    } else {
+        Some(sell_token_id)
+    }
+}
+
+/// Build an asset-exchange proposal from a live 1Click quote, the way the exchange page does
+///
+/// `reference` is appended to the description, naming what the exchange was proposed for.
+pub fn exchange_proposal(
+    sell_token_id: &str,
+    receive_token_id: &str,
+    slippage_bps: u32,
+    body: &Value,
+    reference: (&str, String),
+) -> Result<ExchangeProposal, String> {
+    let quote = &body["quote"];
+    let field = |name: &str| {
+        quote[name]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("Quote response is missing {}", name))
+    };
+    let deposit_address = field("depositAddress")?;
+    let amount_in = field("amountIn")?;
+    let amount_out = field("amountOut")?;
+    let deadline = field("deadline")?;
+
+    let transfer = |method_name: &str, args: Value| {
+        json!({
+            "method_name": method_name,
+            "args": base64_args(&args),
+            "deposit": "1",
+            "gas": NearGas::from_tgas(150).as_gas().to_string(),
+        })
+    };
+    let kind = if is_intents_token(sell_token_id) {
+        json!({
+            "FunctionCall": {
+                "receiver_id": INTENTS_CONTRACT_ID.as_str(),
+                "actions": [transfer("mt_transfer", json!({
+                    "receiver_id": deposit_address,
+                    "amount": amount_in,
+                    "token_id": sell_token_id,
+                }))],
+            }
+        })
+    } else if sell_token_id == "near" {
+        json!({
+            "FunctionCall": {
+                "receiver_id": "wrap.near",
+                "actions": [
+                    {
+                        "method_name": "near_deposit",
+                        "args": base64_args(&json!({})),
+                        "deposit": amount_in,
+                        "gas": NearGas::from_tgas(10).as_gas().to_string(),
+                    },
+                    transfer("ft_transfer", json!({
+                        "receiver_id": deposit_address,
+                        "amount": amount_in,
+                    })),
+                ],
+            }
+        })
+    } else {
+        json!({
+            "FunctionCall": {
+                "receiver_id": sell_token_id,
+                "actions": [transfer("ft_transfer", json!({
+                    "receiver_id": deposit_address,
+                    "amount": amount_in,
+                }))],
+            }
+        })
+    };
+
+    let text = |name: &str| quote[name].as_str().unwrap_or_default().to_string();
+    let description = encode_description(&[
+        ("Proposal Action", "asset-exchange".to_string()),
+        (
+            "Notes",
+            format!(
+                "**Must be executed before {}** for transferring tokens to 1Click's deposit address for swap execution.",
+                deadline
+            ),
+        ),
+        ("Token In Address", sell_token_id.to_string()),
+        ("Token Out Address", receive_token_id.to_string()),
+        ("Amount In", text("amountInFormatted")),
+        ("Amount Out", text("amountOutFormatted")),
+        ("Slippage", (f64::from(slippage_bps) / 100.0).to_string()),
+        ("Quote Deadline", deadline),
+        (
+            "Time Estimate",
+            quote["timeEstimate"]
+                .as_u64()
+                .map(|seconds| format!("{} seconds", seconds))
+                .unwrap_or_default(),
+        ),
+        ("Deposit Address", deposit_address.clone()),
+        (
+            "Signature",
+            body["signature"].as_str().unwrap_or_default().to_string(),
+        ),
+        (reference.0, reference.1),
+    ]);
+
+    Ok(ExchangeProposal {
+        description,
+        kind,
+        deposit_address,
+        amount_out,
+    })
+}
+
+/// USD price of the sell token in a 1Click quote
+pub fn quoted_price_usd(body: &Value) -> Option<f64> {
+    let quote = &body["quote"];
+    let amount_in_usd: f64 = quote["amountInUsd"].as_str()?.parse().ok()?;
+    let amount_in: f64 = quote["amountInFormatted"].as_str()?.parse().ok()?;
+    (amount_in > 0.0).then(|| amount_in_usd / amount_in)
+}
+
+/// Quote and quoted price of a limit order slice, if the price meets the trigger
+async fn quote_at_trigger(
+    state: &AppState,
+    order: &ExchangeOrder,
+    request: QuoteRequest,
+    trigger_price: f64,
+    direction: TriggerDirection,
+) -> Option<(f64, Value)> {
+    let body = match request_quote(state, request).await {
+        Ok(body) => body,
+        Err((_, e)) => {
+            log::warn!("Failed to quote exchange order {}: {}", order.id, e);
+            return None;
+        }
+    };
+    let Some(price) = quoted_price_usd(&body) else {
+        log::warn!("Quote for exchange order {} has no USD price", order.id);
+        return None;
+    };
+    direction
+        .is_met(price, trigger_price)
+        .then_some((price, body))
+}
+
+/// Live quote of a limit order slice and its price, if the quoted price meets the trigger
+///
+/// A dry-run quote is checked first, so a deposit address is only reserved once the order
+/// triggers; the live quote the slice is proposed with must meet the trigger too.
+async fn triggered_quote(
+    state: &AppState,
+    order: &ExchangeOrder,
+    amount: u128,
+    trigger_price: f64,
+    direction: TriggerDirection,
+    now: DateTime<Utc>,
+) -> Option<(f64, Value)> {
+    let mut dry_request = slice_quote_request(order, amount, now);
+    dry_request.dry = Some(true);
+    quote_at_trigger(state, order, dry_request, trigger_price, direction).await?;
+
+    let request = slice_quote_request(order, amount, now);
+    quote_at_trigger(state, order, request, trigger_price, direction).await
+}
+
+/// Register the deposit address on a NEAR token contract, as the exchange page does
+/// before proposing an `ft_transfer` to it
+async fn register_deposit_address(
+    state: &AppState,
+    token_contract: &str,
+    deposit_address: &str,
+) -> Result<(), String> {
+    let contract: AccountId = token_contract
+        .parse()
+        .map_err(|e| format!("Invalid token contract {}: {}", token_contract, e))?;
+
+    let network = &state.network;
+    let (_, result) = state
+        .relayer_pool
+        .send(move |relayer| {
+            let contract = contract.clone();
+            async move {
+                Contract(contract)
+                    .call_function(
+                        "storage_deposit",
+                        json!({
+                            "account_id": deposit_address,
+                            "registration_only": true,
+                        }),
+                    )
+                    .transaction()
+                    .gas(NearGas::from_tgas(10))
+                    .deposit(NearToken::from_yoctonear(STORAGE_DEPOSIT_YOCTO))
+                    .with_signer(relayer.account_id, relayer.signer)
+                    .send_to(network)
+                    .await
+            }
+        })
+        .await
+        .map_err(|e| format!("Failed to register deposit address: {}", e))?;
+    result
+        .into_result()
+        .map_err(|e| format!("Failed to register deposit address: {}", e))?;
+
+    Ok(())
+}
+
+/// Add the exchange proposal of a slice to the DAO, from its live quote if it already has one
+async fn propose_slice(
+    state: &AppState,
+    order: &ExchangeOrder,
+    slice_index: i32,
+    amount: u128,
+    quote: Option<Value>,
+) -> Result<ProposedSlice, String> {
+    let dao_id: AccountId = order
+        .dao_id
+        .parse()
+        .map_err(|e| format!("Invalid DAO ID: {}", e))?;
+
+    check_credits(state, &order.dao_id, false).await?;
+
+    let body = match quote {
+        Some(body) => body,
+        None => request_quote(state, slice_quote_request(order, amount, Utc::now()))
+            .await
+            .map_err(|(_, e)| format!("Failed to get quote: {}", e))?,
+    };
+    let proposal = build_exchange_proposal(order, slice_index, &body)?;
+
+    if let Some(token_contract) = deposit_token_contract(&order.sell_token_id) {
+        register_deposit_address(state, token_contract, &proposal.deposit_address).await?;
+    }
+
+    let proposal_id =
+        add_relayer_proposal(state, &dao_id, &proposal.description, &proposal.kind).await?;
+
+    // The swap order worker would find the proposal by its deposit address too; linking it
+    // here makes the swap visible right away
+    if let Some(proposal_id) = proposal_id
+        && let Err(e) = sqlx::query(
+            r#"
+            UPDATE swap_orders
+            SET proposal_id = $3, updated_at = NOW()
+            WHERE dao_id = $1 AND deposit_address = $2 AND proposal_id IS NULL
+            "#,
+        )
+        .bind(&order.dao_id)
+        .bind(&proposal.deposit_address)
+        .bind(proposal_id as i64)
+        .execute(&state.db_pool)
+        .await
+    {
+        log::warn!(
+            "Failed to link swap order {} to proposal {}: {}",
+            proposal.deposit_address,
+            proposal_id,
+            e
+        );
+    }
+
+    Ok(ProposedSlice {
+        proposal_id,
+        deposit_address: proposal.deposit_address,
+        amount_out: proposal.amount_out,
+    })
+}
+
+/// Claim a slice so it is proposed at most once, even with several workers
+///
+/// A slice that failed before is claimed again, which is how resumed orders retry it.
+async fn claim_slice(
+    state: &AppState,
+    order_id: i64,
+    slice_index: i32,
+    scheduled_for: DateTime<Utc>,
+    amount: u128,
+    price_usd: Option<f64>,
+) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO exchange_order_slices
+            (order_id, slice_index, scheduled_for, amount_in, status, price_usd)
+        VALUES ($1, $2, $3, $4, 'pending', $5)
+        ON CONFLICT (order_id, slice_index) DO UPDATE
+        SET status = 'pending', price_usd = EXCLUDED.price_usd, error = NULL, updated_at = NOW()
+        WHERE exchange_order_slices.status = 'failed'
+        RETURNING id
+        "#,
+    )
+    .bind(order_id)
+    .bind(slice_index)
+    .bind(scheduled_for)
+    .bind(amount.to_string())
+    .bind(price_usd)
+    .fetch_optional(&state.db_pool)
+    .await
+}
+
+/// Move the order to its next slice, completing it after the last one
+async fn advance_order(state: &AppState, order: &ExchangeOrder) -> Result<(), sqlx::Error> {
+    let slices_created = order.slices_created + 1;
+    let next_run_at = (slices_created < order.slice_count).then(|| {
+        slice_time(
+            order.start_at,
+            order.end_at,
+            order.slice_count as u32,
+            slices_created as u32,
+        )
+    });
+
+    sqlx::query(
+        r#"
+        UPDATE exchange_orders
+        SET slices_created = $2,
+            next_run_at = $3,
+            status = CASE WHEN $3 IS NULL THEN 'completed' ELSE status END,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(order.id)
+    .bind(slices_created)
+    .bind(next_run_at)
+    .execute(&state.db_pool)
+    .await?;
+
+    Ok(())
+}
+
+async fn set_order_status(
+    state: &AppState,
+    order_id: i64,
+    status: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE exchange_orders
+        SET status = $2,
+            next_run_at = CASE WHEN $2 = 'paused' THEN next_run_at ELSE NULL END,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(order_id)
+    .bind(status)
+    .execute(&state.db_pool)
+    .await?;
+
+    Ok(())
+}
+
+async fn process_order(
+    state: &AppState,
+    order: &ExchangeOrder,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let Some(scheduled_for) = order.next_run_at else {
+        return Ok(());
+    };
+    let is_limit = order.order_type == OrderType::Limit.as_str();
+
+    if is_limit && order.end_at.is_some_and(|end| end <= now) {
+        log::info!("Exchange order {} expired before triggering", order.id);
+        return set_order_status(state, order.id, "expired").await;
+    }
+
+    let Ok(total) = order.amount.parse::<u128>() else {
+        log::error!("Exchange order {} has an invalid amount", order.id);
+        return set_order_status(state, order.id, "paused").await;
+    };
+    let slice_index = order.slices_created;
+    let amount = slice_amount(total, order.slice_count as u32, slice_index as u32);
+
+    let (price_usd, quote) = match (
+        order.trigger_price_usd,
+        order
+            .trigger_direction
+            .as_deref()
+            .and_then(TriggerDirection::parse),
+    ) {
+        (Some(trigger_price), Some(direction)) => {
+            match triggered_quote(state, order, amount, trigger_price, direction, now).await {
+                Some((price, body)) => (Some(price), Some(body)),
+                None => return Ok(()),
+            }
+        }
+        _ => (None, None),
+    };
+
+    let Some(slice_id) = claim_slice(
+        state,
+        order.id,
+        slice_index,
+        scheduled_for,
+        amount,
+        price_usd,
+    )
+    .await?
+    else {
+        return Ok(());
+    };
+
+    log::info!(
+        "Creating exchange proposal for order {} ({}) slice {}",
+        order.id,
+        order.dao_id,
+        slice_index
+    );
+
+    match propose_slice(state, order, slice_index, amount, quote).await {
+        Ok(slice) => {
+            sqlx::query(
+                r#"
+                UPDATE exchange_order_slices
+                SET status = 'created', proposal_id = $2, deposit_address = $3, amount_out = $4,
+                    updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(slice_id)
+            .bind(slice.proposal_id.map(|id| id as i64))
+            .bind(&slice.deposit_address)
+            .bind(&slice.amount_out)
+            .execute(&state.db_pool)
+            .await?;
+
+            log::info!(
+                "Exchange proposal created for {} (order {}, slice {}, proposal {:?})",
+                order.dao_id,
+                order.id,
+                slice_index,
+                slice.proposal_id
+            );
+
+            advance_order(state, order).await
+        }
+        Err(e) => {
+            log::error!(
+                "Failed to create exchange proposal for order {}: {}",
+                order.id,
+                e
+            );
+            sqlx::query(
+                r#"
+                UPDATE exchange_order_slices
+                SET status = 'failed', error = $2, updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(slice_id)
+            .bind(&e)
+            .execute(&state.db_pool)
+            .await?;
+
+            let message = format!(
+                "Exchange order paused for {} (order {}, slice {}): {}",
+                order.dao_id, order.id, slice_index, e
+            );
+            if let Err(e) = state.telegram_client.send_message(&message).await {
+                log::warn!("Failed to send Telegram notification: {}", e);
+            }
+
+            set_order_status(state, order.id, "paused").await
+        }
+    }
+}
+
+/// Propose the due slice of every active order
+///
+/// Returns the number of orders checked.
+pub async fn process_due_orders(
+    state: &Arc<AppState>,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let now = Utc::now();
+
+    let due = sqlx::query_as::<_, ExchangeOrder>(
+        r#"
+        SELECT id, dao_id, created_by, order_type, notes, sell_token_id, receive_token_id,
+               amount, slippage_bps, trigger_price_usd, trigger_direction, slice_count,
+               slices_created, start_at, end_at, next_run_at, status, created_at, updated_at
+        FROM exchange_orders
+        WHERE status = 'active'
+          AND next_run_at <= $1
+        ORDER BY next_run_at
+        "#,
+    )
+    .bind(now)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    for order in &due {
+        process_order(state, order, now).await?;
+    }
+
+    Ok(due.len())
+}
+
+/// Run the exchange order worker every minute
+pub async fn run_exchange_order_service(state: Arc<AppState>) {
+    log::info!("Starting exchange order worker (60 second poll interval)");
+
+    // Wait a bit before first run to let server fully start
+    tokio::time::sleep(std::time::Duration::from_secs(25)).await;
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = process_due_orders(&state).await {
+            log::error!("Exchange order worker error: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(sell_token_id: &str, receive_token_id: &str) -> ExchangeOrder {
+        let now = Utc::now();
+        ExchangeOrder {
+            id: 7,
+            dao_id: "testdao.sputnik-dao.near".to_string(),
+            created_by: "alice.near".to_string(),
+            order_type: "twap".to_string(),
+            notes: None,
+            sell_token_id: sell_token_id.to_string(),
+            receive_token_id: receive_token_id.to_string(),
+            amount: "3000000".to_string(),
+            slippage_bps: 50,
+            trigger_price_usd: None,
+            trigger_direction: None,
+            slice_count: 3,
+            slices_created: 1,
+            start_at: now,
+            end_at: Some(now + Duration::days(3)),
+            next_run_at: Some(now),
+            status: "active".to_string(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn quote_body() -> Value {
+        json!({
+            "quote": {
+                "depositAddress": "3e5f2c6a8b1d4e7f9a0b2c4d6e8f0a1b3c5d7e9f1a2b3c4d5e6f7a8b9c0d1e2f",
+                "amountIn": "1000000",
+                "amountInFormatted": "1.0",
+                "amountOut": "400000000000000000000000",
+                "amountOutFormatted": "0.4",
+                "deadline": "2026-03-02T00:00:00.000Z",
+                "timeEstimate": 20
+            },
+            "signature": "ed25519:abc"
+        })
+    }
+
+    fn decoded_args(action: &Value) -> Value {
+        let args = action["args"].as_str().unwrap();
+        let bytes =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, args).unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_slice_quote_request() {
+        let order = order("nep141:usdc.omft.near", "near");
+        let request = slice_quote_request(&order, 1_000_000, Utc::now());
+
+        assert_eq!(request.dry, Some(false));
+        assert_eq!(request.origin_asset, "nep141:usdc.omft.near");
+        assert_eq!(request.destination_asset, "nep141:wrap.near");
+        assert_eq!(request.deposit_type.as_deref(), Some("INTENTS"));
+        assert_eq!(request.recipient_type.as_deref(), Some("DESTINATION_CHAIN"));
+        assert_eq!(
+            request.refund_to.as_deref(),
+            Some("testdao.sputnik-dao.near")
+        );
+        assert_eq!(
+            request.recipient.as_deref(),
+            Some("testdao.sputnik-dao.near")
+        );
+        assert_eq!(request.slippage_tolerance, Some(50));
+        assert_eq!(request.amount, "1000000");
+    }
+
+    #[test]
+    fn test_intents_sell_uses_mt_transfer() {
+        let order = order("nep141:usdc.omft.near", "near");
+        let proposal = build_exchange_proposal(&order, 1, &quote_body()).unwrap();
+
+        let call = &proposal.kind["FunctionCall"];
+        assert_eq!(call["receiver_id"], "intents.near");
+        assert_eq!(call["actions"][0]["method_name"], "mt_transfer");
+        let args = decoded_args(&call["actions"][0]);
+        assert_eq!(args["receiver_id"], proposal.deposit_address);
+        assert_eq!(args["amount"], "1000000");
+        assert_eq!(args["token_id"], "nep141:usdc.omft.near");
+
+        assert!(
+            proposal
+                .description
+                .starts_with("* Proposal Action: asset-exchange <br>")
+        );
+        assert!(proposal.description.contains(&format!(
+            "* Deposit Address: {} <br>",
+            proposal.deposit_address
+        )));
+        assert!(proposal.description.contains("* Slippage: 0.5 <br>"));
+        assert!(
+            proposal
+                .description
+                .contains("* Time Estimate: 20 seconds <br>")
+        );
+        assert!(
+            proposal
+                .description
+                .ends_with("* Exchange Order: 7 (slice 2 of 3)")
+        );
+    }
+
+    #[test]
+    fn test_native_near_sell_wraps_first() {
+        let order = order("near", "nep141:usdc.omft.near");
+        let proposal = build_exchange_proposal(&order, 0, &quote_body()).unwrap();
+
+        let call = &proposal.kind["FunctionCall"];
+        assert_eq!(call["receiver_id"], "wrap.near");
+        assert_eq!(call["actions"][0]["method_name"], "near_deposit");
+        assert_eq!(call["actions"][0]["deposit"], "1000000");
+        assert_eq!(call["actions"][1]["method_name"], "ft_transfer");
+        assert_eq!(
+            decoded_args(&call["actions"][1])["receiver_id"],
+            proposal.deposit_address
+        );
+    }
+
+    #[test]
+    fn test_quoted_price_usd() {
+        let mut body = quote_body();
+        assert_eq!(quoted_price_usd(&body), None);
+
+        body["quote"]["amountInUsd"] = "0.9998".into();
+        assert_eq!(quoted_price_usd(&body), Some(0.9998));
+        body["quote"]["amountInFormatted"] = "2.0".into();
+        assert_eq!(quoted_price_usd(&body), Some(0.4999));
+        body["quote"]["amountInFormatted"] = "0".into();
+        assert_eq!(quoted_price_usd(&body), None);
+    }
+
+    #[test]
+    fn test_quote_without_deposit_address_is_rejected() {
+        let order = order("near", "nep141:usdc.omft.near");
+        let mut body = quote_body();
+        body["quote"]
+            .as_object_mut()
+            .unwrap()
+            .remove("depositAddress");
+
+        assert!(build_exchange_proposal(&order, 0, &body).is_err());
+    }
+}
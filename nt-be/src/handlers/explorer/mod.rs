@@ -0,0 +1,3 @@
+//! Transaction explorer over the archival RPC
+
+pub mod transaction;
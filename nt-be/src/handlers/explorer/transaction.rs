@@ -0,0 +1,708 @@
+//! Transaction view of the explorer
+//!
+//! A transaction is fetched once with `EXPERIMENTAL_tx_status` from the archival RPC and its
+//! response is stored in `explorer_transactions`. Every read decodes the stored response into
+//! the receipt tree, the decoded actions and function call args, the gas and fees burnt, and
+//! the balance changes the transaction made per account.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sqlx::types::Json as SqlJson;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::handlers::balance_changes::block_info::get_block_by_hash;
+use crate::handlers::balance_changes::transfer_hints::tx_resolver::fetch_transaction_receipts;
+use crate::handlers::proposals::execution::receipt_transfers;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutcomeStatus {
+    Success,
+    Failure,
+    /// Not executed yet
+    Pending,
+}
+
+/// An action of a transaction or receipt
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedAction {
+    /// Action kind, e.g. "FunctionCall" or "Transfer"
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method_name: Option<String>,
+    /// Function call args decoded from JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<Value>,
+    /// Function call args that aren't JSON, base64 encoded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args_base64: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas: Option<u64>,
+    /// Attached NEAR in yoctoNEAR
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit: Option<String>,
+    /// Fields of the other action kinds (keys, stake, code size, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    /// Inner actions of a meta transaction (`Delegate`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegated_actions: Option<Vec<DecodedAction>>,
+}
+
+/// A receipt with the receipts it caused
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplorerReceipt {
+    pub receipt_id: String,
+    pub predecessor_id: String,
+    pub receiver_id: String,
+    /// Gas and deposit refunds sent by the protocol
+    pub is_refund: bool,
+    pub block_hash: Option<String>,
+    pub actions: Vec<DecodedAction>,
+    pub status: OutcomeStatus,
+    /// Value returned by a successful receipt, decoded from JSON when possible
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<Value>,
+    pub logs: Vec<String>,
+    pub gas_burnt: u64,
+    /// Fees burnt in yoctoNEAR
+    pub tokens_burnt: String,
+    pub children: Vec<ExplorerReceipt>,
+}
+
+/// Net change of one token balance of one account
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceDelta {
+    pub account_id: String,
+    /// "near", "nep141" or "nep245"
+    pub standard: String,
+    /// Token contract, none for NEAR
+    pub contract_id: Option<String>,
+    /// "near", the FT contract or the multi-token ID
+    pub token_id: String,
+    /// Signed amount in the token's base units
+    pub delta: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplorerTransaction {
+    pub transaction_hash: String,
+    pub signer_id: String,
+    pub receiver_id: String,
+    pub block_hash: String,
+    pub block_height: i64,
+    /// Block timestamp in nanoseconds
+    pub block_timestamp: i64,
+    pub status: OutcomeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<Value>,
+    pub actions: Vec<DecodedAction>,
+    /// Gas burnt by the transaction and all its receipts
+    pub gas_burnt: u64,
+    /// Fees burnt by the transaction and all its receipts in yoctoNEAR, paid by the signer
+    pub tokens_burnt: String,
+    /// Receipts the transaction converted into, with the receipts they caused
+    pub receipts: Vec<ExplorerReceipt>,
+    /// Balance changes made by the successful receipts, excluding fees
+    pub balance_deltas: Vec<BalanceDelta>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct StoredTransaction {
+    block_height: i64,
+    block_timestamp: i64,
+    rpc_response: SqlJson<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionQuery {
+    /// Signer of the transaction, looked up in the balance changes when omitted
+    pub signer_id: Option<String>,
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    log::error!("Explorer database error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Database error".to_string(),
+    )
+}
+
+/// Decode an action in RPC JSON form
+pub fn decode_action(action: &Value) -> DecodedAction {
+    // Actions without fields, such as "CreateAccount", are plain strings
+    if let Some(kind) = action.as_str() {
+        return DecodedAction {
+            kind: kind.to_string(),
+            ..Default::default()
+        };
+    }
+    let Some((kind, body)) = action.as_object().and_then(|a| a.iter().next()) else {
+        return DecodedAction {
+            kind: "Unknown".to_string(),
+            details: Some(action.clone()),
+            ..Default::default()
+        };
+    };
+
+    let mut decoded = DecodedAction {
+        kind: kind.clone(),
+        ..Default::default()
+    };
+    match kind.as_str() {
+        "FunctionCall" => {
+            decoded.method_name = body["method_name"].as_str().map(String::from);
+            decoded.gas = body["gas"].as_u64();
+            decoded.deposit = body["deposit"].as_str().map(String::from);
+            if let Some(args) = body["args"].as_str() {
+                match BASE64
+                    .decode(args)
+                    .ok()
+                    .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                {
+                    Some(json) => decoded.args = Some(json),
+                    None => decoded.args_base64 = Some(args.to_string()),
+                }
+            }
+        }
+        "Transfer" => decoded.deposit = body["deposit"].as_str().map(String::from),
+        "DeployContract" | "DeployGlobalContract" => {
+            // The code itself is too large to return
+            let code_size = body["code"]
+                .as_str()
+                .and_then(|code| BASE64.decode(code).ok())
+                .map(|code| code.len());
+            decoded.details = Some(json!({ "codeSize": code_size }));
+        }
+        "Delegate" => {
+            let delegate = &body["delegate_action"];
+            decoded.details = Some(json!({
+                "senderId": delegate["sender_id"],
+                "receiverId": delegate["receiver_id"],
+            }));
+            decoded.delegated_actions = delegate["actions"]
+                .as_array()
+                .map(|actions| actions.iter().map(decode_action).collect());
+        }
+        _ => decoded.details = Some(body.clone()),
+    }
+    decoded
+}
+
+fn decode_actions(actions: &Value) -> Vec<DecodedAction> {
+    actions
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(decode_action)
+        .collect()
+}
+
+/// Status, return value and failure of an execution outcome status in RPC JSON form
+fn decode_status(status: &Value) -> (OutcomeStatus, Option<Value>, Option<Value>) {
+    if let Some(failure) = status.get("Failure") {
+        return (OutcomeStatus::Failure, None, Some(failure.clone()));
+    }
+    if let Some(value) = status.get("SuccessValue").and_then(|v| v.as_str()) {
+        let return_value = (!value.is_empty()).then(|| match BASE64.decode(value) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .ok()
+                .or_else(|| String::from_utf8(bytes).ok().map(Value::String))
+                .unwrap_or_else(|| Value::String(value.to_string())),
+            Err(_) => Value::String(value.to_string()),
+        });
+        return (OutcomeStatus::Success, return_value, None);
+    }
+    if status.get("SuccessReceiptId").is_some() {
+        return (OutcomeStatus::Success, None, None);
+    }
+    (OutcomeStatus::Pending, None, None)
+}
+
+fn receipt_ids(outcome: &Value) -> Vec<&str> {
+    outcome["outcome"]["receipt_ids"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|id| id.as_str())
+        .collect()
+}
+
+fn build_receipt(
+    receipt_id: &str,
+    receipts: &HashMap<&str, &Value>,
+    outcomes: &HashMap<&str, &Value>,
+    visited: &mut HashSet<String>,
+) -> ExplorerReceipt {
+    visited.insert(receipt_id.to_string());
+    let receipt = receipts.get(receipt_id).copied().unwrap_or(&Value::Null);
+    let outcome = outcomes.get(receipt_id).copied().unwrap_or(&Value::Null);
+    let (status, return_value, failure) = decode_status(&outcome["outcome"]["status"]);
+    let predecessor_id = receipt["predecessor_id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let mut children = Vec::new();
+    for child_id in receipt_ids(outcome) {
+        if !visited.contains(child_id) {
+            children.push(build_receipt(child_id, receipts, outcomes, visited));
+        }
+    }
+
+    ExplorerReceipt {
+        receipt_id: receipt_id.to_string(),
+        is_refund: predecessor_id == "system",
+        predecessor_id,
+        receiver_id: receipt["receiver_id"]
+            .as_str()
+            .or_else(|| outcome["outcome"]["executor_id"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+        block_hash: outcome["block_hash"].as_str().map(String::from),
+        actions: decode_actions(&receipt["receipt"]["Action"]["actions"]),
+        status,
+        return_value,
+        failure,
+        logs: outcome["outcome"]["logs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|l| l.as_str().map(String::from))
+            .collect(),
+        gas_burnt: outcome["outcome"]["gas_burnt"].as_u64().unwrap_or(0),
+        tokens_burnt: outcome["outcome"]["tokens_burnt"]
+            .as_str()
+            .unwrap_or("0")
+            .to_string(),
+        children,
+    }
+}
+
+/// Net balance changes of the successful receipts of a transaction
+///
+/// Failed receipts are skipped, their transfers were reverted. Gas fees, gas refunds and the
+/// 1 yoctoNEAR deposits of token calls aren't balance changes.
+pub fn balance_deltas(transaction: &Value) -> Vec<BalanceDelta> {
+    let receipts: HashMap<&str, &Value> = transaction["receipts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|r| Some((r["receipt_id"].as_str()?, r)))
+        .collect();
+
+    let mut deltas: BTreeMap<(String, String, Option<String>, String), i128> = BTreeMap::new();
+    let outcomes = transaction["receipts_outcome"]
+        .as_array()
+        .into_iter()
+        .flatten();
+    for outcome in outcomes {
+        if outcome["outcome"]["status"].get("Failure").is_some() {
+            continue;
+        }
+        let receipt = outcome["id"]
+            .as_str()
+            .and_then(|id| receipts.get(id).copied());
+        for transfer in receipt_transfers(receipt, outcome) {
+            let Ok(amount) = transfer.amount.parse::<i128>() else {
+                continue;
+            };
+            let key = |account_id: &str| {
+                (
+                    account_id.to_string(),
+                    transfer.standard.clone(),
+                    transfer.contract_id.clone(),
+                    transfer.token_id.clone(),
+                )
+            };
+            *deltas.entry(key(&transfer.sender_id)).or_default() -= amount;
+            *deltas.entry(key(&transfer.receiver_id)).or_default() += amount;
+        }
+    }
+
+    deltas
+        .into_iter()
+        .filter(|(_, delta)| *delta != 0)
+        .map(
+            |((account_id, standard, contract_id, token_id), delta)| BalanceDelta {
+                account_id,
+                standard,
+                contract_id,
+                token_id,
+                delta: delta.to_string(),
+            },
+        )
+        .collect()
+}
+
+/// Decode an `EXPERIMENTAL_tx_status` result in RPC JSON form
+pub fn decode_transaction(
+    transaction: &Value,
+    block_height: i64,
+    block_timestamp: i64,
+) -> Result<ExplorerTransaction, String> {
+    let tx = &transaction["transaction"];
+    let transaction_hash = tx["hash"]
+        .as_str()
+        .ok_or("Transaction hash missing from the RPC response")?;
+    let tx_outcome = &transaction["transaction_outcome"];
+
+    let receipts: HashMap<&str, &Value> = transaction["receipts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|r| Some((r["receipt_id"].as_str()?, r)))
+        .collect();
+    let outcomes: HashMap<&str, &Value> = transaction["receipts_outcome"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|o| Some((o["id"].as_str()?, o)))
+        .collect();
+
+    let mut gas_burnt = tx_outcome["outcome"]["gas_burnt"].as_u64().unwrap_or(0);
+    let mut tokens_burnt = tx_outcome["outcome"]["tokens_burnt"]
+        .as_str()
+        .and_then(|t| t.parse::<u128>().ok())
+        .unwrap_or(0);
+    for outcome in outcomes.values() {
+        gas_burnt += outcome["outcome"]["gas_burnt"].as_u64().unwrap_or(0);
+        tokens_burnt += outcome["outcome"]["tokens_burnt"]
+            .as_str()
+            .and_then(|t| t.parse::<u128>().ok())
+            .unwrap_or(0);
+    }
+
+    let mut visited = HashSet::new();
+    let root_receipts = receipt_ids(tx_outcome)
+        .into_iter()
+        .map(|id| build_receipt(id, &receipts, &outcomes, &mut visited))
+        .collect();
+
+    let (status, _, failure) = decode_status(&transaction["status"]);
+
+    Ok(ExplorerTransaction {
+        transaction_hash: transaction_hash.to_string(),
+        signer_id: tx["signer_id"].as_str().unwrap_or_default().to_string(),
+        receiver_id: tx["receiver_id"].as_str().unwrap_or_default().to_string(),
+        block_hash: tx_outcome["block_hash"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        block_height,
+        block_timestamp,
+        status,
+        failure,
+        actions: decode_actions(&tx["actions"]),
+        gas_burnt,
+        tokens_burnt: tokens_burnt.to_string(),
+        receipts: root_receipts,
+        balance_deltas: balance_deltas(transaction),
+    })
+}
+
+async fn load_transaction(
+    pool: &sqlx::PgPool,
+    transaction_hash: &str,
+) -> Result<Option<StoredTransaction>, sqlx::Error> {
+    sqlx::query_as::<_, StoredTransaction>(
+        r#"
+        SELECT block_height, block_timestamp, rpc_response
+        FROM explorer_transactions
+        WHERE transaction_hash = $1
+        "#,
+    )
+    .bind(transaction_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Signer of a transaction that changed the balance of a tracked account
+async fn find_signer(
+    pool: &sqlx::PgPool,
+    transaction_hash: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT signer_id
+        FROM balance_changes
+        WHERE transaction_hashes @> ARRAY[$1]::TEXT[]
+          AND signer_id IS NOT NULL
+        LIMIT 1
+        "#,
+    )
+    .bind(transaction_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Decoded view of a transaction, fetched from the archival RPC on the first lookup
+pub async fn explore_transaction(
+    state: &AppState,
+    transaction_hash: &str,
+    signer_id: Option<&str>,
+) -> Result<ExplorerTransaction, (StatusCode, String)> {
+    if transaction_hash
+        .parse::<near_primitives::hash::CryptoHash>()
+        .is_err()
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid transaction hash: {}", transaction_hash),
+        ));
+    }
+    let decode_error = |e: String| {
+        log::error!("Failed to decode transaction {}: {}", transaction_hash, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to decode transaction".to_string(),
+        )
+    };
+
+    if let Some(stored) = load_transaction(&state.db_pool, transaction_hash)
+        .await
+        .map_err(db_error)?
+    {
+        return decode_transaction(
+            &stored.rpc_response,
+            stored.block_height,
+            stored.block_timestamp,
+        )
+        .map_err(decode_error);
+    }
+
+    let signer_id = match signer_id {
+        Some(signer_id) => signer_id.to_string(),
+        None => find_signer(&state.db_pool, transaction_hash)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!(
+                        "Signer of {} is unknown, pass it as signerId",
+                        transaction_hash
+                    ),
+                )
+            })?,
+    };
+
+    let rpc_error = |e: String| {
+        log::warn!("Failed to fetch transaction {}: {}", transaction_hash, e);
+        (
+            StatusCode::BAD_GATEWAY,
+            format!("Failed to fetch transaction {}", transaction_hash),
+        )
+    };
+    let response =
+        fetch_transaction_receipts(&state.archival_network, transaction_hash, &signer_id)
+            .await
+            .map_err(|e| rpc_error(e.to_string()))?;
+    let block_hash = response["transaction_outcome"]["block_hash"]
+        .as_str()
+        .ok_or_else(|| rpc_error("Transaction outcome has no block hash".to_string()))?;
+    let (block_height, block_timestamp) = get_block_by_hash(&state.archival_network, block_hash)
+        .await
+        .map_err(|e| rpc_error(e.to_string()))?;
+
+    let transaction = decode_transaction(&response, block_height as i64, block_timestamp)
+        .map_err(decode_error)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO explorer_transactions
+            (transaction_hash, signer_id, receiver_id, block_hash, block_height,
+             block_timestamp, rpc_response)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(&transaction.transaction_hash)
+    .bind(&transaction.signer_id)
+    .bind(&transaction.receiver_id)
+    .bind(&transaction.block_hash)
+    .bind(transaction.block_height)
+    .bind(transaction.block_timestamp)
+    .bind(SqlJson(&response))
+    .execute(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(transaction)
+}
+
+/// Get a transaction with its receipt tree, decoded actions, fees and balance changes
+pub async fn get_transaction(
+    State(state): State<Arc<AppState>>,
+    Path(transaction_hash): Path<String>,
+    Query(query): Query<TransactionQuery>,
+) -> Result<Json<ExplorerTransaction>, (StatusCode, String)> {
+    explore_transaction(&state, &transaction_hash, query.signer_id.as_deref())
+        .await
+        .map(Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAO: &str = "testing-treasury.sputnik-dao.near";
+    const USDC: &str = "17208628f84f5d6ad33f0da3bbbeb27ffcb398eac501a31bd6ad2011e36133a1";
+
+    /// `EXPERIMENTAL_tx_status` of an approved Transfer proposal paying 100 USDC
+    fn transfer_proposal_tx() -> Value {
+        serde_json::from_str(include_str!(
+            "../../../tests/test_data/proposal_execution/transfer_proposal_tx.json"
+        ))
+        .unwrap()
+    }
+
+    /// `EXPERIMENTAL_tx_status` of an `add_proposal` call that panicked, with its deposit and
+    /// gas refunds
+    fn failed_add_proposal_tx() -> Value {
+        serde_json::from_str(include_str!(
+            "../../../tests/test_data/explorer/failed_add_proposal_tx.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_transaction() {
+        let tx = decode_transaction(&transfer_proposal_tx(), 151200000, 1_700_000_000).unwrap();
+
+        assert_eq!(
+            tx.transaction_hash,
+            "RNFBzRdzmKcJRwoqd1rGjv2VAAoMBHimge48se3UxKvp"
+        );
+        assert_eq!(tx.signer_id, "alice.near");
+        assert_eq!(tx.receiver_id, DAO);
+        assert_eq!(tx.status, OutcomeStatus::Success);
+        assert_eq!(tx.actions[0].method_name.as_deref(), Some("act_proposal"));
+        assert_eq!(
+            tx.actions[0].args,
+            Some(json!({ "id": 42, "action": "VoteApprove" }))
+        );
+
+        // The transaction outcome and its six receipts burnt the same amount each
+        assert_eq!(tx.gas_burnt, 7 * 2428319796434);
+        assert_eq!(tx.tokens_burnt, (7 * 242831979643400000000u128).to_string());
+
+        // act_proposal caused the USDC transfer, the proposal callback, the NEAR payment and
+        // a gas refund; the USDC contract refunded its own unused gas
+        assert_eq!(tx.receipts.len(), 1);
+        let act_proposal = &tx.receipts[0];
+        assert_eq!(act_proposal.predecessor_id, "alice.near");
+        assert_eq!(act_proposal.children.len(), 4);
+        let ft_transfer = &act_proposal.children[0];
+        assert_eq!(ft_transfer.receiver_id, USDC);
+        assert_eq!(
+            ft_transfer.actions[0].method_name.as_deref(),
+            Some("ft_transfer")
+        );
+        assert_eq!(
+            ft_transfer.actions[0].args.as_ref().unwrap()["receiver_id"],
+            "bob.near"
+        );
+        assert_eq!(ft_transfer.logs.len(), 1);
+        assert_eq!(ft_transfer.children.len(), 1);
+        assert!(ft_transfer.children[0].is_refund);
+        assert!(act_proposal.children[3].is_refund);
+    }
+
+    #[test]
+    fn test_balance_deltas() {
+        let deltas = balance_deltas(&transfer_proposal_tx());
+
+        let delta = |account: &str, token: &str| {
+            deltas
+                .iter()
+                .find(|d| d.account_id == account && d.token_id == token)
+                .map(|d| d.delta.as_str())
+        };
+        assert_eq!(deltas.len(), 4);
+        assert_eq!(delta(DAO, USDC), Some("-100000000"));
+        assert_eq!(delta("bob.near", USDC), Some("100000000"));
+        assert_eq!(delta(DAO, "near"), Some("-100000000000000000000000"));
+        assert_eq!(
+            delta("carol.near", "near"),
+            Some("100000000000000000000000")
+        );
+    }
+
+    #[test]
+    fn test_decode_failed_transaction() {
+        let tx = decode_transaction(&failed_add_proposal_tx(), 151200007, 1_700_000_000).unwrap();
+
+        assert_eq!(tx.status, OutcomeStatus::Failure);
+        assert!(tx.failure.as_ref().unwrap()["ActionError"].is_object());
+
+        let add_proposal = &tx.receipts[0];
+        assert_eq!(add_proposal.status, OutcomeStatus::Failure);
+        assert_eq!(add_proposal.actions.len(), 2);
+        assert_eq!(
+            add_proposal.actions[0].args.as_ref().unwrap()["proposal"]["description"],
+            "Payment to carol"
+        );
+        assert_eq!(add_proposal.actions[1].kind, "Transfer");
+        assert_eq!(
+            add_proposal.actions[1].deposit.as_deref(),
+            Some("2000000000000000000000000")
+        );
+        assert!(add_proposal.children.iter().all(|r| r.is_refund));
+
+        // The deposits were refunded, so nothing changed hands
+        assert!(tx.balance_deltas.is_empty());
+    }
+
+    #[test]
+    fn test_decode_action() {
+        assert_eq!(decode_action(&json!("CreateAccount")).kind, "CreateAccount");
+
+        let binary_args = decode_action(&json!({
+            "FunctionCall": {
+                "method_name": "upload",
+                "args": BASE64.encode([0u8, 159, 146, 150]),
+                "gas": 30000000000000u64,
+                "deposit": "0"
+            }
+        }));
+        assert_eq!(binary_args.args, None);
+        assert_eq!(binary_args.args_base64.as_deref(), Some("AJ+Slg=="));
+
+        let deploy = decode_action(&json!({
+            "DeployContract": { "code": BASE64.encode([0u8; 42]) }
+        }));
+        assert_eq!(deploy.details, Some(json!({ "codeSize": 42 })));
+
+        let delegate = decode_action(&json!({
+            "Delegate": {
+                "delegate_action": {
+                    "sender_id": "alice.near",
+                    "receiver_id": DAO,
+                    "actions": [{ "Transfer": { "deposit": "1000" } }]
+                },
+                "signature": "ed25519:..."
+            }
+        }));
+        assert_eq!(delegate.details.as_ref().unwrap()["senderId"], "alice.near");
+        assert_eq!(
+            delegate.delegated_actions.unwrap()[0].deposit.as_deref(),
+            Some("1000")
+        );
+    }
+}
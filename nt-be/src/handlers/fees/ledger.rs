@@ -0,0 +1,523 @@
+//! Fee ledger
+//!
+//! NEAR charges the gas of a transaction and of every receipt it spawns to the transaction
+//! signer, so the tokens burnt by a transaction are paid by exactly one account:
+//! - The treasury itself, for transactions it signs
+//! - One of its members, for proposals, votes and executions they sign themselves
+//! - A platform key, for relayed delegate actions (on behalf of the member who signed the
+//!   delegate action), payouts, list submissions and proposals added by the relayer
+//!
+//! Platform transactions are queued by the code that sends them. Transactions signed by a
+//! treasury or its members are picked up from the balance changes and proposal executions of
+//! the treasury; a vote that moves no tokens is only seen when it was relayed.
+//!
+//! The worker looks each queued transaction up on the archival RPC (through the explorer cache)
+//! and records its fees in `fee_ledger`.
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::handlers::explorer::transaction::{OutcomeStatus, explore_transaction};
+
+/// Lookups of a transaction before it is marked failed
+const MAX_ATTEMPTS: i32 = 5;
+
+/// Queued transactions processed per worker run
+const BATCH_SIZE: i64 = 50;
+
+/// What a fee ledger transaction was sent for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeSource {
+    /// Delegate action relayed by `relay_delegate_action`
+    Relay,
+    /// `payout_batch` call of the payout worker
+    Payout,
+    /// `submit_list` call to the bulk payment contract
+    ListSubmission,
+    /// Proposal added by the relayer (payment schedules)
+    RelayerProposal,
+    /// Transaction signed by a treasury or one of its members
+    TreasuryActivity,
+}
+
+impl FeeSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeSource::Relay => "relay",
+            FeeSource::Payout => "payout",
+            FeeSource::ListSubmission => "list_submission",
+            FeeSource::RelayerProposal => "relayer_proposal",
+            FeeSource::TreasuryActivity => "treasury_activity",
+        }
+    }
+}
+
+/// Who paid the fees of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayerKind {
+    Treasury,
+    Member,
+    /// One of the platform keys
+    Relayer,
+    Other,
+}
+
+impl PayerKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayerKind::Treasury => "treasury",
+            PayerKind::Member => "member",
+            PayerKind::Relayer => "relayer",
+            PayerKind::Other => "other",
+        }
+    }
+}
+
+/// Classify the signer of a transaction sent for a treasury
+pub fn classify_payer(
+    payer_id: &str,
+    dao_id: Option<&str>,
+    platform_keys: &[String],
+    is_member: bool,
+) -> PayerKind {
+    if platform_keys.iter().any(|key| key == payer_id) {
+        PayerKind::Relayer
+    } else if dao_id == Some(payer_id) {
+        PayerKind::Treasury
+    } else if is_member {
+        PayerKind::Member
+    } else {
+        PayerKind::Other
+    }
+}
+
+/// Accounts the platform signs transactions with
+pub fn platform_keys(state: &AppState) -> Vec<String> {
+    state
+        .relayer_pool
+        .account_ids()
+        .into_iter()
+        .chain(state.bulk_payment_pool.account_ids())
+        .map(|account_id| account_id.to_string())
+        .collect()
+}
+
+/// A transaction sent by a platform key
+pub struct PlatformTransaction<'a> {
+    pub transaction_hash: &'a str,
+    pub signer_id: &'a str,
+    pub source: FeeSource,
+    pub dao_id: Option<&'a str>,
+    pub on_behalf_of: Option<&'a str>,
+    pub list_id: Option<&'a str>,
+}
+
+/// Queue a transaction sent by a platform key for fee accounting
+///
+/// Failures are logged only - the transaction has already been sent.
+pub async fn record_platform_transaction(pool: &PgPool, transaction: PlatformTransaction<'_>) {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO fee_transactions
+            (transaction_hash, signer_id, dao_id, on_behalf_of, list_id, source)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (transaction_hash) DO NOTHING
+        "#,
+    )
+    .bind(transaction.transaction_hash)
+    .bind(transaction.signer_id)
+    .bind(transaction.dao_id)
+    .bind(transaction.on_behalf_of)
+    .bind(transaction.list_id)
+    .bind(transaction.source.as_str())
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        log::error!(
+            "Failed to queue transaction {} for fee accounting: {}",
+            transaction.transaction_hash,
+            e
+        );
+    }
+}
+
+/// Queue the transactions signed by treasuries and their members
+///
+/// Only rows created since `since` are scanned, or all of them when `None`.
+pub async fn enqueue_treasury_activity(
+    pool: &PgPool,
+    since: Option<DateTime<Utc>>,
+) -> Result<u64, sqlx::Error> {
+    let balance_changes = sqlx::query(
+        r#"
+        INSERT INTO fee_transactions (transaction_hash, signer_id, dao_id, source)
+        SELECT DISTINCT ON (tx.hash) tx.hash, bc.signer_id, bc.account_id, $2
+        FROM balance_changes bc
+        CROSS JOIN LATERAL unnest(bc.transaction_hashes) AS tx(hash)
+        WHERE ($1::TIMESTAMPTZ IS NULL OR bc.created_at >= $1)
+          AND bc.signer_id IS NOT NULL
+          AND (
+            bc.signer_id = bc.account_id
+            OR EXISTS (
+                SELECT 1 FROM dao_members m
+                WHERE m.dao_id = bc.account_id AND m.account_id = bc.signer_id
+            )
+          )
+        ORDER BY tx.hash, bc.id
+        ON CONFLICT (transaction_hash) DO NOTHING
+        "#,
+    )
+    .bind(since)
+    .bind(FeeSource::TreasuryActivity.as_str())
+    .execute(pool)
+    .await?;
+
+    let executions = sqlx::query(
+        r#"
+        INSERT INTO fee_transactions (transaction_hash, signer_id, dao_id, source)
+        SELECT transaction_hash, signer_id, dao_id, $2
+        FROM proposal_executions
+        WHERE $1::TIMESTAMPTZ IS NULL OR created_at >= $1
+        ON CONFLICT (transaction_hash) DO NOTHING
+        "#,
+    )
+    .bind(since)
+    .bind(FeeSource::TreasuryActivity.as_str())
+    .execute(pool)
+    .await?;
+
+    Ok(balance_changes.rows_affected() + executions.rows_affected())
+}
+
+/// Where the next scan starts, `None` before the first one
+async fn load_scan_watermark(
+    pool: &PgPool,
+    scan: &str,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    sqlx::query_scalar("SELECT scanned_from FROM fee_ledger_scans WHERE scan = $1")
+        .bind(scan)
+        .fetch_optional(pool)
+        .await
+}
+
+async fn save_scan_watermark(
+    pool: &PgPool,
+    scan: &str,
+    scanned_from: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO fee_ledger_scans (scan, scanned_from)
+        VALUES ($1, $2)
+        ON CONFLICT (scan) DO UPDATE
+        SET scanned_from = EXCLUDED.scanned_from, updated_at = NOW()
+        "#,
+    )
+    .bind(scan)
+    .bind(scanned_from)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Queue the treasury transactions recorded since the stored watermark, and move it
+///
+/// The first scan covers all balance changes and proposal executions.
+pub async fn enqueue_new_treasury_activity(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let scan = FeeSource::TreasuryActivity.as_str();
+    let since = load_scan_watermark(pool, scan).await?;
+    let queued = enqueue_treasury_activity(pool, since).await?;
+    // Overlap scans so rows committed while this one ran are not missed
+    save_scan_watermark(pool, scan, now - chrono::Duration::minutes(5)).await?;
+    Ok(queued)
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct QueuedTransaction {
+    transaction_hash: String,
+    signer_id: String,
+    dao_id: Option<String>,
+    on_behalf_of: Option<String>,
+    source: String,
+    attempts: i32,
+}
+
+async fn is_dao_member(pool: &PgPool, dao_id: &str, account_id: &str) -> Result<bool, String> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM dao_members WHERE dao_id = $1 AND account_id = $2)",
+    )
+    .bind(dao_id)
+    .bind(account_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("Failed to check membership: {}", e))
+}
+
+/// Look a queued transaction up and write its ledger entry
+async fn record_fees(
+    state: &AppState,
+    queued: &QueuedTransaction,
+    platform_keys: &[String],
+) -> Result<(), String> {
+    let transaction = explore_transaction(
+        state,
+        &queued.transaction_hash,
+        Some(queued.signer_id.as_str()),
+    )
+    .await
+    .map_err(|(_, e)| e)?;
+
+    if transaction.status == OutcomeStatus::Pending {
+        return Err("Transaction is not final yet".to_string());
+    }
+
+    let payer_id = transaction.signer_id.as_str();
+    let dao_id = queued.dao_id.as_deref();
+    let is_member = match dao_id {
+        Some(dao_id) => is_dao_member(&state.db_pool, dao_id, payer_id).await?,
+        None => false,
+    };
+    let payer_kind = classify_payer(payer_id, dao_id, platform_keys, is_member);
+
+    let tokens_burnt = BigDecimal::from_str(&transaction.tokens_burnt)
+        .map_err(|e| format!("Invalid tokens burnt: {}", e))?;
+    let block_timestamp = DateTime::from_timestamp_nanos(transaction.block_timestamp);
+
+    sqlx::query(
+        r#"
+        INSERT INTO fee_ledger
+            (transaction_hash, payer_id, payer_kind, dao_id, on_behalf_of, source,
+             gas_burnt, tokens_burnt, block_timestamp)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT (transaction_hash, payer_id) DO NOTHING
+        "#,
+    )
+    .bind(&queued.transaction_hash)
+    .bind(payer_id)
+    .bind(payer_kind.as_str())
+    .bind(dao_id)
+    .bind(&queued.on_behalf_of)
+    .bind(&queued.source)
+    .bind(transaction.gas_burnt as i64)
+    .bind(tokens_burnt)
+    .bind(block_timestamp)
+    .execute(&state.db_pool)
+    .await
+    .map_err(|e| format!("Failed to record fees: {}", e))?;
+
+    Ok(())
+}
+
+/// Record the fees of pending queued transactions
+///
+/// Returns the number of transactions recorded.
+pub async fn process_pending_transactions(state: &Arc<AppState>) -> Result<usize, String> {
+    // Payout transactions carry only their list; the treasury comes from the list submission
+    // or the bulk payment run of the list
+    let queued = sqlx::query_as::<_, QueuedTransaction>(
+        r#"
+        SELECT
+            ft.transaction_hash,
+            ft.signer_id,
+            COALESCE(
+                ft.dao_id,
+                (SELECT s.dao_id FROM fee_transactions s
+                 WHERE s.list_id = ft.list_id AND s.dao_id IS NOT NULL
+                 LIMIT 1),
+                (SELECT r.dao_id FROM bulk_payment_run_lists l
+                 JOIN bulk_payment_runs r ON r.id = l.run_id
+                 WHERE l.list_id = ft.list_id)
+            ) AS dao_id,
+            ft.on_behalf_of,
+            ft.source,
+            ft.attempts
+        FROM fee_transactions ft
+        WHERE ft.status = 'pending'
+          AND ft.created_at < NOW() - INTERVAL '30 seconds'
+        ORDER BY ft.updated_at
+        LIMIT $1
+        "#,
+    )
+    .bind(BATCH_SIZE)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| format!("Failed to load queued transactions: {}", e))?;
+
+    let platform_keys = platform_keys(state);
+    let mut recorded = 0;
+
+    for transaction in &queued {
+        let (status, error) = match record_fees(state, transaction, &platform_keys).await {
+            Ok(()) => {
+                recorded += 1;
+                ("recorded", None)
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to record fees of {}: {}",
+                    transaction.transaction_hash,
+                    e
+                );
+                let status = if transaction.attempts + 1 >= MAX_ATTEMPTS {
+                    "failed"
+                } else {
+                    "pending"
+                };
+                (status, Some(e))
+            }
+        };
+
+        if let Err(e) = sqlx::query(
+            r#"
+            UPDATE fee_transactions
+            SET status = $2, error = $3, dao_id = COALESCE(dao_id, $4),
+                attempts = attempts + 1, updated_at = NOW()
+            WHERE transaction_hash = $1
+            "#,
+        )
+        .bind(&transaction.transaction_hash)
+        .bind(status)
+        .bind(error)
+        .bind(&transaction.dao_id)
+        .execute(&state.db_pool)
+        .await
+        {
+            log::error!(
+                "Failed to update queued transaction {}: {}",
+                transaction.transaction_hash,
+                e
+            );
+        }
+    }
+
+    Ok(recorded)
+}
+
+/// Run the fee ledger worker
+///
+/// Each run scans the balance changes and proposal executions created since the previous
+/// scan, which is remembered across restarts.
+pub async fn run_fee_ledger_service(state: Arc<AppState>) {
+    log::info!("Starting fee ledger worker (60 second poll interval)");
+
+    // Wait a bit before first run to let server fully start
+    tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+
+    loop {
+        interval.tick().await;
+
+        match enqueue_new_treasury_activity(&state.db_pool, Utc::now()).await {
+            Ok(queued) if queued > 0 => {
+                log::info!(
+                    "Queued {} treasury transaction(s) for fee accounting",
+                    queued
+                );
+            }
+            Ok(_) => {}
+            Err(e) => log::error!("Failed to queue treasury transactions: {}", e),
+        }
+
+        match process_pending_transactions(&state).await {
+            Ok(recorded) if recorded > 0 => {
+                log::info!("Recorded fees of {} transaction(s)", recorded)
+            }
+            Ok(_) => {}
+            Err(e) => log::error!("Fee ledger error: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> Vec<String> {
+        vec!["relayer.near".to_string(), "bulkpayment.near".to_string()]
+    }
+
+    #[test]
+    fn test_classify_payer() {
+        let dao = Some("dao.sputnik-dao.near");
+
+        assert_eq!(
+            classify_payer("relayer.near", dao, &keys(), false),
+            PayerKind::Relayer
+        );
+        assert_eq!(
+            classify_payer("bulkpayment.near", None, &keys(), false),
+            PayerKind::Relayer
+        );
+        assert_eq!(
+            classify_payer("dao.sputnik-dao.near", dao, &keys(), false),
+            PayerKind::Treasury
+        );
+        assert_eq!(
+            classify_payer("alice.near", dao, &keys(), true),
+            PayerKind::Member
+        );
+        assert_eq!(
+            classify_payer("bob.near", dao, &keys(), false),
+            PayerKind::Other
+        );
+    }
+
+    #[test]
+    fn test_platform_key_wins_over_membership() {
+        // The relayer can be a member of a treasury; what it signs is still platform spend
+        assert_eq!(
+            classify_payer("relayer.near", Some("dao.sputnik-dao.near"), &keys(), true),
+            PayerKind::Relayer
+        );
+    }
+
+    async fn insert_execution(
+        pool: &PgPool,
+        proposal_id: i64,
+        transaction_hash: &str,
+        created_at: DateTime<Utc>,
+    ) -> sqlx::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO proposal_executions
+                (dao_id, proposal_id, action, transaction_hash, signer_id, receipt_id,
+                 block_height, block_timestamp, transfers, created_at)
+            VALUES ('dao.sputnik-dao.near', $1, 'VoteApprove', $2, 'alice.near', $2, 1, 1,
+                    '[]', $3)
+            "#,
+        )
+        .bind(proposal_id)
+        .bind(transaction_hash)
+        .bind(created_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    #[sqlx::test]
+    async fn test_treasury_activity_scan_resumes_from_watermark(pool: PgPool) -> sqlx::Result<()> {
+        let now = Utc::now();
+        insert_execution(&pool, 1, "tx-old", now - chrono::Duration::days(1)).await?;
+        assert_eq!(enqueue_new_treasury_activity(&pool, now).await?, 1);
+
+        // Rows before the stored watermark are not scanned again, as after a restart
+        sqlx::query("DELETE FROM fee_transactions")
+            .execute(&pool)
+            .await?;
+        assert_eq!(enqueue_new_treasury_activity(&pool, Utc::now()).await?, 0);
+
+        insert_execution(&pool, 2, "tx-new", Utc::now()).await?;
+        assert_eq!(enqueue_new_treasury_activity(&pool, Utc::now()).await?, 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+//! Gas and fee cost accounting per treasury and per platform key
+
+pub mod ledger;
+pub mod reports;
@@ -0,0 +1,374 @@
+//! Monthly operating-cost reports from the fee ledger
+//!
+//! - Per treasury: fees burnt for the treasury, by payer (the treasury, its members or a
+//!   platform key) and by what the transaction was sent for
+//! - Per platform key: fees the platform keys paid, by treasury and by what they were sent for
+//!
+//! NEAR is converted to USD at the price of the day each transaction was included; a month
+//! has no USD value when a price is missing for one of its days.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use super::ledger::PayerKind;
+use crate::AppState;
+use crate::auth::AdminUser;
+
+/// Price service token ID of NEAR
+const NEAR_TOKEN_ID: &str = "near";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreasuryFeesQuery {
+    pub dao_id: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformFeesQuery {
+    /// Only this platform key
+    #[serde(default)]
+    pub payer_id: Option<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+/// Ledger entries of one day, payer, source and treasury
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DailyFees {
+    pub day: NaiveDate,
+    pub payer_id: String,
+    pub payer_kind: String,
+    pub source: String,
+    pub dao_id: Option<String>,
+    pub transactions: i64,
+    pub gas_burnt: i64,
+    /// yoctoNEAR
+    pub tokens_burnt: BigDecimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyFees {
+    /// "YYYY-MM"
+    pub month: String,
+    pub payer_id: String,
+    pub payer_kind: String,
+    pub source: String,
+    pub dao_id: Option<String>,
+    pub transactions: i64,
+    pub gas_burnt: i64,
+    /// NEAR
+    pub tokens_burnt: BigDecimal,
+    pub usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyFeeTotal {
+    /// "YYYY-MM"
+    pub month: String,
+    pub transactions: i64,
+    pub gas_burnt: i64,
+    /// NEAR
+    pub tokens_burnt: BigDecimal,
+    pub usd: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeReport {
+    pub entries: Vec<MonthlyFees>,
+    pub totals: Vec<MonthlyFeeTotal>,
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    log::error!("Fee report database error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Database error".to_string(),
+    )
+}
+
+fn yocto_to_near(yocto: &BigDecimal) -> BigDecimal {
+    (yocto * BigDecimal::new(1.into(), 24)).normalized()
+}
+
+fn add_usd(total: Option<f64>, near: &BigDecimal, price: Option<f64>) -> Option<f64> {
+    Some(total? + near.to_f64()? * price?)
+}
+
+/// Fold daily ledger totals into months, valuing each day at its NEAR price
+pub fn fold_monthly(rows: &[DailyFees], prices: &HashMap<NaiveDate, f64>) -> FeeReport {
+    type Key = (String, String, String, String, Option<String>);
+    let mut entries: BTreeMap<Key, MonthlyFees> = BTreeMap::new();
+
+    for row in rows {
+        let month = row.day.format("%Y-%m").to_string();
+        let near = yocto_to_near(&row.tokens_burnt);
+        let price = prices.get(&row.day).copied();
+
+        let entry = entries
+            .entry((
+                month.clone(),
+                row.payer_kind.clone(),
+                row.payer_id.clone(),
+                row.source.clone(),
+                row.dao_id.clone(),
+            ))
+            .or_insert_with(|| MonthlyFees {
+                month,
+                payer_id: row.payer_id.clone(),
+                payer_kind: row.payer_kind.clone(),
+                source: row.source.clone(),
+                dao_id: row.dao_id.clone(),
+                transactions: 0,
+                gas_burnt: 0,
+                tokens_burnt: BigDecimal::zero(),
+                usd: Some(0.0),
+            });
+        entry.transactions += row.transactions;
+        entry.gas_burnt += row.gas_burnt;
+        entry.usd = add_usd(entry.usd, &near, price);
+        entry.tokens_burnt = (&entry.tokens_burnt + near).normalized();
+    }
+
+    let mut totals: BTreeMap<String, MonthlyFeeTotal> = BTreeMap::new();
+    for entry in entries.values() {
+        let total = totals
+            .entry(entry.month.clone())
+            .or_insert_with(|| MonthlyFeeTotal {
+                month: entry.month.clone(),
+                transactions: 0,
+                gas_burnt: 0,
+                tokens_burnt: BigDecimal::zero(),
+                usd: Some(0.0),
+            });
+        total.transactions += entry.transactions;
+        total.gas_burnt += entry.gas_burnt;
+        total.tokens_burnt = (&total.tokens_burnt + &entry.tokens_burnt).normalized();
+        total.usd = match (total.usd, entry.usd) {
+            (Some(total), Some(usd)) => Some(total + usd),
+            _ => None,
+        };
+    }
+
+    FeeReport {
+        entries: entries.into_values().collect(),
+        totals: totals.into_values().collect(),
+    }
+}
+
+async fn build_report(state: &AppState, rows: Vec<DailyFees>) -> FeeReport {
+    let mut days: Vec<NaiveDate> = rows.iter().map(|row| row.day).collect();
+    days.sort();
+    days.dedup();
+
+    let prices = if days.is_empty() {
+        HashMap::new()
+    } else {
+        state
+            .price_service
+            .get_prices_batch(NEAR_TOKEN_ID, &days)
+            .await
+            .unwrap_or_else(|e| {
+                log::debug!("Failed to batch fetch NEAR prices: {}", e);
+                HashMap::new()
+            })
+    };
+
+    fold_monthly(&rows, &prices)
+}
+
+fn validate_range(
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> Result<(), (StatusCode, String)> {
+    if start_time >= end_time {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "startTime must be before endTime".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Monthly fees burnt for a treasury, by payer and source
+pub async fn get_treasury_fees(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<TreasuryFeesQuery>,
+) -> Result<Json<FeeReport>, (StatusCode, String)> {
+    validate_range(query.start_time, query.end_time)?;
+
+    let rows = sqlx::query_as::<_, DailyFees>(
+        r#"
+        SELECT
+            (block_timestamp AT TIME ZONE 'UTC')::DATE AS day,
+            payer_id,
+            payer_kind,
+            source,
+            dao_id,
+            COUNT(*) AS transactions,
+            SUM(gas_burnt)::BIGINT AS gas_burnt,
+            SUM(tokens_burnt) AS tokens_burnt
+        FROM fee_ledger
+        WHERE dao_id = $1
+          AND block_timestamp >= $2
+          AND block_timestamp < $3
+        GROUP BY 1, 2, 3, 4, 5
+        ORDER BY 1
+        "#,
+    )
+    .bind(&query.dao_id)
+    .bind(query.start_time)
+    .bind(query.end_time)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(build_report(&state, rows).await))
+}
+
+/// Monthly fees paid by the platform keys, by treasury and source. Admins only.
+pub async fn get_platform_fees(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<PlatformFeesQuery>,
+) -> Result<Json<FeeReport>, (StatusCode, String)> {
+    validate_range(query.start_time, query.end_time)?;
+
+    let rows = sqlx::query_as::<_, DailyFees>(
+        r#"
+        SELECT
+            (block_timestamp AT TIME ZONE 'UTC')::DATE AS day,
+            payer_id,
+            payer_kind,
+            source,
+            dao_id,
+            COUNT(*) AS transactions,
+            SUM(gas_burnt)::BIGINT AS gas_burnt,
+            SUM(tokens_burnt) AS tokens_burnt
+        FROM fee_ledger
+        WHERE payer_kind = $1
+          AND ($2::TEXT IS NULL OR payer_id = $2)
+          AND block_timestamp >= $3
+          AND block_timestamp < $4
+        GROUP BY 1, 2, 3, 4, 5
+        ORDER BY 1
+        "#,
+    )
+    .bind(PayerKind::Relayer.as_str())
+    .bind(&query.payer_id)
+    .bind(query.start_time)
+    .bind(query.end_time)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(build_report(&state, rows).await))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn day(date: &str, payer_id: &str, payer_kind: &str, yocto: &str) -> DailyFees {
+        DailyFees {
+            day: NaiveDate::from_str(date).unwrap(),
+            payer_id: payer_id.to_string(),
+            payer_kind: payer_kind.to_string(),
+            source: "treasury_activity".to_string(),
+            dao_id: Some("dao.sputnik-dao.near".to_string()),
+            transactions: 2,
+            gas_burnt: 10,
+            tokens_burnt: BigDecimal::from_str(yocto).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_fold_monthly_values_each_day() {
+        let rows = vec![
+            // 0.001 NEAR at $4, 0.002 NEAR at $5
+            day(
+                "2026-03-01",
+                "alice.near",
+                "member",
+                "1000000000000000000000",
+            ),
+            day(
+                "2026-03-20",
+                "alice.near",
+                "member",
+                "2000000000000000000000",
+            ),
+            day(
+                "2026-04-02",
+                "dao.sputnik-dao.near",
+                "treasury",
+                "500000000000000000000",
+            ),
+        ];
+        let prices = HashMap::from([
+            (NaiveDate::from_str("2026-03-01").unwrap(), 4.0),
+            (NaiveDate::from_str("2026-03-20").unwrap(), 5.0),
+            (NaiveDate::from_str("2026-04-02").unwrap(), 2.0),
+        ]);
+
+        let report = fold_monthly(&rows, &prices);
+
+        assert_eq!(report.entries.len(), 2);
+        let march = &report.entries[0];
+        assert_eq!(march.month, "2026-03");
+        assert_eq!(march.transactions, 4);
+        assert_eq!(march.gas_burnt, 20);
+        assert_eq!(march.tokens_burnt, BigDecimal::from_str("0.003").unwrap());
+        assert!((march.usd.unwrap() - 0.014).abs() < 1e-12);
+
+        assert_eq!(report.totals.len(), 2);
+        assert_eq!(report.totals[1].month, "2026-04");
+        assert_eq!(
+            report.totals[1].tokens_burnt,
+            BigDecimal::from_str("0.0005").unwrap()
+        );
+        assert!((report.totals[1].usd.unwrap() - 0.001).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fold_monthly_missing_price_clears_usd() {
+        let rows = vec![
+            day(
+                "2026-03-01",
+                "alice.near",
+                "member",
+                "1000000000000000000000",
+            ),
+            day(
+                "2026-03-02",
+                "alice.near",
+                "member",
+                "1000000000000000000000",
+            ),
+        ];
+        let prices = HashMap::from([(NaiveDate::from_str("2026-03-01").unwrap(), 4.0)]);
+
+        let report = fold_monthly(&rows, &prices);
+
+        assert_eq!(report.entries[0].usd, None);
+        assert_eq!(report.totals[0].usd, None);
+        assert_eq!(
+            report.totals[0].tokens_burnt,
+            BigDecimal::from_str("0.002").unwrap()
+        );
+    }
+}
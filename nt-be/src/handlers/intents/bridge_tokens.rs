@@ -8,7 +8,7 @@ use std::sync::Arc;
 use super::supported_tokens::fetch_supported_tokens_data;
 use crate::{
     AppState,
-    constants::intents_chains::ChainIcons,
+    constants::{intents_chains::ChainIcons, intents_tokens::find_token_by_defuse_asset_id},
     handlers::token::metadata::{TokenMetadata, fetch_tokens_metadata},
 };
 
@@ -38,6 +38,16 @@ pub struct DepositAssetsResponse {
     pub assets: Vec<AssetOption>,
 }
 
+/// Bridge chain ID ("eth:1") of a defuse asset identifier ("eth:1:0xa0b8...")
+pub fn chain_id_from_defuse_identifier(defuse_id: &str) -> String {
+    let parts: Vec<&str> = defuse_id.split(':').collect();
+    if parts.len() >= 2 {
+        format!("{}:{}", parts[0], parts[1])
+    } else {
+        parts.first().unwrap_or(&"").to_string()
+    }
+}
+
 pub async fn get_bridge_tokens(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<DepositAssetsResponse>, (StatusCode, String)> {
@@ -112,33 +122,41 @@ pub async fn get_bridge_tokens(
                     continue;
                 }
 
-                let canonical_symbol = meta.symbol.to_uppercase();
+                // The token registry's symbol, name and icon (admin overrides included) win
+                // over the fetched metadata
+                let registered = find_token_by_defuse_asset_id(intents_id);
+                let symbol = registered
+                    .as_ref()
+                    .map_or_else(|| meta.symbol.clone(), |t| t.symbol.clone());
+                let canonical_symbol = symbol.to_uppercase();
 
                 if !asset_map.contains_key(&canonical_symbol) {
                     asset_map.insert(
                         canonical_symbol.clone(),
                         AssetOption {
                             id: canonical_symbol.to_lowercase(),
-                            asset_name: meta.symbol.clone(),
-                            name: meta.name.clone(),
-                            symbol: meta.symbol.clone(),
-                            icon: meta.icon.clone(),
+                            asset_name: symbol.clone(),
+                            name: registered
+                                .as_ref()
+                                .map_or_else(|| meta.name.clone(), |t| t.name.clone()),
+                            symbol,
+                            icon: registered
+                                .as_ref()
+                                .map(|t| t.icon.clone())
+                                .filter(|icon| !icon.is_empty())
+                                .or_else(|| meta.icon.clone()),
                             networks: Vec::new(),
                         },
                     );
                 }
 
                 // Derive chain_id from defuse_asset_identifier
-                let defuse_id = token
-                    .get("defuse_asset_identifier")
-                    .and_then(|d| d.as_str())
-                    .unwrap_or("");
-                let parts: Vec<&str> = defuse_id.split(':').collect();
-                let chain_id = if parts.len() >= 2 {
-                    format!("{}:{}", parts[0], parts[1])
-                } else {
-                    parts.first().unwrap_or(&"").to_string()
-                };
+                let chain_id = chain_id_from_defuse_identifier(
+                    token
+                        .get("defuse_asset_identifier")
+                        .and_then(|d| d.as_str())
+                        .unwrap_or(""),
+                );
 
                 // Get chain name from metadata
                 let net_name = meta.network.as_ref().or(meta.chain_name.as_ref()).cloned();
@@ -19,13 +19,14 @@ pub struct DepositAddressResult {
     pub address: String,
 }
 
-/// Fetch deposit address for a specific account and chain
-pub async fn get_deposit_address(
-    State(state): State<Arc<AppState>>,
-    Json(request): Json<DepositAddressRequest>,
-) -> Result<Json<DepositAddressResult>, (StatusCode, String)> {
-    let account_id = request.account_id.clone();
-    let chain = request.chain.clone();
+/// Fetch the bridge deposit address of an account on a chain
+pub async fn fetch_deposit_address(
+    state: &Arc<AppState>,
+    account_id: &str,
+    chain: &str,
+) -> Result<DepositAddressResult, (StatusCode, String)> {
+    let account_id = account_id.to_string();
+    let chain = chain.to_string();
 
     let cache_key = CacheKey::new("bridge:deposit-address")
         .with(&account_id)
@@ -33,7 +34,7 @@ pub async fn get_deposit_address(
         .build();
 
     let state_clone = state.clone();
-    let result = state
+    state
         .cache
         .cached(CacheTier::LongTerm, cache_key, async move {
             // Prepare JSON-RPC request
@@ -78,7 +79,15 @@ pub async fn get_deposit_address(
             data.result
                 .ok_or_else(|| "No deposit address found".to_string())
         })
-        .await?;
+        .await
+}
+
+/// Fetch deposit address for a specific account and chain
+pub async fn get_deposit_address(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<DepositAddressRequest>,
+) -> Result<Json<DepositAddressResult>, (StatusCode, String)> {
+    let result = fetch_deposit_address(&state, &request.account_id, &request.chain).await?;
 
     Ok(Json(result))
 }
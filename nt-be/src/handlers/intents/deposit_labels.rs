@@ -0,0 +1,429 @@
+//! Labeled deposit addresses and deposit attribution
+//!
+//! Treasury members issue bridge deposit addresses with a purpose label, such as "Grant from
+//! Foundation" or "Exchange withdrawal". Bridge deposit addresses are deterministic per account
+//! and chain, so the label of a deposit is the one issued most recently on its chain before the
+//! deposit landed.
+//!
+//! The attribution worker matches incoming intents balance changes to those labels. A bridge
+//! deposit is minted by the bridge contract, so its transaction is not sent to `intents.near`;
+//! transfers between intents accounts and swap fulfillments are, and are left unattributed.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::bridge_tokens::chain_id_from_defuse_identifier;
+use super::deposit_address::fetch_deposit_address;
+use super::supported_tokens::fetch_supported_tokens_data;
+use crate::AppState;
+use crate::auth::{AuthUser, require_policy_member};
+
+const MAX_LABEL_LENGTH: usize = 128;
+
+const DEPOSIT_ADDRESS_COLUMNS: &str = "id, dao_id, chain, address, label, issued_by, issued_at";
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositAddress {
+    pub id: i64,
+    pub dao_id: String,
+    pub chain: String,
+    pub address: String,
+    pub label: String,
+    pub issued_by: String,
+    pub issued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueDepositAddressRequest {
+    pub dao_id: String,
+    /// Bridge chain ID, e.g. "eth:1"
+    pub chain: String,
+    pub label: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositAddressesQuery {
+    pub dao_id: String,
+}
+
+/// Incoming intents balance change that may be a bridge deposit
+#[derive(Debug, sqlx::FromRow)]
+struct CandidateDeposit {
+    id: i64,
+    token_id: String,
+    block_time: DateTime<Utc>,
+}
+
+fn bad_request(message: impl Into<String>) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, message.into())
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    log::error!("Deposit address database error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Database error".to_string(),
+    )
+}
+
+/// Map intents token IDs of the bridge's supported tokens to their chain IDs
+pub fn deposit_chains(supported: &Value) -> HashMap<String, String> {
+    supported
+        .get("tokens")
+        .and_then(|t| t.as_array())
+        .map(|tokens| {
+            tokens
+                .iter()
+                .filter_map(|token| {
+                    let intents_id = token.get("intents_token_id")?.as_str()?;
+                    let defuse_id = token.get("defuse_asset_identifier")?.as_str()?;
+                    Some((
+                        intents_id.to_string(),
+                        chain_id_from_defuse_identifier(defuse_id),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The labeled address a deposit on `chain` at `block_time` was sent to
+///
+/// That is the label of the chain issued most recently before the deposit.
+pub fn attribute_deposit<'a>(
+    addresses: &'a [DepositAddress],
+    chain: &str,
+    block_time: DateTime<Utc>,
+) -> Option<&'a DepositAddress> {
+    addresses
+        .iter()
+        .filter(|a| a.chain == chain && a.issued_at <= block_time)
+        .max_by_key(|a| a.issued_at)
+}
+
+/// Match the unattributed incoming deposits of every treasury with labeled addresses
+pub async fn attribute_deposits(state: &Arc<AppState>) -> Result<u64, String> {
+    let addresses = sqlx::query_as::<_, DepositAddress>(&format!(
+        "SELECT {} FROM intents_deposit_addresses",
+        DEPOSIT_ADDRESS_COLUMNS
+    ))
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if addresses.is_empty() {
+        return Ok(0);
+    }
+
+    let supported = fetch_supported_tokens_data(state)
+        .await
+        .map_err(|(_, message)| message)?;
+    let chains = deposit_chains(&supported);
+
+    let mut by_dao: HashMap<&str, Vec<DepositAddress>> = HashMap::new();
+    for address in &addresses {
+        by_dao
+            .entry(address.dao_id.as_str())
+            .or_default()
+            .push(address.clone());
+    }
+
+    let mut attributed = 0;
+    for (dao_id, dao_addresses) in by_dao {
+        let Some(first_issued) = dao_addresses.iter().map(|a| a.issued_at).min() else {
+            continue;
+        };
+
+        let candidates = sqlx::query_as::<_, CandidateDeposit>(
+            r#"
+            SELECT bc.id, bc.token_id, bc.block_time
+            FROM balance_changes bc
+            WHERE bc.account_id = $1
+              AND bc.token_id LIKE 'intents.near:%'
+              AND bc.amount > 0
+              AND bc.receiver_id IS DISTINCT FROM 'intents.near'
+              AND bc.block_time >= $2
+              AND NOT EXISTS (
+                SELECT 1 FROM deposit_attributions da WHERE da.balance_change_id = bc.id
+              )
+              AND NOT EXISTS (
+                SELECT 1 FROM detected_swaps ds
+                WHERE ds.account_id = bc.account_id
+                  AND ds.fulfillment_balance_change_id = bc.id
+              )
+            ORDER BY bc.block_time
+            "#,
+        )
+        .bind(dao_id)
+        .bind(first_issued)
+        .fetch_all(&state.db_pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for candidate in candidates {
+            let Some(chain) = candidate
+                .token_id
+                .strip_prefix("intents.near:")
+                .and_then(|token| chains.get(token))
+            else {
+                continue;
+            };
+            let Some(address) = attribute_deposit(&dao_addresses, chain, candidate.block_time)
+            else {
+                continue;
+            };
+
+            let result = sqlx::query(
+                r#"
+                INSERT INTO deposit_attributions (balance_change_id, deposit_address_id)
+                VALUES ($1, $2)
+                ON CONFLICT (balance_change_id) DO NOTHING
+                "#,
+            )
+            .bind(candidate.id)
+            .bind(address.id)
+            .execute(&state.db_pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            attributed += result.rows_affected();
+        }
+    }
+
+    Ok(attributed)
+}
+
+/// Deposit labels of balance changes, by balance change ID
+pub async fn load_deposit_labels(
+    pool: &sqlx::PgPool,
+    balance_change_ids: &[i64],
+) -> Result<HashMap<i64, String>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (i64, String)>(
+        r#"
+        SELECT da.balance_change_id, a.label
+        FROM deposit_attributions da
+        JOIN intents_deposit_addresses a ON a.id = da.deposit_address_id
+        WHERE da.balance_change_id = ANY($1)
+        "#,
+    )
+    .bind(balance_change_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Deposit labels of an account's balance changes in a time range, by token ID and block height
+pub async fn load_deposit_labels_in_range(
+    pool: &sqlx::PgPool,
+    account_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> Result<HashMap<(String, i64), String>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, i64, String)>(
+        r#"
+        SELECT bc.token_id, bc.block_height, a.label
+        FROM deposit_attributions da
+        JOIN balance_changes bc ON bc.id = da.balance_change_id
+        JOIN intents_deposit_addresses a ON a.id = da.deposit_address_id
+        WHERE bc.account_id = $1
+          AND bc.block_time >= $2
+          AND bc.block_time < $3
+        "#,
+    )
+    .bind(account_id)
+    .bind(start_time)
+    .bind(end_time)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(token_id, block_height, label)| ((token_id, block_height), label))
+        .collect())
+}
+
+/// Run the deposit attribution worker every minute
+pub async fn run_deposit_attribution_service(state: Arc<AppState>) {
+    log::info!("Starting deposit attribution worker (60 second poll interval)");
+
+    // Wait a bit before first run to let server fully start
+    tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+
+    loop {
+        interval.tick().await;
+
+        match attribute_deposits(&state).await {
+            Ok(attributed) if attributed > 0 => {
+                log::info!("Attributed {} deposit(s) to their labels", attributed)
+            }
+            Ok(_) => {}
+            Err(e) => log::error!("Deposit attribution error: {}", e),
+        }
+    }
+}
+
+/// Labeled deposit addresses of a treasury, most recently issued first
+pub async fn list_deposit_addresses(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<DepositAddressesQuery>,
+) -> Result<Json<Vec<DepositAddress>>, (StatusCode, String)> {
+    require_policy_member(&state, &query.dao_id, &auth_user.account_id).await?;
+
+    let addresses = sqlx::query_as::<_, DepositAddress>(&format!(
+        "SELECT {} FROM intents_deposit_addresses WHERE dao_id = $1 ORDER BY issued_at DESC",
+        DEPOSIT_ADDRESS_COLUMNS
+    ))
+    .bind(&query.dao_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(addresses))
+}
+
+/// Issue the bridge deposit address of a treasury under a purpose label
+///
+/// Issuing an existing label again makes it the current label of its chain.
+pub async fn issue_deposit_address(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<IssueDepositAddressRequest>,
+) -> Result<Json<DepositAddress>, (StatusCode, String)> {
+    let label = request.label.trim();
+    if label.is_empty() {
+        return Err(bad_request("A label is required"));
+    }
+    if label.chars().count() > MAX_LABEL_LENGTH {
+        return Err(bad_request(format!(
+            "Labels can be at most {} characters",
+            MAX_LABEL_LENGTH
+        )));
+    }
+    if request.chain.trim().is_empty() {
+        return Err(bad_request("A chain is required"));
+    }
+
+    require_policy_member(&state, &request.dao_id, &auth_user.account_id).await?;
+
+    let result = fetch_deposit_address(&state, &request.dao_id, &request.chain).await?;
+
+    let address = sqlx::query_as::<_, DepositAddress>(&format!(
+        r#"
+        INSERT INTO intents_deposit_addresses (dao_id, chain, address, label, issued_by)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (dao_id, chain, label) DO UPDATE
+        SET address = EXCLUDED.address, issued_by = EXCLUDED.issued_by, issued_at = NOW()
+        RETURNING {}
+        "#,
+        DEPOSIT_ADDRESS_COLUMNS
+    ))
+    .bind(&request.dao_id)
+    .bind(&request.chain)
+    .bind(&result.address)
+    .bind(label)
+    .bind(&auth_user.account_id)
+    .fetch_one(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    log::info!(
+        "Deposit address on {} of {} issued as \"{}\" by {}",
+        address.chain,
+        address.dao_id,
+        address.label,
+        address.issued_by
+    );
+
+    Ok(Json(address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn address(id: i64, chain: &str, label: &str, day: u32) -> DepositAddress {
+        DepositAddress {
+            id,
+            dao_id: "treasury.sputnik-dao.near".to_string(),
+            chain: chain.to_string(),
+            address: "0x2b5a7c1e9f3d4a6b8c0e2f4a6b8c0d2e4f6a8b0c".to_string(),
+            label: label.to_string(),
+            issued_by: "alice.near".to_string(),
+            issued_at: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_deposit_chains() {
+        let supported = json!({
+            "tokens": [
+                {
+                    "defuse_asset_identifier": "eth:1:0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                    "intents_token_id": "nep141:eth-0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48.omft.near",
+                    "standard": "nep141"
+                },
+                {
+                    "defuse_asset_identifier": "btc:mainnet:native",
+                    "intents_token_id": "nep141:btc.omft.near",
+                    "standard": "nep141"
+                },
+                { "standard": "nep141" }
+            ]
+        });
+
+        let chains = deposit_chains(&supported);
+        assert_eq!(chains.len(), 2);
+        assert_eq!(
+            chains["nep141:eth-0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48.omft.near"],
+            "eth:1"
+        );
+        assert_eq!(chains["nep141:btc.omft.near"], "btc:mainnet");
+    }
+
+    #[test]
+    fn test_attribute_deposit() {
+        let addresses = vec![
+            address(1, "eth:1", "Grant from Foundation", 1),
+            address(2, "eth:1", "Exchange withdrawal", 10),
+            address(3, "btc:mainnet", "Donations", 5),
+        ];
+        let at = |day| Utc.with_ymd_and_hms(2026, 3, day, 18, 0, 0).unwrap();
+
+        // Before any label of the chain was issued
+        assert!(
+            attribute_deposit(
+                &addresses,
+                "eth:1",
+                Utc.with_ymd_and_hms(2026, 2, 28, 0, 0, 0).unwrap()
+            )
+            .is_none()
+        );
+        assert_eq!(attribute_deposit(&addresses, "eth:1", at(3)).unwrap().id, 1);
+        assert_eq!(
+            attribute_deposit(&addresses, "eth:1", at(12)).unwrap().id,
+            2
+        );
+        assert_eq!(
+            attribute_deposit(&addresses, "btc:mainnet", at(12))
+                .unwrap()
+                .id,
+            3
+        );
+        assert!(attribute_deposit(&addresses, "sol:mainnet", at(12)).is_none());
+    }
+}
@@ -1,6 +1,10 @@
 pub mod bridge_tokens;
 pub mod deposit_address;
+pub mod deposit_labels;
 pub mod quote;
 pub mod search_tokens;
 pub mod supported_tokens;
+pub mod swap_analytics;
+pub mod swap_orders;
 pub mod swap_status;
+pub mod token_overrides;
@@ -1,8 +1,10 @@
 use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
 
+use super::swap_orders::record_quote;
 use crate::AppState;
 
 /// App fee configuration for the quote request
@@ -50,6 +52,66 @@ pub struct QuoteRequest {
     // They will be injected from server-side environment variables
 }
 
+/// Tokens held on the intents contract are referenced by their full asset ID
+pub fn is_intents_token(token_id: &str) -> bool {
+    token_id.starts_with("nep141:") || token_id.starts_with("nep245:")
+}
+
+/// 1Click asset ID of a treasury token, as the exchange page formats it
+///
+/// Takes "near", a NEP-141 contract ID, or an intents asset ID ("nep141:..." / "nep245:...").
+pub fn oneclick_asset_id(token_id: &str) -> String {
+    if is_intents_token(token_id) {
+        token_id.to_string()
+    } else if token_id == "near" {
+        "nep141:wrap.near".to_string()
+    } else {
+        format!("nep141:{}", token_id)
+    }
+}
+
+impl QuoteRequest {
+    /// Exact-input quote of a treasury exchange, refunded to and received by the treasury
+    ///
+    /// Tokens given by their intents asset ID are sent from and received on intents.
+    pub fn treasury_exchange(
+        dao_id: &str,
+        sell_token_id: &str,
+        receive_token_id: &str,
+        amount: u128,
+        slippage_bps: u32,
+        dry: bool,
+        deadline: DateTime<Utc>,
+    ) -> Self {
+        let deposit_type = if is_intents_token(sell_token_id) {
+            "INTENTS"
+        } else {
+            "ORIGIN_CHAIN"
+        };
+        let recipient_type = if is_intents_token(receive_token_id) {
+            "INTENTS"
+        } else {
+            "DESTINATION_CHAIN"
+        };
+
+        QuoteRequest {
+            dry: Some(dry),
+            swap_type: Some("EXACT_INPUT".to_string()),
+            slippage_tolerance: Some(slippage_bps),
+            origin_asset: oneclick_asset_id(sell_token_id),
+            deposit_type: Some(deposit_type.to_string()),
+            destination_asset: oneclick_asset_id(receive_token_id),
+            amount: amount.to_string(),
+            refund_to: Some(dao_id.to_string()),
+            refund_type: Some(deposit_type.to_string()),
+            recipient: Some(dao_id.to_string()),
+            recipient_type: Some(recipient_type.to_string()),
+            deadline: deadline.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            quote_waiting_time_ms: Some(3000),
+        }
+    }
+}
+
 /// Internal request sent to 1click API with injected appFees and referral
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -106,12 +168,12 @@ impl From<QuoteRequest> for OneClickQuoteRequest {
     }
 }
 
-/// Proxy endpoint for 1click API quote
+/// Request a quote from the 1click API
 /// Injects server-side appFees and referral from environment variables
-pub async fn get_quote(
-    State(state): State<Arc<AppState>>,
-    Json(request): Json<QuoteRequest>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+pub async fn request_quote(
+    state: &AppState,
+    request: QuoteRequest,
+) -> Result<Value, (StatusCode, String)> {
     // Convert client request to internal request
     let mut oneclick_request: OneClickQuoteRequest = request.into();
 
@@ -179,7 +241,18 @@ pub async fn get_quote(
         ));
     }
 
-    Ok(Json(body))
+    // Live quotes become swap orders once an exchange proposal uses them
+    record_quote(&state.db_pool, &body).await;
+
+    Ok(body)
+}
+
+/// Proxy endpoint for 1click API quote
+pub async fn get_quote(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<QuoteRequest>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    request_quote(&state, request).await.map(Json)
 }
 
 #[cfg(test)]
@@ -372,11 +445,13 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let mut env_vars = EnvVars::default();
-        env_vars.oneclick_jwt_token = None; // No JWT token
-        env_vars.oneclick_app_fee_bps = Some(50);
-        env_vars.oneclick_app_fee_recipient = Some("treasury.near".to_string());
-        env_vars.oneclick_referral = None;
+        let env_vars = EnvVars {
+            oneclick_jwt_token: None, // No JWT token
+            oneclick_app_fee_bps: Some(50),
+            oneclick_app_fee_recipient: Some("treasury.near".to_string()),
+            oneclick_referral: None,
+            ..Default::default()
+        };
 
         let state = create_test_state(&mock_server.uri(), Some(env_vars)).await;
 
@@ -415,6 +490,67 @@ mod tests {
         assert_eq!(json.get("originAsset").unwrap(), "nep141:wrap.near");
     }
 
+    #[test]
+    fn test_oneclick_asset_id() {
+        assert_eq!(oneclick_asset_id("near"), "nep141:wrap.near");
+        assert_eq!(
+            oneclick_asset_id("usdt.tether-token.near"),
+            "nep141:usdt.tether-token.near"
+        );
+        assert_eq!(
+            oneclick_asset_id("nep141:usdc.omft.near"),
+            "nep141:usdc.omft.near"
+        );
+        // NEAR token contracts may start with "nep" too
+        assert_eq!(
+            oneclick_asset_id("nepal.token.near"),
+            "nep141:nepal.token.near"
+        );
+    }
+
+    #[test]
+    fn test_treasury_exchange_request() {
+        let deadline = chrono::Utc::now();
+        let request = QuoteRequest::treasury_exchange(
+            "testdao.sputnik-dao.near",
+            "nep141:usdc.omft.near",
+            "near",
+            1_000_000,
+            50,
+            true,
+            deadline,
+        );
+
+        assert_eq!(request.dry, Some(true));
+        assert_eq!(request.origin_asset, "nep141:usdc.omft.near");
+        assert_eq!(request.destination_asset, "nep141:wrap.near");
+        assert_eq!(request.deposit_type.as_deref(), Some("INTENTS"));
+        assert_eq!(request.refund_type.as_deref(), Some("INTENTS"));
+        assert_eq!(request.recipient_type.as_deref(), Some("DESTINATION_CHAIN"));
+        assert_eq!(
+            request.refund_to.as_deref(),
+            Some("testdao.sputnik-dao.near")
+        );
+        assert_eq!(
+            request.recipient.as_deref(),
+            Some("testdao.sputnik-dao.near")
+        );
+        assert_eq!(request.slippage_tolerance, Some(50));
+        assert_eq!(request.amount, "1000000");
+
+        let request = QuoteRequest::treasury_exchange(
+            "testdao.sputnik-dao.near",
+            "nepal.token.near",
+            "nep245:v2_1.omni.hot.tg:56_11111111111111111111",
+            1_000_000,
+            50,
+            true,
+            deadline,
+        );
+        assert_eq!(request.deposit_type.as_deref(), Some("ORIGIN_CHAIN"));
+        assert_eq!(request.recipient_type.as_deref(), Some("INTENTS"));
+    }
+
     #[tokio::test]
     async fn test_app_fees_injection() {
         // Test that app fees are correctly constructed
@@ -512,14 +648,16 @@ mod tests {
         dotenvy::from_filename(".env").ok();
         dotenvy::from_filename(".env.test").ok();
 
-        let mut env_vars = EnvVars::default();
-        // Use real API URL
-        env_vars.oneclick_api_url = "https://1click.chaindefuser.com".to_string();
-        // JWT token from env if available
-        env_vars.oneclick_jwt_token = std::env::var("ONECLICK_JWT_TOKEN").ok();
-        env_vars.oneclick_app_fee_bps = Some(50);
-        env_vars.oneclick_app_fee_recipient = Some("treasury.near".to_string());
-        env_vars.oneclick_referral = Some("near-treasury".to_string());
+        let env_vars = EnvVars {
+            // Use real API URL
+            oneclick_api_url: "https://1click.chaindefuser.com".to_string(),
+            // JWT token from env if available
+            oneclick_jwt_token: std::env::var("ONECLICK_JWT_TOKEN").ok(),
+            oneclick_app_fee_bps: Some(50),
+            oneclick_app_fee_recipient: Some("treasury.near".to_string()),
+            oneclick_referral: Some("near-treasury".to_string()),
+            ..Default::default()
+        };
 
         let db_pool = sqlx::postgres::PgPool::connect_lazy(&env_vars.database_url)
             .expect("Failed to create lazy pool");
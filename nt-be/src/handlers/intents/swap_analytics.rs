@@ -0,0 +1,667 @@
+//! Execution quality of treasury swaps
+//!
+//! Each detected swap is compared with the 1Click quote of its swap order, or else with the
+//! expected output of the exchange proposal that used its deposit address:
+//! - Quoted versus realized output, as slippage in bps (positive when less was received)
+//! - The effective rate versus the rate of the USD reference prices on the settlement day
+//! - Fees: our app fee (from the quote, or `oneclick_app_fee_bps`) and the all-in cost, the
+//!   USD value sent minus the USD value received
+//!
+//! Results are returned per swap, in aggregate and per month, as JSON or CSV. The monthly
+//! summary is also part of the balance history export.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use bigdecimal::{BigDecimal, Signed, ToPrimitive};
+use chrono::{DateTime, NaiveDate, Utc};
+use near_api::AccountId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::types::Json as SqlJson;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::constants::intents_tokens::find_token_by_defuse_asset_id;
+use crate::handlers::balance_changes::counterparty::convert_raw_to_decimal;
+use crate::handlers::proposals::get_proposals::fetch_dao_proposals_cached;
+use crate::handlers::proposals::scraper::{AssetExchangeInfo, Proposal, ProposalType};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapAnalyticsQuery {
+    pub account_id: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    /// CSV only: "monthly" (default) or "swaps"
+    #[serde(default)]
+    pub view: Option<String>,
+}
+
+/// Detected swap with the quote of its swap order
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SwapRow {
+    pub id: i64,
+    pub solver_transaction_hash: String,
+    pub settled_at: DateTime<Utc>,
+    pub deposit_address: Option<String>,
+    pub sent_token_id: Option<String>,
+    /// Decimal-adjusted, negative
+    pub sent_amount: Option<BigDecimal>,
+    pub received_token_id: String,
+    /// Decimal-adjusted
+    pub received_amount: BigDecimal,
+    pub proposal_id: Option<i64>,
+    pub destination_asset: Option<String>,
+    /// Base units
+    pub quoted_amount_out: Option<String>,
+    pub min_amount_out: Option<String>,
+    pub quote: Option<SqlJson<Value>>,
+    /// Swaps without a swap order: `amount_out` of the exchange proposal, decimal-adjusted
+    #[sqlx(skip)]
+    pub proposal_amount_out: Option<BigDecimal>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapExecution {
+    pub detected_swap_id: i64,
+    pub transaction_hash: String,
+    pub settled_at: DateTime<Utc>,
+    pub proposal_id: Option<i64>,
+    pub sent_token_id: Option<String>,
+    pub sent_amount: Option<BigDecimal>,
+    pub received_token_id: String,
+    pub received_amount: BigDecimal,
+    pub quoted_amount_out: Option<BigDecimal>,
+    pub min_amount_out: Option<BigDecimal>,
+    /// Realized output below the quote, in bps (negative when more was received)
+    pub slippage_bps: Option<f64>,
+    pub sent_usd: Option<f64>,
+    pub received_usd: Option<f64>,
+    /// Received per sent token
+    pub effective_rate: Option<f64>,
+    /// Received per sent token at the reference USD prices of the settlement day
+    pub reference_rate: Option<f64>,
+    /// Effective rate below the reference rate, in bps
+    pub rate_deviation_bps: Option<f64>,
+    pub app_fee_bps: u32,
+    pub app_fee_usd: Option<f64>,
+    /// USD sent minus USD received: fees, spread and slippage together
+    pub total_cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapSummary {
+    pub swap_count: usize,
+    /// Swaps with a quote to compare with
+    pub quoted_swap_count: usize,
+    pub total_sent_usd: f64,
+    pub total_received_usd: f64,
+    pub total_app_fees_usd: f64,
+    pub total_cost_usd: f64,
+    /// Mean slippage of the quoted swaps
+    pub average_slippage_bps: Option<f64>,
+    /// Mean deviation of the swaps with reference prices
+    pub average_rate_deviation_bps: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlySwapSummary {
+    /// "YYYY-MM"
+    pub month: String,
+    #[serde(flatten)]
+    pub summary: SwapSummary,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapAnalyticsResponse {
+    pub swaps: Vec<SwapExecution>,
+    pub summary: SwapSummary,
+    pub monthly: Vec<MonthlySwapSummary>,
+}
+
+/// App fee of a quote: the fees 1Click echoed back, or the configured fee
+fn quote_app_fee_bps(quote: Option<&Value>, default_fee_bps: u32) -> u32 {
+    quote
+        .and_then(|q| q["quoteRequest"]["appFees"].as_array())
+        .map(|fees| {
+            fees.iter()
+                .filter_map(|fee| fee["fee"].as_u64())
+                .sum::<u64>() as u32
+        })
+        .unwrap_or(default_fee_bps)
+}
+
+/// Quoted base-unit amount of the destination token, decimal-adjusted
+fn quoted_decimal(amount: Option<&str>, destination_asset: Option<&str>) -> Option<BigDecimal> {
+    let decimals = find_token_by_defuse_asset_id(destination_asset?)?.decimals;
+    convert_raw_to_decimal(amount?, decimals).ok()
+}
+
+fn bps_below(reference: f64, actual: f64) -> Option<f64> {
+    (reference > 0.0).then(|| (reference - actual) / reference * 10_000.0)
+}
+
+/// Execution metrics of a swap, given the USD prices of its tokens on the settlement day
+pub fn analyze_swap(
+    row: &SwapRow,
+    price_in: Option<f64>,
+    price_out: Option<f64>,
+    default_fee_bps: u32,
+) -> SwapExecution {
+    let sent_amount = row.sent_amount.as_ref().map(|amount| amount.abs());
+    let sent = sent_amount.as_ref().and_then(|a| a.to_f64());
+    let received = row.received_amount.to_f64();
+
+    let quoted_amount_out = quoted_decimal(
+        row.quoted_amount_out.as_deref(),
+        row.destination_asset.as_deref(),
+    )
+    .or_else(|| row.proposal_amount_out.clone());
+    let min_amount_out = quoted_decimal(
+        row.min_amount_out.as_deref(),
+        row.destination_asset.as_deref(),
+    );
+    let slippage_bps = quoted_amount_out
+        .as_ref()
+        .and_then(|q| q.to_f64())
+        .zip(received)
+        .and_then(|(quoted, received)| bps_below(quoted, received));
+
+    let sent_usd = sent.zip(price_in).map(|(amount, price)| amount * price);
+    let received_usd = received
+        .zip(price_out)
+        .map(|(amount, price)| amount * price);
+    let effective_rate = sent
+        .filter(|sent| *sent > 0.0)
+        .zip(received)
+        .map(|(sent, received)| received / sent);
+    let reference_rate = price_in
+        .zip(price_out.filter(|price| *price > 0.0))
+        .map(|(price_in, price_out)| price_in / price_out);
+    let rate_deviation_bps = reference_rate
+        .zip(effective_rate)
+        .and_then(|(reference, effective)| bps_below(reference, effective));
+
+    let app_fee_bps = quote_app_fee_bps(row.quote.as_ref().map(|q| &q.0), default_fee_bps);
+
+    SwapExecution {
+        detected_swap_id: row.id,
+        transaction_hash: row.solver_transaction_hash.clone(),
+        settled_at: row.settled_at,
+        proposal_id: row.proposal_id,
+        sent_token_id: row.sent_token_id.clone(),
+        sent_amount,
+        received_token_id: row.received_token_id.clone(),
+        received_amount: row.received_amount.clone(),
+        quoted_amount_out,
+        min_amount_out,
+        slippage_bps,
+        sent_usd,
+        received_usd,
+        effective_rate,
+        reference_rate,
+        rate_deviation_bps,
+        app_fee_bps,
+        app_fee_usd: sent_usd.map(|usd| usd * app_fee_bps as f64 / 10_000.0),
+        total_cost_usd: sent_usd
+            .zip(received_usd)
+            .map(|(sent, received)| sent - received),
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+pub fn summarize<'a>(swaps: impl IntoIterator<Item = &'a SwapExecution>) -> SwapSummary {
+    let swaps: Vec<&SwapExecution> = swaps.into_iter().collect();
+    SwapSummary {
+        swap_count: swaps.len(),
+        quoted_swap_count: swaps.iter().filter(|s| s.slippage_bps.is_some()).count(),
+        total_sent_usd: swaps.iter().filter_map(|s| s.sent_usd).sum(),
+        total_received_usd: swaps.iter().filter_map(|s| s.received_usd).sum(),
+        total_app_fees_usd: swaps.iter().filter_map(|s| s.app_fee_usd).sum(),
+        total_cost_usd: swaps.iter().filter_map(|s| s.total_cost_usd).sum(),
+        average_slippage_bps: mean(swaps.iter().filter_map(|s| s.slippage_bps)),
+        average_rate_deviation_bps: mean(swaps.iter().filter_map(|s| s.rate_deviation_bps)),
+    }
+}
+
+/// Summaries per settlement month, oldest first
+pub fn summarize_monthly(swaps: &[SwapExecution]) -> Vec<MonthlySwapSummary> {
+    let mut months: BTreeMap<String, Vec<&SwapExecution>> = BTreeMap::new();
+    for swap in swaps {
+        months
+            .entry(swap.settled_at.format("%Y-%m").to_string())
+            .or_default()
+            .push(swap);
+    }
+    months
+        .into_iter()
+        .map(|(month, swaps)| MonthlySwapSummary {
+            month,
+            summary: summarize(swaps.iter().copied()),
+        })
+        .collect()
+}
+
+/// Expected output of the exchange proposal that sent to a deposit address
+fn proposal_amount_out(info: &AssetExchangeInfo) -> Option<BigDecimal> {
+    BigDecimal::from_str(&info.amount_out)
+        .ok()
+        .filter(|amount| amount.is_positive())
+}
+
+/// Swaps without a swap order: take the expected output from the exchange proposal that
+/// sent to their deposit address
+fn fill_from_proposals(rows: &mut [SwapRow], proposals: &[Proposal]) {
+    let exchanges: HashMap<String, (u64, AssetExchangeInfo)> = proposals
+        .iter()
+        .filter_map(|proposal| {
+            let info = AssetExchangeInfo::from_proposal(proposal)?;
+            Some((info.deposit_address.clone()?, (proposal.id, info)))
+        })
+        .collect();
+
+    for row in rows
+        .iter_mut()
+        .filter(|row| row.quoted_amount_out.is_none())
+    {
+        let Some((proposal_id, info)) = row
+            .deposit_address
+            .as_ref()
+            .and_then(|address| exchanges.get(address))
+        else {
+            continue;
+        };
+        row.proposal_id = Some(*proposal_id as i64);
+        row.proposal_amount_out = proposal_amount_out(info);
+    }
+}
+
+async fn load_swaps(
+    state: &AppState,
+    account_id: &str,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+) -> Result<Vec<SwapRow>, sqlx::Error> {
+    let mut rows = sqlx::query_as::<_, SwapRow>(
+        r#"
+        SELECT
+            d.id,
+            d.solver_transaction_hash,
+            bc.block_time AS settled_at,
+            d.deposit_address,
+            d.sent_token_id,
+            d.sent_amount,
+            d.received_token_id,
+            d.received_amount,
+            o.proposal_id,
+            o.destination_asset,
+            o.amount_out AS quoted_amount_out,
+            o.min_amount_out,
+            o.quote
+        FROM detected_swaps d
+        JOIN balance_changes bc ON bc.id = d.fulfillment_balance_change_id
+        LEFT JOIN swap_orders o ON o.detected_swap_id = d.id
+        WHERE d.account_id = $1
+          AND bc.block_time >= $2
+          AND bc.block_time < $3
+        ORDER BY bc.block_time
+        "#,
+    )
+    .bind(account_id)
+    .bind(start_time)
+    .bind(end_time)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let needs_proposals = rows
+        .iter()
+        .any(|row| row.quoted_amount_out.is_none() && row.deposit_address.is_some());
+    if needs_proposals && let Ok(dao_id) = account_id.parse::<AccountId>() {
+        match fetch_dao_proposals_cached(state, &dao_id).await {
+            Ok((proposals, _)) => fill_from_proposals(&mut rows, &proposals),
+            Err((_, e)) => log::warn!("Failed to fetch proposals of {}: {}", account_id, e),
+        }
+    }
+
+    Ok(rows)
+}
+
+/// Analyze the swaps of a treasury settled in a period
+pub async fn analyze_swaps(
+    state: &AppState,
+    query: &SwapAnalyticsQuery,
+) -> Result<SwapAnalyticsResponse, (StatusCode, String)> {
+    let rows = load_swaps(state, &query.account_id, query.start_time, query.end_time)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to load swaps of {}: {}", query.account_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load swaps".to_string(),
+            )
+        })?;
+
+    // Reference prices of every token on its settlement days, fetched per token
+    let mut token_dates: HashMap<String, HashSet<NaiveDate>> = HashMap::new();
+    for row in &rows {
+        let date = row.settled_at.date_naive();
+        for token_id in row.sent_token_id.iter().chain([&row.received_token_id]) {
+            token_dates
+                .entry(token_id.clone())
+                .or_default()
+                .insert(date);
+        }
+    }
+    let mut prices: HashMap<(String, NaiveDate), f64> = HashMap::new();
+    for (token_id, dates) in token_dates {
+        let dates: Vec<NaiveDate> = dates.into_iter().collect();
+        match state
+            .price_service
+            .get_prices_batch(&token_id, &dates)
+            .await
+        {
+            Ok(token_prices) => {
+                for (date, price) in token_prices {
+                    prices.insert((token_id.clone(), date), price);
+                }
+            }
+            Err(e) => log::debug!("Failed to batch fetch prices for {}: {}", token_id, e),
+        }
+    }
+
+    let default_fee_bps = state.env_vars.oneclick_app_fee_bps.unwrap_or(0);
+    let swaps: Vec<SwapExecution> = rows
+        .iter()
+        .map(|row| {
+            let date = row.settled_at.date_naive();
+            let price_in = row
+                .sent_token_id
+                .as_ref()
+                .and_then(|token_id| prices.get(&(token_id.clone(), date)).copied());
+            let price_out = prices.get(&(row.received_token_id.clone(), date)).copied();
+            analyze_swap(row, price_in, price_out, default_fee_bps)
+        })
+        .collect();
+
+    Ok(SwapAnalyticsResponse {
+        summary: summarize(&swaps),
+        monthly: summarize_monthly(&swaps),
+        swaps,
+    })
+}
+
+/// Swap execution quality of a treasury
+pub async fn get_swap_analytics(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SwapAnalyticsQuery>,
+) -> Result<Json<SwapAnalyticsResponse>, (StatusCode, String)> {
+    Ok(Json(analyze_swaps(&state, &query).await?))
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+fn format_bps(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_default()
+}
+
+pub fn monthly_csv(monthly: &[MonthlySwapSummary]) -> String {
+    let mut csv = String::from(
+        "month,swap_count,quoted_swap_count,total_sent_usd,total_received_usd,total_app_fees_usd,total_cost_usd,average_slippage_bps,average_rate_deviation_bps\n",
+    );
+    for month in monthly {
+        let s = &month.summary;
+        csv.push_str(&format!(
+            "{},{},{},{:.2},{:.2},{:.2},{:.2},{},{}\n",
+            month.month,
+            s.swap_count,
+            s.quoted_swap_count,
+            s.total_sent_usd,
+            s.total_received_usd,
+            s.total_app_fees_usd,
+            s.total_cost_usd,
+            format_bps(s.average_slippage_bps),
+            format_bps(s.average_rate_deviation_bps),
+        ));
+    }
+    csv
+}
+
+pub fn swaps_csv(swaps: &[SwapExecution]) -> String {
+    let mut csv = String::from(
+        "settled_at,transaction_hash,proposal_id,sent_token_id,sent_amount,received_token_id,received_amount,quoted_amount_out,min_amount_out,slippage_bps,sent_usd,received_usd,effective_rate,reference_rate,rate_deviation_bps,app_fee_bps,app_fee_usd,total_cost_usd\n",
+    );
+    for swap in swaps {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            swap.settled_at.to_rfc3339(),
+            swap.transaction_hash,
+            optional(&swap.proposal_id),
+            optional(&swap.sent_token_id),
+            optional(&swap.sent_amount),
+            swap.received_token_id,
+            swap.received_amount,
+            optional(&swap.quoted_amount_out),
+            optional(&swap.min_amount_out),
+            format_bps(swap.slippage_bps),
+            optional(&swap.sent_usd),
+            optional(&swap.received_usd),
+            optional(&swap.effective_rate),
+            optional(&swap.reference_rate),
+            format_bps(swap.rate_deviation_bps),
+            swap.app_fee_bps,
+            optional(&swap.app_fee_usd),
+            optional(&swap.total_cost_usd),
+        ));
+    }
+    csv
+}
+
+/// CSV export of the monthly swap summary (or of every swap with `view=swaps`)
+pub async fn export_swap_analytics_csv(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SwapAnalyticsQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let analytics = analyze_swaps(&state, &query).await?;
+
+    let (view, csv_data) = match query.view.as_deref().unwrap_or("monthly") {
+        "monthly" => ("monthly", monthly_csv(&analytics.monthly)),
+        "swaps" => ("swaps", swaps_csv(&analytics.swaps)),
+        other => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported view: {}", other),
+            ));
+        }
+    };
+
+    let filename = format!(
+        "swap_analytics_{}_{}_{}_to_{}.csv",
+        view, query.account_id, query.start_time, query.end_time
+    );
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                &format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        csv_data,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const USDT: &str = "nep141:usdt.tether-token.near";
+
+    fn row(settled_at: &str, quoted_amount_out: Option<&str>, quote: Option<Value>) -> SwapRow {
+        SwapRow {
+            id: 1,
+            solver_transaction_hash: "7kSKx3fF1XmMpYcE2Hq1Yc8V9dGq8bLkWc6h5iXGrHbN".to_string(),
+            settled_at: settled_at.parse().unwrap(),
+            deposit_address: None,
+            sent_token_id: Some("intents.near:nep141:wrap.near".to_string()),
+            sent_amount: Some(BigDecimal::from_str("-100").unwrap()),
+            received_token_id: format!("intents.near:{}", USDT),
+            received_amount: BigDecimal::from_str("297").unwrap(),
+            proposal_id: quoted_amount_out.map(|_| 12),
+            destination_asset: quoted_amount_out.map(|_| USDT.to_string()),
+            quoted_amount_out: quoted_amount_out.map(str::to_string),
+            min_amount_out: None,
+            quote: quote.map(SqlJson),
+            proposal_amount_out: None,
+        }
+    }
+
+    #[test]
+    fn test_analyze_quoted_swap() {
+        let quote = json!({
+            "quoteRequest": { "appFees": [{ "recipient": "treasury.near", "fee": 50 }] }
+        });
+        // Quoted 300 USDT (6 decimals), received 297
+        let swap = analyze_swap(
+            &row("2026-03-09T12:00:00Z", Some("300000000"), Some(quote)),
+            Some(3.0),
+            Some(1.0),
+            1,
+        );
+
+        assert_eq!(
+            swap.quoted_amount_out,
+            Some(BigDecimal::from_str("300").unwrap())
+        );
+        assert_eq!(swap.sent_amount, Some(BigDecimal::from_str("100").unwrap()));
+        assert!((swap.slippage_bps.unwrap() - 100.0).abs() < 1e-9);
+        assert_eq!(swap.sent_usd, Some(300.0));
+        assert_eq!(swap.received_usd, Some(297.0));
+        assert!((swap.effective_rate.unwrap() - 2.97).abs() < 1e-9);
+        assert_eq!(swap.reference_rate, Some(3.0));
+        assert!((swap.rate_deviation_bps.unwrap() - 100.0).abs() < 1e-9);
+        // The fee echoed in the quote wins over the configured one
+        assert_eq!(swap.app_fee_bps, 50);
+        assert!((swap.app_fee_usd.unwrap() - 1.5).abs() < 1e-9);
+        assert!((swap.total_cost_usd.unwrap() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_analyze_unquoted_swap_without_prices() {
+        let swap = analyze_swap(&row("2026-03-09T12:00:00Z", None, None), None, None, 1);
+
+        assert_eq!(swap.quoted_amount_out, None);
+        assert_eq!(swap.slippage_bps, None);
+        assert_eq!(swap.sent_usd, None);
+        assert_eq!(swap.reference_rate, None);
+        assert_eq!(swap.rate_deviation_bps, None);
+        assert_eq!(swap.app_fee_bps, 1);
+        assert_eq!(swap.app_fee_usd, None);
+        assert!((swap.effective_rate.unwrap() - 2.97).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_monthly_summary() {
+        let swaps = vec![
+            analyze_swap(
+                &row("2026-02-27T12:00:00Z", Some("300000000"), None),
+                Some(3.0),
+                Some(1.0),
+                0,
+            ),
+            analyze_swap(
+                &row("2026-03-02T12:00:00Z", Some("297000000"), None),
+                Some(3.0),
+                Some(1.0),
+                0,
+            ),
+            analyze_swap(&row("2026-03-09T12:00:00Z", None, None), None, None, 0),
+        ];
+
+        let monthly = summarize_monthly(&swaps);
+        assert_eq!(monthly.len(), 2);
+        assert_eq!(monthly[0].month, "2026-02");
+        assert_eq!(monthly[0].summary.swap_count, 1);
+        assert_eq!(monthly[1].month, "2026-03");
+        assert_eq!(monthly[1].summary.swap_count, 2);
+        assert_eq!(monthly[1].summary.quoted_swap_count, 1);
+        assert_eq!(monthly[1].summary.average_slippage_bps, Some(0.0));
+        assert_eq!(monthly[1].summary.total_sent_usd, 300.0);
+
+        let total = summarize(&swaps);
+        assert_eq!(total.swap_count, 3);
+        assert!((total.average_slippage_bps.unwrap() - 50.0).abs() < 1e-9);
+
+        let csv = monthly_csv(&monthly);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "2026-02,1,1,300.00,297.00,0.00,3.00,100.00,100.00"
+        );
+    }
+
+    #[test]
+    fn test_unquoted_swap_uses_proposal_amount_out() {
+        let body = json!({
+            "quote": {
+                "depositAddress": "3e5f2c6a8b1d4e7f9a0b2c4d6e8f0a1b3c5d7e9f1a2b3c4d5e6f7a8b9c0d1e2f",
+                "amountIn": "100000000000000000000000000",
+                "amountInFormatted": "100",
+                "amountOut": "300000000",
+                "amountOutFormatted": "300",
+                "deadline": "2026-03-10T00:00:00.000Z"
+            }
+        });
+        let exchange = crate::handlers::exchange_orders::worker::exchange_proposal(
+            "nep141:wrap.near",
+            USDT,
+            100,
+            &body,
+            ("Notes", String::new()),
+        )
+        .unwrap();
+        let proposal = Proposal {
+            id: 21,
+            proposer: "alice.near".to_string(),
+            description: exchange.description,
+            kind: exchange.kind,
+            status: crate::handlers::proposals::scraper::ProposalStatus::Approved,
+            vote_counts: HashMap::new(),
+            votes: HashMap::new(),
+            submission_time: near_api::types::json::U64::from(0),
+            last_actions_log: None,
+        };
+
+        let mut rows = vec![row("2026-03-09T12:00:00Z", None, None)];
+        rows[0].deposit_address = Some(exchange.deposit_address);
+        fill_from_proposals(&mut rows, &[proposal]);
+        assert_eq!(rows[0].proposal_id, Some(21));
+
+        let swap = analyze_swap(&rows[0], Some(3.0), Some(1.0), 0);
+        assert_eq!(
+            swap.quoted_amount_out,
+            Some(BigDecimal::from_str("300").unwrap())
+        );
+        assert!((swap.slippage_bps.unwrap() - 100.0).abs() < 1e-9);
+    }
+}
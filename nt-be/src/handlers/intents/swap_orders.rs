@@ -0,0 +1,609 @@
+//! Swap orders of exchange proposals
+//!
+//! A live (non-dry) 1Click quote is recorded as a swap order when it is requested. The swap
+//! order worker then:
+//! 1. Links the order to the asset-exchange proposal that sends funds to its deposit address.
+//!    Quotes that no proposal picks up within an hour are dropped.
+//! 2. Polls the 1Click status of linked orders until the swap is final, recording refunds and
+//!    marking orders whose deadline passed without a deposit as expired.
+//! 3. Links settled orders to the `detected_swaps` row of their solver transaction.
+//!
+//! Orders are returned with their proposals, so refunds and expiries show up there.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use near_api::AccountId;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::types::Json as SqlJson;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::swap_status::{FullSwapStatusResponse, SwapStatus, fetch_swap_status};
+use crate::AppState;
+use crate::handlers::proposals::get_proposals::fetch_dao_proposals_cached;
+use crate::handlers::proposals::scraper::{
+    AssetExchangeInfo, Proposal, ProposalType, extract_from_description,
+};
+
+/// Linked orders polled on 1Click per worker tick
+const ORDERS_PER_REFRESH: i64 = 20;
+
+/// Orders are followed for this long after their deadline, in case the deposit is late and
+/// gets refunded
+const FOLLOW_AFTER_DEADLINE_DAYS: i32 = 7;
+
+const SWAP_ORDER_COLUMNS: &str = r#"
+    id, dao_id, proposal_id, deposit_address, deposit_memo, origin_asset, destination_asset,
+    amount_in, amount_out, min_amount_out, slippage_bps, deadline, quote, status,
+    amount_out_settled, refunded_amount, refund_reason, near_tx_hashes, detected_swap_id,
+    expired_at, settled_at, created_at, updated_at
+"#;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapOrder {
+    pub id: i64,
+    pub dao_id: String,
+    pub proposal_id: Option<i64>,
+    pub deposit_address: String,
+    pub deposit_memo: Option<String>,
+    pub origin_asset: String,
+    pub destination_asset: String,
+    pub amount_in: String,
+    pub amount_out: String,
+    pub min_amount_out: Option<String>,
+    pub slippage_bps: Option<i32>,
+    pub deadline: DateTime<Utc>,
+    #[serde(skip_serializing)]
+    pub quote: SqlJson<Value>,
+    /// 1Click swap status
+    pub status: String,
+    /// Amount actually received
+    pub amount_out_settled: Option<String>,
+    pub refunded_amount: Option<String>,
+    pub refund_reason: Option<String>,
+    pub near_tx_hashes: Vec<String>,
+    pub detected_swap_id: Option<i64>,
+    /// Set when the deadline passed without a deposit
+    pub expired_at: Option<DateTime<Utc>>,
+    pub settled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Swap order fields taken from a 1Click quote response
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotedOrder {
+    pub dao_id: String,
+    pub deposit_address: String,
+    pub deposit_memo: Option<String>,
+    pub origin_asset: String,
+    pub destination_asset: String,
+    pub amount_in: String,
+    pub amount_out: String,
+    pub min_amount_out: Option<String>,
+    pub slippage_bps: Option<i32>,
+    pub deadline: DateTime<Utc>,
+}
+
+/// Read the order of a live 1Click quote response; dry quotes have no deposit address
+pub fn parse_quote(body: &Value) -> Result<QuotedOrder, String> {
+    let quote = &body["quote"];
+    let request = &body["quoteRequest"];
+    let field = |value: &Value, name: &str| {
+        value[name]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("Quote response is missing {}", name))
+    };
+
+    let deadline = field(quote, "deadline").or_else(|_| field(request, "deadline"))?;
+    let deadline = DateTime::parse_from_rfc3339(&deadline)
+        .map_err(|e| format!("Invalid quote deadline {}: {}", deadline, e))?
+        .with_timezone(&Utc);
+
+    Ok(QuotedOrder {
+        dao_id: field(request, "refundTo")?,
+        deposit_address: field(quote, "depositAddress")?,
+        deposit_memo: quote["depositMemo"].as_str().map(str::to_string),
+        origin_asset: field(request, "originAsset")?,
+        destination_asset: field(request, "destinationAsset")?,
+        amount_in: field(quote, "amountIn")?,
+        amount_out: field(quote, "amountOut")?,
+        min_amount_out: quote["minAmountOut"].as_str().map(str::to_string),
+        slippage_bps: request["slippageTolerance"]
+            .as_i64()
+            .and_then(|bps| i32::try_from(bps).ok()),
+        deadline,
+    })
+}
+
+/// Record a live quote as a swap order, to be linked to its proposal by the worker
+///
+/// Failures are logged only; the quote is still returned to the caller.
+pub async fn record_quote(pool: &sqlx::PgPool, body: &Value) {
+    if body["quote"]["depositAddress"].as_str().is_none() {
+        return;
+    }
+    let order = match parse_quote(body) {
+        Ok(order) => order,
+        Err(e) => {
+            log::warn!("Not recording swap order: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = sqlx::query(
+        r#"
+        INSERT INTO swap_orders (
+            dao_id, deposit_address, deposit_memo, origin_asset, destination_asset,
+            amount_in, amount_out, min_amount_out, slippage_bps, deadline, quote
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ON CONFLICT ON CONSTRAINT unique_swap_order_deposit DO NOTHING
+        "#,
+    )
+    .bind(&order.dao_id)
+    .bind(&order.deposit_address)
+    .bind(&order.deposit_memo)
+    .bind(&order.origin_asset)
+    .bind(&order.destination_asset)
+    .bind(&order.amount_in)
+    .bind(&order.amount_out)
+    .bind(&order.min_amount_out)
+    .bind(order.slippage_bps)
+    .bind(order.deadline)
+    .bind(SqlJson(body))
+    .execute(pool)
+    .await
+    {
+        log::error!(
+            "Failed to record swap order for {}: {}",
+            order.deposit_address,
+            e
+        );
+    }
+}
+
+/// Swap order of a proposal
+pub async fn load_swap_order(
+    pool: &sqlx::PgPool,
+    dao_id: &str,
+    proposal_id: u64,
+) -> Result<Option<SwapOrder>, sqlx::Error> {
+    sqlx::query_as::<_, SwapOrder>(&format!(
+        "SELECT {} FROM swap_orders WHERE dao_id = $1 AND proposal_id = $2",
+        SWAP_ORDER_COLUMNS
+    ))
+    .bind(dao_id)
+    .bind(proposal_id as i64)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Swap orders of a DAO's proposals, by proposal ID
+pub async fn load_swap_orders(
+    pool: &sqlx::PgPool,
+    dao_id: &str,
+    proposal_ids: &[u64],
+) -> Result<HashMap<u64, SwapOrder>, sqlx::Error> {
+    if proposal_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let ids: Vec<i64> = proposal_ids.iter().map(|id| *id as i64).collect();
+
+    let orders = sqlx::query_as::<_, SwapOrder>(&format!(
+        "SELECT {} FROM swap_orders WHERE dao_id = $1 AND proposal_id = ANY($2)",
+        SWAP_ORDER_COLUMNS
+    ))
+    .bind(dao_id)
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(orders
+        .into_iter()
+        .filter_map(|order| Some((order.proposal_id? as u64, order)))
+        .collect())
+}
+
+/// 1Click deposit address an asset-exchange proposal sends funds to
+pub fn proposal_deposit_address(proposal: &Proposal) -> Option<String> {
+    if extract_from_description(&proposal.description, "proposalaction").as_deref()
+        != Some("asset-exchange")
+    {
+        return None;
+    }
+    extract_from_description(&proposal.description, "depositAddress")
+        .or_else(|| AssetExchangeInfo::from_proposal(proposal)?.deposit_address)
+}
+
+/// Link recorded quotes to the exchange proposals that use them, and drop the quotes no
+/// proposal picked up
+pub async fn link_quotes_to_proposals(
+    state: &AppState,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let dao_ids: Vec<String> =
+        sqlx::query_scalar("SELECT DISTINCT dao_id FROM swap_orders WHERE proposal_id IS NULL")
+            .fetch_all(&state.db_pool)
+            .await?;
+
+    let mut linked = 0;
+    for dao_id in dao_ids {
+        let Ok(dao) = dao_id.parse::<AccountId>() else {
+            continue;
+        };
+        let proposals = match fetch_dao_proposals_cached(state, &dao).await {
+            Ok((proposals, _)) => proposals,
+            Err((_, e)) => {
+                log::warn!("Failed to fetch proposals of {}: {}", dao_id, e);
+                continue;
+            }
+        };
+
+        for proposal in &proposals {
+            let Some(deposit_address) = proposal_deposit_address(proposal) else {
+                continue;
+            };
+            let result = sqlx::query(
+                r#"
+                UPDATE swap_orders
+                SET proposal_id = $3, updated_at = NOW()
+                WHERE dao_id = $1 AND deposit_address = $2 AND proposal_id IS NULL
+                  AND NOT EXISTS (
+                      SELECT 1 FROM swap_orders WHERE dao_id = $1 AND proposal_id = $3
+                  )
+                "#,
+            )
+            .bind(&dao_id)
+            .bind(&deposit_address)
+            .bind(proposal.id as i64)
+            .execute(&state.db_pool)
+            .await?;
+            linked += result.rows_affected() as usize;
+        }
+    }
+
+    // Exchange proposals are submitted right after their quote is fetched
+    sqlx::query(
+        r#"
+        DELETE FROM swap_orders
+        WHERE proposal_id IS NULL AND created_at < NOW() - INTERVAL '1 hour'
+        "#,
+    )
+    .execute(&state.db_pool)
+    .await?;
+
+    Ok(linked)
+}
+
+/// Changes to a swap order from its 1Click status
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapOrderUpdate {
+    pub status: SwapStatus,
+    pub amount_out_settled: Option<String>,
+    pub refunded_amount: Option<String>,
+    pub refund_reason: Option<String>,
+    pub near_tx_hashes: Vec<String>,
+    pub expired: bool,
+    pub settled: bool,
+}
+
+pub fn order_update(
+    deadline: DateTime<Utc>,
+    response: &FullSwapStatusResponse,
+    now: DateTime<Utc>,
+) -> SwapOrderUpdate {
+    let details = response.swap_details.clone().unwrap_or_default();
+    let status = response.status;
+
+    SwapOrderUpdate {
+        status,
+        amount_out_settled: (status == SwapStatus::Success)
+            .then_some(details.amount_out)
+            .flatten(),
+        refunded_amount: details.refunded_amount.filter(|amount| amount != "0"),
+        refund_reason: details.refund_reason,
+        near_tx_hashes: details.near_tx_hashes,
+        // Nothing was deposited before the quote ran out
+        expired: status == SwapStatus::PendingDeposit && deadline < now,
+        settled: status.is_final(),
+    }
+}
+
+/// Poll 1Click for the linked orders that are not final yet
+pub async fn refresh_swap_orders(
+    state: &AppState,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let orders = sqlx::query_as::<_, SwapOrder>(&format!(
+        r#"
+        SELECT {} FROM swap_orders
+        WHERE proposal_id IS NOT NULL
+          AND status NOT IN ('SUCCESS', 'REFUNDED', 'FAILED')
+          AND deadline > NOW() - make_interval(days => $1)
+        ORDER BY updated_at
+        LIMIT $2
+        "#,
+        SWAP_ORDER_COLUMNS
+    ))
+    .bind(FOLLOW_AFTER_DEADLINE_DAYS)
+    .bind(ORDERS_PER_REFRESH)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    let mut settled = 0;
+    for order in orders {
+        let response = match fetch_swap_status(
+            &state.http_client,
+            &state.env_vars.oneclick_api_url,
+            state.env_vars.oneclick_jwt_token.as_deref(),
+            &order.deposit_address,
+            order.deposit_memo.as_deref(),
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err((_, e)) => {
+                log::warn!("Failed to poll swap order {}: {}", order.id, e);
+                // Move the order to the back of the queue
+                sqlx::query("UPDATE swap_orders SET updated_at = NOW() WHERE id = $1")
+                    .bind(order.id)
+                    .execute(&state.db_pool)
+                    .await?;
+                continue;
+            }
+        };
+
+        let update = order_update(order.deadline, &response, Utc::now());
+        sqlx::query(
+            r#"
+            UPDATE swap_orders SET
+                status = $2,
+                amount_out_settled = COALESCE($3, amount_out_settled),
+                refunded_amount = COALESCE($4, refunded_amount),
+                refund_reason = COALESCE($5, refund_reason),
+                near_tx_hashes = $6,
+                expired_at = CASE WHEN $7 THEN COALESCE(expired_at, NOW()) ELSE expired_at END,
+                settled_at = CASE WHEN $8 THEN COALESCE(settled_at, NOW()) ELSE settled_at END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(order.id)
+        .bind(update.status.as_str())
+        .bind(&update.amount_out_settled)
+        .bind(&update.refunded_amount)
+        .bind(&update.refund_reason)
+        .bind(&update.near_tx_hashes)
+        .bind(update.expired)
+        .bind(update.settled)
+        .execute(&state.db_pool)
+        .await?;
+
+        if update.settled {
+            log::info!(
+                "Swap order {} of proposal {} in {} settled: {}",
+                order.id,
+                order.proposal_id.unwrap_or_default(),
+                order.dao_id,
+                update.status.as_str()
+            );
+            settled += 1;
+        }
+    }
+
+    Ok(settled)
+}
+
+/// Link settled orders to the swaps detected from their solver transactions
+pub async fn link_detected_swaps(pool: &sqlx::PgPool) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE swap_orders o SET detected_swap_id = d.id, updated_at = NOW()
+        FROM detected_swaps d
+        WHERE o.status = 'SUCCESS'
+          AND o.detected_swap_id IS NULL
+          AND d.account_id = o.dao_id
+          AND d.solver_transaction_hash = ANY(o.near_tx_hashes)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Run the swap order worker every 30 seconds
+pub async fn run_swap_order_service(state: Arc<AppState>) {
+    log::info!("Starting swap order worker (30 second poll interval)");
+
+    // Wait a bit before first run to let server fully start
+    tokio::time::sleep(std::time::Duration::from_secs(20)).await;
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+
+    loop {
+        interval.tick().await;
+
+        match link_quotes_to_proposals(&state).await {
+            Ok(linked) if linked > 0 => log::info!("Linked {} swap order(s)", linked),
+            Ok(_) => {}
+            Err(e) => log::error!("Swap order linking error: {}", e),
+        }
+
+        match refresh_swap_orders(&state).await {
+            Ok(settled) if settled > 0 => log::info!("Settled {} swap order(s)", settled),
+            Ok(_) => {}
+            Err(e) => log::error!("Swap order refresh error: {}", e),
+        }
+
+        if let Err(e) = link_detected_swaps(&state.db_pool).await {
+            log::error!("Failed to link swap orders to detected swaps: {}", e);
+        }
+    }
+}
+
+/// Swap order of an exchange proposal
+pub async fn get_swap_order(
+    State(state): State<Arc<AppState>>,
+    Path((dao_id, proposal_id)): Path<(AccountId, u64)>,
+) -> Result<Json<SwapOrder>, (StatusCode, String)> {
+    load_swap_order(&state.db_pool, dao_id.as_str(), proposal_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to load swap order: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load swap order".to_string(),
+            )
+        })?
+        .map(Json)
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("No swap order for proposal {}", proposal_id),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn live_quote() -> Value {
+        json!({
+            "quote": {
+                "amountIn": "1000000000000000000000000",
+                "amountInFormatted": "1.0",
+                "amountOut": "1714985",
+                "amountOutFormatted": "1.714985",
+                "minAmountOut": "1697835",
+                "depositAddress": "3e5f2c6a8b1d4e7f9a0b2c4d6e8f0a1b3c5d7e9f1a2b3c4d5e6f7a8b9c0d1e2f",
+                "deadline": "2026-03-09T16:30:00.000Z",
+                "timeEstimate": 20
+            },
+            "quoteRequest": {
+                "dry": false,
+                "swapType": "EXACT_INPUT",
+                "slippageTolerance": 100,
+                "originAsset": "nep141:wrap.near",
+                "destinationAsset": "nep141:usdt.tether-token.near",
+                "amount": "1000000000000000000000000",
+                "refundTo": "treasury.sputnik-dao.near",
+                "recipient": "treasury.sputnik-dao.near",
+                "deadline": "2026-03-09T16:30:00.000Z"
+            },
+            "signature": "ed25519:Sqg1sRLhpg1QtC9g69DKphB4qBBLUbqVYcPgytZ6LbQR275LtXNojsgpFBs9EKpdMn9sLkfPXZjBAMVPmVNEcre",
+            "timestamp": "2026-03-08T16:30:00.000Z",
+            "correlationId": "261f3a3b-9568-4dd6-85a5-2688b370d07a"
+        })
+    }
+
+    fn status_response(value: Value) -> FullSwapStatusResponse {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_live_quote() {
+        let order = parse_quote(&live_quote()).unwrap();
+
+        assert_eq!(order.dao_id, "treasury.sputnik-dao.near");
+        assert_eq!(
+            order.deposit_address,
+            "3e5f2c6a8b1d4e7f9a0b2c4d6e8f0a1b3c5d7e9f1a2b3c4d5e6f7a8b9c0d1e2f"
+        );
+        assert_eq!(order.deposit_memo, None);
+        assert_eq!(order.amount_in, "1000000000000000000000000");
+        assert_eq!(order.amount_out, "1714985");
+        assert_eq!(order.min_amount_out.as_deref(), Some("1697835"));
+        assert_eq!(order.slippage_bps, Some(100));
+        assert_eq!(order.deadline.to_rfc3339(), "2026-03-09T16:30:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_dry_quote_fails() {
+        let mut quote = live_quote();
+        quote["quote"]
+            .as_object_mut()
+            .unwrap()
+            .remove("depositAddress");
+
+        assert!(parse_quote(&quote).unwrap_err().contains("depositAddress"));
+    }
+
+    #[test]
+    fn test_order_update_success_and_refund() {
+        let deadline = "2026-03-09T16:30:00Z".parse().unwrap();
+        let before = "2026-03-09T12:00:00Z".parse().unwrap();
+
+        let success = order_update(
+            deadline,
+            &status_response(json!({
+                "status": "SUCCESS",
+                "updatedAt": "2026-03-09T12:00:00.000Z",
+                "swapDetails": {
+                    "nearTxHashes": ["7kSKx3fF1XmMpYcE2Hq1Yc8V9dGq8bLkWc6h5iXGrHbN"],
+                    "amountOut": "1710000",
+                    "refundedAmount": "0"
+                }
+            })),
+            before,
+        );
+        assert!(success.settled);
+        assert!(!success.expired);
+        assert_eq!(success.amount_out_settled.as_deref(), Some("1710000"));
+        assert_eq!(success.refunded_amount, None);
+        assert_eq!(success.near_tx_hashes.len(), 1);
+
+        let refund = order_update(
+            deadline,
+            &status_response(json!({
+                "status": "REFUNDED",
+                "updatedAt": "2026-03-09T12:00:00.000Z",
+                "swapDetails": {
+                    "nearTxHashes": [],
+                    "amountOut": "0",
+                    "refundedAmount": "1000000000000000000000000",
+                    "refundReason": "Deposit received after the quote deadline"
+                }
+            })),
+            before,
+        );
+        assert!(refund.settled);
+        assert_eq!(refund.amount_out_settled, None);
+        assert_eq!(
+            refund.refunded_amount.as_deref(),
+            Some("1000000000000000000000000")
+        );
+        assert!(refund.refund_reason.is_some());
+    }
+
+    #[test]
+    fn test_order_update_expiry() {
+        let deadline = "2026-03-09T16:30:00Z".parse().unwrap();
+        let after = "2026-03-09T17:00:00Z".parse().unwrap();
+        let pending = |status: &str| {
+            status_response(json!({
+                "status": status,
+                "updatedAt": "2026-03-09T12:00:00.000Z"
+            }))
+        };
+
+        let expired = order_update(deadline, &pending("PENDING_DEPOSIT"), after);
+        assert!(expired.expired);
+        assert!(!expired.settled);
+
+        // A deposit made in time is still being processed
+        assert!(!order_update(deadline, &pending("PROCESSING"), after).expired);
+        assert!(
+            !order_update(
+                deadline,
+                &pending("PENDING_DEPOSIT"),
+                "2026-03-09T12:00:00Z".parse().unwrap()
+            )
+            .expired
+        );
+    }
+}
@@ -19,7 +19,7 @@ pub struct SwapStatusQuery {
     pub deposit_memo: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SwapStatus {
     KnownDepositTx,
@@ -31,6 +31,37 @@ pub enum SwapStatus {
     Failed,
 }
 
+impl SwapStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapStatus::KnownDepositTx => "KNOWN_DEPOSIT_TX",
+            SwapStatus::PendingDeposit => "PENDING_DEPOSIT",
+            SwapStatus::IncompleteDeposit => "INCOMPLETE_DEPOSIT",
+            SwapStatus::Processing => "PROCESSING",
+            SwapStatus::Success => "SUCCESS",
+            SwapStatus::Refunded => "REFUNDED",
+            SwapStatus::Failed => "FAILED",
+        }
+    }
+
+    /// Whether the swap has settled and its status no longer changes
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            SwapStatus::Success | SwapStatus::Refunded | SwapStatus::Failed
+        )
+    }
+}
+
+impl std::str::FromStr for SwapStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_string()))
+            .map_err(|_| format!("Unknown swap status: {}", s))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SimplifiedSwapStatusResponse {
     pub status: SwapStatus,
@@ -38,15 +69,79 @@ pub struct SimplifiedSwapStatusResponse {
     pub updated_at: String,
 }
 
+/// Settlement details of a swap, as reported by the 1Click API
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapDetails {
+    /// NEAR transactions of the swap (deposit, solver fulfillment, withdrawal)
+    #[serde(default)]
+    pub near_tx_hashes: Vec<String>,
+    #[serde(default)]
+    pub amount_out: Option<String>,
+    #[serde(default)]
+    pub refunded_amount: Option<String>,
+    #[serde(default)]
+    pub refund_reason: Option<String>,
+}
+
 // Full response structure for deserialization from 1Click API
 #[derive(Debug, Deserialize)]
-struct FullSwapStatusResponse {
-    status: SwapStatus,
+pub struct FullSwapStatusResponse {
+    pub status: SwapStatus,
     #[serde(rename = "updatedAt")]
-    updated_at: String,
-    // Ignore other fields we don't need
-    #[serde(flatten)]
-    _other: serde_json::Value,
+    pub updated_at: String,
+    #[serde(rename = "swapDetails", default)]
+    pub swap_details: Option<SwapDetails>,
+}
+
+/// Fetch the status of the swap behind a 1Click deposit address
+pub async fn fetch_swap_status(
+    http_client: &reqwest::Client,
+    api_url: &str,
+    jwt_token: Option<&str>,
+    deposit_address: &str,
+    deposit_memo: Option<&str>,
+) -> Result<FullSwapStatusResponse, (StatusCode, String)> {
+    let mut url = format!("{}/v0/status?depositAddress={}", api_url, deposit_address);
+
+    if let Some(memo) = deposit_memo {
+        url.push_str(&format!("&depositMemo={}", memo));
+    }
+
+    let mut request = http_client.get(&url);
+
+    // Add JWT auth if available
+    if let Some(jwt_token) = jwt_token {
+        request = request.header("Authorization", format!("Bearer {}", jwt_token));
+    }
+
+    let response = request.send().await.map_err(|e| {
+        log::error!("Error fetching swap status: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch swap status: {}", e),
+        )
+    })?;
+
+    let status_code = response.status();
+
+    if !status_code.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        log::error!("1Click API error ({}): {}", status_code, error_text);
+
+        return Err((
+            StatusCode::from_u16(status_code.as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            format!("1Click API error: {}", error_text),
+        ));
+    }
+
+    response.json().await.map_err(|e| {
+        log::error!("Error parsing swap status response: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to parse swap status response: {}", e),
+        )
+    })
 }
 
 pub async fn get_swap_status(
@@ -63,55 +158,20 @@ pub async fn get_swap_status(
         .build();
 
     let http_client = state.http_client.clone();
+    let api_url = state.env_vars.oneclick_api_url.clone();
     let oneclick_jwt_token = state.env_vars.oneclick_jwt_token.clone();
 
     let result = state
         .cache
         .cached(CacheTier::ShortTerm, cache_key, async move {
-            let mut url = format!(
-                "https://1click.chaindefuser.com/v0/status?depositAddress={}",
-                deposit_address
-            );
-
-            if let Some(memo) = deposit_memo {
-                url.push_str(&format!("&depositMemo={}", memo));
-            }
-
-            let mut request = http_client.get(&url);
-
-            // Add JWT auth if available
-            if let Some(jwt_token) = oneclick_jwt_token.as_ref() {
-                request = request.header("Authorization", format!("Bearer {}", jwt_token));
-            }
-
-            let response = request.send().await.map_err(|e| {
-                log::error!("Error fetching swap status: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to fetch swap status: {}", e),
-                )
-            })?;
-
-            let status_code = response.status();
-
-            if !status_code.is_success() {
-                let error_text = response.text().await.unwrap_or_default();
-                log::error!("1Click API error ({}): {}", status_code, error_text);
-
-                return Err((
-                    StatusCode::from_u16(status_code.as_u16())
-                        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
-                    format!("1Click API error: {}", error_text),
-                ));
-            }
-
-            let full_response: FullSwapStatusResponse = response.json().await.map_err(|e| {
-                log::error!("Error parsing swap status response: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to parse swap status response: {}", e),
-                )
-            })?;
+            let full_response = fetch_swap_status(
+                &http_client,
+                &api_url,
+                oneclick_jwt_token.as_deref(),
+                &deposit_address,
+                deposit_memo.as_deref(),
+            )
+            .await?;
 
             // Return simplified response with only status
             Ok::<_, (StatusCode, String)>(SimplifiedSwapStatusResponse {
@@ -0,0 +1,184 @@
+//! Admin overrides of the intents token registry
+//!
+//! Overrides replace the symbol, name or icon of a token, or move it to another unified token.
+//! Every change reloads the token registry, so it applies right away.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::auth::AdminUser;
+use crate::services::token_registry::{
+    TokenOverride, delete_token_override, load_token_overrides, reload_token_registry,
+    save_token_override,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenOverrideRequest {
+    pub defuse_asset_id: String,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub unified_asset_id: Option<String>,
+}
+
+/// Blank fields keep the synced or bundled value
+fn non_blank(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl TokenOverrideRequest {
+    fn into_override(self) -> Result<TokenOverride, (StatusCode, String)> {
+        let defuse_asset_id = self.defuse_asset_id.trim().to_string();
+        if defuse_asset_id.is_empty() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "A defuseAssetId is required".to_string(),
+            ));
+        }
+
+        Ok(TokenOverride {
+            defuse_asset_id,
+            symbol: non_blank(self.symbol),
+            name: non_blank(self.name),
+            icon: non_blank(self.icon),
+            unified_asset_id: non_blank(self.unified_asset_id),
+        })
+    }
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    log::error!("Token override database error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Database error".to_string(),
+    )
+}
+
+/// All token overrides. Admins only.
+pub async fn list_token_overrides(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+) -> Result<Json<Vec<TokenOverride>>, (StatusCode, String)> {
+    let overrides = load_token_overrides(&state.db_pool)
+        .await
+        .map_err(db_error)?;
+    Ok(Json(overrides))
+}
+
+/// Create or replace the override of a token. Admins only.
+pub async fn put_token_override(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(request): Json<TokenOverrideRequest>,
+) -> Result<Json<TokenOverride>, (StatusCode, String)> {
+    let token_override = request.into_override()?;
+
+    save_token_override(&state.db_pool, &token_override)
+        .await
+        .map_err(db_error)?;
+    reload_token_registry(&state.db_pool)
+        .await
+        .map_err(db_error)?;
+
+    log::info!(
+        "Token override of {} saved by {}",
+        token_override.defuse_asset_id,
+        admin.account_id
+    );
+
+    Ok(Json(token_override))
+}
+
+/// Remove the override of a token. Admins only.
+pub async fn remove_token_override(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(defuse_asset_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let deleted = delete_token_override(&state.db_pool, &defuse_asset_id)
+        .await
+        .map_err(db_error)?;
+    if !deleted {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("No override for {}", defuse_asset_id),
+        ));
+    }
+    reload_token_registry(&state.db_pool)
+        .await
+        .map_err(db_error)?;
+
+    log::info!(
+        "Token override of {} removed by {}",
+        defuse_asset_id,
+        admin.account_id
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_utils::build_test_state;
+    use sqlx::PgPool;
+
+    fn request(defuse_asset_id: &str) -> TokenOverrideRequest {
+        TokenOverrideRequest {
+            defuse_asset_id: defuse_asset_id.to_string(),
+            symbol: None,
+            name: None,
+            icon: None,
+            unified_asset_id: None,
+        }
+    }
+
+    #[test]
+    fn test_into_override() {
+        let (status, _) = request("  ").into_override().unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let token_override = TokenOverrideRequest {
+            symbol: Some(" USDC.e ".to_string()),
+            name: Some("".to_string()),
+            icon: Some("   ".to_string()),
+            unified_asset_id: Some("usdc-eth".to_string()),
+            ..request(" nep141:usdc.near ")
+        }
+        .into_override()
+        .unwrap();
+
+        assert_eq!(token_override.defuse_asset_id, "nep141:usdc.near");
+        assert_eq!(token_override.symbol.as_deref(), Some("USDC.e"));
+        assert_eq!(token_override.name, None);
+        assert_eq!(token_override.icon, None);
+        assert_eq!(token_override.unified_asset_id.as_deref(), Some("usdc-eth"));
+    }
+
+    #[sqlx::test]
+    async fn test_remove_missing_override(pool: PgPool) {
+        let state = Arc::new(build_test_state(pool));
+        let admin = AdminUser {
+            account_id: "admin.near".to_string(),
+        };
+
+        let (status, _) =
+            remove_token_override(State(state), admin, Path("nep141:missing.near".to_string()))
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -1,10 +1,16 @@
 pub mod balance_changes;
 pub mod bulkpayment;
 pub mod dao;
+pub mod exchange_orders;
+pub mod explorer;
+pub mod fees;
 pub mod intents;
 pub mod lookup;
+pub mod payment_schedules;
 pub mod proposals;
 pub mod proxy;
+pub mod rebalancing;
+pub mod receipts;
 pub mod relay;
 pub mod subscription;
 pub mod token;
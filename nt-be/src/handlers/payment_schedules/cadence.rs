@@ -0,0 +1,165 @@
+//! Cadence arithmetic for payment schedules.
+
+use chrono::{DateTime, Duration, Months, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cadence {
+    Once,
+    Daily,
+    Weekly,
+    Biweekly,
+    Monthly,
+    Quarterly,
+    Yearly,
+}
+
+impl Cadence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Cadence::Once => "once",
+            Cadence::Daily => "daily",
+            Cadence::Weekly => "weekly",
+            Cadence::Biweekly => "biweekly",
+            Cadence::Monthly => "monthly",
+            Cadence::Quarterly => "quarterly",
+            Cadence::Yearly => "yearly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "once" => Some(Cadence::Once),
+            "daily" => Some(Cadence::Daily),
+            "weekly" => Some(Cadence::Weekly),
+            "biweekly" => Some(Cadence::Biweekly),
+            "monthly" => Some(Cadence::Monthly),
+            "quarterly" => Some(Cadence::Quarterly),
+            "yearly" => Some(Cadence::Yearly),
+            _ => None,
+        }
+    }
+
+    /// The n-th occurrence of a schedule starting at `start` (the 0-th is `start` itself).
+    ///
+    /// Month-based cadences are always computed from `start`, so a schedule starting on
+    /// the 31st pays on the last day of shorter months without drifting afterwards.
+    pub fn occurrence(&self, start: DateTime<Utc>, n: u32) -> Option<DateTime<Utc>> {
+        match self {
+            Cadence::Once => (n == 0).then_some(start),
+            Cadence::Daily => start.checked_add_signed(Duration::days(n as i64)),
+            Cadence::Weekly => start.checked_add_signed(Duration::weeks(n as i64)),
+            Cadence::Biweekly => start.checked_add_signed(Duration::weeks(2 * n as i64)),
+            Cadence::Monthly => start.checked_add_months(Months::new(n)),
+            Cadence::Quarterly => start.checked_add_months(Months::new(n.checked_mul(3)?)),
+            Cadence::Yearly => start.checked_add_months(Months::new(n.checked_mul(12)?)),
+        }
+    }
+
+    /// First occurrence strictly after `after`, or None once the schedule has ended
+    pub fn next_after(
+        &self,
+        start: DateTime<Utc>,
+        end: Option<DateTime<Utc>>,
+        after: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        (0..)
+            .map_while(|n| self.occurrence(start, n))
+            .take_while(|at| end.is_none_or(|end| *at <= end))
+            .find(|at| *at > after)
+    }
+
+    /// First occurrence at or after `from`, used when a schedule is created or edited
+    pub fn first_from(
+        &self,
+        start: DateTime<Utc>,
+        end: Option<DateTime<Utc>>,
+        from: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        self.next_after(start, end, from - Duration::nanoseconds(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("timestamp should parse")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        for cadence in [
+            Cadence::Once,
+            Cadence::Daily,
+            Cadence::Weekly,
+            Cadence::Biweekly,
+            Cadence::Monthly,
+            Cadence::Quarterly,
+            Cadence::Yearly,
+        ] {
+            assert_eq!(Cadence::parse(cadence.as_str()), Some(cadence));
+        }
+        assert_eq!(Cadence::parse("hourly"), None);
+    }
+
+    #[test]
+    fn test_monthly_does_not_drift() {
+        let start = at("2026-01-31T09:00:00Z");
+
+        assert_eq!(
+            Cadence::Monthly.occurrence(start, 1),
+            Some(at("2026-02-28T09:00:00Z"))
+        );
+        assert_eq!(
+            Cadence::Monthly.occurrence(start, 2),
+            Some(at("2026-03-31T09:00:00Z"))
+        );
+        assert_eq!(
+            Cadence::Quarterly.occurrence(start, 1),
+            Some(at("2026-04-30T09:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_next_after() {
+        let start = at("2026-03-01T00:00:00Z");
+
+        assert_eq!(
+            Cadence::Weekly.next_after(start, None, at("2026-03-01T00:00:00Z")),
+            Some(at("2026-03-08T00:00:00Z"))
+        );
+        assert_eq!(
+            Cadence::Biweekly.next_after(start, None, at("2026-03-20T12:00:00Z")),
+            Some(at("2026-03-29T00:00:00Z"))
+        );
+        assert_eq!(
+            Cadence::Monthly.first_from(start, None, at("2026-03-01T00:00:00Z")),
+            Some(start)
+        );
+    }
+
+    #[test]
+    fn test_schedule_end() {
+        let start = at("2026-03-01T00:00:00Z");
+        let end = Some(at("2026-05-01T00:00:00Z"));
+
+        // The end date is inclusive
+        assert_eq!(
+            Cadence::Monthly.next_after(start, end, at("2026-04-15T00:00:00Z")),
+            Some(at("2026-05-01T00:00:00Z"))
+        );
+        assert_eq!(
+            Cadence::Monthly.next_after(start, end, at("2026-05-01T00:00:00Z")),
+            None
+        );
+        assert_eq!(
+            Cadence::Once.next_after(start, None, at("2026-03-01T00:00:00Z")),
+            None
+        );
+    }
+}
@@ -0,0 +1,5 @@
+//! Recurring and scheduled payments that are proposed to the DAO automatically when due
+
+pub mod cadence;
+pub mod schedules;
+pub mod worker;
@@ -0,0 +1,436 @@
+//! Payment schedule management endpoints.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use near_api::AccountId;
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::types::Json as SqlJson;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::cadence::Cadence;
+use crate::AppState;
+use crate::auth::{AuthUser, require_policy_member};
+use crate::handlers::bulkpayment::submit::{MAX_RECIPIENTS_PER_BULK_PAYMENT, PaymentInput};
+use crate::handlers::proposals::forecast::can_add_proposal;
+use crate::handlers::proposals::scraper::fetch_policy;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentSchedule {
+    pub id: i64,
+    pub dao_id: String,
+    pub created_by: String,
+    pub title: String,
+    pub notes: Option<String>,
+    pub token_id: String,
+    pub payments: SqlJson<Vec<PaymentInput>>,
+    pub cadence: String,
+    pub start_at: DateTime<Utc>,
+    pub end_at: Option<DateTime<Utc>>,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A proposal generated from a schedule
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentScheduleRun {
+    pub id: i64,
+    pub schedule_id: i64,
+    pub scheduled_for: DateTime<Utc>,
+    pub status: String,
+    pub proposal_id: Option<i64>,
+    pub list_id: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentScheduleDetails {
+    #[serde(flatten)]
+    pub schedule: PaymentSchedule,
+    pub runs: Vec<PaymentScheduleRun>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateScheduleRequest {
+    pub dao_id: String,
+    pub title: String,
+    pub notes: Option<String>,
+    pub token_id: String,
+    pub payments: Vec<PaymentInput>,
+    pub cadence: Cadence,
+    pub start_at: DateTime<Utc>,
+    pub end_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateScheduleRequest {
+    /// Pause (false) or resume (true) the schedule
+    pub is_active: Option<bool>,
+    /// New end date; `null` removes the end date, leaving it out keeps the current one
+    #[serde(default, deserialize_with = "present_field")]
+    pub end_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Tell a field sent as `null` (Some(None)) apart from a missing one (None)
+fn present_field<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSchedulesQuery {
+    pub dao_id: String,
+}
+
+const SCHEDULE_COLUMNS: &str = "id, dao_id, created_by, title, notes, token_id, payments, cadence, \
+     start_at, end_at, next_run_at, is_active, created_at, updated_at";
+
+/// Sputnik proposal kind label of the proposals a schedule generates
+pub fn schedule_proposal_kind(token_id: &str, num_payments: usize) -> &'static str {
+    if num_payments == 1 && !token_id.starts_with("nep141:") {
+        "transfer"
+    } else {
+        "call"
+    }
+}
+
+fn bad_request(message: impl Into<String>) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, message.into())
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    log::error!("Payment schedule database error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Database error".to_string(),
+    )
+}
+
+/// Validate the payments of a schedule
+pub fn validate_payments(payments: &[PaymentInput]) -> Result<(), String> {
+    if payments.is_empty() {
+        return Err("At least one payment is required".to_string());
+    }
+    if payments.len() > MAX_RECIPIENTS_PER_BULK_PAYMENT {
+        return Err(format!(
+            "Maximum number of recipients per schedule is {}",
+            MAX_RECIPIENTS_PER_BULK_PAYMENT
+        ));
+    }
+
+    let mut recipients = HashSet::new();
+    for payment in payments {
+        if payment.recipient.parse::<AccountId>().is_err() {
+            return Err(format!("Invalid recipient: {}", payment.recipient));
+        }
+        match payment.amount.parse::<u128>() {
+            Ok(amount) if amount > 0 => {}
+            _ => return Err(format!("Invalid amount for {}", payment.recipient)),
+        }
+        if payment.token_id.is_some() {
+            return Err("Schedules pay a single token per run".to_string());
+        }
+        if !recipients.insert(payment.recipient.as_str()) {
+            return Err(format!("Duplicate recipient: {}", payment.recipient));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that both the user and the relayer may add the proposals a schedule generates
+async fn authorize_schedule(
+    state: &AppState,
+    account_id: &str,
+    dao_id: &str,
+    token_id: &str,
+    num_payments: usize,
+) -> Result<(), (StatusCode, String)> {
+    let dao: AccountId = dao_id
+        .parse()
+        .map_err(|_| bad_request(format!("Invalid DAO ID: {}", dao_id)))?;
+    let policy = fetch_policy(&state.network, &dao).await.map_err(|e| {
+        log::error!("Error fetching policy for {}: {}", dao_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to fetch DAO policy".to_string(),
+        )
+    })?;
+
+    let label = schedule_proposal_kind(token_id, num_payments);
+    if !can_add_proposal(&policy, label, account_id) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!(
+                "{} is not allowed to create {} proposals in {}",
+                account_id, label, dao_id
+            ),
+        ));
+    }
+    if !can_add_proposal(&policy, label, state.signer_id.as_str()) {
+        return Err(bad_request(format!(
+            "The DAO policy must allow {} to create {} proposals for scheduled payments",
+            state.signer_id, label
+        )));
+    }
+
+    Ok(())
+}
+
+async fn fetch_schedule(
+    state: &AppState,
+    id: i64,
+) -> Result<PaymentSchedule, (StatusCode, String)> {
+    sqlx::query_as::<_, PaymentSchedule>(&format!(
+        "SELECT {} FROM payment_schedules WHERE id = $1",
+        SCHEDULE_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(db_error)?
+    .ok_or((
+        StatusCode::NOT_FOUND,
+        format!("Payment schedule {} not found", id),
+    ))
+}
+
+/// Create a payment schedule
+pub async fn create_schedule(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<CreateScheduleRequest>,
+) -> Result<Json<PaymentSchedule>, (StatusCode, String)> {
+    if request.title.trim().is_empty() {
+        return Err(bad_request("Title is required"));
+    }
+    if request.token_id.is_empty() {
+        return Err(bad_request("Token ID is required"));
+    }
+    validate_payments(&request.payments).map_err(bad_request)?;
+    if request.end_at.is_some_and(|end| end < request.start_at) {
+        return Err(bad_request("End date must not be before the start date"));
+    }
+
+    let next_run_at = request
+        .cadence
+        .first_from(request.start_at, request.end_at, Utc::now())
+        .ok_or_else(|| bad_request("Schedule has no upcoming payments"))?;
+
+    authorize_schedule(
+        &state,
+        &auth_user.account_id,
+        &request.dao_id,
+        &request.token_id,
+        request.payments.len(),
+    )
+    .await?;
+
+    let schedule = sqlx::query_as::<_, PaymentSchedule>(&format!(
+        r#"
+        INSERT INTO payment_schedules
+            (dao_id, created_by, title, notes, token_id, payments, cadence, start_at, end_at, next_run_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING {}
+        "#,
+        SCHEDULE_COLUMNS
+    ))
+    .bind(&request.dao_id)
+    .bind(&auth_user.account_id)
+    .bind(request.title.trim())
+    .bind(&request.notes)
+    .bind(&request.token_id)
+    .bind(SqlJson(&request.payments))
+    .bind(request.cadence.as_str())
+    .bind(request.start_at)
+    .bind(request.end_at)
+    .bind(next_run_at)
+    .fetch_one(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    log::info!(
+        "Payment schedule {} created for {} by {}",
+        schedule.id,
+        schedule.dao_id,
+        schedule.created_by
+    );
+
+    Ok(Json(schedule))
+}
+
+/// List the payment schedules of a treasury
+pub async fn list_schedules(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<ListSchedulesQuery>,
+) -> Result<Json<Vec<PaymentSchedule>>, (StatusCode, String)> {
+    require_policy_member(&state, &query.dao_id, &auth_user.account_id).await?;
+
+    let schedules = sqlx::query_as::<_, PaymentSchedule>(&format!(
+        "SELECT {} FROM payment_schedules WHERE dao_id = $1 ORDER BY created_at DESC",
+        SCHEDULE_COLUMNS
+    ))
+    .bind(&query.dao_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(schedules))
+}
+
+/// Get a payment schedule together with the proposals generated from it
+pub async fn get_schedule(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<PaymentScheduleDetails>, (StatusCode, String)> {
+    let schedule = fetch_schedule(&state, id).await?;
+    require_policy_member(&state, &schedule.dao_id, &auth_user.account_id).await?;
+
+    let runs = sqlx::query_as::<_, PaymentScheduleRun>(
+        r#"
+        SELECT id, schedule_id, scheduled_for, status, proposal_id, list_id, error, created_at
+        FROM payment_schedule_runs
+        WHERE schedule_id = $1
+        ORDER BY scheduled_for DESC
+        "#,
+    )
+    .bind(id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(PaymentScheduleDetails { schedule, runs }))
+}
+
+/// Pause, resume or change the end date of a payment schedule
+pub async fn update_schedule(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Json(request): Json<UpdateScheduleRequest>,
+) -> Result<Json<PaymentSchedule>, (StatusCode, String)> {
+    let schedule = fetch_schedule(&state, id).await?;
+
+    authorize_schedule(
+        &state,
+        &auth_user.account_id,
+        &schedule.dao_id,
+        &schedule.token_id,
+        schedule.payments.0.len(),
+    )
+    .await?;
+
+    let end_at = request.end_at.unwrap_or(schedule.end_at);
+    if end_at.is_some_and(|end| end < schedule.start_at) {
+        return Err(bad_request("End date must not be before the start date"));
+    }
+    let is_active = request.is_active.unwrap_or(schedule.is_active);
+
+    let cadence = Cadence::parse(&schedule.cadence).ok_or((
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("Unknown cadence: {}", schedule.cadence),
+    ))?;
+
+    // Occurrences that passed while paused are not proposed retroactively
+    let next_run_at = if is_active {
+        let next = cadence.first_from(schedule.start_at, end_at, Utc::now());
+        if next.is_none() {
+            return Err(bad_request("Schedule has no upcoming payments"));
+        }
+        next
+    } else {
+        None
+    };
+
+    let updated = sqlx::query_as::<_, PaymentSchedule>(&format!(
+        r#"
+        UPDATE payment_schedules
+        SET is_active = $2,
+            end_at = $3,
+            next_run_at = $4,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING {}
+        "#,
+        SCHEDULE_COLUMNS
+    ))
+    .bind(id)
+    .bind(is_active)
+    .bind(end_at)
+    .bind(next_run_at)
+    .fetch_one(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(updated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payment(recipient: &str, amount: &str) -> PaymentInput {
+        PaymentInput {
+            recipient: recipient.to_string(),
+            amount: amount.to_string(),
+            token_id: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_payments() {
+        assert!(validate_payments(&[payment("bob.near", "1")]).is_ok());
+        assert!(validate_payments(&[]).is_err());
+        assert!(validate_payments(&[payment("Not An Account", "1")]).is_err());
+        assert!(validate_payments(&[payment("bob.near", "0")]).is_err());
+        assert!(validate_payments(&[payment("bob.near", "1.5")]).is_err());
+        assert!(validate_payments(&[payment("bob.near", "1"), payment("bob.near", "2")]).is_err());
+
+        let too_many: Vec<_> = (0..=MAX_RECIPIENTS_PER_BULK_PAYMENT)
+            .map(|i| payment(&format!("user{}.near", i), "1"))
+            .collect();
+        assert!(validate_payments(&too_many).is_err());
+    }
+
+    #[test]
+    fn test_update_request_end_at() {
+        let parse = |json: &str| serde_json::from_str::<UpdateScheduleRequest>(json).unwrap();
+
+        assert_eq!(parse(r#"{"isActive":false}"#).end_at, None);
+        assert_eq!(parse(r#"{"endAt":null}"#).end_at, Some(None));
+        assert!(
+            parse(r#"{"endAt":"2026-06-30T00:00:00Z"}"#)
+                .end_at
+                .is_some_and(|end_at| end_at.is_some())
+        );
+    }
+
+    #[test]
+    fn test_schedule_proposal_kind() {
+        assert_eq!(schedule_proposal_kind("native", 1), "transfer");
+        assert_eq!(
+            schedule_proposal_kind("usdt.tether-token.near", 1),
+            "transfer"
+        );
+        assert_eq!(schedule_proposal_kind("nep141:usdc.omft.near", 1), "call");
+        assert_eq!(schedule_proposal_kind("native", 3), "call");
+    }
+}
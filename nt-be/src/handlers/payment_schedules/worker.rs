@@ -0,0 +1,783 @@
+//! Background job that turns due payment schedules into DAO proposals.
+//!
+//! A schedule with a single recipient becomes a Transfer proposal (or an `ft_withdraw`
+//! function call for intents tokens). A schedule with several recipients becomes a bulk
+//! payment: the list is submitted to the bulk payment contract first, so the approval
+//! proposal never references a list that does not exist. The list ID is salted with the
+//! schedule ID and occurrence time, so every occurrence gets its own list.
+//!
+//! Proposals are signed by the backend relayer account, so the DAO policy has to grant it
+//! `AddProposal` for the relevant proposal kind.
+
+use chrono::{DateTime, Utc};
+use near_api::{AccountId, Contract, NearGas, NearToken};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use super::cadence::Cadence;
+use super::schedules::PaymentSchedule;
+use crate::AppState;
+use crate::config::plans::{PlanType, has_gas_covered_credits};
+use crate::constants::INTENTS_CONTRACT_ID;
+use crate::handlers::balance_changes::counterparty::convert_raw_to_decimal;
+use crate::handlers::bulkpayment::submit::{
+    compute_salted_list_hash, decrement_batch_payment_credits, submit_list_to_contract,
+};
+use crate::handlers::bulkpayment::worker::add_pending_list;
+use crate::handlers::fees::ledger::{FeeSource, PlatformTransaction, record_platform_transaction};
+use crate::handlers::proposals::scraper::{Proposal, fetch_ft_metadata, fetch_policy};
+
+/// Number of recent proposals searched for the one that was just created
+const PROPOSAL_LOOKUP_WINDOW: u64 = 10;
+
+/// A proposal ready to be submitted to the DAO
+#[derive(Debug, Clone)]
+pub struct GeneratedProposal {
+    pub description: String,
+    pub kind: Value,
+    /// Bulk payment list ID, set when the schedule has several recipients
+    pub list_id: Option<String>,
+}
+
+/// Token ID as used by the bulk payment contract and its list hash
+fn list_token_id(token_id: &str) -> &str {
+    if token_id == "near" {
+        "native"
+    } else {
+        token_id
+    }
+}
+
+fn is_native(token_id: &str) -> bool {
+    token_id == "native" || token_id == "near"
+}
+
+/// Contract that holds the token metadata
+fn metadata_contract(token_id: &str) -> &str {
+    if is_native(token_id) {
+        "near"
+    } else {
+        token_id.strip_prefix("nep141:").unwrap_or(token_id)
+    }
+}
+
+/// Salt of the bulk payment list of one schedule occurrence
+pub fn occurrence_salt(schedule_id: i64, scheduled_for: DateTime<Utc>) -> String {
+    format!("schedule:{}:{}", schedule_id, scheduled_for.to_rfc3339())
+}
+
+/// Encode key/value pairs the same way the frontend's `encodeToMarkdown` does
+pub fn encode_description(fields: &[(&str, String)]) -> String {
+    fields
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| format!("* {}: {}", key, value))
+        .collect::<Vec<_>>()
+        .join(" <br>")
+}
+
+pub fn base64_args(args: &Value) -> String {
+    base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        serde_json::to_vec(args).unwrap_or_default(),
+    )
+}
+
+/// Build the proposal for one occurrence of a schedule
+pub fn build_proposal(
+    schedule: &PaymentSchedule,
+    scheduled_for: DateTime<Utc>,
+    bulk_payment_contract_id: &str,
+    symbol: &str,
+    decimals: u8,
+) -> Result<GeneratedProposal, String> {
+    let payments = &schedule.payments.0;
+    let total = payments
+        .iter()
+        .map(|p| p.amount.parse::<u128>())
+        .try_fold(0u128, |acc, amount| {
+            amount.ok().and_then(|amount| acc.checked_add(amount))
+        })
+        .ok_or_else(|| "Invalid payment amounts".to_string())?;
+    let total_display = convert_raw_to_decimal(&total.to_string(), decimals)
+        .map(|d| d.to_string())
+        .unwrap_or_else(|_| total.to_string());
+    let schedule_ref = schedule.id.to_string();
+
+    if let [payment] = payments.as_slice() {
+        let kind = if let Some(token_contract) = schedule.token_id.strip_prefix("nep141:") {
+            json!({
+                "FunctionCall": {
+                    "receiver_id": INTENTS_CONTRACT_ID.as_str(),
+                    "actions": [{
+                        "method_name": "ft_withdraw",
+                        "args": base64_args(&json!({
+                            "token": token_contract,
+                            "receiver_id": payment.recipient,
+                            "amount": payment.amount,
+                        })),
+                        "deposit": "1",
+                        "gas": NearGas::from_tgas(30).as_gas().to_string(),
+                    }],
+                }
+            })
+        } else {
+            json!({
+                "Transfer": {
+                    "token_id": if is_native(&schedule.token_id) { "" } else { schedule.token_id.as_str() },
+                    "receiver_id": payment.recipient,
+                    "amount": payment.amount,
+                    "msg": null,
+                }
+            })
+        };
+
+        let description = encode_description(&[
+            ("Title", schedule.title.clone()),
+            ("Notes", schedule.notes.clone().unwrap_or_default()),
+            ("Schedule Id", schedule_ref),
+        ]);
+
+        return Ok(GeneratedProposal {
+            description,
+            kind,
+            list_id: None,
+        });
+    }
+
+    let token_id = list_token_id(&schedule.token_id);
+    let salt = occurrence_salt(schedule.id, scheduled_for);
+    let list_id = compute_salted_list_hash(&schedule.dao_id, token_id, payments, Some(&salt));
+
+    let kind = if is_native(token_id) {
+        json!({
+            "FunctionCall": {
+                "receiver_id": bulk_payment_contract_id,
+                "actions": [{
+                    "method_name": "approve_list",
+                    "args": base64_args(&json!({ "list_id": list_id })),
+                    "deposit": total.to_string(),
+                    "gas": NearGas::from_tgas(150).as_gas().to_string(),
+                }],
+            }
+        })
+    } else if token_id.starts_with("nep141:") {
+        json!({
+            "FunctionCall": {
+                "receiver_id": INTENTS_CONTRACT_ID.as_str(),
+                "actions": [{
+                    "method_name": "mt_transfer_call",
+                    "args": base64_args(&json!({
+                        "receiver_id": bulk_payment_contract_id,
+                        "token_id": token_id,
+                        "amount": total.to_string(),
+                        "msg": list_id,
+                    })),
+                    "deposit": "1",
+                    "gas": NearGas::from_tgas(150).as_gas().to_string(),
+                }],
+            }
+        })
+    } else {
+        json!({
+            "FunctionCall": {
+                "receiver_id": token_id,
+                "actions": [{
+                    "method_name": "ft_transfer_call",
+                    "args": base64_args(&json!({
+                        "receiver_id": bulk_payment_contract_id,
+                        "amount": total.to_string(),
+                        "msg": list_id,
+                    })),
+                    "deposit": "1",
+                    "gas": NearGas::from_tgas(100).as_gas().to_string(),
+                }],
+            }
+        })
+    };
+
+    let description = encode_description(&[
+        ("Proposal Action", "bulk-payment".to_string()),
+        ("Title", schedule.title.clone()),
+        ("Recipients", payments.len().to_string()),
+        ("Contract", symbol.to_string()),
+        ("Amount", total_display),
+        ("List Id", list_id.clone()),
+        ("Schedule Id", schedule_ref),
+    ]);
+
+    Ok(GeneratedProposal {
+        description,
+        kind,
+        list_id: Some(list_id),
+    })
+}
+
+/// Find the ID of the proposal the relayer just added
+async fn find_generated_proposal(
+    state: &AppState,
+    dao_id: &AccountId,
+    description: &str,
+) -> Result<Option<u64>, String> {
+    let last_proposal_id: u64 = Contract(dao_id.clone())
+        .call_function("get_last_proposal_id", ())
+        .read_only::<u64>()
+        .fetch_from(&state.network)
+        .await
+        .map_err(|e| format!("Failed to fetch last proposal ID: {}", e))?
+        .data;
+
+    let proposals: Vec<Proposal> = Contract(dao_id.clone())
+        .call_function(
+            "get_proposals",
+            json!({
+                "from_index": last_proposal_id.saturating_sub(PROPOSAL_LOOKUP_WINDOW),
+                "limit": PROPOSAL_LOOKUP_WINDOW,
+            }),
+        )
+        .read_only::<Vec<Proposal>>()
+        .fetch_from(&state.network)
+        .await
+        .map_err(|e| format!("Failed to fetch proposals: {}", e))?
+        .data;
+
+    Ok(proposals
+        .iter()
+        .rev()
+        .find(|p| p.proposer == state.signer_id.as_str() && p.description == description)
+        .map(|p| p.id))
+}
+
+/// Check the treasury still has the credits this run consumes
+///
+/// The credits are decremented once the list is submitted and the proposal added.
+pub async fn check_credits(state: &AppState, dao_id: &str, is_bulk: bool) -> Result<(), String> {
+    let account = sqlx::query_as::<_, (i32, i32, PlanType)>(
+        r#"
+        SELECT gas_covered_transactions, batch_payment_credits, plan_type
+        FROM monitored_accounts
+        WHERE account_id = $1
+        "#,
+    )
+    .bind(dao_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| format!("Database error: {}", e))?;
+
+    let Some((gas_credits, batch_credits, plan_type)) = account else {
+        return Err(format!(
+            "Treasury '{}' not found in monitored accounts",
+            dao_id
+        ));
+    };
+
+    if !has_gas_covered_credits(plan_type, gas_credits) {
+        return Err("No gas-covered transaction credits remaining".to_string());
+    }
+    if is_bulk && batch_credits <= 0 {
+        return Err("No batch payment credits remaining".to_string());
+    }
+    Ok(())
+}
+
+/// Use up one gas-covered transaction of the treasury for a proposal the relayer added
+///
+/// Failures are logged only - the proposal is already on-chain at this point.
+pub async fn decrement_gas_covered_transactions(pool: &sqlx::PgPool, dao_id: &str) {
+    let result = sqlx::query(
+        r#"
+        UPDATE monitored_accounts
+        SET gas_covered_transactions = GREATEST(gas_covered_transactions - 1, 0),
+            updated_at = NOW()
+        WHERE account_id = $1
+        "#,
+    )
+    .bind(dao_id)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        log::error!("Failed to decrement gas credits for {}: {}", dao_id, e);
+    }
+}
+
+/// Cancel a list whose approval proposal could not be added, returning its storage credits
+async fn cancel_submitted_list(state: &AppState, list_id: &str) -> Result<(), String> {
+    let network = &state.network;
+    state
+        .bulk_payment_pool
+        .send_primary(move |contract| async move {
+            Contract(contract.account_id.clone())
+                .call_function("cancel_list", json!({ "list_id": list_id }))
+                .transaction()
+                .with_signer(contract.account_id, contract.signer)
+                .send_to(network)
+                .await
+        })
+        .await
+        .map_err(|e| format!("Failed to cancel list: {}", e))?
+        .into_result()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to cancel list: {}", e))
+}
+
+/// Add a proposal to the DAO with the relayer account, paying the proposal bond
+///
+/// Returns the ID of the created proposal when it can be resolved.
+pub async fn add_relayer_proposal(
+    state: &AppState,
+    dao_id: &AccountId,
+    description: &str,
+    kind: &Value,
+) -> Result<Option<u64>, String> {
+    let policy = fetch_policy(&state.network, dao_id)
+        .await
+        .map_err(|e| format!("Failed to fetch DAO policy: {}", e))?;
+    let proposal_bond = policy
+        .proposal_bond
+        .parse::<u128>()
+        .map(NearToken::from_yoctonear)
+        .unwrap_or(NearToken::from_yoctonear(0));
+
+    // Proposals are added by the primary relayer account, which holds the DAO roles
+    let network = &state.network;
+    let result = state
+        .relayer_pool
+        .send_primary(move |relayer| async move {
+            Contract(dao_id.clone())
+                .call_function(
+                    "add_proposal",
+                    json!({
+                        "proposal": {
+                            "description": description,
+                            "kind": kind,
+                        }
+                    }),
+                )
+                .transaction()
+                .gas(NearGas::from_tgas(50))
+                .deposit(proposal_bond)
+                .with_signer(relayer.account_id, relayer.signer)
+                .send_to(network)
+                .await
+        })
+        .await
+        .map_err(|e| format!("Failed to add proposal: {}", e))?;
+
+    record_platform_transaction(
+        &state.db_pool,
+        PlatformTransaction {
+            transaction_hash: &result.outcome().transaction_hash.to_string(),
+            signer_id: state.signer_id.as_str(),
+            source: FeeSource::RelayerProposal,
+            dao_id: Some(dao_id.as_str()),
+            on_behalf_of: None,
+            list_id: None,
+        },
+    )
+    .await;
+
+    result
+        .into_result()
+        .map_err(|e| format!("Failed to add proposal: {}", e))?;
+
+    match find_generated_proposal(state, dao_id, description).await {
+        Ok(id) => Ok(id),
+        Err(e) => {
+            log::warn!(
+                "Failed to resolve the ID of a proposal added to {}: {}",
+                dao_id,
+                e
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Submit the list for bulk payments, then add the proposal to the DAO
+///
+/// Returns the created proposal ID and bulk payment list ID.
+async fn create_scheduled_proposal(
+    state: &AppState,
+    schedule: &PaymentSchedule,
+    scheduled_for: DateTime<Utc>,
+) -> Result<(Option<u64>, Option<String>), String> {
+    let dao_id: AccountId = schedule
+        .dao_id
+        .parse()
+        .map_err(|e| format!("Invalid DAO ID: {}", e))?;
+    let is_bulk = schedule.payments.0.len() > 1;
+
+    check_credits(state, &schedule.dao_id, is_bulk).await?;
+
+    let token_contract: AccountId = metadata_contract(&schedule.token_id)
+        .parse()
+        .map_err(|e| format!("Invalid token ID: {}", e))?;
+    let metadata = fetch_ft_metadata(&state.cache, &state.network, &token_contract)
+        .await
+        .map_err(|e| format!("Failed to fetch token metadata: {}", e))?;
+
+    let proposal = build_proposal(
+        schedule,
+        scheduled_for,
+        state.bulk_payment_contract_id.as_str(),
+        &metadata.symbol,
+        metadata.decimals,
+    )?;
+
+    if let Some(list_id) = &proposal.list_id {
+        // The list of this occurrence may be left on the contract by an earlier attempt;
+        // a list that is stored, or was paid and closed, can't be submitted again
+        for method in ["view_list", "view_list_receipt"] {
+            let existing = Contract(state.bulk_payment_contract_id.clone())
+                .call_function(method, json!({ "list_id": list_id }))
+                .read_only::<Value>()
+                .fetch_from(&state.network)
+                .await;
+            if existing.is_ok() {
+                return Err(format!(
+                    "An identical payment list ({}) already exists on the bulk payment contract",
+                    list_id
+                ));
+            }
+        }
+
+        let salt = occurrence_salt(schedule.id, scheduled_for);
+        submit_list_to_contract(
+            state,
+            list_id,
+            &schedule.dao_id,
+            &schedule.dao_id,
+            list_token_id(&schedule.token_id),
+            &schedule.payments.0,
+            Some(&salt),
+        )
+        .await?;
+    }
+
+    let proposal_id =
+        match add_relayer_proposal(state, &dao_id, &proposal.description, &proposal.kind).await {
+            Ok(proposal_id) => proposal_id,
+            Err(e) => {
+                if let Some(list_id) = &proposal.list_id
+                    && let Err(cancel_error) = cancel_submitted_list(state, list_id).await
+                {
+                    log::warn!(
+                        "List {} of payment schedule {} was left pending: {}",
+                        list_id,
+                        schedule.id,
+                        cancel_error
+                    );
+                }
+                return Err(e);
+            }
+        };
+    decrement_gas_covered_transactions(&state.db_pool, &schedule.dao_id).await;
+
+    if let Some(list_id) = &proposal.list_id {
+        decrement_batch_payment_credits(&state.db_pool, &schedule.dao_id).await;
+        add_pending_list(list_id.clone()).await;
+    }
+
+    Ok((proposal_id, proposal.list_id))
+}
+
+/// Claim an occurrence so it is proposed at most once, even with several workers
+async fn claim_run(
+    state: &AppState,
+    schedule_id: i64,
+    scheduled_for: DateTime<Utc>,
+) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO payment_schedule_runs (schedule_id, scheduled_for, status)
+        VALUES ($1, $2, 'pending')
+        ON CONFLICT (schedule_id, scheduled_for) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(schedule_id)
+    .bind(scheduled_for)
+    .fetch_optional(&state.db_pool)
+    .await
+}
+
+/// Move the schedule to its next occurrence after `now`
+///
+/// Occurrences missed while the service was down are skipped rather than proposed in bulk.
+async fn advance_schedule(
+    state: &AppState,
+    schedule: &PaymentSchedule,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let next_run_at = Cadence::parse(&schedule.cadence)
+        .and_then(|cadence| cadence.next_after(schedule.start_at, schedule.end_at, now));
+
+    sqlx::query(
+        r#"
+        UPDATE payment_schedules
+        SET next_run_at = $2,
+            is_active = is_active AND $2 IS NOT NULL,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(schedule.id)
+    .bind(next_run_at)
+    .execute(&state.db_pool)
+    .await?;
+
+    Ok(())
+}
+
+async fn process_schedule(
+    state: &AppState,
+    schedule: &PaymentSchedule,
+    now: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let Some(scheduled_for) = schedule.next_run_at else {
+        return Ok(());
+    };
+
+    if let Some(run_id) = claim_run(state, schedule.id, scheduled_for).await? {
+        log::info!(
+            "Creating proposal for payment schedule {} ({}) due at {}",
+            schedule.id,
+            schedule.dao_id,
+            scheduled_for
+        );
+
+        match create_scheduled_proposal(state, schedule, scheduled_for).await {
+            Ok((proposal_id, list_id)) => {
+                sqlx::query(
+                    r#"
+                    UPDATE payment_schedule_runs
+                    SET status = 'created', proposal_id = $2, list_id = $3
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(run_id)
+                .bind(proposal_id.map(|id| id as i64))
+                .bind(list_id)
+                .execute(&state.db_pool)
+                .await?;
+
+                log::info!(
+                    "Scheduled payment proposal created for {} (schedule {}, proposal {:?})",
+                    schedule.dao_id,
+                    schedule.id,
+                    proposal_id
+                );
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to create proposal for payment schedule {}: {}",
+                    schedule.id,
+                    e
+                );
+                sqlx::query(
+                    r#"
+                    UPDATE payment_schedule_runs
+                    SET status = 'failed', error = $2
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(run_id)
+                .bind(&e)
+                .execute(&state.db_pool)
+                .await?;
+
+                let message = format!(
+                    "Scheduled payment failed for {} (schedule {}): {}",
+                    schedule.dao_id, schedule.id, e
+                );
+                if let Err(e) = state.telegram_client.send_message(&message).await {
+                    log::warn!("Failed to send Telegram notification: {}", e);
+                }
+            }
+        }
+    }
+
+    advance_schedule(state, schedule, now).await
+}
+
+/// Create proposals for every schedule that is due
+///
+/// Returns the number of schedules processed.
+pub async fn process_due_schedules(
+    state: &Arc<AppState>,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let now = Utc::now();
+
+    let due = sqlx::query_as::<_, PaymentSchedule>(
+        r#"
+        SELECT id, dao_id, created_by, title, notes, token_id, payments, cadence,
+               start_at, end_at, next_run_at, is_active, created_at, updated_at
+        FROM payment_schedules
+        WHERE is_active = true
+          AND next_run_at <= $1
+        ORDER BY next_run_at
+        "#,
+    )
+    .bind(now)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    for schedule in &due {
+        process_schedule(state, schedule, now).await?;
+    }
+
+    Ok(due.len())
+}
+
+/// Run the payment schedule worker every minute
+pub async fn run_payment_schedule_service(state: Arc<AppState>) {
+    log::info!("Starting payment schedule worker (60 second poll interval)");
+
+    // Wait a bit before first run to let server fully start
+    tokio::time::sleep(std::time::Duration::from_secs(20)).await;
+
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+
+    loop {
+        interval.tick().await;
+
+        match process_due_schedules(&state).await {
+            Ok(processed) if processed > 0 => {
+                log::info!("Processed {} due payment schedule(s)", processed);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                log::error!("Payment schedule worker error: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::bulkpayment::submit::PaymentInput;
+    use sqlx::types::Json;
+
+    fn schedule(token_id: &str, payments: Vec<(&str, &str)>) -> PaymentSchedule {
+        let now = Utc::now();
+        PaymentSchedule {
+            id: 42,
+            dao_id: "testdao.sputnik-dao.near".to_string(),
+            created_by: "alice.near".to_string(),
+            title: "Monthly salaries".to_string(),
+            notes: Some("March".to_string()),
+            token_id: token_id.to_string(),
+            payments: Json(
+                payments
+                    .into_iter()
+                    .map(|(recipient, amount)| PaymentInput {
+                        recipient: recipient.to_string(),
+                        amount: amount.to_string(),
+                        token_id: None,
+                    })
+                    .collect(),
+            ),
+            cadence: "monthly".to_string(),
+            start_at: now,
+            end_at: None,
+            next_run_at: Some(now),
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Occurrence on the given day of March 2026
+    fn occurrence(day: u32) -> DateTime<Utc> {
+        chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 3, day, 0, 0, 0).unwrap()
+    }
+
+    fn decoded_args(kind: &Value, receiver: &str) -> Value {
+        let call = &kind["FunctionCall"];
+        assert_eq!(call["receiver_id"], receiver);
+        let args = call["actions"][0]["args"].as_str().unwrap();
+        let bytes =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, args).unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_single_native_payment_is_transfer() {
+        let schedule = schedule("native", vec![("bob.near", "1000")]);
+        let proposal =
+            build_proposal(&schedule, occurrence(1), "bulkpayment.near", "NEAR", 24).unwrap();
+
+        assert_eq!(proposal.kind["Transfer"]["token_id"], "");
+        assert_eq!(proposal.kind["Transfer"]["receiver_id"], "bob.near");
+        assert_eq!(proposal.kind["Transfer"]["amount"], "1000");
+        assert!(proposal.list_id.is_none());
+        assert_eq!(
+            proposal.description,
+            "* Title: Monthly salaries <br>* Notes: March <br>* Schedule Id: 42"
+        );
+    }
+
+    #[test]
+    fn test_single_intents_payment_withdraws() {
+        let schedule = schedule("nep141:usdc.omft.near", vec![("bob.near", "5")]);
+        let proposal =
+            build_proposal(&schedule, occurrence(1), "bulkpayment.near", "USDC", 6).unwrap();
+
+        let args = decoded_args(&proposal.kind, "intents.near");
+        assert_eq!(args["token"], "usdc.omft.near");
+        assert_eq!(args["receiver_id"], "bob.near");
+        assert_eq!(args["amount"], "5");
+    }
+
+    #[test]
+    fn test_bulk_ft_payment() {
+        let schedule = schedule(
+            "usdt.tether-token.near",
+            vec![("bob.near", "1500000"), ("alice.near", "2500000")],
+        );
+        let proposal =
+            build_proposal(&schedule, occurrence(1), "bulkpayment.near", "USDT", 6).unwrap();
+
+        let list_id = proposal.list_id.clone().unwrap();
+        assert_eq!(
+            list_id,
+            compute_salted_list_hash(
+                "testdao.sputnik-dao.near",
+                "usdt.tether-token.near",
+                &schedule.payments.0,
+                Some("schedule:42:2026-03-01T00:00:00+00:00"),
+            )
+        );
+
+        // Every occurrence pays into its own list
+        let next = build_proposal(&schedule, occurrence(2), "bulkpayment.near", "USDT", 6).unwrap();
+        assert_ne!(next.list_id.unwrap(), list_id);
+
+        let args = decoded_args(&proposal.kind, "usdt.tether-token.near");
+        assert_eq!(args["receiver_id"], "bulkpayment.near");
+        assert_eq!(args["amount"], "4000000");
+        assert_eq!(args["msg"], list_id);
+
+        assert!(proposal.description.contains("* Amount: 4 <br>"));
+        assert!(proposal.description.contains("* Recipients: 2 <br>"));
+        assert!(
+            proposal
+                .description
+                .contains(&format!("* List Id: {} <br>", list_id))
+        );
+    }
+
+    #[test]
+    fn test_bulk_native_payment_attaches_total() {
+        let schedule = schedule("native", vec![("bob.near", "1"), ("carol.near", "2")]);
+        let proposal =
+            build_proposal(&schedule, occurrence(1), "bulkpayment.near", "NEAR", 24).unwrap();
+
+        let call = &proposal.kind["FunctionCall"];
+        assert_eq!(call["receiver_id"], "bulkpayment.near");
+        assert_eq!(call["actions"][0]["method_name"], "approve_list");
+        assert_eq!(call["actions"][0]["deposit"], "3");
+    }
+}
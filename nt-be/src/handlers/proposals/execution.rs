@@ -0,0 +1,582 @@
+//! Proposal execution tracing on our own archival RPC
+//!
+//! The DAO records the block height of the last actions on a proposal (`last_actions_log`).
+//! The logged block where the proposal left `InProgress` is found by reading the proposal at
+//! those heights, and the `act_proposal` receipt executed there, whichever action it took, is
+//! resolved to its transaction. The receipt tree below it is walked to collect every transfer
+//! the execution made. Traces are stored in `proposal_executions`, so each proposal is traced once.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use near_api::AccountId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::types::Json as SqlJson;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use super::scraper::{ProposalStatus, fetch_proposal, fetch_proposal_at_block};
+use crate::AppState;
+use crate::handlers::balance_changes::block_info::{get_block_data, get_block_timestamp};
+use crate::handlers::balance_changes::transfer_hints::tx_resolver::{
+    fetch_transaction_receipts, resolve_receipt_to_transaction,
+};
+
+/// Blocks before a logged action searched for its receipt (receipts can be delayed)
+const RECEIPT_SEARCH_BLOCKS: u64 = 3;
+
+/// A transfer made by a proposal execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionTransfer {
+    /// "near", "nep141" or "nep245"
+    pub standard: String,
+    /// Token contract, none for NEAR
+    pub contract_id: Option<String>,
+    /// "near", the FT contract or the multi-token ID
+    pub token_id: String,
+    pub sender_id: String,
+    pub receiver_id: String,
+    pub amount: String,
+    /// Receipt the transfer happened in
+    pub receipt_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalExecution {
+    pub dao_id: String,
+    pub proposal_id: i64,
+    /// Finalizing action, e.g. "VoteApprove"
+    pub action: String,
+    pub transaction_hash: String,
+    pub signer_id: String,
+    /// The `act_proposal` receipt
+    pub receipt_id: String,
+    pub block_height: i64,
+    /// Block timestamp in nanoseconds
+    pub block_timestamp: i64,
+    pub transfers: SqlJson<Vec<ExecutionTransfer>>,
+}
+
+/// Decoded JSON args of a FunctionCall action (RPC JSON form)
+fn function_call_args(action: &Value, method: &str) -> Option<Value> {
+    let call = action.get("FunctionCall")?;
+    if call["method_name"].as_str() != Some(method) {
+        return None;
+    }
+    let bytes = BASE64.decode(call["args"].as_str()?).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Action taken by a receipt (RPC JSON form) that is an `act_proposal` call on a proposal
+pub fn act_proposal_action(receipt: &Value, dao_id: &str, proposal_id: u64) -> Option<String> {
+    if receipt["receiver_id"].as_str() != Some(dao_id) {
+        return None;
+    }
+    receipt["receipt"]["Action"]["actions"]
+        .as_array()?
+        .iter()
+        .filter_map(|a| function_call_args(a, "act_proposal"))
+        .find(|args| args["id"].as_u64() == Some(proposal_id))
+        .and_then(|args| args["action"].as_str().map(String::from))
+}
+
+/// Transfers made by one receipt: attached NEAR and NEP-141/NEP-245 transfer events
+pub fn receipt_transfers(receipt: Option<&Value>, outcome: &Value) -> Vec<ExecutionTransfer> {
+    let receipt_id = outcome["id"].as_str().unwrap_or_default().to_string();
+    let mut transfers = Vec::new();
+
+    // Gas refunds come from "system" and aren't part of the execution
+    if let Some(receipt) = receipt.filter(|r| r["predecessor_id"].as_str() != Some("system")) {
+        let actions = receipt["receipt"]["Action"]["actions"].as_array();
+        for action in actions.into_iter().flatten() {
+            let deposit = action
+                .get("Transfer")
+                .or_else(|| action.get("FunctionCall"))
+                .and_then(|a| a["deposit"].as_str())
+                .unwrap_or("0");
+            // 1 yoctoNEAR is the deposit required by token transfer calls, not a payment
+            if deposit.parse::<u128>().unwrap_or(0) > 1 {
+                transfers.push(ExecutionTransfer {
+                    standard: "near".to_string(),
+                    contract_id: None,
+                    token_id: "near".to_string(),
+                    sender_id: receipt["predecessor_id"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    receiver_id: receipt["receiver_id"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    amount: deposit.to_string(),
+                    receipt_id: receipt_id.clone(),
+                });
+            }
+        }
+    }
+
+    let executor_id = outcome["outcome"]["executor_id"]
+        .as_str()
+        .unwrap_or_default();
+    let logs = outcome["outcome"]["logs"].as_array();
+    for log in logs.into_iter().flatten().filter_map(|l| l.as_str()) {
+        let Some(event) = log
+            .strip_prefix("EVENT_JSON:")
+            .and_then(|json| serde_json::from_str::<Value>(json).ok())
+        else {
+            continue;
+        };
+        let data = event["data"].as_array().into_iter().flatten();
+
+        match (event["standard"].as_str(), event["event"].as_str()) {
+            (Some("nep141"), Some("ft_transfer")) => {
+                for item in data {
+                    transfers.push(ExecutionTransfer {
+                        standard: "nep141".to_string(),
+                        contract_id: Some(executor_id.to_string()),
+                        token_id: executor_id.to_string(),
+                        sender_id: item["old_owner_id"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        receiver_id: item["new_owner_id"]
+                            .as_str()
+                            .unwrap_or_default()
+                            .to_string(),
+                        amount: item["amount"].as_str().unwrap_or_default().to_string(),
+                        receipt_id: receipt_id.clone(),
+                    });
+                }
+            }
+            (Some("nep245"), Some("mt_transfer")) => {
+                for item in data {
+                    let token_ids = item["token_ids"].as_array().into_iter().flatten();
+                    let amounts = item["amounts"].as_array().into_iter().flatten();
+                    for (token_id, amount) in token_ids.zip(amounts) {
+                        transfers.push(ExecutionTransfer {
+                            standard: "nep245".to_string(),
+                            contract_id: Some(executor_id.to_string()),
+                            token_id: token_id.as_str().unwrap_or_default().to_string(),
+                            sender_id: item["old_owner_id"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                            receiver_id: item["new_owner_id"]
+                                .as_str()
+                                .unwrap_or_default()
+                                .to_string(),
+                            amount: amount.as_str().unwrap_or_default().to_string(),
+                            receipt_id: receipt_id.clone(),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    transfers
+}
+
+/// Transfers made by the receipts a receipt caused, in execution order
+///
+/// `transaction` is an `EXPERIMENTAL_tx_status` result in RPC JSON form. Failed receipts are
+/// skipped: their transfers were reverted.
+pub fn trace_transfers(
+    transaction: &Value,
+    root_receipt_id: &str,
+) -> Result<Vec<ExecutionTransfer>, String> {
+    let receipts: HashMap<&str, &Value> = transaction["receipts"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|r| Some((r["receipt_id"].as_str()?, r)))
+        .collect();
+    let outcomes: HashMap<&str, &Value> = transaction["receipts_outcome"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|o| Some((o["id"].as_str()?, o)))
+        .collect();
+
+    let root = outcomes
+        .get(root_receipt_id)
+        .ok_or_else(|| format!("Receipt {} is not part of the transaction", root_receipt_id))?;
+
+    let children = |outcome: &Value| -> Vec<String> {
+        outcome["outcome"]["receipt_ids"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|id| id.as_str().map(String::from))
+            .collect()
+    };
+
+    let mut transfers = Vec::new();
+    let mut queue: VecDeque<String> = children(root).into();
+    while let Some(receipt_id) = queue.pop_front() {
+        let Some(outcome) = outcomes.get(receipt_id.as_str()) else {
+            continue;
+        };
+        if outcome["outcome"]["status"].get("Failure").is_none() {
+            transfers.extend(receipt_transfers(
+                receipts.get(receipt_id.as_str()).copied(),
+                outcome,
+            ));
+        }
+        queue.extend(children(outcome));
+    }
+
+    Ok(transfers)
+}
+
+/// Stored trace of a proposal execution
+pub async fn load_execution(
+    pool: &sqlx::PgPool,
+    dao_id: &str,
+    proposal_id: u64,
+) -> Result<Option<ProposalExecution>, sqlx::Error> {
+    sqlx::query_as::<_, ProposalExecution>(
+        r#"
+        SELECT dao_id, proposal_id, action, transaction_hash, signer_id, receipt_id,
+               block_height, block_timestamp, transfers
+        FROM proposal_executions
+        WHERE dao_id = $1 AND proposal_id = $2
+        "#,
+    )
+    .bind(dao_id)
+    .bind(proposal_id as i64)
+    .fetch_optional(pool)
+    .await
+}
+
+async fn store_execution(
+    pool: &sqlx::PgPool,
+    execution: &ProposalExecution,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO proposal_executions
+            (dao_id, proposal_id, action, transaction_hash, signer_id, receipt_id,
+             block_height, block_timestamp, transfers)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(&execution.dao_id)
+    .bind(execution.proposal_id)
+    .bind(&execution.action)
+    .bind(&execution.transaction_hash)
+    .bind(&execution.signer_id)
+    .bind(&execution.receipt_id)
+    .bind(execution.block_height)
+    .bind(execution.block_timestamp)
+    .bind(&execution.transfers)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Whether the action logged at a block moved the proposal out of `InProgress`
+async fn moved_status_at(
+    state: &AppState,
+    dao_id: &AccountId,
+    proposal_id: u64,
+    logged_height: u64,
+) -> Result<bool, String> {
+    let after =
+        fetch_proposal_at_block(&state.archival_network, dao_id, proposal_id, logged_height)
+            .await
+            .map_err(|e| format!("Failed to get proposal at block {}: {}", logged_height, e))?;
+    if after.status == ProposalStatus::InProgress {
+        return Ok(false);
+    }
+
+    // The proposal doesn't exist before the block that added it
+    match fetch_proposal_at_block(
+        &state.archival_network,
+        dao_id,
+        proposal_id,
+        logged_height - 1,
+    )
+    .await
+    {
+        Ok(before) => Ok(before.status == ProposalStatus::InProgress),
+        Err(e) => {
+            log::debug!(
+                "Proposal {} of {} not readable at block {}: {}",
+                proposal_id,
+                dao_id,
+                logged_height - 1,
+                e
+            );
+            Ok(false)
+        }
+    }
+}
+
+/// `act_proposal` receipt included at or shortly before a logged block, with its action and
+/// block
+async fn find_act_proposal_receipt(
+    state: &AppState,
+    dao_id: &AccountId,
+    proposal_id: u64,
+    logged_height: u64,
+) -> Result<Option<(Value, String, u64)>, String> {
+    for height in (logged_height.saturating_sub(RECEIPT_SEARCH_BLOCKS)..=logged_height).rev() {
+        let block = get_block_data(&state.archival_network, dao_id.as_str(), height)
+            .await
+            .map_err(|e| format!("Failed to get block {}: {}", height, e))?;
+
+        for receipt in &block.receipts {
+            let receipt = serde_json::to_value(receipt).map_err(|e| e.to_string())?;
+            if let Some(action) = act_proposal_action(&receipt, dao_id.as_str(), proposal_id) {
+                return Ok(Some((receipt, action, height)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Trace the execution of a finalized proposal, or load its stored trace
+pub async fn trace_execution(
+    state: &AppState,
+    dao_id: &AccountId,
+    proposal_id: u64,
+) -> Result<ProposalExecution, (StatusCode, String)> {
+    let db_error = |e: sqlx::Error| {
+        log::error!("Failed to access proposal executions: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error".to_string(),
+        )
+    };
+
+    if let Some(execution) = load_execution(&state.db_pool, dao_id.as_str(), proposal_id)
+        .await
+        .map_err(db_error)?
+    {
+        return Ok(execution);
+    }
+
+    let proposal = fetch_proposal(&state.network, dao_id, proposal_id)
+        .await
+        .map_err(|e| {
+            log::warn!(
+                "Failed to fetch proposal {} of {}: {}",
+                proposal_id,
+                dao_id,
+                e
+            );
+            (
+                StatusCode::NOT_FOUND,
+                format!("Proposal {} not found", proposal_id),
+            )
+        })?;
+
+    if matches!(
+        proposal.status,
+        ProposalStatus::InProgress | ProposalStatus::Moved
+    ) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Proposal {} has not been finalized", proposal_id),
+        ));
+    }
+
+    let trace_error = |e: String| {
+        log::warn!(
+            "Failed to trace execution of proposal {} of {}: {}",
+            proposal_id,
+            dao_id,
+            e
+        );
+        (
+            StatusCode::BAD_GATEWAY,
+            format!("Failed to trace execution of proposal {}", proposal_id),
+        )
+    };
+
+    // The finalizing action is the logged one that moved the status, usually the latest
+    let mut logged_heights: Vec<u64> = proposal
+        .last_actions_log
+        .iter()
+        .flatten()
+        .map(|log| log.block_height.0)
+        .collect();
+    logged_heights.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut finalized_height = None;
+    for logged_height in logged_heights {
+        if moved_status_at(state, dao_id, proposal_id, logged_height)
+            .await
+            .map_err(trace_error)?
+        {
+            finalized_height = Some(logged_height);
+            break;
+        }
+    }
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            format!("No finalizing receipt found for proposal {}", proposal_id),
+        )
+    };
+    let execution_height = finalized_height.ok_or_else(not_found)?;
+    // The receipt executed in the logged block, possibly after being included earlier
+    let (receipt, action, inclusion_height) =
+        find_act_proposal_receipt(state, dao_id, proposal_id, execution_height)
+            .await
+            .map_err(trace_error)?
+            .ok_or_else(not_found)?;
+    let receipt_id = receipt["receipt_id"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let transaction =
+        resolve_receipt_to_transaction(&state.archival_network, &receipt_id, inclusion_height)
+            .await
+            .map_err(|e| trace_error(e.to_string()))?;
+    let tx = fetch_transaction_receipts(
+        &state.archival_network,
+        &transaction.transaction_hash,
+        &transaction.signer_id,
+    )
+    .await
+    .map_err(|e| trace_error(e.to_string()))?;
+    let transfers = trace_transfers(&tx, &receipt_id).map_err(trace_error)?;
+
+    let block_timestamp = get_block_timestamp(&state.archival_network, execution_height, None)
+        .await
+        .map_err(|e| e.to_string())
+        .map_err(trace_error)?;
+
+    let execution = ProposalExecution {
+        dao_id: dao_id.to_string(),
+        proposal_id: proposal_id as i64,
+        action,
+        transaction_hash: transaction.transaction_hash,
+        signer_id: transaction.signer_id,
+        receipt_id,
+        block_height: execution_height as i64,
+        block_timestamp,
+        transfers: SqlJson(transfers),
+    };
+
+    store_execution(&state.db_pool, &execution)
+        .await
+        .map_err(db_error)?;
+
+    Ok(execution)
+}
+
+/// Get the execution of a proposal: its transaction and the transfers it made
+pub async fn get_proposal_execution(
+    State(state): State<Arc<AppState>>,
+    Path((dao_id, proposal_id)): Path<(AccountId, u64)>,
+) -> Result<Json<ProposalExecution>, (StatusCode, String)> {
+    trace_execution(&state, &dao_id, proposal_id)
+        .await
+        .map(Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAO: &str = "testing-treasury.sputnik-dao.near";
+    const USDC: &str = "17208628f84f5d6ad33f0da3bbbeb27ffcb398eac501a31bd6ad2011e36133a1";
+    const ACT_PROPOSAL_RECEIPT: &str = "E5oQoX9cj287AmzC4JMFg2fdCUysw4LSA7j6T2jjYxSF";
+
+    /// `EXPERIMENTAL_tx_status` of an approved Transfer proposal paying 100 USDC
+    fn transfer_proposal_tx() -> Value {
+        serde_json::from_str(include_str!(
+            "../../../tests/test_data/proposal_execution/transfer_proposal_tx.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_act_proposal_action() {
+        let tx = transfer_proposal_tx();
+        let receipts = tx["receipts"].as_array().unwrap();
+
+        assert_eq!(
+            act_proposal_action(&receipts[0], DAO, 42).as_deref(),
+            Some("VoteApprove")
+        );
+        assert_eq!(act_proposal_action(&receipts[0], DAO, 41), None);
+        assert_eq!(
+            act_proposal_action(&receipts[0], "other.sputnik-dao.near", 42),
+            None
+        );
+        // on_proposal_callback on the DAO isn't the act_proposal call
+        assert_eq!(act_proposal_action(&receipts[2], DAO, 42), None);
+    }
+
+    #[test]
+    fn test_trace_transfers() {
+        let transfers = trace_transfers(&transfer_proposal_tx(), ACT_PROPOSAL_RECEIPT).unwrap();
+
+        // The USDC payment and the proposal bond returned to the proposer; the 1 yoctoNEAR
+        // ft_transfer deposit and gas refunds aren't transfers
+        assert_eq!(
+            transfers,
+            vec![
+                ExecutionTransfer {
+                    standard: "nep141".to_string(),
+                    contract_id: Some(USDC.to_string()),
+                    token_id: USDC.to_string(),
+                    sender_id: DAO.to_string(),
+                    receiver_id: "bob.near".to_string(),
+                    amount: "100000000".to_string(),
+                    receipt_id: "2695WoSUbpJb2y1iWMC9t5n3oxkxwqv7jn9zyTinp6fa".to_string(),
+                },
+                ExecutionTransfer {
+                    standard: "near".to_string(),
+                    contract_id: None,
+                    token_id: "near".to_string(),
+                    sender_id: DAO.to_string(),
+                    receiver_id: "carol.near".to_string(),
+                    amount: "100000000000000000000000".to_string(),
+                    receipt_id: "XjG5GcdgAYN8FRko8uVfpnDcrYSPzJuP2CenAhAZaiCT".to_string(),
+                },
+            ]
+        );
+
+        assert!(trace_transfers(&transfer_proposal_tx(), "unknown").is_err());
+    }
+
+    #[test]
+    fn test_trace_transfers_skips_failed_receipts() {
+        let mut tx = transfer_proposal_tx();
+        tx["receipts_outcome"][1]["outcome"]["status"] =
+            serde_json::json!({ "Failure": { "ActionError": { "index": 0 } } });
+
+        let transfers = trace_transfers(&tx, ACT_PROPOSAL_RECEIPT).unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].receiver_id, "carol.near");
+    }
+
+    #[test]
+    fn test_trace_transfers_mt_transfer() {
+        let mut tx = transfer_proposal_tx();
+        tx["receipts_outcome"][1]["outcome"]["executor_id"] = "intents.near".into();
+        tx["receipts_outcome"][1]["outcome"]["logs"] = serde_json::json!([
+            r#"EVENT_JSON:{"standard":"nep245","version":"1.0.0","event":"mt_transfer","data":[{"old_owner_id":"testing-treasury.sputnik-dao.near","new_owner_id":"bob.near","token_ids":["nep141:btc.omft.near"],"amounts":["1500"]}]}"#
+        ]);
+
+        let transfers = trace_transfers(&tx, ACT_PROPOSAL_RECEIPT).unwrap();
+        assert_eq!(transfers[0].standard, "nep245");
+        assert_eq!(transfers[0].contract_id.as_deref(), Some("intents.near"));
+        assert_eq!(transfers[0].token_id, "nep141:btc.omft.near");
+        assert_eq!(transfers[0].amount, "1500");
+    }
+}
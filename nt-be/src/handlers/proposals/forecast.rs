@@ -0,0 +1,458 @@
+//! Expiry and quorum forecasting for DAO proposals
+//!
+//! Mirrors the Sputnik DAO vote counting rules to tell approvers how many more
+//! votes a proposal needs in each role, when it expires, and whether it can
+//! still pass with the voters that are left.
+
+use near_api::types::json::U64;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::handlers::proposals::scraper::{
+    CountsVersions, Policy, Proposal, ProposalStatus, get_current_time_nanos, get_expiration_period,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleVoteForecast {
+    pub role: String,
+    pub weight_kind: String,
+    /// Approvals needed for the proposal to pass through this role.
+    /// None when the role has no fixed membership (Everyone, token holders).
+    pub threshold: Option<u128>,
+    pub approvals: u128,
+    pub rejections: u128,
+    /// Approvals still missing to reach the threshold
+    pub votes_required: Option<u128>,
+    /// Members of the role that haven't voted yet
+    pub remaining_voters: Option<u128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalForecast {
+    /// Expiry timestamp in nanoseconds
+    pub expires_at: U64,
+    pub is_expired: bool,
+    /// False once no role can reach its threshold with the remaining voters
+    pub is_passable: bool,
+    pub roles: Vec<RoleVoteForecast>,
+    /// Whether the authenticated user's vote is still needed (None if not authenticated)
+    pub my_vote_required: Option<bool>,
+}
+
+/// Proposal kind label used by Sputnik DAO for permissions and vote policies
+pub fn proposal_kind_label(kind: &Value) -> &'static str {
+    let key = match kind {
+        Value::String(s) => s.as_str(),
+        Value::Object(obj) => obj.keys().next().map(|k| k.as_str()).unwrap_or(""),
+        _ => "",
+    };
+
+    match key {
+        "ChangeConfig" => "config",
+        "ChangePolicy" => "policy",
+        "AddMemberToRole" => "add_member_to_role",
+        "RemoveMemberFromRole" => "remove_member_from_role",
+        "FunctionCall" => "call",
+        "UpgradeSelf" => "upgrade_self",
+        "UpgradeRemote" => "upgrade_remote",
+        "Transfer" => "transfer",
+        "SetStakingContract" => "set_vote_token",
+        "AddBounty" => "add_bounty",
+        "BountyDone" => "bounty_done",
+        "Vote" => "vote",
+        "FactoryInfoUpdate" => "factory_info_update",
+        "ChangePolicyAddOrUpdateRole" => "policy_add_or_update_role",
+        "ChangePolicyRemoveRole" => "policy_remove_role",
+        "ChangePolicyUpdateDefaultVotePolicy" => "policy_update_default_vote_policy",
+        "ChangePolicyUpdateParameters" => "policy_update_parameters",
+        _ => "",
+    }
+}
+
+/// Check whether a role's permissions allow any of the given actions on proposals of this kind
+fn has_permission(role: &Value, label: &str, actions: &[&str]) -> bool {
+    role.get("permissions")
+        .and_then(|p| p.as_array())
+        .map(|permissions| {
+            permissions.iter().filter_map(|p| p.as_str()).any(|p| {
+                let (kind, action) = p.split_once(':').unwrap_or((p, ""));
+                (kind == "*" || kind == label) && (action == "*" || actions.contains(&action))
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Check whether a role's permissions allow approving proposals of this kind
+fn can_vote_approve(role: &Value, label: &str) -> bool {
+    has_permission(role, label, &["VoteApprove"])
+}
+
+/// Check whether an account belongs to a role that may approve or reject the proposal
+pub fn is_eligible_voter(proposal: &Proposal, policy: &Policy, account_id: &str) -> bool {
+    let label = proposal_kind_label(&proposal.kind);
+    policy.roles.iter().any(|role| {
+        has_permission(role, label, &["VoteApprove", "VoteReject"])
+            && group_members(role)
+                .map(|members| members.contains(&account_id))
+                .unwrap_or(false)
+    })
+}
+
+/// Check whether an account may add proposals of the given kind label
+pub fn can_add_proposal(policy: &Policy, label: &str, account_id: &str) -> bool {
+    policy.roles.iter().any(|role| {
+        let is_member = role.get("kind").and_then(|k| k.as_str()) == Some("Everyone")
+            || group_members(role)
+                .map(|members| members.contains(&account_id))
+                .unwrap_or(false);
+        is_member && has_permission(role, label, &["AddProposal"])
+    })
+}
+
+/// Check whether an account is a member of one of the policy's group roles
+pub fn is_policy_member(policy: &Policy, account_id: &str) -> bool {
+    policy.roles.iter().any(|role| {
+        group_members(role)
+            .map(|members| members.contains(&account_id))
+            .unwrap_or(false)
+    })
+}
+
+fn group_members(role: &Value) -> Option<Vec<&str>> {
+    role.get("kind")
+        .and_then(|k| k.get("Group"))
+        .and_then(|g| g.as_array())
+        .map(|g| g.iter().filter_map(|a| a.as_str()).collect())
+}
+
+fn parse_weight(value: Option<&Value>) -> Option<u128> {
+    match value? {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_u64().map(u128::from),
+        _ => None,
+    }
+}
+
+fn counts_to_u128(counts: &CountsVersions) -> u128 {
+    match counts {
+        CountsVersions::V1(v) => *v as u128,
+        CountsVersions::V2(v) => v.0,
+    }
+}
+
+/// Votes needed to pass through a role, following Sputnik's
+/// `max(quorum, threshold.to_weight(total_weight))`
+fn role_threshold(vote_policy: &Value, total_weight: u128) -> Option<u128> {
+    let quorum = parse_weight(vote_policy.get("quorum")).unwrap_or(0);
+    let threshold = match vote_policy.get("threshold")? {
+        Value::Array(ratio) if ratio.len() == 2 => {
+            let numerator = ratio[0].as_u64()? as u128;
+            let denominator = ratio[1].as_u64()? as u128;
+            if denominator == 0 {
+                return None;
+            }
+            std::cmp::min(numerator * total_weight / denominator + 1, total_weight)
+        }
+        weight => std::cmp::min(parse_weight(Some(weight))?, total_weight),
+    };
+    Some(std::cmp::max(quorum, threshold))
+}
+
+/// Compute the vote forecast of a proposal under the given policy
+pub fn forecast_proposal(
+    proposal: &Proposal,
+    policy: &Policy,
+    viewer: Option<&str>,
+) -> ProposalForecast {
+    forecast_proposal_at(proposal, policy, viewer, get_current_time_nanos().0)
+}
+
+pub fn forecast_proposal_at(
+    proposal: &Proposal,
+    policy: &Policy,
+    viewer: Option<&str>,
+    now_ns: u64,
+) -> ProposalForecast {
+    let expires_at = proposal
+        .submission_time
+        .0
+        .saturating_add(get_expiration_period(
+            policy.proposal_period.0,
+            Some(proposal),
+        ));
+    let is_expired = expires_at < now_ns;
+    let is_open = proposal.status == ProposalStatus::InProgress && !is_expired;
+
+    let label = proposal_kind_label(&proposal.kind);
+    let voted: HashSet<&str> = proposal.votes.keys().map(|k| k.as_str()).collect();
+
+    let mut roles = Vec::new();
+    let mut viewer_needed = false;
+
+    for role in policy.roles.iter().filter(|r| can_vote_approve(r, label)) {
+        let name = role
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or("")
+            .to_string();
+        let vote_policy = role
+            .get("vote_policy")
+            .and_then(|vp| vp.get(label))
+            .unwrap_or(&policy.default_vote_policy);
+        let weight_kind = vote_policy
+            .get("weight_kind")
+            .and_then(|w| w.as_str())
+            .unwrap_or("RoleWeight")
+            .to_string();
+
+        let counts = proposal.vote_counts.get(&name);
+        let approvals = counts.map(|c| counts_to_u128(&c[0])).unwrap_or(0);
+        let rejections = counts.map(|c| counts_to_u128(&c[1])).unwrap_or(0);
+
+        let members = group_members(role).filter(|_| weight_kind == "RoleWeight");
+        let threshold = members
+            .as_ref()
+            .and_then(|m| role_threshold(vote_policy, m.len() as u128));
+        let remaining_voters = members
+            .as_ref()
+            .map(|m| m.iter().filter(|a| !voted.contains(*a)).count() as u128);
+        let votes_required = threshold.map(|t| t.saturating_sub(approvals));
+
+        if let (Some(viewer), Some(members), Some(required)) = (viewer, &members, votes_required)
+            && required > 0
+            && members.contains(&viewer)
+            && !voted.contains(viewer)
+        {
+            viewer_needed = true;
+        }
+
+        roles.push(RoleVoteForecast {
+            role: name,
+            weight_kind,
+            threshold,
+            approvals,
+            rejections,
+            votes_required,
+            remaining_voters,
+        });
+    }
+
+    let is_passable = is_open
+        && (roles.is_empty()
+            || roles
+                .iter()
+                .any(|r| match (r.votes_required, r.remaining_voters) {
+                    (Some(required), Some(remaining)) => required <= remaining,
+                    // Unknown membership: can't rule it out
+                    _ => true,
+                }));
+
+    ProposalForecast {
+        expires_at: U64::from(expires_at),
+        is_expired,
+        is_passable,
+        roles,
+        my_vote_required: viewer.map(|_| is_open && viewer_needed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::proposals::scraper::Vote;
+    use near_api::types::json::U128;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    const HOUR_NS: u64 = 60 * 60 * 1_000_000_000;
+
+    fn policy() -> Policy {
+        Policy {
+            roles: vec![
+                json!({
+                    "name": "Requestor",
+                    "kind": { "Group": ["requestor.near"] },
+                    "permissions": ["transfer:AddProposal", "transfer:VoteRemove"],
+                    "vote_policy": {}
+                }),
+                json!({
+                    "name": "Approver",
+                    "kind": { "Group": ["a.near", "b.near", "c.near"] },
+                    "permissions": ["transfer:VoteApprove", "transfer:VoteReject"],
+                    "vote_policy": {
+                        "transfer": { "weight_kind": "RoleWeight", "quorum": "0", "threshold": "2" }
+                    }
+                }),
+                json!({
+                    "name": "Admin",
+                    "kind": { "Group": ["admin1.near", "admin2.near", "admin3.near", "admin4.near"] },
+                    "permissions": ["*:*"],
+                    "vote_policy": {}
+                }),
+            ],
+            default_vote_policy: json!({
+                "weight_kind": "RoleWeight",
+                "quorum": "0",
+                "threshold": [1, 2]
+            }),
+            proposal_bond: "0".to_string(),
+            proposal_period: U64::from(168 * HOUR_NS),
+            bounty_bond: "0".to_string(),
+            bounty_forgiveness_period: U64::from(0),
+        }
+    }
+
+    fn transfer_proposal(votes: &[(&str, Vote)], counts: &[(&str, u128, u128)]) -> Proposal {
+        Proposal {
+            id: 7,
+            proposer: "requestor.near".to_string(),
+            description: "Pay contributor".to_string(),
+            kind: json!({ "Transfer": { "token_id": "", "receiver_id": "bob.near", "amount": "1" } }),
+            status: ProposalStatus::InProgress,
+            vote_counts: counts
+                .iter()
+                .map(|(role, approve, reject)| {
+                    (
+                        role.to_string(),
+                        [
+                            CountsVersions::V2(U128::from(*approve)),
+                            CountsVersions::V2(U128::from(*reject)),
+                            CountsVersions::V2(U128::from(0)),
+                        ],
+                    )
+                })
+                .collect(),
+            votes: votes
+                .iter()
+                .map(|(account, vote)| (account.to_string(), vote.clone()))
+                .collect::<HashMap<_, _>>(),
+            submission_time: U64::from(0),
+            last_actions_log: None,
+        }
+    }
+
+    #[test]
+    fn test_kind_labels() {
+        assert_eq!(proposal_kind_label(&json!({ "Transfer": {} })), "transfer");
+        assert_eq!(proposal_kind_label(&json!({ "FunctionCall": {} })), "call");
+        assert_eq!(proposal_kind_label(&json!("Vote")), "vote");
+        assert_eq!(
+            proposal_kind_label(&json!({ "ChangePolicyUpdateParameters": {} })),
+            "policy_update_parameters"
+        );
+    }
+
+    #[test]
+    fn test_role_thresholds() {
+        let ratio = json!({ "quorum": "0", "threshold": [1, 2] });
+        assert_eq!(role_threshold(&ratio, 4), Some(3));
+        assert_eq!(role_threshold(&ratio, 1), Some(1));
+
+        let weight = json!({ "quorum": "0", "threshold": "5" });
+        assert_eq!(role_threshold(&weight, 3), Some(3));
+
+        let quorum = json!({ "quorum": "3", "threshold": "1" });
+        assert_eq!(role_threshold(&quorum, 5), Some(3));
+    }
+
+    #[test]
+    fn test_forecast_counts_required_votes_per_role() {
+        let proposal = transfer_proposal(&[("a.near", Vote::Approve)], &[("Approver", 1, 0)]);
+        let forecast = forecast_proposal_at(&proposal, &policy(), Some("b.near"), HOUR_NS);
+
+        // Requestor can't approve transfers, so only Approver and Admin are listed
+        assert_eq!(forecast.roles.len(), 2);
+
+        let approver = &forecast.roles[0];
+        assert_eq!(approver.role, "Approver");
+        assert_eq!(approver.threshold, Some(2));
+        assert_eq!(approver.votes_required, Some(1));
+        assert_eq!(approver.remaining_voters, Some(2));
+
+        let admin = &forecast.roles[1];
+        assert_eq!(admin.role, "Admin");
+        assert_eq!(admin.threshold, Some(3));
+        assert_eq!(admin.votes_required, Some(3));
+
+        assert_eq!(forecast.expires_at.0, 168 * HOUR_NS);
+        assert!(!forecast.is_expired);
+        assert!(forecast.is_passable);
+        assert_eq!(forecast.my_vote_required, Some(true));
+    }
+
+    #[test]
+    fn test_forecast_my_vote_not_required_after_voting_or_for_non_members() {
+        let proposal = transfer_proposal(&[("a.near", Vote::Approve)], &[("Approver", 1, 0)]);
+
+        let voted = forecast_proposal_at(&proposal, &policy(), Some("a.near"), HOUR_NS);
+        assert_eq!(voted.my_vote_required, Some(false));
+
+        let outsider = forecast_proposal_at(&proposal, &policy(), Some("bob.near"), HOUR_NS);
+        assert_eq!(outsider.my_vote_required, Some(false));
+
+        let anonymous = forecast_proposal_at(&proposal, &policy(), None, HOUR_NS);
+        assert_eq!(anonymous.my_vote_required, None);
+    }
+
+    #[test]
+    fn test_forecast_not_passable_when_voters_exhausted() {
+        // Two approvers rejected, admins all rejected: nobody left to reach any threshold
+        let proposal = transfer_proposal(
+            &[
+                ("a.near", Vote::Reject),
+                ("b.near", Vote::Reject),
+                ("admin1.near", Vote::Reject),
+                ("admin2.near", Vote::Reject),
+            ],
+            &[("Approver", 0, 2), ("Admin", 0, 2)],
+        );
+        let forecast = forecast_proposal_at(&proposal, &policy(), None, HOUR_NS);
+
+        assert_eq!(forecast.roles[0].remaining_voters, Some(1));
+        assert_eq!(forecast.roles[1].remaining_voters, Some(2));
+        assert!(!forecast.is_passable);
+    }
+
+    #[test]
+    fn test_is_eligible_voter() {
+        let proposal = transfer_proposal(&[], &[]);
+
+        assert!(is_eligible_voter(&proposal, &policy(), "a.near"));
+        assert!(is_eligible_voter(&proposal, &policy(), "admin1.near"));
+        // Requestors can only remove transfer proposals, not approve or reject them
+        assert!(!is_eligible_voter(&proposal, &policy(), "requestor.near"));
+        assert!(!is_eligible_voter(&proposal, &policy(), "bob.near"));
+    }
+
+    #[test]
+    fn test_can_add_proposal() {
+        let mut policy = policy();
+
+        assert!(can_add_proposal(&policy, "transfer", "requestor.near"));
+        assert!(!can_add_proposal(&policy, "call", "requestor.near"));
+        assert!(!can_add_proposal(&policy, "transfer", "a.near"));
+        assert!(can_add_proposal(&policy, "call", "admin1.near"));
+
+        policy.roles.push(json!({
+            "name": "all",
+            "kind": "Everyone",
+            "permissions": ["call:AddProposal"],
+            "vote_policy": {}
+        }));
+        assert!(can_add_proposal(&policy, "call", "anyone.near"));
+        assert!(!can_add_proposal(&policy, "transfer", "anyone.near"));
+    }
+
+    #[test]
+    fn test_forecast_expired() {
+        let proposal = transfer_proposal(&[], &[]);
+        let forecast = forecast_proposal_at(&proposal, &policy(), Some("a.near"), 200 * HOUR_NS);
+
+        assert!(forecast.is_expired);
+        assert!(!forecast.is_passable);
+        assert_eq!(forecast.my_vote_required, Some(false));
+    }
+}
@@ -7,12 +7,15 @@ use near_api::AccountId;
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::handlers::intents::swap_orders::{SwapOrder, load_swap_order, load_swap_orders};
 use crate::handlers::proposals::{
     filters::{ProposalFilters, SortBy},
+    forecast::{ProposalForecast, forecast_proposal},
     scraper::{Policy, Proposal, fetch_policy, fetch_proposal, fetch_proposals},
 };
 use crate::{
     AppState,
+    auth::middleware::OptionalAuthUser,
     utils::cache::{CacheKey, CacheTier},
 };
 
@@ -53,73 +56,110 @@ pub struct GetProposalsQuery {
     pub page_size: Option<usize>,
 }
 
+impl From<GetProposalsQuery> for ProposalFilters {
+    fn from(query: GetProposalsQuery) -> Self {
+        ProposalFilters {
+            statuses: query.statuses,
+            search: query.search,
+            search_not: query.search_not,
+            proposal_types: query.proposal_types,
+            sort_by: query.sort_by.and_then(|s| match s.as_str() {
+                "CreationTime" => Some(SortBy::CreationTime),
+                "ExpiryTime" => Some(SortBy::ExpiryTime),
+                _ => None,
+            }),
+            types: query.types,
+            types_not: query.types_not,
+            sort_direction: query.sort_direction,
+            created_date_from: query.created_date_from,
+            created_date_to: query.created_date_to,
+            created_date_from_not: query.created_date_from_not,
+            created_date_to_not: query.created_date_to_not,
+            amount_min: query.amount_min,
+            amount_max: query.amount_max,
+            amount_equal: query.amount_equal,
+            proposers: query.proposers,
+            proposers_not: query.proposers_not,
+            approvers: query.approvers,
+            approvers_not: query.approvers_not,
+            voter_votes: query.voter_votes,
+            source: query.source,
+            source_not: query.source_not,
+            recipients: query.recipients,
+            recipients_not: query.recipients_not,
+            token: query.token,
+            token_not: query.token_not,
+            stake_type: query.stake_type,
+            stake_type_not: query.stake_type_not,
+            validators: query.validators,
+            validators_not: query.validators_not,
+            page: query.page,
+            page_size: query.page_size,
+        }
+    }
+}
+
+/// Proposal as stored on chain, enriched with its vote forecast
+#[derive(serde::Serialize, Clone)]
+pub struct ProposalResponse {
+    #[serde(flatten)]
+    pub proposal: Proposal,
+    pub forecast: ProposalForecast,
+    /// Swap of an exchange proposal, with its refund or expiry
+    #[serde(rename = "swapOrder", skip_serializing_if = "Option::is_none")]
+    pub swap_order: Option<SwapOrder>,
+}
+
+impl ProposalResponse {
+    pub fn new(proposal: Proposal, policy: &Policy, viewer: Option<&str>) -> Self {
+        let forecast = forecast_proposal(&proposal, policy, viewer);
+        Self {
+            proposal,
+            forecast,
+            swap_order: None,
+        }
+    }
+}
+
 #[derive(serde::Serialize)]
 pub struct PaginatedProposals {
-    pub proposals: Vec<Proposal>,
+    pub proposals: Vec<ProposalResponse>,
     pub total: usize,
     pub page: usize,
     pub page_size: usize,
 }
 
-pub async fn get_proposals(
-    State(state): State<Arc<AppState>>,
-    Path(dao_id): Path<AccountId>,
-    Query(query): Query<GetProposalsQuery>,
-) -> Result<(StatusCode, Json<PaginatedProposals>), (StatusCode, String)> {
-    // Create cache key for proposals
-    let cache_key = CacheKey::new("dao-proposals").with(&dao_id).build();
+/// Fetch all proposals of a DAO together with its policy, through the short-term cache
+pub async fn fetch_dao_proposals_cached(
+    state: &AppState,
+    dao_id: &AccountId,
+) -> Result<(Vec<Proposal>, Policy), (StatusCode, String)> {
+    let cache_key = CacheKey::new("dao-proposals").with(dao_id).build();
 
-    // Try to get from cache first
-    let (proposals, policy): (Vec<Proposal>, Policy) = state
+    state
         .cache
         .cached_contract_call(CacheTier::ShortTerm, cache_key, async {
-            let proposals = fetch_proposals(&state.network, &dao_id).await?;
+            let proposals = fetch_proposals(&state.network, dao_id).await?;
 
-            let policy = fetch_policy(&state.network, &dao_id).await?;
+            let policy = fetch_policy(&state.network, dao_id).await?;
 
             Ok((proposals, policy))
         })
-        .await?;
+        .await
+}
+
+pub async fn get_proposals(
+    State(state): State<Arc<AppState>>,
+    OptionalAuthUser(user): OptionalAuthUser,
+    Path(dao_id): Path<AccountId>,
+    Query(query): Query<GetProposalsQuery>,
+) -> Result<(StatusCode, Json<PaginatedProposals>), (StatusCode, String)> {
+    let (proposals, policy) = fetch_dao_proposals_cached(&state, &dao_id).await?;
+
+    let (page, page_size) = (query.page, query.page_size);
 
     // Create filters from query params
-    let filters = ProposalFilters {
-        statuses: query.statuses,
-        search: query.search,
-        search_not: query.search_not,
-        proposal_types: query.proposal_types,
-        sort_by: query.sort_by.and_then(|s| match s.as_str() {
-            "CreationTime" => Some(SortBy::CreationTime),
-            "ExpiryTime" => Some(SortBy::ExpiryTime),
-            _ => None,
-        }),
-        types: query.types,
-        types_not: query.types_not,
-        sort_direction: query.sort_direction,
-        created_date_from: query.created_date_from,
-        created_date_to: query.created_date_to,
-        created_date_from_not: query.created_date_from_not,
-        created_date_to_not: query.created_date_to_not,
-        amount_min: query.amount_min,
-        amount_max: query.amount_max,
-        amount_equal: query.amount_equal,
-        proposers: query.proposers,
-        proposers_not: query.proposers_not,
-        approvers: query.approvers,
-        approvers_not: query.approvers_not,
-        voter_votes: query.voter_votes,
-        source: query.source,
-        source_not: query.source_not,
-        recipients: query.recipients,
-        recipients_not: query.recipients_not,
-        token: query.token,
-        token_not: query.token_not,
-        stake_type: query.stake_type,
-        stake_type_not: query.stake_type_not,
-        validators: query.validators,
-        validators_not: query.validators_not,
-        page: query.page,
-        page_size: query.page_size,
-    };
+    let filters = ProposalFilters::from(query);
 
     // Apply filters
     let filtered_proposals = filters
@@ -142,7 +182,7 @@ pub async fn get_proposals(
     let total = filtered_proposals.len();
 
     // Handle pagination
-    let proposals = match (query.page, query.page_size) {
+    let proposals = match (page, page_size) {
         (Some(page), Some(page_size)) => {
             let start = page * page_size;
             let end = start + page_size;
@@ -156,11 +196,32 @@ pub async fn get_proposals(
         _ => filtered_proposals,
     };
 
+    // Swap orders are extra information; proposals are returned without them on failure
+    let proposal_ids: Vec<u64> = proposals.iter().map(|p| p.id).collect();
+    let mut swap_orders = load_swap_orders(&state.db_pool, dao_id.as_str(), &proposal_ids)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load swap orders of {}: {}", dao_id, e);
+            Default::default()
+        });
+
+    let viewer = user.as_ref().map(|u| u.account_id.as_str());
+    let proposals = proposals
+        .into_iter()
+        .map(|proposal| {
+            let swap_order = swap_orders.remove(&proposal.id);
+            ProposalResponse {
+                swap_order,
+                ..ProposalResponse::new(proposal, &policy, viewer)
+            }
+        })
+        .collect();
+
     let response = PaginatedProposals {
         proposals,
         total,
-        page: query.page.unwrap_or(0),
-        page_size: query.page_size.unwrap_or(total),
+        page: page.unwrap_or(0),
+        page_size: page_size.unwrap_or(total),
     };
 
     Ok((StatusCode::OK, Json(response)))
@@ -168,8 +229,9 @@ pub async fn get_proposals(
 
 pub async fn get_proposal(
     State(state): State<Arc<AppState>>,
+    OptionalAuthUser(user): OptionalAuthUser,
     Path((dao_id, proposal_id)): Path<(AccountId, u64)>,
-) -> Result<(StatusCode, Json<Proposal>), (StatusCode, String)> {
+) -> Result<(StatusCode, Json<ProposalResponse>), (StatusCode, String)> {
     // Create cache key for specific proposal
     let cache_key = CacheKey::new("dao-proposal")
         .with(&dao_id)
@@ -184,7 +246,34 @@ pub async fn get_proposal(
         })
         .await?;
 
-    Ok((StatusCode::OK, Json(proposal)))
+    let policy_cache_key = CacheKey::new("dao-policy").with(&dao_id).build();
+    let policy: Policy = state
+        .cache
+        .cached_contract_call(CacheTier::ShortTerm, policy_cache_key, async {
+            fetch_policy(&state.network, &dao_id).await
+        })
+        .await?;
+
+    let swap_order = load_swap_order(&state.db_pool, dao_id.as_str(), proposal_id)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!(
+                "Failed to load swap order of proposal {}: {}",
+                proposal_id,
+                e
+            );
+            None
+        });
+
+    let viewer = user.as_ref().map(|u| u.account_id.as_str());
+
+    Ok((
+        StatusCode::OK,
+        Json(ProposalResponse {
+            swap_order,
+            ..ProposalResponse::new(proposal, &policy, viewer)
+        }),
+    ))
 }
 
 #[derive(serde::Serialize)]
@@ -197,20 +286,7 @@ pub async fn get_dao_proposers(
     State(state): State<Arc<AppState>>,
     Path(dao_id): Path<AccountId>,
 ) -> Result<(StatusCode, Json<ProposersResponse>), (StatusCode, String)> {
-    // Create cache key for proposals
-    let cache_key = CacheKey::new("dao-proposals").with(&dao_id).build();
-
-    // Get cached proposals
-    let (proposals, _policy): (Vec<Proposal>, Policy) = state
-        .cache
-        .cached_contract_call(CacheTier::ShortTerm, cache_key, async {
-            let proposals = fetch_proposals(&state.network, &dao_id).await?;
-
-            let policy = fetch_policy(&state.network, &dao_id).await?;
-
-            Ok((proposals, policy))
-        })
-        .await?;
+    let (proposals, _policy) = fetch_dao_proposals_cached(&state, &dao_id).await?;
 
     // Extract unique proposers from all proposals
     let mut proposers: std::collections::HashSet<String> = std::collections::HashSet::new();
@@ -241,20 +317,7 @@ pub async fn get_dao_approvers(
     State(state): State<Arc<AppState>>,
     Path(dao_id): Path<AccountId>,
 ) -> Result<(StatusCode, Json<ApproversResponse>), (StatusCode, String)> {
-    // Create cache key for proposals
-    let cache_key = CacheKey::new("dao-proposals").with(&dao_id).build();
-
-    // Get cached proposals
-    let (proposals, _policy): (Vec<Proposal>, Policy) = state
-        .cache
-        .cached_contract_call(CacheTier::ShortTerm, cache_key, async {
-            let proposals = fetch_proposals(&state.network, &dao_id).await?;
-
-            let policy = fetch_policy(&state.network, &dao_id).await?;
-
-            Ok((proposals, policy))
-        })
-        .await?;
+    let (proposals, _policy) = fetch_dao_proposals_cached(&state, &dao_id).await?;
 
     // Extract unique approvers from all proposals
     let mut approvers: std::collections::HashSet<String> = std::collections::HashSet::new();
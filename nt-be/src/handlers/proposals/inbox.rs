@@ -0,0 +1,421 @@
+//! Approver action inbox
+//!
+//! Aggregates the pending proposals of every treasury a user is a policy member of,
+//! keeping only the ones the user can still vote on.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use futures::StreamExt;
+use near_api::AccountId;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    auth::AuthUser,
+    handlers::proposals::{
+        filters::ProposalFilters,
+        forecast::is_eligible_voter,
+        get_proposals::{GetProposalsQuery, ProposalResponse, fetch_dao_proposals_cached},
+        scraper::{Policy, Proposal, ProposalStatus},
+    },
+};
+
+/// Number of DAOs fetched concurrently when building the inbox
+const INBOX_DAO_CONCURRENCY: usize = 5;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxItem {
+    pub dao_id: String,
+    #[serde(flatten)]
+    pub proposal: ProposalResponse,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxResponse {
+    pub proposals: Vec<InboxItem>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+    /// DAOs whose proposals could not be loaded
+    pub failed_daos: Vec<String>,
+}
+
+/// Fetch the user's DAOs from the policy membership table
+async fn fetch_member_daos(
+    state: &AppState,
+    account_id: &str,
+) -> Result<Vec<AccountId>, (StatusCode, String)> {
+    let dao_ids: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT dao_id
+        FROM dao_members
+        WHERE account_id = $1
+          AND is_policy_member = true
+        ORDER BY dao_id
+        "#,
+    )
+    .bind(account_id)
+    .fetch_all(&state.db_pool)
+    .await
+    .map_err(|e| {
+        log::error!("Error fetching DAOs for {}: {}", account_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to fetch user DAOs".to_string(),
+        )
+    })?;
+
+    Ok(dao_ids
+        .into_iter()
+        .filter_map(|dao_id| match dao_id.parse() {
+            Ok(id) => Some(id),
+            Err(e) => {
+                log::warn!("Invalid DAO ID in database: {} - {}", dao_id, e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Pending proposals the account has not voted on yet and may vote on
+fn actionable_proposals(
+    proposals: Vec<Proposal>,
+    policy: &Policy,
+    account_id: &str,
+) -> Vec<Proposal> {
+    proposals
+        .into_iter()
+        .filter(|p| {
+            p.status == ProposalStatus::InProgress
+                && !p.votes.contains_key(account_id)
+                && is_eligible_voter(p, policy, account_id)
+        })
+        .collect()
+}
+
+/// Collect the pending, unvoted proposals of one DAO that pass the filters
+async fn dao_inbox_items(
+    state: &AppState,
+    dao_id: &AccountId,
+    account_id: &str,
+    filters: &ProposalFilters,
+) -> Result<Vec<InboxItem>, String> {
+    let (proposals, policy) = fetch_dao_proposals_cached(state, dao_id)
+        .await
+        .map_err(|(_, e)| e)?;
+
+    let actionable = actionable_proposals(proposals, &policy, account_id);
+
+    let filtered = filters
+        .filter_proposals_async(
+            actionable,
+            &policy,
+            &state.cache,
+            &state.network,
+            &state.bulk_payment_contract_id,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(filtered
+        .into_iter()
+        .map(|proposal| ProposalResponse::new(proposal, &policy, Some(account_id)))
+        // Expired proposals can no longer be voted on
+        .filter(|p| !p.forecast.is_expired)
+        .map(|proposal| InboxItem {
+            dao_id: dao_id.to_string(),
+            proposal,
+        })
+        .collect())
+}
+
+/// Merge the per-DAO results into one page, sorted by expiry across DAOs
+///
+/// DAOs whose proposals could not be loaded are reported instead of failing the inbox.
+fn build_inbox(
+    results: Vec<(AccountId, Result<Vec<InboxItem>, String>)>,
+    is_ascending: bool,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> InboxResponse {
+    let mut items = Vec::new();
+    let mut failed_daos = Vec::new();
+    for (dao_id, result) in results {
+        match result {
+            Ok(dao_items) => items.extend(dao_items),
+            Err(e) => {
+                log::warn!("Failed to build inbox for DAO {}: {}", dao_id, e);
+                failed_daos.push(dao_id.to_string());
+            }
+        }
+    }
+    failed_daos.sort_unstable();
+
+    items.sort_by(|a, b| {
+        let ordering = a
+            .proposal
+            .forecast
+            .expires_at
+            .0
+            .cmp(&b.proposal.forecast.expires_at.0)
+            .then_with(|| a.dao_id.cmp(&b.dao_id))
+            .then_with(|| a.proposal.proposal.id.cmp(&b.proposal.proposal.id));
+        if is_ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+
+    let total = items.len();
+
+    let proposals = match (page, page_size) {
+        (Some(page), Some(page_size)) => {
+            let start = page * page_size;
+            let end = start + page_size;
+
+            if start < total {
+                items[start..total.min(end)].to_vec()
+            } else {
+                vec![]
+            }
+        }
+        _ => items,
+    };
+
+    InboxResponse {
+        proposals,
+        total,
+        page: page.unwrap_or(0),
+        page_size: page_size.unwrap_or(total),
+        failed_daos,
+    }
+}
+
+/// Pending proposals across all of the authenticated user's treasuries, sorted by expiry
+pub async fn get_approver_inbox(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<GetProposalsQuery>,
+) -> Result<(StatusCode, Json<InboxResponse>), (StatusCode, String)> {
+    let account_id = auth_user.account_id;
+    let (page, page_size) = (query.page, query.page_size);
+    let is_ascending = query
+        .sort_direction
+        .as_deref()
+        .map(|d| d.to_lowercase() != "desc")
+        .unwrap_or(true);
+
+    let mut filters = ProposalFilters::from(query);
+    // Ordering is applied across all DAOs below
+    filters.sort_by = None;
+
+    let dao_ids = fetch_member_daos(&state, &account_id).await?;
+
+    let results: Vec<(AccountId, Result<Vec<InboxItem>, String>)> =
+        futures::stream::iter(dao_ids.into_iter().map(|dao_id| {
+            let state = state.clone();
+            let account_id = account_id.clone();
+            let filters = filters.clone();
+            async move {
+                let items = dao_inbox_items(&state, &dao_id, &account_id, &filters).await;
+                (dao_id, items)
+            }
+        }))
+        .buffer_unordered(INBOX_DAO_CONCURRENCY)
+        .collect()
+        .await;
+
+    let response = build_inbox(results, is_ascending, page, page_size);
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::proposals::scraper::Vote;
+    use near_api::types::json::U64;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    const HOUR_NS: u64 = 60 * 60 * 1_000_000_000;
+
+    fn policy() -> Policy {
+        Policy {
+            roles: vec![
+                json!({
+                    "name": "Requestor",
+                    "kind": { "Group": ["requestor.near"] },
+                    "permissions": ["transfer:AddProposal"],
+                    "vote_policy": {}
+                }),
+                json!({
+                    "name": "Approver",
+                    "kind": { "Group": ["a.near", "b.near"] },
+                    "permissions": ["transfer:VoteApprove", "transfer:VoteReject"],
+                    "vote_policy": {}
+                }),
+            ],
+            default_vote_policy: json!({
+                "weight_kind": "RoleWeight",
+                "quorum": "0",
+                "threshold": [1, 2]
+            }),
+            proposal_bond: "0".to_string(),
+            proposal_period: U64::from(168 * HOUR_NS),
+            bounty_bond: "0".to_string(),
+            bounty_forgiveness_period: U64::from(0),
+        }
+    }
+
+    fn proposal(id: u64, kind: serde_json::Value, status: ProposalStatus) -> Proposal {
+        Proposal {
+            id,
+            proposer: "requestor.near".to_string(),
+            description: "Pay contributor".to_string(),
+            kind,
+            status,
+            vote_counts: HashMap::new(),
+            votes: HashMap::new(),
+            submission_time: U64::from(id * HOUR_NS),
+            last_actions_log: None,
+        }
+    }
+
+    fn transfer(id: u64) -> Proposal {
+        proposal(
+            id,
+            json!({ "Transfer": { "token_id": "", "receiver_id": "bob.near", "amount": "1" } }),
+            ProposalStatus::InProgress,
+        )
+    }
+
+    fn item(dao_id: &str, id: u64) -> InboxItem {
+        InboxItem {
+            dao_id: dao_id.to_string(),
+            proposal: ProposalResponse::new(transfer(id), &policy(), Some("a.near")),
+        }
+    }
+
+    fn ids(response: &InboxResponse) -> Vec<(String, u64)> {
+        response
+            .proposals
+            .iter()
+            .map(|i| (i.dao_id.clone(), i.proposal.proposal.id))
+            .collect()
+    }
+
+    #[test]
+    fn test_actionable_proposals() {
+        let mut voted = transfer(2);
+        voted.votes.insert("a.near".to_string(), Vote::Approve);
+        let proposals = vec![
+            transfer(1),
+            voted,
+            proposal(3, json!({ "Transfer": {} }), ProposalStatus::Approved),
+            // Approvers have no permission on policy changes
+            proposal(4, json!({ "ChangePolicy": {} }), ProposalStatus::InProgress),
+            transfer(5),
+        ];
+
+        let actionable = actionable_proposals(proposals.clone(), &policy(), "a.near");
+        assert_eq!(
+            actionable.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![1, 5]
+        );
+
+        // Not a voter on any of them
+        assert!(actionable_proposals(proposals, &policy(), "requestor.near").is_empty());
+    }
+
+    #[test]
+    fn test_inbox_sorted_by_expiry_across_daos() {
+        let results = || {
+            vec![
+                (
+                    "b.sputnik-dao.near".parse().unwrap(),
+                    Ok(vec![
+                        item("b.sputnik-dao.near", 3),
+                        item("b.sputnik-dao.near", 1),
+                    ]),
+                ),
+                (
+                    "a.sputnik-dao.near".parse().unwrap(),
+                    Ok(vec![
+                        item("a.sputnik-dao.near", 2),
+                        item("a.sputnik-dao.near", 4),
+                    ]),
+                ),
+            ]
+        };
+
+        let inbox = build_inbox(results(), true, None, None);
+        assert_eq!(inbox.total, 4);
+        assert_eq!(inbox.page_size, 4);
+        assert_eq!(
+            ids(&inbox),
+            vec![
+                ("b.sputnik-dao.near".to_string(), 1),
+                ("a.sputnik-dao.near".to_string(), 2),
+                ("b.sputnik-dao.near".to_string(), 3),
+                ("a.sputnik-dao.near".to_string(), 4),
+            ]
+        );
+
+        let page = build_inbox(results(), true, Some(1), Some(3));
+        assert_eq!(page.total, 4);
+        assert_eq!(page.page, 1);
+        assert_eq!(ids(&page), vec![("a.sputnik-dao.near".to_string(), 4)]);
+
+        let descending = build_inbox(results(), false, Some(0), Some(2));
+        assert_eq!(
+            ids(&descending),
+            vec![
+                ("a.sputnik-dao.near".to_string(), 4),
+                ("b.sputnik-dao.near".to_string(), 3),
+            ]
+        );
+
+        assert!(
+            build_inbox(results(), true, Some(2), Some(3))
+                .proposals
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_inbox_reports_failed_daos() {
+        let inbox = build_inbox(
+            vec![
+                (
+                    "c.sputnik-dao.near".parse().unwrap(),
+                    Err("RPC timeout".to_string()),
+                ),
+                (
+                    "a.sputnik-dao.near".parse().unwrap(),
+                    Ok(vec![item("a.sputnik-dao.near", 1)]),
+                ),
+                (
+                    "b.sputnik-dao.near".parse().unwrap(),
+                    Err("RPC timeout".to_string()),
+                ),
+            ],
+            true,
+            None,
+            None,
+        );
+
+        assert_eq!(ids(&inbox), vec![("a.sputnik-dao.near".to_string(), 1)]);
+        assert_eq!(
+            inbox.failed_daos,
+            vec!["b.sputnik-dao.near", "c.sputnik-dao.near"]
+        );
+    }
+}
@@ -1,4 +1,8 @@
+pub mod execution;
 pub mod filters;
+pub mod forecast;
 pub mod get_proposals;
+pub mod inbox;
 pub mod scraper;
+pub mod simulate;
 pub mod tx;
@@ -310,7 +310,7 @@ pub fn extract_from_description(desc: &str, key: &str) -> Option<String> {
     None
 }
 
-fn get_current_time_nanos() -> U64 {
+pub fn get_current_time_nanos() -> U64 {
     let nanos = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("Time went backwards")
@@ -319,6 +319,22 @@ fn get_current_time_nanos() -> U64 {
     U64::from(nanos as u64)
 }
 
+/// Voting period of a proposal in nanoseconds
+///
+/// Exchange proposals use a 24-hour expiration instead of the policy period,
+/// since the underlying 1Click quote doesn't live longer than that.
+pub fn get_expiration_period(period: u64, proposal: Option<&Proposal>) -> u64 {
+    if let Some(p) = proposal
+        && extract_from_description(&p.description, "proposalaction")
+            == Some("asset-exchange".to_string())
+    {
+        // 24 hours in nanoseconds
+        24 * 60 * 60 * 1_000_000_000
+    } else {
+        period
+    }
+}
+
 pub fn get_status_display(
     status: &ProposalStatus,
     submission_time: u64,
@@ -329,20 +345,7 @@ pub fn get_status_display(
     match status {
         ProposalStatus::InProgress => {
             let current_time = get_current_time_nanos().0;
-
-            // For exchange proposals, use 24-hour expiration instead of policy period
-            let expiration_period = if let Some(p) = proposal {
-                if extract_from_description(&p.description, "proposalaction")
-                    == Some("asset-exchange".to_string())
-                {
-                    // 24 hours in nanoseconds
-                    24 * 60 * 60 * 1_000_000_000
-                } else {
-                    period
-                }
-            } else {
-                period
-            };
+            let expiration_period = get_expiration_period(period, proposal);
 
             if submission_time + expiration_period < current_time {
                 "Expired".to_string()
@@ -404,9 +407,13 @@ pub struct BulkPayment {
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct BatchPayment {
-    pub recipient: AccountId,
+    /// NEAR account, or origin-chain address for intents withdrawals
+    pub recipient: String,
     pub amount: String,
     pub status: serde_json::Value,
+    /// Token of the payment when the list token is "mixed"
+    #[serde(default)]
+    pub token_id: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -423,6 +430,8 @@ pub struct AssetExchangeInfo {
     pub token_out_symbol: String,
     pub amount_out: String,
     pub deposit_address: Option<String>,
+    /// 1Click quote deadline (ISO 8601) recorded in the proposal description
+    pub quote_deadline: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -790,6 +799,7 @@ impl ProposalType for AssetExchangeInfo {
                             .unwrap_or_default()
                             .to_string(),
                         deposit_address: None,
+                        quote_deadline: None,
                     });
                 } else {
                     let near_token: U128 = U128::from(
@@ -813,6 +823,7 @@ impl ProposalType for AssetExchangeInfo {
                             .unwrap_or("0")
                             .to_string(),
                         deposit_address: None,
+                        quote_deadline: None,
                     });
                 }
             } else if extract_from_description(&proposal.description, "proposalaction")
@@ -890,12 +901,17 @@ impl ProposalType for AssetExchangeInfo {
                     println!("deposit_address: {:?}", deposit_address);
                 }
 
+                // Extract the quote deadline from description
+                let quote_deadline =
+                    extract_from_description(&proposal.description, "quoteDeadline");
+
                 return Some(AssetExchangeInfo {
                     token_in_address,
                     amount_in,
                     token_out_symbol,
                     amount_out,
                     deposit_address,
+                    quote_deadline,
                 });
             }
         }
@@ -1105,3 +1121,45 @@ impl ProposalType for BulkPayment {
         "bulk-payment"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_payment_list_with_origin_chain_recipients() {
+        let list: BatchPaymentResponse = serde_json::from_value(json!({
+            "token_id": "nep141:eth-0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48.omft.near",
+            "submitter": "dao.sputnik-dao.near",
+            "status": "Approved",
+            "payments": [
+                {
+                    "recipient": "0x52908400098527886E0F7030069857D2E4169EE7",
+                    "amount": "1000000",
+                    "status": "Pending"
+                },
+                {
+                    "recipient": "7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV",
+                    "amount": "2000000",
+                    "status": { "Paid": { "block_height": 123 } }
+                },
+                {
+                    "recipient": "alice.near",
+                    "amount": "3000000",
+                    "status": "Pending"
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            list.payments[0].recipient,
+            "0x52908400098527886E0F7030069857D2E4169EE7"
+        );
+        assert_eq!(
+            list.payments[1].recipient,
+            "7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV"
+        );
+        assert_eq!(list.payments[2].recipient, "alice.near");
+    }
+}
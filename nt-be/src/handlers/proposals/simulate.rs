@@ -0,0 +1,804 @@
+//! Pre-execution simulation of pending proposals
+//!
+//! Statically checks a pending proposal against current on-chain state so approvers
+//! can see whether execution is likely to fail before they vote:
+//! - Treasury balance covers every token the proposal spends
+//! - FT receivers are registered for storage on the token contract
+//! - Attached gas stays within protocol limits
+//! - 1Click quote deadlines for exchange proposals have not passed
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use near_api::AccountId;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    constants::INTENTS_CONTRACT_ID,
+    handlers::{
+        proposals::scraper::{
+            AssetExchangeInfo, Proposal, ProposalStatus, ProposalType, fetch_policy,
+            fetch_proposal, get_status_display,
+        },
+        token::storage_deposit::is_registered::check_storage_deposit,
+        user::balance::{fetch_ft_balance, fetch_intents_balance, fetch_near_balance},
+    },
+    utils::cache::{CacheKey, CacheTier},
+};
+
+/// Maximum gas a single transaction can use (300 TGas)
+const MAX_TRANSACTION_GAS: u64 = 300_000_000_000_000;
+/// Gas we leave for the DAO's own `act_proposal` execution (30 TGas)
+const DAO_EXECUTION_GAS_OVERHEAD: u64 = 30_000_000_000_000;
+/// Actions with less gas than this are very likely to run out (5 TGas)
+const MIN_ACTION_GAS: u64 = 5_000_000_000_000;
+/// Warn when a quote expires within this window
+const QUOTE_DEADLINE_WARNING_SECS: i64 = 60 * 60;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl SimulationCheck {
+    fn new(name: &str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationReport {
+    pub dao_id: String,
+    pub proposal_id: u64,
+    pub status: CheckStatus,
+    pub checks: Vec<SimulationCheck>,
+}
+
+/// A token amount the treasury must hold for the proposal to execute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceRequirement {
+    /// "near", an FT contract ID, or an intents token ID (e.g. "nep141:usdc.near")
+    pub token_id: String,
+    pub amount: u128,
+}
+
+/// An FT receiver that needs a storage deposit on the token contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageRequirement {
+    pub token_id: String,
+    pub account_id: String,
+    /// True if the proposal itself calls `storage_deposit` for this account first
+    pub registered_in_proposal: bool,
+}
+
+fn decode_action_args(action: &serde_json::Value) -> Option<serde_json::Value> {
+    let args_b64 = action.get("args").and_then(|a| a.as_str())?;
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(args_b64)
+        .ok()?;
+    serde_json::from_slice(&decoded).ok()
+}
+
+fn parse_u128(value: Option<&serde_json::Value>) -> u128 {
+    value
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<u128>().ok())
+        .unwrap_or(0)
+}
+
+fn function_call_actions(proposal: &Proposal) -> Option<(&str, &[serde_json::Value])> {
+    let function_call = proposal.kind.get("FunctionCall")?;
+    let receiver_id = function_call
+        .get("receiver_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let actions = function_call
+        .get("actions")
+        .and_then(|a| a.as_array())
+        .map(|a| a.as_slice())
+        .unwrap_or(&[]);
+    Some((receiver_id, actions))
+}
+
+/// Collect the per-token amounts a proposal spends from the treasury
+pub fn collect_balance_requirements(proposal: &Proposal) -> Vec<BalanceRequirement> {
+    let mut totals: BTreeMap<String, u128> = BTreeMap::new();
+    let mut add = |token_id: String, amount: u128| {
+        if amount > 0 {
+            let entry = totals.entry(token_id).or_insert(0);
+            *entry = entry.saturating_add(amount);
+        }
+    };
+
+    if let Some(transfer) = proposal.kind.get("Transfer") {
+        let token_id = transfer
+            .get("token_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        let token_id = if token_id.is_empty() {
+            "near"
+        } else {
+            token_id
+        };
+        add(token_id.to_string(), parse_u128(transfer.get("amount")));
+    }
+
+    if let Some((receiver_id, actions)) = function_call_actions(proposal) {
+        // NEAR wrapped within this proposal offsets later wNEAR transfers
+        let mut wrapped_in_proposal: u128 = 0;
+
+        for action in actions {
+            let method_name = action
+                .get("method_name")
+                .and_then(|m| m.as_str())
+                .unwrap_or("");
+            let deposit = parse_u128(action.get("deposit"));
+            add("near".to_string(), deposit);
+
+            if method_name == "near_deposit" {
+                wrapped_in_proposal = wrapped_in_proposal.saturating_add(deposit);
+                continue;
+            }
+
+            let Some(args) = decode_action_args(action) else {
+                continue;
+            };
+            let amount = parse_u128(args.get("amount"));
+
+            match method_name {
+                "ft_transfer" | "ft_transfer_call"
+                    if receiver_id != INTENTS_CONTRACT_ID.as_str() =>
+                {
+                    let amount = amount.saturating_sub(wrapped_in_proposal);
+                    wrapped_in_proposal = 0;
+                    add(receiver_id.to_string(), amount);
+                }
+                "mt_transfer" | "mt_transfer_call"
+                    if receiver_id == INTENTS_CONTRACT_ID.as_str() =>
+                {
+                    if let Some(token_id) = args.get("token_id").and_then(|v| v.as_str()) {
+                        add(token_id.to_string(), amount);
+                    }
+                }
+                "ft_withdraw" if receiver_id == INTENTS_CONTRACT_ID.as_str() => {
+                    if let Some(token) = args.get("token").and_then(|v| v.as_str()) {
+                        add(format!("nep141:{}", token), amount);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|(token_id, amount)| BalanceRequirement { token_id, amount })
+        .collect()
+}
+
+/// Collect FT receivers that must be storage-registered for the proposal to succeed
+pub fn collect_storage_requirements(proposal: &Proposal) -> Vec<StorageRequirement> {
+    let mut requirements = Vec::new();
+
+    if let Some(transfer) = proposal.kind.get("Transfer") {
+        let token_id = transfer
+            .get("token_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        let receiver_id = transfer
+            .get("receiver_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        if !token_id.is_empty() && token_id != "near" && !receiver_id.is_empty() {
+            requirements.push(StorageRequirement {
+                token_id: token_id.to_string(),
+                account_id: receiver_id.to_string(),
+                registered_in_proposal: false,
+            });
+        }
+    }
+
+    if let Some((receiver_id, actions)) = function_call_actions(proposal) {
+        if receiver_id == INTENTS_CONTRACT_ID.as_str() {
+            return requirements;
+        }
+
+        let mut registered: Vec<String> = Vec::new();
+        for action in actions {
+            let method_name = action
+                .get("method_name")
+                .and_then(|m| m.as_str())
+                .unwrap_or("");
+            let Some(args) = decode_action_args(action) else {
+                continue;
+            };
+
+            match method_name {
+                "storage_deposit" => {
+                    if let Some(account_id) = args.get("account_id").and_then(|v| v.as_str()) {
+                        registered.push(account_id.to_string());
+                    }
+                }
+                "ft_transfer" | "ft_transfer_call" => {
+                    if let Some(account_id) = args.get("receiver_id").and_then(|v| v.as_str()) {
+                        requirements.push(StorageRequirement {
+                            token_id: receiver_id.to_string(),
+                            account_id: account_id.to_string(),
+                            registered_in_proposal: registered.iter().any(|r| r == account_id),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    requirements
+}
+
+/// Check attached gas of function call actions against protocol limits
+pub fn check_gas(proposal: &Proposal) -> SimulationCheck {
+    let Some((_, actions)) = function_call_actions(proposal) else {
+        return SimulationCheck::new(
+            "gas",
+            CheckStatus::Pass,
+            "Gas is attached by the DAO contract for this proposal kind",
+        );
+    };
+
+    let mut total_gas: u64 = 0;
+    for action in actions {
+        let method_name = action
+            .get("method_name")
+            .and_then(|m| m.as_str())
+            .unwrap_or("");
+        let gas = action
+            .get("gas")
+            .and_then(|g| g.as_str())
+            .and_then(|g| g.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        if gas < MIN_ACTION_GAS {
+            return SimulationCheck::new(
+                "gas",
+                CheckStatus::Warn,
+                format!(
+                    "Action '{}' attaches {} TGas, which is likely too little",
+                    method_name,
+                    gas / 1_000_000_000_000
+                ),
+            );
+        }
+        total_gas = total_gas.saturating_add(gas);
+    }
+
+    let total_tgas = total_gas / 1_000_000_000_000;
+    if total_gas > MAX_TRANSACTION_GAS {
+        SimulationCheck::new(
+            "gas",
+            CheckStatus::Fail,
+            format!(
+                "Actions attach {} TGas in total, above the 300 TGas transaction limit",
+                total_tgas
+            ),
+        )
+    } else if total_gas > MAX_TRANSACTION_GAS - DAO_EXECUTION_GAS_OVERHEAD {
+        SimulationCheck::new(
+            "gas",
+            CheckStatus::Warn,
+            format!(
+                "Actions attach {} TGas, leaving little room for the DAO's own execution",
+                total_tgas
+            ),
+        )
+    } else {
+        SimulationCheck::new(
+            "gas",
+            CheckStatus::Pass,
+            format!("Actions attach {} TGas in total", total_tgas),
+        )
+    }
+}
+
+/// Check the 1Click quote deadline of an exchange proposal
+pub fn check_quote_deadline(proposal: &Proposal, now: DateTime<Utc>) -> Option<SimulationCheck> {
+    let exchange = AssetExchangeInfo::from_proposal(proposal)?;
+    let deadline = exchange.quote_deadline?;
+
+    let Ok(deadline_time) = DateTime::parse_from_rfc3339(deadline.trim()) else {
+        return Some(SimulationCheck::new(
+            "quote_deadline",
+            CheckStatus::Warn,
+            format!("Could not parse quote deadline '{}'", deadline),
+        ));
+    };
+    let remaining = deadline_time.with_timezone(&Utc) - now;
+
+    Some(if remaining.num_seconds() <= 0 {
+        SimulationCheck::new(
+            "quote_deadline",
+            CheckStatus::Fail,
+            format!("Quote expired at {}", deadline),
+        )
+    } else if remaining.num_seconds() < QUOTE_DEADLINE_WARNING_SECS {
+        SimulationCheck::new(
+            "quote_deadline",
+            CheckStatus::Warn,
+            format!(
+                "Quote expires in {} minutes ({})",
+                remaining.num_minutes(),
+                deadline
+            ),
+        )
+    } else {
+        SimulationCheck::new(
+            "quote_deadline",
+            CheckStatus::Pass,
+            format!("Quote valid until {}", deadline),
+        )
+    })
+}
+
+async fn check_balance(
+    state: &Arc<AppState>,
+    dao_id: &AccountId,
+    requirement: &BalanceRequirement,
+) -> SimulationCheck {
+    let name = format!("balance:{}", requirement.token_id);
+
+    let balance = if requirement.token_id == "near" {
+        fetch_near_balance(state, dao_id.clone()).await
+    } else if requirement.token_id.starts_with("nep141:") {
+        fetch_intents_balance(state, dao_id.clone(), requirement.token_id.clone()).await
+    } else {
+        match requirement.token_id.parse::<AccountId>() {
+            Ok(token_id) => fetch_ft_balance(state, dao_id.clone(), token_id).await,
+            Err(_) => Err(format!(
+                "Balance of {} can't be verified",
+                requirement.token_id
+            )),
+        }
+    };
+
+    match balance {
+        Ok(balance) if balance.balance.0 >= requirement.amount => SimulationCheck::new(
+            &name,
+            CheckStatus::Pass,
+            format!(
+                "Treasury holds {} of the required {}",
+                balance.balance.0, requirement.amount
+            ),
+        ),
+        Ok(balance) => SimulationCheck::new(
+            &name,
+            CheckStatus::Fail,
+            format!(
+                "Insufficient balance: treasury holds {} but the proposal needs {}",
+                balance.balance.0, requirement.amount
+            ),
+        ),
+        Err(e) => SimulationCheck::new(
+            &name,
+            CheckStatus::Warn,
+            format!("Could not fetch balance: {}", e),
+        ),
+    }
+}
+
+async fn check_storage(state: &Arc<AppState>, requirement: &StorageRequirement) -> SimulationCheck {
+    let name = format!(
+        "storage:{}:{}",
+        requirement.token_id, requirement.account_id
+    );
+
+    if requirement.registered_in_proposal {
+        return SimulationCheck::new(
+            &name,
+            CheckStatus::Pass,
+            "Proposal registers the receiver before transferring",
+        );
+    }
+
+    let (Ok(account_id), Ok(token_id)) = (
+        requirement.account_id.parse::<AccountId>(),
+        requirement.token_id.parse::<AccountId>(),
+    ) else {
+        return SimulationCheck::new(
+            &name,
+            CheckStatus::Warn,
+            "Receiver or token is not a valid NEAR account",
+        );
+    };
+
+    match check_storage_deposit(state, account_id, token_id).await {
+        Ok(true) => SimulationCheck::new(
+            &name,
+            CheckStatus::Pass,
+            "Receiver is registered on the token contract",
+        ),
+        Ok(false) => SimulationCheck::new(
+            &name,
+            CheckStatus::Fail,
+            "Receiver has no storage deposit on the token contract",
+        ),
+        Err(e) => SimulationCheck::new(
+            &name,
+            CheckStatus::Warn,
+            format!("Could not check storage registration: {}", e),
+        ),
+    }
+}
+
+/// Run every check against a proposal and return the checklist
+pub async fn simulate_proposal(
+    state: &Arc<AppState>,
+    dao_id: &AccountId,
+    proposal: &Proposal,
+    proposal_period: u64,
+) -> SimulationReport {
+    let mut checks = Vec::new();
+
+    let status_display = get_status_display(
+        &proposal.status,
+        proposal.submission_time.0,
+        proposal_period,
+        "InProgress",
+        Some(proposal),
+    );
+    checks.push(match proposal.status {
+        ProposalStatus::InProgress if status_display == "Expired" => SimulationCheck::new(
+            "status",
+            CheckStatus::Fail,
+            "Proposal voting period has expired",
+        ),
+        ProposalStatus::InProgress => {
+            SimulationCheck::new("status", CheckStatus::Pass, "Proposal is pending")
+        }
+        _ => SimulationCheck::new(
+            "status",
+            CheckStatus::Fail,
+            format!("Proposal is {} and can no longer execute", status_display),
+        ),
+    });
+
+    let balance_requirements = collect_balance_requirements(proposal);
+    let balance_checks = futures::future::join_all(
+        balance_requirements
+            .iter()
+            .map(|requirement| check_balance(state, dao_id, requirement)),
+    )
+    .await;
+    checks.extend(balance_checks);
+
+    let storage_requirements = collect_storage_requirements(proposal);
+    let storage_checks = futures::future::join_all(
+        storage_requirements
+            .iter()
+            .map(|requirement| check_storage(state, requirement)),
+    )
+    .await;
+    checks.extend(storage_checks);
+
+    checks.push(check_gas(proposal));
+
+    if let Some(deadline_check) = check_quote_deadline(proposal, Utc::now()) {
+        checks.push(deadline_check);
+    }
+
+    let status = checks
+        .iter()
+        .map(|c| c.status)
+        .max()
+        .unwrap_or(CheckStatus::Pass);
+
+    SimulationReport {
+        dao_id: dao_id.to_string(),
+        proposal_id: proposal.id,
+        status,
+        checks,
+    }
+}
+
+pub async fn simulate_proposal_execution(
+    State(state): State<Arc<AppState>>,
+    Path((dao_id, proposal_id)): Path<(AccountId, u64)>,
+) -> Result<(StatusCode, Json<SimulationReport>), (StatusCode, String)> {
+    let proposal_cache_key = CacheKey::new("dao-proposal")
+        .with(&dao_id)
+        .with(proposal_id)
+        .build();
+    let proposal: Proposal = state
+        .cache
+        .cached_contract_call(CacheTier::ShortTerm, proposal_cache_key, async {
+            fetch_proposal(&state.network, &dao_id, proposal_id).await
+        })
+        .await?;
+
+    let policy_cache_key = CacheKey::new("dao-policy").with(&dao_id).build();
+    let policy = state
+        .cache
+        .cached_contract_call(CacheTier::ShortTerm, policy_cache_key, async {
+            fetch_policy(&state.network, &dao_id).await
+        })
+        .await?;
+
+    let report = simulate_proposal(&state, &dao_id, &proposal, policy.proposal_period.0).await;
+
+    Ok((StatusCode::OK, Json(report)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_api::types::json::U64;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn encode_args(args: serde_json::Value) -> String {
+        base64::engine::general_purpose::STANDARD.encode(args.to_string())
+    }
+
+    fn proposal_with_kind(kind: serde_json::Value, description: &str) -> Proposal {
+        Proposal {
+            id: 1,
+            proposer: "alice.near".to_string(),
+            description: description.to_string(),
+            kind,
+            status: ProposalStatus::InProgress,
+            vote_counts: HashMap::new(),
+            votes: HashMap::new(),
+            submission_time: U64::from(0),
+            last_actions_log: None,
+        }
+    }
+
+    #[test]
+    fn test_transfer_requirements() {
+        let proposal = proposal_with_kind(
+            json!({
+                "Transfer": {
+                    "token_id": "usdt.tether-token.near",
+                    "receiver_id": "bob.near",
+                    "amount": "1000000"
+                }
+            }),
+            "",
+        );
+
+        assert_eq!(
+            collect_balance_requirements(&proposal),
+            vec![BalanceRequirement {
+                token_id: "usdt.tether-token.near".to_string(),
+                amount: 1_000_000,
+            }]
+        );
+        assert_eq!(
+            collect_storage_requirements(&proposal),
+            vec![StorageRequirement {
+                token_id: "usdt.tether-token.near".to_string(),
+                account_id: "bob.near".to_string(),
+                registered_in_proposal: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_near_transfer_has_no_storage_requirement() {
+        let proposal = proposal_with_kind(
+            json!({
+                "Transfer": {
+                    "token_id": "",
+                    "receiver_id": "bob.near",
+                    "amount": "5"
+                }
+            }),
+            "",
+        );
+
+        assert_eq!(
+            collect_balance_requirements(&proposal),
+            vec![BalanceRequirement {
+                token_id: "near".to_string(),
+                amount: 5,
+            }]
+        );
+        assert!(collect_storage_requirements(&proposal).is_empty());
+    }
+
+    #[test]
+    fn test_wrap_and_transfer_offsets_wnear_requirement() {
+        let proposal = proposal_with_kind(
+            json!({
+                "FunctionCall": {
+                    "receiver_id": "wrap.near",
+                    "actions": [
+                        {
+                            "method_name": "near_deposit",
+                            "args": encode_args(json!({})),
+                            "deposit": "1000",
+                            "gas": "10000000000000"
+                        },
+                        {
+                            "method_name": "ft_transfer",
+                            "args": encode_args(json!({"receiver_id": "deposit.near", "amount": "1000"})),
+                            "deposit": "1",
+                            "gas": "150000000000000"
+                        }
+                    ]
+                }
+            }),
+            "",
+        );
+
+        assert_eq!(
+            collect_balance_requirements(&proposal),
+            vec![BalanceRequirement {
+                token_id: "near".to_string(),
+                amount: 1001,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_storage_deposit_in_proposal_counts_as_registered() {
+        let proposal = proposal_with_kind(
+            json!({
+                "FunctionCall": {
+                    "receiver_id": "usdt.tether-token.near",
+                    "actions": [
+                        {
+                            "method_name": "storage_deposit",
+                            "args": encode_args(json!({"account_id": "bob.near"})),
+                            "deposit": "1250000000000000000000",
+                            "gas": "10000000000000"
+                        },
+                        {
+                            "method_name": "ft_transfer",
+                            "args": encode_args(json!({"receiver_id": "bob.near", "amount": "50"})),
+                            "deposit": "1",
+                            "gas": "10000000000000"
+                        }
+                    ]
+                }
+            }),
+            "",
+        );
+
+        let storage = collect_storage_requirements(&proposal);
+        assert_eq!(storage.len(), 1);
+        assert!(storage[0].registered_in_proposal);
+    }
+
+    #[test]
+    fn test_intents_withdraw_requirement() {
+        let proposal = proposal_with_kind(
+            json!({
+                "FunctionCall": {
+                    "receiver_id": "intents.near",
+                    "actions": [
+                        {
+                            "method_name": "ft_withdraw",
+                            "args": encode_args(json!({
+                                "token": "usdc.near",
+                                "receiver_id": "bob.near",
+                                "amount": "42"
+                            })),
+                            "deposit": "1",
+                            "gas": "50000000000000"
+                        }
+                    ]
+                }
+            }),
+            "",
+        );
+
+        let requirements = collect_balance_requirements(&proposal);
+        assert!(requirements.contains(&BalanceRequirement {
+            token_id: "nep141:usdc.near".to_string(),
+            amount: 42,
+        }));
+        assert!(collect_storage_requirements(&proposal).is_empty());
+    }
+
+    #[test]
+    fn test_gas_checks() {
+        let make = |gas: &str| {
+            proposal_with_kind(
+                json!({
+                    "FunctionCall": {
+                        "receiver_id": "contract.near",
+                        "actions": [
+                            {"method_name": "a", "args": "", "deposit": "0", "gas": gas},
+                            {"method_name": "b", "args": "", "deposit": "0", "gas": gas}
+                        ]
+                    }
+                }),
+                "",
+            )
+        };
+
+        assert_eq!(
+            check_gas(&make("100000000000000")).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            check_gas(&make("140000000000000")).status,
+            CheckStatus::Warn
+        );
+        assert_eq!(
+            check_gas(&make("160000000000000")).status,
+            CheckStatus::Fail
+        );
+        assert_eq!(check_gas(&make("1000")).status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn test_quote_deadline_check() {
+        let proposal = proposal_with_kind(
+            json!({
+                "FunctionCall": {
+                    "receiver_id": "intents.near",
+                    "actions": [
+                        {
+                            "method_name": "mt_transfer",
+                            "args": encode_args(json!({
+                                "token_id": "nep141:usdc.near",
+                                "receiver_id": "deposit-address",
+                                "amount": "100"
+                            })),
+                            "deposit": "1",
+                            "gas": "100000000000000"
+                        }
+                    ]
+                }
+            }),
+            "* Proposal Action: asset-exchange <br>* Quote Deadline: 2026-01-18T16:30:00.000Z",
+        );
+
+        let before = DateTime::parse_from_rfc3339("2026-01-18T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let close = DateTime::parse_from_rfc3339("2026-01-18T16:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let after = DateTime::parse_from_rfc3339("2026-01-18T17:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            check_quote_deadline(&proposal, before).unwrap().status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            check_quote_deadline(&proposal, close).unwrap().status,
+            CheckStatus::Warn
+        );
+        assert_eq!(
+            check_quote_deadline(&proposal, after).unwrap().status,
+            CheckStatus::Fail
+        );
+    }
+}
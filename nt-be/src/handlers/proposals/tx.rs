@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
 
+use super::execution::trace_execution;
 use crate::AppState;
 use crate::utils::cache::{CacheKey, CacheTier};
 
@@ -41,7 +42,7 @@ struct NearBlocksResponse {
     txns: Vec<NearBlocksTransaction>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ProposalTransactionResponse {
     pub transaction_hash: String,
     pub nearblocks_url: String,
@@ -129,42 +130,93 @@ fn find_matching_transaction<'a>(
     })
 }
 
-/// Find the execution transaction for a proposal by querying NearBlocks API
+/// Find the execution transaction for a proposal
 pub async fn find_proposal_execution_transaction(
     State(state): State<Arc<AppState>>,
     Path((dao_id, proposal_id)): Path<(AccountId, u64)>,
     Query(params): Query<TransactionQueryParams>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, String)> {
-    log::info!(
-        "Searching for proposal {} execution between {} and {}",
+    let transaction = fetch_execution_transaction(
+        &state,
+        &dao_id,
         proposal_id,
+        &params.action,
         params.after_date,
-        params.before_date
-    );
+        params.before_date,
+    )
+    .await?;
 
-    let Some(nearblocks_api_key) = state.env_vars.nearblocks_api_key.as_ref() else {
-        return Err((
+    let value = serde_json::to_value(transaction).map_err(|e| {
+        log::error!("Failed to serialize execution transaction: {}", e);
+        (
             StatusCode::INTERNAL_SERVER_ERROR,
-            "NearBlocks API is not enabled".to_string(),
-        ));
+            "Failed to serialize result".to_string(),
+        )
+    })?;
+    Ok((StatusCode::OK, Json(value)))
+}
+
+/// Execution transaction of a proposal (`action` of its final vote, e.g. "VoteApprove")
+///
+/// Traced on the archival RPC; NearBlocks is searched between the two dates when tracing fails
+/// and an API key is configured.
+pub async fn fetch_execution_transaction(
+    state: &AppState,
+    dao_id: &AccountId,
+    proposal_id: u64,
+    action: &str,
+    after_date: NaiveDate,
+    before_date: NaiveDate,
+) -> Result<ProposalTransactionResponse, (StatusCode, String)> {
+    let traced = trace_execution(state, dao_id, proposal_id).await;
+    let trace_error = match traced {
+        Ok(execution) if execution.action == action => {
+            return Ok(ProposalTransactionResponse {
+                nearblocks_url: format!(
+                    "https://nearblocks.io/txns/{}",
+                    execution.transaction_hash
+                ),
+                transaction_hash: execution.transaction_hash,
+                block_height: execution.block_height as u64,
+                timestamp: execution.block_timestamp as u64,
+            });
+        }
+        Ok(execution) => (
+            StatusCode::NOT_FOUND,
+            format!(
+                "Proposal {} was finalized with {}, not {}",
+                proposal_id, execution.action, action
+            ),
+        ),
+        Err(e) => e,
+    };
+
+    let Some(nearblocks_api_key) = state.env_vars.nearblocks_api_key.as_ref() else {
+        return Err(trace_error);
     };
 
+    log::info!(
+        "Tracing proposal {} failed ({}), searching NearBlocks between {} and {}",
+        proposal_id,
+        trace_error.1,
+        after_date,
+        before_date
+    );
+
     let cache_key = CacheKey::new("proposal-tx")
-        .with(&dao_id)
+        .with(dao_id)
         .with(proposal_id)
-        .with(&params.action)
+        .with(action)
         .build();
 
     let http_client = state.http_client.clone();
     let api_key = nearblocks_api_key.clone();
     let dao_id_clone = dao_id.clone();
-    let action = params.action.clone();
-    let after_date = params.after_date;
-    let before_date = params.before_date;
+    let action = action.to_string();
 
     state
         .cache
-        .cached_json(CacheTier::LongTerm, cache_key, async move {
+        .cached(CacheTier::LongTerm, cache_key, async move {
             if action == "VoteApprove" {
                 // Try on_proposal_callback first
                 let callback_txns = fetch_nearblocks_transactions(
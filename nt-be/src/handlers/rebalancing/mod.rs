@@ -0,0 +1,4 @@
+//! Target allocations of treasuries and the planner that drafts the trades to meet them
+
+pub mod planner;
+pub mod targets;
@@ -0,0 +1,958 @@
+//! Rebalancing planner.
+//!
+//! Values the treasury's holdings (`get_user_assets`), groups them into the allocation buckets
+//! of its targets and, when a bucket drifted outside its tolerance band, plans the trades that
+//! bring every bucket back to its target share. Overweight buckets sell their largest
+//! holdings first. Exchanges are priced with 1Click dry-run quotes; staking and unstaking
+//! are returned as proposals ready to submit. With `liveQuotes`, exchanges are quoted live
+//! instead and come with their proposal too, built the same way as exchange order slices;
+//! each live quote reserves a 1Click deposit address, so plans are dry by default.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use bigdecimal::BigDecimal;
+use chrono::{Duration, Utc};
+use futures::future::join_all;
+use near_api::{AccountId, NearGas};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+use super::targets::{AllocationTarget, load_targets};
+use crate::AppState;
+use crate::handlers::exchange_orders::worker::{
+    STORAGE_DEPOSIT_YOCTO, deposit_token_contract, exchange_proposal,
+};
+use crate::handlers::intents::quote::{QuoteRequest, request_quote};
+use crate::handlers::payment_schedules::worker::{base64_args, encode_description};
+use crate::handlers::user::assets::{
+    Balance, SimplifiedToken, TokenResidency, UserAssetsQuery, get_user_assets,
+};
+
+/// NEAR kept liquid for storage and gas, never planned for sale (1 NEAR)
+const NEAR_RESERVE_YOCTO: u128 = 1_000_000_000_000_000_000_000_000;
+
+/// Trades smaller than this are not worth their fees
+const MIN_TRADE_USD: f64 = 10.0;
+
+/// Slippage of the quotes when the request doesn't set one
+const DEFAULT_SLIPPAGE_BPS: u32 = 100;
+
+/// Validity of the quotes, matching the exchange page
+const QUOTE_VALIDITY_HOURS: i64 = 24;
+
+/// A priced, tradeable position of the treasury
+#[derive(Debug, Clone)]
+pub struct Holding {
+    /// "near", a NEP-141 contract ID, or an intents asset ID
+    pub token_id: String,
+    pub symbol: String,
+    pub residency: TokenResidency,
+    /// Validator of a staked position
+    pub pool_id: Option<String>,
+    pub decimals: u8,
+    /// Amount in base units
+    pub balance: u128,
+    pub price: f64,
+}
+
+impl Holding {
+    fn usd_value(&self, amount: u128) -> f64 {
+        amount as f64 / 10f64.powi(self.decimals as i32) * self.price
+    }
+
+    /// Base units worth `usd`, rounded down
+    fn amount_for_usd(&self, usd: f64) -> Option<u128> {
+        let usd = BigDecimal::try_from(usd).ok()?;
+        let price = BigDecimal::try_from(self.price).ok()?;
+        let scale = BigDecimal::new(1.into(), -i64::from(self.decimals));
+        (usd / price * scale).with_scale(0).to_string().parse().ok()
+    }
+
+    fn is_staked(&self) -> bool {
+        matches!(self.residency, TokenResidency::Staked)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeAction {
+    Exchange,
+    Stake,
+    Unstake,
+}
+
+/// A trade moving value from an overweight bucket to an underweight one
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTrade {
+    pub action: TradeAction,
+    /// Allocation the value is taken from
+    pub from_allocation: String,
+    /// Allocation the value moves to
+    pub to_allocation: String,
+    /// Token sold, staked or unstaked
+    pub token_id: String,
+    /// Token bought by an exchange
+    pub receive_token_id: Option<String>,
+    /// Validator of a stake or unstake
+    pub pool_id: Option<String>,
+    /// Amount in base units
+    pub amount: String,
+    pub amount_usd: f64,
+    /// Follow-up needed to complete the move
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationStatus {
+    pub name: String,
+    pub target_percent: f64,
+    pub tolerance_percent: f64,
+    pub current_percent: f64,
+    pub current_usd: f64,
+    pub target_usd: f64,
+    pub out_of_band: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebalancePlan {
+    /// Value of the holdings covered by the targets
+    pub total_usd: f64,
+    /// Value of the holdings no allocation lists
+    pub unallocated_usd: f64,
+    pub needs_rebalancing: bool,
+    pub allocations: Vec<AllocationStatus>,
+    pub trades: Vec<PlannedTrade>,
+}
+
+/// Expected result of an exchange, from its 1Click quote
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeEstimate {
+    pub amount_out: Option<String>,
+    pub amount_out_formatted: Option<String>,
+    pub amount_in_usd: Option<String>,
+    pub amount_out_usd: Option<String>,
+    /// Value lost to fees and price impact
+    pub cost_usd: Option<f64>,
+    pub time_estimate: Option<u64>,
+}
+
+/// A proposal ready to submit to the DAO
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposalDraft {
+    pub description: String,
+    pub kind: Value,
+    /// Exchanges of NEAR tokens: registration of the deposit address on the token contract,
+    /// to send alongside the proposal as the exchange page does
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_deposit: Option<StorageDepositDraft>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDepositDraft {
+    /// Token contract to call `storage_deposit` on
+    pub receiver_id: String,
+    pub account_id: String,
+    /// yoctoNEAR
+    pub deposit: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftTrade {
+    #[serde(flatten)]
+    pub trade: PlannedTrade,
+    /// Exchanges: expected output and fees
+    pub estimate: Option<ExchangeEstimate>,
+    /// The proposal to submit: stakes and unstakes always, exchanges with live quotes
+    pub proposal: Option<ProposalDraft>,
+    /// Why the exchange could not be quoted
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebalancePlanResponse {
+    pub dao_id: String,
+    pub total_usd: f64,
+    pub unallocated_usd: f64,
+    pub needs_rebalancing: bool,
+    pub allocations: Vec<AllocationStatus>,
+    pub drafts: Vec<DraftTrade>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanQuery {
+    pub dao_id: String,
+    /// Slippage tolerance of the exchanges in basis points
+    pub slippage_bps: Option<u32>,
+    /// Quote exchanges live and return their proposals
+    #[serde(default)]
+    pub live_quotes: bool,
+}
+
+/// Tradeable holdings of a treasury; lockups and unstaked balances are left out
+pub fn holdings_from_assets(assets: &[SimplifiedToken]) -> Vec<Holding> {
+    let mut holdings = Vec::new();
+    for asset in assets {
+        let price = asset.price.parse::<f64>().unwrap_or(0.0);
+        if price <= 0.0 {
+            continue;
+        }
+        let holding = |balance: u128, residency: TokenResidency, pool_id: Option<String>| Holding {
+            token_id: asset.id.clone(),
+            symbol: asset.symbol.clone(),
+            residency,
+            pool_id,
+            decimals: asset.decimals,
+            balance,
+            price,
+        };
+
+        match (&asset.balance, &asset.residency) {
+            (Balance::Standard { total, locked }, residency)
+                if !matches!(residency, TokenResidency::Lockup) =>
+            {
+                let total = total.parse::<u128>().unwrap_or(0);
+                let locked = locked.parse::<u128>().unwrap_or(0);
+                let mut balance = total.saturating_sub(locked);
+                if matches!(residency, TokenResidency::Near) {
+                    balance = balance.saturating_sub(NEAR_RESERVE_YOCTO);
+                }
+                if balance > 0 {
+                    holdings.push(holding(balance, residency.clone(), None));
+                }
+            }
+            (Balance::Staked(staking), _) => {
+                for pool in &staking.pools {
+                    let balance = pool.staked_balance.as_yoctonear();
+                    if balance > 0 {
+                        holdings.push(holding(
+                            balance,
+                            TokenResidency::Staked,
+                            Some(pool.pool_id.clone()),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    holdings
+}
+
+/// Allocation a holding belongs to
+fn allocation_of(targets: &[AllocationTarget], holding: &Holding) -> Option<usize> {
+    targets.iter().position(|target| {
+        if holding.is_staked() {
+            target.staked
+        } else {
+            !target.staked
+                && target
+                    .symbols
+                    .iter()
+                    .any(|symbol| symbol.eq_ignore_ascii_case(&holding.symbol))
+        }
+    })
+}
+
+/// Trade selling `amount` of `holding` to fill the `to` allocation
+fn trade_for(
+    holding: &Holding,
+    from: &AllocationTarget,
+    to: &AllocationTarget,
+    amount: u128,
+    amount_usd: f64,
+) -> Option<PlannedTrade> {
+    let trade = |action, receive_token_id: Option<String>, pool_id, note| PlannedTrade {
+        action,
+        from_allocation: from.name.clone(),
+        to_allocation: to.name.clone(),
+        token_id: holding.token_id.clone(),
+        receive_token_id,
+        pool_id,
+        amount: amount.to_string(),
+        amount_usd,
+        note,
+    };
+
+    if holding.is_staked() {
+        let pool = holding.pool_id.clone()?;
+        let note = match to.buy_token_id.as_deref() {
+            Some("near") => format!(
+                "Withdraw the NEAR from {} once it is unstaked (about 4 epochs)",
+                pool
+            ),
+            Some(token) => format!(
+                "Exchange the NEAR for {} once it is unstaked and withdrawn from {} (about 4 epochs)",
+                token, pool
+            ),
+            None => format!("Withdraw the NEAR from {} once it is unstaked", pool),
+        };
+        return Some(trade(TradeAction::Unstake, None, Some(pool), Some(note)));
+    }
+
+    if to.staked {
+        let pool = to.staking_pool_id.clone()?;
+        if matches!(holding.residency, TokenResidency::Near) {
+            return Some(trade(TradeAction::Stake, None, Some(pool), None));
+        }
+        let note = format!(
+            "Stake the received NEAR with {} once the exchange settles",
+            pool
+        );
+        return Some(trade(
+            TradeAction::Exchange,
+            Some("near".to_string()),
+            None,
+            Some(note),
+        ));
+    }
+
+    let receive_token_id = to.buy_token_id.clone()?;
+    if receive_token_id == holding.token_id {
+        return None;
+    }
+    Some(trade(
+        TradeAction::Exchange,
+        Some(receive_token_id),
+        None,
+        None,
+    ))
+}
+
+/// Plan the trades that bring the holdings back to their target allocation
+///
+/// Nothing is traded while every allocation is within its tolerance band; once one drifts
+/// out, all allocations are brought back to their target.
+pub fn plan_rebalance(targets: &[AllocationTarget], holdings: &[Holding]) -> RebalancePlan {
+    let buckets: Vec<Option<usize>> = holdings
+        .iter()
+        .map(|holding| allocation_of(targets, holding))
+        .collect();
+
+    let mut current_usd = vec![0.0; targets.len()];
+    let mut unallocated_usd = 0.0;
+    for (holding, bucket) in holdings.iter().zip(&buckets) {
+        let value = holding.usd_value(holding.balance);
+        match bucket {
+            Some(index) => current_usd[*index] += value,
+            None => unallocated_usd += value,
+        }
+    }
+    let total_usd: f64 = current_usd.iter().sum();
+
+    let allocations: Vec<AllocationStatus> = targets
+        .iter()
+        .zip(&current_usd)
+        .map(|(target, current)| {
+            let current_percent = if total_usd > 0.0 {
+                current / total_usd * 100.0
+            } else {
+                0.0
+            };
+            AllocationStatus {
+                name: target.name.clone(),
+                target_percent: target.target_percent,
+                tolerance_percent: target.tolerance_percent,
+                current_percent,
+                current_usd: *current,
+                target_usd: total_usd * target.target_percent / 100.0,
+                out_of_band: total_usd > 0.0
+                    && (current_percent - target.target_percent).abs() > target.tolerance_percent,
+            }
+        })
+        .collect();
+    let needs_rebalancing = allocations.iter().any(|a| a.out_of_band);
+
+    let mut trades = Vec::new();
+    if needs_rebalancing {
+        let mut sellers: Vec<(usize, f64)> = Vec::new();
+        let mut buyers: Vec<(usize, f64)> = Vec::new();
+        for (index, allocation) in allocations.iter().enumerate() {
+            let delta = allocation.target_usd - allocation.current_usd;
+            if delta < 0.0 {
+                sellers.push((index, -delta));
+            } else if delta > 0.0 {
+                buyers.push((index, delta));
+            }
+        }
+        sellers.sort_by(|a, b| b.1.total_cmp(&a.1));
+        buyers.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        // Amount of every holding that is still unsold, largest holdings sold first
+        let mut remaining: Vec<u128> = holdings.iter().map(|h| h.balance).collect();
+        let mut order: Vec<usize> = (0..holdings.len()).collect();
+        order.sort_by(|a, b| {
+            let value = |i: &usize| holdings[*i].usd_value(holdings[*i].balance);
+            value(b).total_cmp(&value(a))
+        });
+
+        let (mut s, mut b) = (0, 0);
+        while s < sellers.len() && b < buyers.len() {
+            let (from, excess) = sellers[s];
+            let (to, deficit) = buyers[b];
+            let mut usd_left = excess.min(deficit);
+
+            for &i in order.iter().filter(|i| buckets[**i] == Some(from)) {
+                if usd_left < MIN_TRADE_USD {
+                    break;
+                }
+                let holding = &holdings[i];
+                let available_usd = holding.usd_value(remaining[i]);
+                let take_usd = available_usd.min(usd_left);
+                if take_usd < MIN_TRADE_USD {
+                    continue;
+                }
+                let amount = if take_usd >= available_usd {
+                    remaining[i]
+                } else {
+                    match holding.amount_for_usd(take_usd) {
+                        Some(amount) => amount.min(remaining[i]),
+                        None => continue,
+                    }
+                };
+
+                if let Some(trade) =
+                    trade_for(holding, &targets[from], &targets[to], amount, take_usd)
+                {
+                    remaining[i] -= amount;
+                    usd_left -= take_usd;
+                    trades.push(trade);
+                }
+            }
+
+            let moved = excess.min(deficit);
+            sellers[s].1 -= moved;
+            buyers[b].1 -= moved;
+            if sellers[s].1 < MIN_TRADE_USD {
+                s += 1;
+            }
+            if buyers[b].1 < MIN_TRADE_USD {
+                b += 1;
+            }
+        }
+    }
+
+    RebalancePlan {
+        total_usd,
+        unallocated_usd,
+        needs_rebalancing,
+        allocations,
+        trades,
+    }
+}
+
+/// Proposal of a stake or unstake trade
+pub fn staking_proposal(trade: &PlannedTrade) -> Option<ProposalDraft> {
+    let pool = trade.pool_id.as_deref()?;
+    let (action, method_name, args, deposit) = match trade.action {
+        TradeAction::Stake => (
+            "stake",
+            "deposit_and_stake",
+            json!({}),
+            trade.amount.clone(),
+        ),
+        TradeAction::Unstake => (
+            "unstake",
+            "unstake",
+            json!({ "amount": trade.amount }),
+            "0".to_string(),
+        ),
+        TradeAction::Exchange => return None,
+    };
+
+    let description = encode_description(&[
+        ("Proposal Action", action.to_string()),
+        (
+            "Notes",
+            format!(
+                "Rebalancing from {} to {}",
+                trade.from_allocation, trade.to_allocation
+            ),
+        ),
+    ]);
+    let kind = json!({
+        "FunctionCall": {
+            "receiver_id": pool,
+            "actions": [{
+                "method_name": method_name,
+                "args": base64_args(&args),
+                "deposit": deposit,
+                "gas": NearGas::from_tgas(200).as_gas().to_string(),
+            }],
+        }
+    });
+
+    Some(ProposalDraft {
+        description,
+        kind,
+        storage_deposit: None,
+    })
+}
+
+/// Expected output and fees of an exchange from its quote response
+fn exchange_estimate(body: &Value) -> ExchangeEstimate {
+    let quote = &body["quote"];
+    let text = |name: &str| quote[name].as_str().map(str::to_string);
+    let usd = |name: &str| quote[name].as_str().and_then(|v| v.parse::<f64>().ok());
+
+    ExchangeEstimate {
+        amount_out: text("amountOut"),
+        amount_out_formatted: text("amountOutFormatted"),
+        amount_in_usd: text("amountInUsd"),
+        amount_out_usd: text("amountOutUsd"),
+        cost_usd: usd("amountInUsd")
+            .zip(usd("amountOutUsd"))
+            .map(|(amount_in, amount_out)| amount_in - amount_out),
+        time_estimate: quote["timeEstimate"].as_u64(),
+    }
+}
+
+/// Exchange proposal of a trade from its live quote
+pub fn exchange_proposal_draft(
+    trade: &PlannedTrade,
+    receive_token_id: &str,
+    slippage_bps: u32,
+    body: &Value,
+) -> Result<ProposalDraft, String> {
+    let proposal = exchange_proposal(
+        &trade.token_id,
+        receive_token_id,
+        slippage_bps,
+        body,
+        (
+            "Rebalancing",
+            format!("{} to {}", trade.from_allocation, trade.to_allocation),
+        ),
+    )?;
+
+    Ok(ProposalDraft {
+        storage_deposit: deposit_token_contract(&trade.token_id).map(|token_contract| {
+            StorageDepositDraft {
+                receiver_id: token_contract.to_string(),
+                account_id: proposal.deposit_address.clone(),
+                deposit: STORAGE_DEPOSIT_YOCTO.to_string(),
+            }
+        }),
+        description: proposal.description,
+        kind: proposal.kind,
+    })
+}
+
+/// Quote an exchange trade with 1Click: a dry run for its estimate, or a live quote that
+/// also yields its proposal
+async fn quote_exchange(
+    state: &AppState,
+    dao_id: &str,
+    trade: &PlannedTrade,
+    slippage_bps: u32,
+    live: bool,
+) -> Result<(ExchangeEstimate, Option<ProposalDraft>), String> {
+    let receive_token_id = trade
+        .receive_token_id
+        .as_deref()
+        .ok_or_else(|| "Exchange has no token to receive".to_string())?;
+    let amount = trade
+        .amount
+        .parse::<u128>()
+        .map_err(|e| format!("Invalid amount: {}", e))?;
+
+    let request = QuoteRequest::treasury_exchange(
+        dao_id,
+        &trade.token_id,
+        receive_token_id,
+        amount,
+        slippage_bps,
+        !live,
+        Utc::now() + Duration::hours(QUOTE_VALIDITY_HOURS),
+    );
+    let body = request_quote(state, request)
+        .await
+        .map_err(|(_, e)| format!("Failed to get quote: {}", e))?;
+
+    let proposal = if live {
+        Some(exchange_proposal_draft(
+            trade,
+            receive_token_id,
+            slippage_bps,
+            &body,
+        )?)
+    } else {
+        None
+    };
+    Ok((exchange_estimate(&body), proposal))
+}
+
+async fn draft_trade(
+    state: &AppState,
+    dao_id: &str,
+    trade: PlannedTrade,
+    slippage_bps: u32,
+    live: bool,
+) -> DraftTrade {
+    if trade.action != TradeAction::Exchange {
+        return DraftTrade {
+            proposal: staking_proposal(&trade),
+            trade,
+            estimate: None,
+            error: None,
+        };
+    }
+
+    match quote_exchange(state, dao_id, &trade, slippage_bps, live).await {
+        Ok((estimate, proposal)) => DraftTrade {
+            trade,
+            estimate: Some(estimate),
+            proposal,
+            error: None,
+        },
+        Err(e) => DraftTrade {
+            trade,
+            estimate: None,
+            proposal: None,
+            error: Some(e),
+        },
+    }
+}
+
+/// Plan the trades that bring a treasury back to its target allocation
+pub async fn get_rebalancing_plan(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PlanQuery>,
+) -> Result<Json<RebalancePlanResponse>, (StatusCode, String)> {
+    let account_id: AccountId = query.dao_id.parse().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid DAO ID: {}", query.dao_id),
+        )
+    })?;
+    let slippage_bps = query.slippage_bps.unwrap_or(DEFAULT_SLIPPAGE_BPS);
+
+    let targets = load_targets(&state.db_pool, &query.dao_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to load allocation targets: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database error".to_string(),
+            )
+        })?
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("No allocation targets set for {}", query.dao_id),
+        ))?;
+
+    let Json(assets) =
+        get_user_assets(State(state.clone()), Query(UserAssetsQuery { account_id })).await?;
+    let plan = plan_rebalance(&targets.targets.0, &holdings_from_assets(&assets));
+
+    let drafts = join_all(plan.trades.into_iter().map(|trade| {
+        draft_trade(
+            &state,
+            &query.dao_id,
+            trade,
+            slippage_bps,
+            query.live_quotes,
+        )
+    }))
+    .await;
+
+    Ok(Json(RebalancePlanResponse {
+        dao_id: query.dao_id,
+        total_usd: plan.total_usd,
+        unallocated_usd: plan.unallocated_usd,
+        needs_rebalancing: plan.needs_rebalancing,
+        allocations: plan.allocations,
+        drafts,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+    fn target(name: &str, symbols: &[&str], percent: f64, buy: &str) -> AllocationTarget {
+        AllocationTarget {
+            name: name.to_string(),
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            staked: false,
+            target_percent: percent,
+            tolerance_percent: 5.0,
+            buy_token_id: Some(buy.to_string()),
+            staking_pool_id: None,
+        }
+    }
+
+    fn targets() -> Vec<AllocationTarget> {
+        vec![
+            target(
+                "Stablecoins",
+                &["USDC", "USDT"],
+                40.0,
+                "nep141:usdc.omft.near",
+            ),
+            target("NEAR", &["NEAR"], 40.0, "near"),
+            AllocationTarget {
+                name: "Staked NEAR".to_string(),
+                symbols: Vec::new(),
+                staked: true,
+                target_percent: 20.0,
+                tolerance_percent: 5.0,
+                buy_token_id: None,
+                staking_pool_id: Some("astro-stakers.poolv1.near".to_string()),
+            },
+        ]
+    }
+
+    fn holding(token_id: &str, symbol: &str, residency: TokenResidency, usd: f64) -> Holding {
+        let (decimals, price) = if symbol == "NEAR" {
+            (24, 5.0)
+        } else {
+            (6, 1.0)
+        };
+        let mut holding = Holding {
+            token_id: token_id.to_string(),
+            symbol: symbol.to_string(),
+            pool_id: matches!(residency, TokenResidency::Staked)
+                .then(|| "astro-stakers.poolv1.near".to_string()),
+            residency,
+            decimals,
+            balance: 0,
+            price,
+        };
+        holding.balance = holding.amount_for_usd(usd).unwrap();
+        holding
+    }
+
+    #[test]
+    fn test_on_target_holdings_need_no_trades() {
+        let holdings = vec![
+            holding(
+                "nep141:usdc.omft.near",
+                "USDC",
+                TokenResidency::Intents,
+                4200.0,
+            ),
+            holding("near", "NEAR", TokenResidency::Near, 3800.0),
+            holding("near", "NEAR", TokenResidency::Staked, 2000.0),
+        ];
+        let plan = plan_rebalance(&targets(), &holdings);
+
+        assert!((plan.total_usd - 10_000.0).abs() < 0.01);
+        assert!(!plan.needs_rebalancing);
+        assert!(plan.trades.is_empty());
+        assert!((plan.allocations[0].current_percent - 42.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_overweight_stablecoins_buy_near_and_stake() {
+        let holdings = vec![
+            holding(
+                "nep141:usdc.omft.near",
+                "USDC",
+                TokenResidency::Intents,
+                6000.0,
+            ),
+            holding("usdt.tether-token.near", "USDT", TokenResidency::Ft, 2000.0),
+            holding("near", "NEAR", TokenResidency::Near, 2000.0),
+            holding(
+                "nep141:btc.omft.near",
+                "BTC",
+                TokenResidency::Intents,
+                500.0,
+            ),
+        ];
+        let plan = plan_rebalance(&targets(), &holdings);
+
+        assert!(plan.needs_rebalancing);
+        assert!((plan.unallocated_usd - 500.0).abs() < 0.01);
+
+        // Stablecoins sell 4000 USD: 2000 to NEAR and 2000 to staked NEAR
+        let sold: f64 = plan
+            .trades
+            .iter()
+            .filter(|t| t.from_allocation == "Stablecoins")
+            .map(|t| t.amount_usd)
+            .sum();
+        assert!((sold - 4000.0).abs() < 0.01);
+        assert!(
+            plan.trades
+                .iter()
+                .all(|t| t.token_id == "nep141:usdc.omft.near")
+        );
+
+        let to_staked = plan
+            .trades
+            .iter()
+            .find(|t| t.to_allocation == "Staked NEAR")
+            .unwrap();
+        assert_eq!(to_staked.action, TradeAction::Exchange);
+        assert_eq!(to_staked.receive_token_id.as_deref(), Some("near"));
+        assert!(to_staked.note.as_deref().unwrap().contains("astro-stakers"));
+
+        let to_near = plan
+            .trades
+            .iter()
+            .find(|t| t.to_allocation == "NEAR")
+            .unwrap();
+        assert_eq!(to_near.receive_token_id.as_deref(), Some("near"));
+        assert_eq!(to_near.amount, "2000000000");
+    }
+
+    #[test]
+    fn test_liquid_near_is_staked_directly() {
+        let holdings = vec![
+            holding(
+                "nep141:usdc.omft.near",
+                "USDC",
+                TokenResidency::Intents,
+                4000.0,
+            ),
+            holding("near", "NEAR", TokenResidency::Near, 6000.0),
+        ];
+        let plan = plan_rebalance(&targets(), &holdings);
+
+        assert_eq!(plan.trades.len(), 1);
+        let trade = &plan.trades[0];
+        assert_eq!(trade.action, TradeAction::Stake);
+        assert_eq!(trade.pool_id.as_deref(), Some("astro-stakers.poolv1.near"));
+        assert_eq!(trade.amount, (400 * NEAR).to_string());
+
+        let proposal = staking_proposal(trade).unwrap();
+        let call = &proposal.kind["FunctionCall"];
+        assert_eq!(call["receiver_id"], "astro-stakers.poolv1.near");
+        assert_eq!(call["actions"][0]["method_name"], "deposit_and_stake");
+        assert_eq!(call["actions"][0]["deposit"], (400 * NEAR).to_string());
+        assert!(
+            proposal
+                .description
+                .starts_with("* Proposal Action: stake <br>")
+        );
+    }
+
+    #[test]
+    fn test_overweight_staked_near_is_unstaked() {
+        let holdings = vec![
+            holding(
+                "nep141:usdc.omft.near",
+                "USDC",
+                TokenResidency::Intents,
+                4000.0,
+            ),
+            holding("near", "NEAR", TokenResidency::Near, 2000.0),
+            holding("near", "NEAR", TokenResidency::Staked, 4000.0),
+        ];
+        let plan = plan_rebalance(&targets(), &holdings);
+
+        assert_eq!(plan.trades.len(), 1);
+        let trade = &plan.trades[0];
+        assert_eq!(trade.action, TradeAction::Unstake);
+        assert_eq!(trade.to_allocation, "NEAR");
+        assert_eq!(trade.amount, (400 * NEAR).to_string());
+
+        let proposal = staking_proposal(trade).unwrap();
+        assert_eq!(
+            proposal.kind["FunctionCall"]["actions"][0]["method_name"],
+            "unstake"
+        );
+        assert_eq!(proposal.kind["FunctionCall"]["actions"][0]["deposit"], "0");
+    }
+
+    #[test]
+    fn test_holdings_keep_near_reserve() {
+        let assets: Vec<SimplifiedToken> = serde_json::from_value(json!([
+            {
+                "id": "near",
+                "contractId": null,
+                "residency": "Near",
+                "network": "near",
+                "chainName": "Near Protocol",
+                "symbol": "NEAR",
+                "balance": { "Standard": { "total": (3 * NEAR).to_string(), "locked": "0" } },
+                "decimals": 24,
+                "price": "5",
+                "name": "NEAR",
+                "icon": null,
+                "chainIcons": null
+            },
+            {
+                "id": "nep141:usdc.omft.near",
+                "contractId": "usdc.omft.near",
+                "residency": "Intents",
+                "network": "eth",
+                "chainName": "Ethereum",
+                "symbol": "USDC",
+                "balance": { "Standard": { "total": "1000000", "locked": "0" } },
+                "decimals": 6,
+                "price": "0",
+                "name": "USD Coin",
+                "icon": null,
+                "chainIcons": null
+            }
+        ]))
+        .unwrap();
+
+        let holdings = holdings_from_assets(&assets);
+        // Unpriced tokens can't be valued
+        assert_eq!(holdings.len(), 1);
+        assert_eq!(holdings[0].balance, 2 * NEAR);
+    }
+
+    #[test]
+    fn test_exchange_proposal_draft() {
+        let trade = PlannedTrade {
+            action: TradeAction::Exchange,
+            from_allocation: "Stablecoins".to_string(),
+            to_allocation: "NEAR".to_string(),
+            token_id: "usdc.near".to_string(),
+            receive_token_id: Some("near".to_string()),
+            pool_id: None,
+            amount: "1000000".to_string(),
+            amount_usd: 1.0,
+            note: None,
+        };
+        let body = json!({
+            "quote": {
+                "depositAddress": "3e5f2c6a8b1d4e7f9a0b2c4d6e8f0a1b3c5d7e9f1a2b3c4d5e6f7a8b9c0d1e2f",
+                "amountIn": "1000000",
+                "amountInFormatted": "1.0",
+                "amountOut": "200000000000000000000000",
+                "amountOutFormatted": "0.2",
+                "deadline": "2026-03-02T00:00:00.000Z",
+                "timeEstimate": 20
+            },
+            "signature": "ed25519:abc"
+        });
+
+        let draft = exchange_proposal_draft(&trade, "near", 100, &body).unwrap();
+        assert_eq!(draft.kind["FunctionCall"]["receiver_id"], "usdc.near");
+        assert!(
+            draft
+                .description
+                .ends_with("* Rebalancing: Stablecoins to NEAR")
+        );
+        let storage_deposit = draft.storage_deposit.unwrap();
+        assert_eq!(storage_deposit.receiver_id, "usdc.near");
+        assert_eq!(
+            storage_deposit.account_id,
+            "3e5f2c6a8b1d4e7f9a0b2c4d6e8f0a1b3c5d7e9f1a2b3c4d5e6f7a8b9c0d1e2f"
+        );
+        assert_eq!(
+            exchange_estimate(&body).amount_out_formatted.as_deref(),
+            Some("0.2")
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Target allocation endpoints.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use near_api::AccountId;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json as SqlJson;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::auth::AuthUser;
+use crate::handlers::proposals::forecast::can_add_proposal;
+use crate::handlers::proposals::scraper::fetch_policy;
+
+/// Allowed difference between the sum of the target shares and 100%
+const TARGET_SUM_EPSILON: f64 = 0.01;
+
+/// An allocation bucket of a treasury
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationTarget {
+    /// Display name, e.g. "Stablecoins"
+    pub name: String,
+    /// Symbols of the liquid tokens held in the bucket, matched case-insensitively
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    /// The bucket holds NEAR staked with validators
+    #[serde(default)]
+    pub staked: bool,
+    /// Target share of the treasury in percent
+    pub target_percent: f64,
+    /// Allowed deviation from the target in percentage points
+    pub tolerance_percent: f64,
+    /// Token bought when the bucket is underweight: "near", a NEP-141 contract ID, or an
+    /// intents asset ID
+    pub buy_token_id: Option<String>,
+    /// Validator staked with when a staked bucket is underweight
+    pub staking_pool_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationTargets {
+    pub dao_id: String,
+    pub targets: SqlJson<Vec<AllocationTarget>>,
+    pub updated_by: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetsQuery {
+    pub dao_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTargetsRequest {
+    pub dao_id: String,
+    pub targets: Vec<AllocationTarget>,
+}
+
+fn bad_request(message: impl Into<String>) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, message.into())
+}
+
+fn db_error(e: sqlx::Error) -> (StatusCode, String) {
+    log::error!("Allocation target database error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Database error".to_string(),
+    )
+}
+
+/// Validate the allocation buckets of a treasury
+pub fn validate_targets(targets: &[AllocationTarget]) -> Result<(), String> {
+    if targets.is_empty() {
+        return Err("At least one allocation target is required".to_string());
+    }
+
+    let mut names = HashSet::new();
+    let mut symbols = HashSet::new();
+    let mut staked_buckets = 0;
+    for target in targets {
+        let name = target.name.trim();
+        if name.is_empty() {
+            return Err("Allocation targets need a name".to_string());
+        }
+        if !names.insert(name.to_lowercase()) {
+            return Err(format!("Duplicate allocation target: {}", name));
+        }
+        if !(0.0..=100.0).contains(&target.target_percent) {
+            return Err(format!("Target of {} must be between 0 and 100%", name));
+        }
+        if !(0.0..=100.0).contains(&target.tolerance_percent) {
+            return Err(format!("Tolerance of {} must be between 0 and 100%", name));
+        }
+
+        if target.staked {
+            staked_buckets += 1;
+            if !target.symbols.is_empty() {
+                return Err(format!("Staked allocation {} can't list symbols", name));
+            }
+            match &target.staking_pool_id {
+                Some(pool) if pool.parse::<AccountId>().is_ok() => {}
+                _ => return Err(format!("Staked allocation {} needs a staking pool", name)),
+            }
+        } else {
+            if target.symbols.is_empty() {
+                return Err(format!(
+                    "Allocation {} needs at least one token symbol",
+                    name
+                ));
+            }
+            for symbol in &target.symbols {
+                if !symbols.insert(symbol.to_uppercase()) {
+                    return Err(format!("Token {} is in several allocations", symbol));
+                }
+            }
+            if target.buy_token_id.as_deref().is_none_or(str::is_empty) {
+                return Err(format!("Allocation {} needs a token to buy", name));
+            }
+        }
+    }
+    if staked_buckets > 1 {
+        return Err("Only one allocation can hold staked NEAR".to_string());
+    }
+
+    let total: f64 = targets.iter().map(|t| t.target_percent).sum();
+    if (total - 100.0).abs() > TARGET_SUM_EPSILON {
+        return Err(format!("Targets must add up to 100%, got {}%", total));
+    }
+
+    Ok(())
+}
+
+/// Load the allocation targets of a treasury
+pub async fn load_targets(
+    pool: &sqlx::PgPool,
+    dao_id: &str,
+) -> Result<Option<AllocationTargets>, sqlx::Error> {
+    sqlx::query_as::<_, AllocationTargets>(
+        r#"
+        SELECT dao_id, targets, updated_by, updated_at
+        FROM treasury_allocation_targets
+        WHERE dao_id = $1
+        "#,
+    )
+    .bind(dao_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Get the allocation targets of a treasury
+pub async fn get_targets(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<TargetsQuery>,
+) -> Result<Json<AllocationTargets>, (StatusCode, String)> {
+    load_targets(&state.db_pool, &query.dao_id)
+        .await
+        .map_err(db_error)?
+        .map(Json)
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("No allocation targets set for {}", query.dao_id),
+        ))
+}
+
+/// Replace the allocation targets of a treasury
+///
+/// Only accounts that may add function call proposals, which the rebalancing drafts are,
+/// can change the targets.
+pub async fn set_targets(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<SetTargetsRequest>,
+) -> Result<Json<AllocationTargets>, (StatusCode, String)> {
+    validate_targets(&request.targets).map_err(bad_request)?;
+
+    let dao: AccountId = request
+        .dao_id
+        .parse()
+        .map_err(|_| bad_request(format!("Invalid DAO ID: {}", request.dao_id)))?;
+    let policy = fetch_policy(&state.network, &dao).await.map_err(|e| {
+        log::error!("Error fetching policy for {}: {}", request.dao_id, e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to fetch DAO policy".to_string(),
+        )
+    })?;
+    if !can_add_proposal(&policy, "call", &auth_user.account_id) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            format!(
+                "{} is not allowed to create call proposals in {}",
+                auth_user.account_id, request.dao_id
+            ),
+        ));
+    }
+
+    let targets = sqlx::query_as::<_, AllocationTargets>(
+        r#"
+        INSERT INTO treasury_allocation_targets (dao_id, targets, updated_by)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (dao_id) DO UPDATE
+        SET targets = EXCLUDED.targets, updated_by = EXCLUDED.updated_by, updated_at = NOW()
+        RETURNING dao_id, targets, updated_by, updated_at
+        "#,
+    )
+    .bind(&request.dao_id)
+    .bind(SqlJson(&request.targets))
+    .bind(&auth_user.account_id)
+    .fetch_one(&state.db_pool)
+    .await
+    .map_err(db_error)?;
+
+    log::info!(
+        "Allocation targets of {} updated by {}",
+        targets.dao_id,
+        targets.updated_by
+    );
+
+    Ok(Json(targets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(name: &str, symbols: &[&str], target: f64, buy: &str) -> AllocationTarget {
+        AllocationTarget {
+            name: name.to_string(),
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            staked: false,
+            target_percent: target,
+            tolerance_percent: 5.0,
+            buy_token_id: Some(buy.to_string()),
+            staking_pool_id: None,
+        }
+    }
+
+    fn staked(target: f64) -> AllocationTarget {
+        AllocationTarget {
+            name: "Staked NEAR".to_string(),
+            symbols: Vec::new(),
+            staked: true,
+            target_percent: target,
+            tolerance_percent: 5.0,
+            buy_token_id: None,
+            staking_pool_id: Some("astro-stakers.poolv1.near".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_validate_targets() {
+        let valid = vec![
+            tokens(
+                "Stablecoins",
+                &["USDC", "USDT"],
+                40.0,
+                "nep141:usdc.omft.near",
+            ),
+            tokens("NEAR", &["NEAR"], 40.0, "near"),
+            staked(20.0),
+        ];
+        assert!(validate_targets(&valid).is_ok());
+
+        assert!(validate_targets(&[]).is_err());
+
+        let mut wrong_sum = valid.clone();
+        wrong_sum[0].target_percent = 30.0;
+        assert!(validate_targets(&wrong_sum).is_err());
+
+        let mut duplicate_symbol = valid.clone();
+        duplicate_symbol[1].symbols.push("usdc".to_string());
+        assert!(validate_targets(&duplicate_symbol).is_err());
+
+        let mut no_pool = valid.clone();
+        no_pool[2].staking_pool_id = None;
+        assert!(validate_targets(&no_pool).is_err());
+
+        let mut no_buy_token = valid.clone();
+        no_buy_token[0].buy_token_id = None;
+        assert!(validate_targets(&no_buy_token).is_err());
+
+        let mut two_staked = valid.clone();
+        two_staked[1] = staked(40.0);
+        two_staked[1].name = "More staked NEAR".to_string();
+        assert!(validate_targets(&two_staked).is_err());
+    }
+}
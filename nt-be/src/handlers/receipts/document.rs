@@ -0,0 +1,255 @@
+//! Receipt document: contents, backend signature and HTML rendering
+//!
+//! The signature is a compact JWS (HS256) whose payload is the receipt itself, so a receipt
+//! can be verified from the signature alone.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::utils::env::EnvVars;
+
+/// Separates the receipt key from other uses of the secret it is derived from
+const SIGNING_KEY_DOMAIN: &[u8] = b"treasury-payment-receipt:";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentReceipt {
+    /// "transfer_proposal" or "bulk_payment"
+    pub source: String,
+    /// Paying DAO
+    pub payer: String,
+    pub recipient: String,
+    pub token_id: String,
+    pub token_symbol: String,
+    pub token_decimals: u8,
+    /// Amount in base units
+    pub amount: String,
+    /// Amount in whole tokens
+    pub amount_formatted: String,
+    /// USD price of one token on the execution date, if known
+    pub usd_price: Option<String>,
+    pub usd_value: Option<String>,
+    /// Date of the price used (the latest known at execution)
+    pub price_date: Option<NaiveDate>,
+    pub transaction_hash: String,
+    pub block_height: u64,
+    pub executed_at: DateTime<Utc>,
+    #[serde(default)]
+    pub proposal_id: Option<u64>,
+    #[serde(default)]
+    pub list_id: Option<String>,
+    pub issued_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedReceipt {
+    pub receipt: PaymentReceipt,
+    /// Compact JWS of the receipt
+    pub signature: String,
+}
+
+/// Receipt signing key, derived from the configured secret
+fn signing_key(env_vars: &EnvVars) -> Vec<u8> {
+    let secret = env_vars
+        .receipt_signing_secret
+        .as_deref()
+        .unwrap_or(&env_vars.jwt_secret);
+
+    let mut hasher = Sha256::new();
+    hasher.update(SIGNING_KEY_DOMAIN);
+    hasher.update(secret.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+pub fn sign_receipt(receipt: PaymentReceipt, key: &[u8]) -> Result<SignedReceipt, String> {
+    let signature = encode(
+        &Header::new(Algorithm::HS256),
+        &receipt,
+        &EncodingKey::from_secret(key),
+    )
+    .map_err(|e| format!("Failed to sign receipt: {}", e))?;
+
+    Ok(SignedReceipt { receipt, signature })
+}
+
+/// Receipt contained in a signature, if the backend signed it
+pub fn verify_signature(signature: &str, key: &[u8]) -> Result<PaymentReceipt, String> {
+    // Receipts don't expire
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.required_spec_claims.clear();
+    validation.validate_exp = false;
+
+    decode::<PaymentReceipt>(signature, &DecodingKey::from_secret(key), &validation)
+        .map(|data| data.claims)
+        .map_err(|e| format!("Invalid receipt signature: {}", e))
+}
+
+pub fn sign(receipt: PaymentReceipt, env_vars: &EnvVars) -> Result<SignedReceipt, String> {
+    sign_receipt(receipt, &signing_key(env_vars))
+}
+
+pub fn verify(signature: &str, env_vars: &EnvVars) -> Result<PaymentReceipt, String> {
+    verify_signature(signature, &signing_key(env_vars))
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Printable HTML document of a signed receipt
+pub fn render_html(signed: &SignedReceipt) -> String {
+    let receipt = &signed.receipt;
+    let reference = match (&receipt.proposal_id, &receipt.list_id) {
+        (Some(id), _) => format!("Transfer proposal #{}", id),
+        (None, Some(list_id)) => format!("Bulk payment list {}", list_id),
+        (None, None) => "Payment".to_string(),
+    };
+    let usd_value = match (&receipt.usd_value, &receipt.usd_price) {
+        (Some(value), Some(price)) => {
+            format!("${} (1 {} = ${})", value, receipt.token_symbol, price)
+        }
+        _ => "Not available".to_string(),
+    };
+
+    let rows = [
+        ("Reference", reference),
+        ("Payer", receipt.payer.clone()),
+        ("Recipient", receipt.recipient.clone()),
+        (
+            "Amount",
+            format!("{} {}", receipt.amount_formatted, receipt.token_symbol),
+        ),
+        ("Token", receipt.token_id.clone()),
+        ("USD value at execution", usd_value),
+        (
+            "Executed at",
+            receipt
+                .executed_at
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+        ),
+        ("Transaction", receipt.transaction_hash.clone()),
+        ("Block", receipt.block_height.to_string()),
+        (
+            "Issued at",
+            receipt
+                .issued_at
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+        ),
+    ]
+    .iter()
+    .map(|(label, value)| format!("<tr><th>{}</th><td>{}</td></tr>", label, escape_html(value)))
+    .collect::<Vec<_>>()
+    .join("\n");
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Payment receipt - {title}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 720px; margin: 40px auto; color: #111; }}
+table {{ width: 100%; border-collapse: collapse; }}
+th, td {{ text-align: left; padding: 8px; border-bottom: 1px solid #ddd; vertical-align: top; }}
+th {{ width: 200px; color: #555; font-weight: normal; }}
+td {{ word-break: break-all; }}
+.signature {{ margin-top: 32px; font-size: 12px; color: #555; word-break: break-all; }}
+</style>
+</head>
+<body>
+<h1>Payment receipt</h1>
+<table>
+{rows}
+</table>
+<div class="signature">
+<p>Signature (verify at /api/receipts/verify):</p>
+<code>{signature}</code>
+</div>
+</body>
+</html>
+"#,
+        title = escape_html(&receipt.transaction_hash),
+        rows = rows,
+        signature = escape_html(&signed.signature),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt() -> PaymentReceipt {
+        PaymentReceipt {
+            source: "bulk_payment".to_string(),
+            payer: "dao.sputnik-dao.near".to_string(),
+            recipient: "alice.near".to_string(),
+            token_id: "native".to_string(),
+            token_symbol: "NEAR".to_string(),
+            token_decimals: 24,
+            amount: "1500000000000000000000000".to_string(),
+            amount_formatted: "1.5".to_string(),
+            usd_price: Some("3.20".to_string()),
+            usd_value: Some("4.80".to_string()),
+            price_date: Some(NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()),
+            transaction_hash: "8n9W2sbUq1aU5PUX2ZPDXzvxEWRGKXyDBMnDNGcfHg5B".to_string(),
+            block_height: 150_000_000,
+            executed_at: "2026-03-01T12:00:00Z".parse().unwrap(),
+            proposal_id: None,
+            list_id: Some("ab".repeat(32)),
+            issued_at: "2026-03-02T08:30:00Z".parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signed = sign_receipt(receipt(), b"key").unwrap();
+
+        assert_eq!(
+            verify_signature(&signed.signature, b"key").unwrap(),
+            receipt()
+        );
+        assert!(verify_signature(&signed.signature, b"other key").is_err());
+
+        // Changing the payload invalidates the signature
+        let mut parts: Vec<&str> = signed.signature.split('.').collect();
+        let other = sign_receipt(
+            PaymentReceipt {
+                amount: "1".to_string(),
+                ..receipt()
+            },
+            b"other key",
+        )
+        .unwrap();
+        parts[1] = other.signature.split('.').nth(1).unwrap();
+        assert!(verify_signature(&parts.join("."), b"key").is_err());
+    }
+
+    #[test]
+    fn test_render_html_escapes_values() {
+        let signed = sign_receipt(
+            PaymentReceipt {
+                recipient: "<script>alert(1)</script>".to_string(),
+                ..receipt()
+            },
+            b"key",
+        )
+        .unwrap();
+
+        let html = render_html(&signed);
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("1.5 NEAR"));
+        assert!(html.contains("$4.80 (1 NEAR = $3.20)"));
+        assert!(html.contains(&signed.signature));
+    }
+}
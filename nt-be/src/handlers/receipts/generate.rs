@@ -0,0 +1,393 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Days, Utc};
+use near_api::AccountId;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use super::document::{self, PaymentReceipt};
+use crate::AppState;
+use crate::handlers::balance_changes::block_info::get_block_timestamp;
+use crate::handlers::balance_changes::counterparty::{convert_raw_to_decimal, format_decimal};
+use crate::handlers::bulkpayment::events::{PaymentEvent, fetch_payment_events};
+use crate::handlers::bulkpayment::fiat::lookup_rate;
+use crate::handlers::bulkpayment::submit::metadata_contract;
+use crate::handlers::bulkpayment::transactions::resolve_payment;
+use crate::handlers::proposals::scraper::{ProposalStatus, fetch_ft_metadata, fetch_proposal};
+use crate::handlers::proposals::tx::fetch_execution_transaction;
+
+#[derive(Deserialize)]
+pub struct ReceiptQuery {
+    /// "json" (default) or "html"
+    ///
+    /// There is no PDF format: the HTML receipt is laid out for printing, so "Save as PDF"
+    /// in the browser produces the PDF without a PDF renderer in the backend.
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// Payment a receipt is issued for, before pricing and signing
+struct ExecutedPayment {
+    source: &'static str,
+    payer: String,
+    recipient: String,
+    /// Token as accepted by the bulk payment price lookup ("near" for NEAR)
+    token_id: String,
+    amount: String,
+    transaction_hash: String,
+    block_height: u64,
+    executed_at: DateTime<Utc>,
+    proposal_id: Option<u64>,
+    list_id: Option<String>,
+}
+
+fn timestamp_from_nanos(nanos: i64) -> Result<DateTime<Utc>, (StatusCode, String)> {
+    if nanos <= 0 {
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Invalid block timestamp".to_string(),
+        ));
+    }
+    Ok(DateTime::from_timestamp_nanos(nanos))
+}
+
+/// `payment_sent` event of a payment to `recipient` that was not reported failed
+///
+/// The contract emits `payment_sent` only once the transfer succeeded, whereas the list
+/// shows a payment as paid as soon as it is dispatched.
+fn confirmed_payment<'a>(
+    events: &'a [PaymentEvent],
+    recipient: &str,
+) -> Result<&'a PaymentEvent, (StatusCode, String)> {
+    let failed = |index: i32| {
+        events
+            .iter()
+            .any(|e| e.event == "payment_failed" && e.payment_index == index)
+    };
+
+    events
+        .iter()
+        .find(|e| e.event == "payment_sent" && e.recipient == recipient && !failed(e.payment_index))
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                format!("Payment to {} has not been confirmed", recipient),
+            )
+        })
+}
+
+/// Price, sign and render the receipt of an executed payment
+async fn issue_receipt(
+    state: &AppState,
+    payment: ExecutedPayment,
+    format: Option<&str>,
+) -> Result<Response, (StatusCode, String)> {
+    let token_contract: AccountId = metadata_contract(&payment.token_id).parse().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid token ID: {}", payment.token_id),
+        )
+    })?;
+    let metadata = fetch_ft_metadata(&state.cache, &state.network, &token_contract)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to fetch metadata for {}: {}", token_contract, e);
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("Failed to fetch token metadata for {}", payment.token_id),
+            )
+        })?;
+
+    let amount = convert_raw_to_decimal(&payment.amount, metadata.decimals).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Invalid payment amount {}: {}", payment.amount, e),
+        )
+    })?;
+
+    // A receipt is still issued when no price is known for the token
+    let rate = lookup_rate(state, &payment.token_id, payment.executed_at.date_naive())
+        .await
+        .unwrap_or_else(|(_, e)| {
+            log::warn!("USD price of {} unavailable: {}", payment.token_id, e);
+            None
+        });
+    let (usd_price, usd_value, price_date) = match rate {
+        Some((date, price)) => {
+            let value = (&amount * &price).with_scale(2);
+            (
+                Some(format_decimal(&price.normalized())),
+                Some(value.to_string()),
+                Some(date),
+            )
+        }
+        None => (None, None, None),
+    };
+
+    let receipt = PaymentReceipt {
+        source: payment.source.to_string(),
+        payer: payment.payer,
+        recipient: payment.recipient,
+        token_id: payment.token_id,
+        token_symbol: metadata.symbol,
+        token_decimals: metadata.decimals,
+        amount: payment.amount,
+        amount_formatted: format_decimal(&amount.normalized()),
+        usd_price,
+        usd_value,
+        price_date,
+        transaction_hash: payment.transaction_hash,
+        block_height: payment.block_height,
+        executed_at: payment.executed_at,
+        proposal_id: payment.proposal_id,
+        list_id: payment.list_id,
+        issued_at: Utc::now(),
+    };
+
+    let signed = document::sign(receipt, &state.env_vars).map_err(|e| {
+        log::error!("{}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to sign receipt".to_string(),
+        )
+    })?;
+
+    match format.unwrap_or("json") {
+        "json" => Ok(Json(signed).into_response()),
+        "html" => Ok((
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+            document::render_html(&signed),
+        )
+            .into_response()),
+        other => Err((
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported receipt format: {}", other),
+        )),
+    }
+}
+
+/// Receipt of an executed Transfer proposal
+pub async fn get_proposal_receipt(
+    State(state): State<Arc<AppState>>,
+    Path((dao_id, proposal_id)): Path<(AccountId, u64)>,
+    Query(query): Query<ReceiptQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let proposal = fetch_proposal(&state.network, &dao_id, proposal_id)
+        .await
+        .map_err(|e| {
+            log::warn!(
+                "Failed to fetch proposal {} of {}: {}",
+                proposal_id,
+                dao_id,
+                e
+            );
+            (
+                StatusCode::NOT_FOUND,
+                format!("Proposal {} not found", proposal_id),
+            )
+        })?;
+
+    if proposal.status != ProposalStatus::Approved {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Proposal {} has not been executed", proposal_id),
+        ));
+    }
+
+    let transfer = &proposal.kind["Transfer"];
+    let (Some(token_id), Some(receiver_id), Some(amount)) = (
+        transfer["token_id"].as_str(),
+        transfer["receiver_id"].as_str(),
+        transfer["amount"].as_str(),
+    ) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Proposal {} is not a Transfer proposal", proposal_id),
+        ));
+    };
+
+    // The execution is searched between the submission and today
+    let submitted_at = timestamp_from_nanos(proposal.submission_time.0 as i64)?;
+    let today = Utc::now().date_naive();
+    let transaction = fetch_execution_transaction(
+        &state,
+        &dao_id,
+        proposal_id,
+        "VoteApprove",
+        submitted_at.date_naive(),
+        today.checked_add_days(Days::new(1)).unwrap_or(today),
+    )
+    .await?;
+
+    let payment = ExecutedPayment {
+        source: "transfer_proposal",
+        payer: dao_id.to_string(),
+        recipient: receiver_id.to_string(),
+        // An empty token ID transfers NEAR
+        token_id: if token_id.is_empty() {
+            "near".to_string()
+        } else {
+            token_id.to_string()
+        },
+        amount: amount.to_string(),
+        transaction_hash: transaction.transaction_hash,
+        block_height: transaction.block_height,
+        executed_at: timestamp_from_nanos(transaction.timestamp as i64)?,
+        proposal_id: Some(proposal_id),
+        list_id: None,
+    };
+
+    issue_receipt(&state, payment, query.format.as_deref()).await
+}
+
+/// Receipt of a paid bulk payment row
+///
+/// Only issued for a payment with a recorded `payment_sent` event, whose transaction is the
+/// one on the receipt.
+pub async fn get_bulk_payment_receipt(
+    State(state): State<Arc<AppState>>,
+    Path((list_id, recipient)): Path<(String, String)>,
+    Query(query): Query<ReceiptQuery>,
+) -> Result<Response, (StatusCode, String)> {
+    let resolved = resolve_payment(&state, &list_id, &recipient).await?;
+
+    let events = fetch_payment_events(&state.db_pool, &list_id)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to load payment events for list {}: {}", list_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load payment events".to_string(),
+            )
+        })?;
+    let transaction_hash = confirmed_payment(&events, &resolved.recipient)?
+        .transaction_hash
+        .clone();
+
+    let executed_at = get_block_timestamp(&state.archival_network, resolved.block_height, None)
+        .await
+        .map_err(|e| e.to_string());
+    let executed_at = match executed_at {
+        Ok(nanos) => timestamp_from_nanos(nanos)?,
+        Err(e) => {
+            log::error!(
+                "Failed to get timestamp of block {}: {}",
+                resolved.block_height,
+                e
+            );
+            return Err((
+                StatusCode::BAD_GATEWAY,
+                "Failed to get execution time of the payment".to_string(),
+            ));
+        }
+    };
+
+    let payment = ExecutedPayment {
+        source: "bulk_payment",
+        payer: resolved.submitter,
+        recipient: resolved.recipient,
+        token_id: resolved.token_id,
+        amount: resolved.amount,
+        transaction_hash,
+        block_height: resolved.block_height,
+        executed_at,
+        proposal_id: None,
+        list_id: Some(list_id),
+    };
+
+    issue_receipt(&state, payment, query.format.as_deref()).await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReceiptRequest {
+    pub signature: String,
+    /// Receipt to compare with the signed one, if the caller has it
+    #[serde(default)]
+    pub receipt: Option<PaymentReceipt>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReceiptResponse {
+    pub valid: bool,
+    /// Receipt the backend signed
+    pub receipt: Option<PaymentReceipt>,
+    pub error: Option<String>,
+}
+
+/// Check that a receipt was issued by this backend
+pub async fn verify_receipt(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<VerifyReceiptRequest>,
+) -> Json<VerifyReceiptResponse> {
+    let response = match document::verify(&request.signature, &state.env_vars) {
+        Ok(signed) if request.receipt.as_ref().is_some_and(|r| *r != signed) => {
+            VerifyReceiptResponse {
+                valid: false,
+                receipt: Some(signed),
+                error: Some("Receipt does not match its signature".to_string()),
+            }
+        }
+        Ok(signed) => VerifyReceiptResponse {
+            valid: true,
+            receipt: Some(signed),
+            error: None,
+        },
+        Err(e) => VerifyReceiptResponse {
+            valid: false,
+            receipt: None,
+            error: Some(e),
+        },
+    };
+    Json(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event: &str, payment_index: i32, recipient: &str, hash: &str) -> PaymentEvent {
+        PaymentEvent {
+            event: event.to_string(),
+            payment_index,
+            recipient: recipient.to_string(),
+            transaction_hash: hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_confirmed_payment() {
+        let events = vec![
+            event("payment_sent", 0, "alice.near", "tx-alice"),
+            event("payment_sent", 1, "bob.near", "tx-bob"),
+            event("payment_failed", 1, "bob.near", "tx-bob-callback"),
+            event("payment_failed", 2, "carol.near", "tx-carol"),
+            event("payment_sent", 3, "bob.near", "tx-bob-retry"),
+        ];
+
+        assert_eq!(
+            confirmed_payment(&events, "alice.near")
+                .unwrap()
+                .transaction_hash,
+            "tx-alice"
+        );
+        // A payment reported failed doesn't count, another one to the recipient does
+        assert_eq!(
+            confirmed_payment(&events, "bob.near")
+                .unwrap()
+                .transaction_hash,
+            "tx-bob-retry"
+        );
+
+        let (status, _) = confirmed_payment(&events, "carol.near").unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        // Shown as paid on the list but no event recorded yet
+        assert!(confirmed_payment(&events, "dave.near").is_err());
+    }
+}
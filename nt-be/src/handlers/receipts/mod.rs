@@ -0,0 +1,7 @@
+//! Signed payment receipts for recipients of treasury payments
+//!
+//! A receipt documents one executed payment - a Transfer proposal or a bulk payment row -
+//! with its transaction and USD value, signed by the backend so it can be verified later.
+
+pub mod document;
+pub mod generate;
@@ -11,6 +11,7 @@ use crate::{
     AppState,
     auth::AuthUser,
     config::plans::{PlanType, has_gas_covered_credits},
+    handlers::fees::ledger::{FeeSource, PlatformTransaction, record_platform_transaction},
 };
 
 #[derive(Debug, Deserialize)]
@@ -114,14 +115,40 @@ pub async fn relay_delegate_action(
     // Per NEP-366, the relayer sends a transaction to the delegate action's sender_id
     let receiver_id = signed_delegate_action.delegate_action.sender_id.clone();
 
-    let execution_result = Transaction::construct(state.signer_id.clone(), receiver_id)
-        .add_action(Action::Delegate(Box::new(signed_delegate_action)))
-        .with_signer(state.signer.clone())
-        .send_to(&state.network)
+    let network = &state.network;
+    let execution_result = state
+        .relayer_pool
+        .send(move |relayer| {
+            let action = Action::Delegate(Box::new(signed_delegate_action.clone()));
+            let receiver_id = receiver_id.clone();
+            async move {
+                Transaction::construct(relayer.account_id, receiver_id)
+                    .add_action(action)
+                    .with_signer(relayer.signer)
+                    .send_to(network)
+                    .await
+            }
+        })
         .await;
 
+    // The relayer pays the gas whether or not the delegate action succeeds
+    if let Ok((relayer_id, result)) = &execution_result {
+        record_platform_transaction(
+            &state.db_pool,
+            PlatformTransaction {
+                transaction_hash: &result.outcome().transaction_hash.to_string(),
+                signer_id: relayer_id.as_str(),
+                source: FeeSource::Relay,
+                dao_id: Some(&request.treasury_id),
+                on_behalf_of: Some(&sender_id),
+                list_id: None,
+            },
+        )
+        .await;
+    }
+
     match execution_result {
-        Ok(result) => match result.into_result() {
+        Ok((_, result)) => match result.into_result() {
             Ok(_) => {
                 // Step 6: Decrement gas-covered transaction credits
                 let db_result = sqlx::query_as::<_, (i32,)>(
@@ -166,23 +166,31 @@ pub async fn create_treasury(
         (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
     })?;
 
-    Contract(TREASURY_FACTORY_CONTRACT_ID.into())
-        .call_function("create", args)
-        .transaction()
-        .max_gas()
-        .deposit(NearToken::from_near(TREASURY_CREATE_DEPOSIT_IN_NEAR))
-        .with_signer(state.signer_id.clone(), state.signer.clone())
-        .send_to(&state.network)
+    let network = &state.network;
+    let (creator_id, result) = state
+        .relayer_pool
+        .send(move |relayer| {
+            let args = args.clone();
+            async move {
+                Contract(TREASURY_FACTORY_CONTRACT_ID.into())
+                    .call_function("create", args)
+                    .transaction()
+                    .max_gas()
+                    .deposit(NearToken::from_near(TREASURY_CREATE_DEPOSIT_IN_NEAR))
+                    .with_signer(relayer.account_id, relayer.signer)
+                    .send_to(network)
+                    .await
+            }
+        })
         .await
-        .map_err(|e| {
-            eprintln!("Error creating treasury: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-        })?
-        .into_result()
         .map_err(|e| {
             eprintln!("Error creating treasury: {}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
         })?;
+    result.into_result().map_err(|e| {
+        eprintln!("Error creating treasury: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })?;
 
     // Register new DAO in local cache for immediate visibility
     if let Err(e) = register_new_dao(&state.db_pool, treasury.as_str()).await {
@@ -191,7 +199,7 @@ pub async fn create_treasury(
     }
 
     // Fetch balance after treasury creation to track the cost
-    let balance_after = Tokens::account(state.signer_id.clone())
+    let balance_after = Tokens::account(creator_id.clone())
         .near_balance()
         .fetch_from(&state.network)
         .await
@@ -202,7 +210,7 @@ pub async fn create_treasury(
 
     // Send success notification (non-blocking - don't fail request if notification fails)
     let message = format!(
-        "Treasury created: {treasury}\nBalance after: {}",
+        "Treasury created: {treasury}\nCreated by: {creator_id}\nBalance after: {}",
         balance_after.total
     );
     if let Err(e) = state.telegram_client.send_message(&message).await {
@@ -348,7 +348,7 @@ pub async fn get_user_assets(
                 // Filter to only tokens with non-zero balances
                 let tokens_with_balances: Vec<(String, String)> = owned_token_ids
                     .into_iter()
-                    .zip(balances.into_iter())
+                    .zip(balances)
                     .filter(|(_, balance)| balance.parse::<u128>().unwrap_or(0) > 0)
                     .collect();
 
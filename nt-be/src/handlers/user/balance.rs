@@ -68,7 +68,7 @@ pub async fn fetch_near_balance(
 }
 
 /// Fetch FT balance for an account
-async fn fetch_ft_balance(
+pub async fn fetch_ft_balance(
     state: &Arc<AppState>,
     account_id: AccountId,
     token_id: AccountId,
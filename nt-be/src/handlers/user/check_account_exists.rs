@@ -3,7 +3,7 @@ use axum::{
     extract::{Query, State},
     http::StatusCode,
 };
-use near_api::{Account, AccountId};
+use near_api::{Account, AccountId, NetworkConfig};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -20,27 +20,24 @@ pub struct CheckAccountExistsResponse {
     pub exists: bool,
 }
 
+/// Whether an account exists on chain
+pub async fn account_exists(
+    network: &NetworkConfig,
+    account_id: &AccountId,
+) -> Result<bool, String> {
+    match Account(account_id.clone()).view().fetch_from(network).await {
+        Ok(_) => Ok(true),
+        Err(e) if e.to_string().contains("UnknownAccount") => Ok(false),
+        Err(e) => Err(format!("Failed to check account: {}", e)),
+    }
+}
+
 pub async fn check_account_exists(
     State(state): State<Arc<AppState>>,
     Query(params): Query<CheckAccountExistsQuery>,
 ) -> Result<Json<CheckAccountExistsResponse>, (StatusCode, String)> {
-    let account_id = params.account_id;
-
-    match Account(account_id.clone())
-        .view()
-        .fetch_from(&state.network)
+    let exists = account_exists(&state.network, &params.account_id)
         .await
-    {
-        Ok(_) => Ok(Json(CheckAccountExistsResponse { exists: true })),
-        Err(e) => {
-            if e.to_string().contains("UnknownAccount") {
-                Ok(Json(CheckAccountExistsResponse { exists: false }))
-            } else {
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to check account: {}", e),
-                ))
-            }
-        }
-    }
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok(Json(CheckAccountExistsResponse { exists }))
 }
@@ -105,6 +105,22 @@ async fn main() {
         });
     }
 
+    // Spawn intents token registry sync service (tokens, overrides and chains from the database)
+    {
+        let pool = state.db_pool.clone();
+        let http_client = state.http_client.clone();
+        let source = match &state.env_vars.intents_tokens_file {
+            Some(path) => nt_be::services::TokenSource::File(path.into()),
+            None => nt_be::services::TokenSource::OneClick {
+                api_url: state.env_vars.oneclick_api_url.clone(),
+                jwt_token: state.env_vars.oneclick_jwt_token.clone(),
+            },
+        };
+        tokio::spawn(async move {
+            nt_be::services::run_token_registry_service(pool, http_client, source).await;
+        });
+    }
+
     // Spawn bulk payment payout worker
     {
         let state_clone = state.clone();
@@ -134,10 +150,77 @@ async fn main() {
                         log::error!("Payout worker error: {}", e);
                     }
                 }
+
+                // Follow the bridge withdrawals of payments to other chains
+                match nt_be::handlers::bulkpayment::withdrawals::refresh_pending_withdrawals(
+                    &state_clone,
+                )
+                .await
+                {
+                    Ok(settled) => {
+                        if settled > 0 {
+                            log::info!("Settled {} bridge withdrawals", settled);
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Withdrawal status refresh error: {}", e);
+                    }
+                }
             }
         });
     }
 
+    // Spawn payment schedule worker (creates proposals for due recurring payments)
+    {
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            nt_be::handlers::payment_schedules::worker::run_payment_schedule_service(state_clone)
+                .await;
+        });
+    }
+
+    // Spawn exchange order worker (proposes limit and TWAP exchange slices when due)
+    {
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            nt_be::handlers::exchange_orders::worker::run_exchange_order_service(state_clone)
+                .await;
+        });
+    }
+
+    // Spawn swap order worker (links live quotes to exchange proposals and follows the swaps)
+    {
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            nt_be::handlers::intents::swap_orders::run_swap_order_service(state_clone).await;
+        });
+    }
+
+    // Spawn deposit attribution worker (matches incoming intents deposits to labeled addresses)
+    {
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            nt_be::handlers::intents::deposit_labels::run_deposit_attribution_service(state_clone)
+                .await;
+        });
+    }
+
+    // Spawn signer balance monitor (Telegram alerts for low-balance relayer and bulk payment accounts)
+    {
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            nt_be::services::signer_pool::run_signer_balance_service(state_clone).await;
+        });
+    }
+
+    // Spawn fee ledger worker (records the fees burnt by treasury and platform transactions)
+    {
+        let state_clone = state.clone();
+        tokio::spawn(async move {
+            nt_be::handlers::fees::ledger::run_fee_ledger_service(state_clone).await;
+        });
+    }
+
     // Spawn dirty account priority monitoring
     if !state.env_vars.disable_balance_monitoring {
         let state_clone = state.clone();
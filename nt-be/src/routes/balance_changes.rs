@@ -11,6 +11,7 @@ use std::sync::Arc;
 
 use crate::AppState;
 use crate::handlers::balance_changes::gap_filler;
+use crate::handlers::intents::deposit_labels::load_deposit_labels;
 use crate::handlers::token::{TokenMetadata, fetch_tokens_metadata};
 
 #[derive(Debug, Deserialize)]
@@ -77,6 +78,9 @@ pub struct RecentActivity {
     pub receipt_ids: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub swap: Option<SwapInfo>,
+    /// Label of the deposit address an incoming intents deposit was sent to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit_label: Option<String>,
 }
 
 pub async fn get_balance_changes(
@@ -336,6 +340,14 @@ pub async fn get_recent_activity(
         .map(|s| (s.fulfillment_balance_change_id, s))
         .collect();
 
+    // Look up the labels of deposits sent to labeled deposit addresses
+    let mut deposit_labels = load_deposit_labels(&state.db_pool, &change_ids)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load deposit labels: {}", e);
+            Default::default()
+        });
+
     // Get unique token IDs for metadata (from balance changes + swap tokens)
     let mut token_id_set: std::collections::HashSet<String> = changes
         .iter()
@@ -417,6 +429,7 @@ pub async fn get_recent_activity(
                 receipt_ids: change.receipt_id,
                 transaction_hashes: change.transaction_hashes,
                 swap,
+                deposit_label: deposit_labels.remove(&change.id),
             }
         })
         .collect();
@@ -2,7 +2,7 @@ use axum::{
     Json, Router,
     extract::State,
     http::StatusCode,
-    routing::{get, patch, post},
+    routing::{delete, get, patch, post},
 };
 use serde_json::{Value, json};
 use std::sync::Arc;
@@ -38,14 +38,27 @@ async fn health_check(
         ));
     }
 
+    // A signer pool whose accounts are all below the balance threshold still sends, but
+    // needs funding
+    let signers = [
+        state.relayer_pool.health(),
+        state.bulk_payment_pool.health(),
+    ];
+    let status = if signers.iter().all(|pool| pool.healthy) {
+        "healthy"
+    } else {
+        "degraded"
+    };
+
     Ok(Json(json!({
-        "status": "healthy",
+        "status": status,
         "timestamp": chrono::Utc::now().to_rfc3339(),
         "database": {
             "connected": true,
             "pool_size": pool_size,
             "idle_connections": idle_connections
-        }
+        },
+        "signers": signers
     })))
 }
 
@@ -104,6 +117,15 @@ pub fn create_routes(state: Arc<AppState>) -> Router {
             "/api/treasury/create",
             post(handlers::treasury::create::create_treasury)
         )
+        .route(
+            "/api/treasury/allocation-targets",
+            get(handlers::rebalancing::targets::get_targets)
+                .put(handlers::rebalancing::targets::set_targets),
+        )
+        .route(
+            "/api/treasury/rebalancing-plan",
+            get(handlers::rebalancing::planner::get_rebalancing_plan),
+        )
         // User endpoints
         .route(
             "/api/user/balance",
@@ -141,6 +163,10 @@ pub fn create_routes(state: Arc<AppState>) -> Router {
             "/api/user/lockup",
             get(handlers::user::lockup::get_user_lockup),
         )
+        .route(
+            "/api/user/inbox",
+            get(handlers::proposals::inbox::get_approver_inbox),
+        )
         // Proposals endpoints
         .route(
             "/api/proposals/{dao_id}",
@@ -162,10 +188,53 @@ pub fn create_routes(state: Arc<AppState>) -> Router {
             "/api/proposal/{dao_id}/{proposal_id}/tx",
             get(handlers::proposals::tx::find_proposal_execution_transaction),
         )
+        .route(
+            "/api/proposal/{dao_id}/{proposal_id}/execution",
+            get(handlers::proposals::execution::get_proposal_execution),
+        )
+        .route(
+            "/api/proposal/{dao_id}/{proposal_id}/simulate",
+            get(handlers::proposals::simulate::simulate_proposal_execution),
+        )
         .route(
             "/api/receipt/search",
             get(handlers::proposals::tx::search_receipt),
         )
+        .route(
+            "/api/explorer/transactions/{tx_hash}",
+            get(handlers::explorer::transaction::get_transaction),
+        )
+        // Fee ledger endpoints
+        .route(
+            "/api/fees/treasury",
+            get(handlers::fees::reports::get_treasury_fees),
+        )
+        .route(
+            "/api/fees/platform",
+            get(handlers::fees::reports::get_platform_fees),
+        )
+        // Payment schedule endpoints
+        .route(
+            "/api/payment-schedules",
+            get(handlers::payment_schedules::schedules::list_schedules)
+                .post(handlers::payment_schedules::schedules::create_schedule),
+        )
+        .route(
+            "/api/payment-schedules/{id}",
+            get(handlers::payment_schedules::schedules::get_schedule)
+                .patch(handlers::payment_schedules::schedules::update_schedule),
+        )
+        // Exchange order endpoints
+        .route(
+            "/api/exchange-orders",
+            get(handlers::exchange_orders::orders::list_orders)
+                .post(handlers::exchange_orders::orders::create_order),
+        )
+        .route(
+            "/api/exchange-orders/{id}",
+            get(handlers::exchange_orders::orders::get_order)
+                .patch(handlers::exchange_orders::orders::update_order),
+        )
         // Lookup endpoints
         .route(
             "/api/lockup/pool",
@@ -180,6 +249,18 @@ pub fn create_routes(state: Arc<AppState>) -> Router {
             "/api/bulk-payment/submit-list",
             post(handlers::bulkpayment::submit::submit_list),
         )
+        .route(
+            "/api/bulk-payment/import",
+            post(handlers::bulkpayment::import::import_list),
+        )
+        .route(
+            "/api/bulk-payment/run",
+            post(handlers::bulkpayment::runs::create_run),
+        )
+        .route(
+            "/api/bulk-payment/run/{id}",
+            get(handlers::bulkpayment::runs::get_run),
+        )
         .route(
             "/api/bulk-payment/storage-credits",
             get(handlers::bulkpayment::storage_credits::get_storage_credits),
@@ -188,6 +269,18 @@ pub fn create_routes(state: Arc<AppState>) -> Router {
             "/api/bulk-payment/list/{list_id}",
             get(handlers::bulkpayment::transactions::get_list_status),
         )
+        .route(
+            "/api/bulk-payment/list/{list_id}/conversion",
+            get(handlers::bulkpayment::fiat::get_list_conversion),
+        )
+        .route(
+            "/api/bulk-payment/fiat-quote",
+            post(handlers::bulkpayment::fiat::create_fiat_quote),
+        )
+        .route(
+            "/api/bulk-payment/fiat-quote/{id}",
+            get(handlers::bulkpayment::fiat::get_fiat_quote),
+        )
         .route(
             "/api/bulk-payment/list/{list_id}/transactions",
             get(handlers::bulkpayment::transactions::get_transactions),
@@ -196,6 +289,23 @@ pub fn create_routes(state: Arc<AppState>) -> Router {
             "/api/bulk-payment/list/{list_id}/transaction/{recipient}",
             get(handlers::bulkpayment::transactions::get_transaction_hash),
         )
+        .route(
+            "/api/bulk-payment/list/{list_id}/withdrawals",
+            get(handlers::bulkpayment::withdrawals::get_withdrawals),
+        )
+        // Payment receipt endpoints
+        .route(
+            "/api/receipts/proposal/{dao_id}/{proposal_id}",
+            get(handlers::receipts::generate::get_proposal_receipt),
+        )
+        .route(
+            "/api/receipts/bulk-payment/{list_id}/{recipient}",
+            get(handlers::receipts::generate::get_bulk_payment_receipt),
+        )
+        .route(
+            "/api/receipts/verify",
+            post(handlers::receipts::generate::verify_receipt),
+        )
         // Relay endpoints
         .route(
             "/api/relay/delegate-action",
@@ -221,10 +331,24 @@ pub fn create_routes(state: Arc<AppState>) -> Router {
             "/api/intents/deposit-address",
             post(handlers::intents::deposit_address::get_deposit_address),
         )
+        .route(
+            "/api/intents/deposit-addresses",
+            get(handlers::intents::deposit_labels::list_deposit_addresses)
+                .post(handlers::intents::deposit_labels::issue_deposit_address),
+        )
         .route(
             "/api/intents/bridge-tokens",
             get(handlers::intents::bridge_tokens::get_bridge_tokens),
         )
+        .route(
+            "/api/intents/token-overrides",
+            get(handlers::intents::token_overrides::list_token_overrides)
+                .put(handlers::intents::token_overrides::put_token_override),
+        )
+        .route(
+            "/api/intents/token-overrides/{defuse_asset_id}",
+            delete(handlers::intents::token_overrides::remove_token_override),
+        )
         .route(
             "/api/intents/quote",
             post(handlers::intents::quote::get_quote),
@@ -233,6 +357,18 @@ pub fn create_routes(state: Arc<AppState>) -> Router {
             "/api/intents/swap-status",
             get(handlers::intents::swap_status::get_swap_status),
         )
+        .route(
+            "/api/intents/swap-orders/{dao_id}/{proposal_id}",
+            get(handlers::intents::swap_orders::get_swap_order),
+        )
+        .route(
+            "/api/intents/swap-analytics",
+            get(handlers::intents::swap_analytics::get_swap_analytics),
+        )
+        .route(
+            "/api/intents/swap-analytics/csv",
+            get(handlers::intents::swap_analytics::export_swap_analytics_csv),
+        )
         // Proxy endpoints - catch-all for external API
         .route(
             "/api/proxy/{*path}",
@@ -6,6 +6,8 @@ pub mod defillama;
 pub mod price_lookup;
 pub mod price_provider;
 pub mod price_sync;
+pub mod signer_pool;
+pub mod token_registry;
 
 pub use coingecko::CoinGeckoClient;
 pub use dao_sync::{
@@ -15,3 +17,4 @@ pub use defillama::DeFiLlamaClient;
 pub use price_lookup::PriceLookupService;
 pub use price_provider::PriceProvider;
 pub use price_sync::{run_price_sync_service, sync_all_prices_now};
+pub use token_registry::{TokenSource, reload_token_registry, run_token_registry_service};
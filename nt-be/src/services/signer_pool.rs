@@ -0,0 +1,514 @@
+//! Pools of platform signing accounts
+//!
+//! Each signing role (the relayer and the bulk payment contract) has a pool of one or more
+//! accounts, and each account one or more access keys. near-api rotates the keys of an account
+//! and tracks the nonce of every key, so concurrent transactions of one account no longer
+//! collide as long as it has a key per transaction in flight. Transactions are spread over the
+//! accounts round-robin, skipping accounts whose balance fell below the alert threshold.
+//!
+//! A transaction rejected with `InvalidNonce` is sent again; near-api refetches the nonce of
+//! the key from the chain before signing it.
+//!
+//! The first account of a pool is its primary account. Proposals are only added by the primary
+//! relayer account, since DAO roles are granted to that account.
+
+use near_api::{AccountId, NearToken, NetworkConfig, SecretKey, Signer, Tokens};
+use serde::Serialize;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::AppState;
+
+/// Times a transaction is sent again after an `InvalidNonce` rejection
+const MAX_NONCE_RETRIES: usize = 3;
+
+/// Interval between balance checks (5 minutes)
+const BALANCE_CHECK_INTERVAL_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerRole {
+    /// Relaying, payouts, relayer proposals and treasury creation
+    Relayer,
+    /// List submissions signed by the bulk payment contract
+    BulkPayment,
+}
+
+impl SignerRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignerRole::Relayer => "relayer",
+            SignerRole::BulkPayment => "bulk_payment",
+        }
+    }
+}
+
+/// An account of a pool with the signer holding its keys
+#[derive(Clone)]
+pub struct PoolSigner {
+    pub account_id: AccountId,
+    pub signer: Arc<Signer>,
+}
+
+struct PoolAccount {
+    signer: PoolSigner,
+    key_count: usize,
+    balance: RwLock<Option<NearToken>>,
+    low_balance: AtomicBool,
+    sent: AtomicU64,
+    nonce_retries: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl PoolAccount {
+    fn new(signer: PoolSigner, key_count: usize) -> Self {
+        Self {
+            signer,
+            key_count,
+            balance: RwLock::new(None),
+            low_balance: AtomicBool::new(false),
+            sent: AtomicU64::new(0),
+            nonce_retries: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerAccountHealth {
+    pub account_id: String,
+    pub keys: usize,
+    /// yoctoNEAR, unknown until the first balance check
+    pub balance: Option<String>,
+    pub low_balance: bool,
+    pub sent: u64,
+    pub nonce_retries: u64,
+    pub failures: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignerPoolHealth {
+    pub role: SignerRole,
+    /// False when every account is below the balance threshold
+    pub healthy: bool,
+    /// yoctoNEAR
+    pub low_balance_threshold: String,
+    pub accounts: Vec<SignerAccountHealth>,
+}
+
+/// Balance alert to send after a balance check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceAlert {
+    Low,
+    Recovered,
+}
+
+/// Alert for a balance check, sent only when the account crosses the threshold
+pub fn balance_alert(
+    was_low: bool,
+    balance: NearToken,
+    threshold: NearToken,
+) -> Option<BalanceAlert> {
+    let is_low = balance < threshold;
+    match (was_low, is_low) {
+        (false, true) => Some(BalanceAlert::Low),
+        (true, false) => Some(BalanceAlert::Recovered),
+        _ => None,
+    }
+}
+
+/// Whether a send error is a rejection for a stale nonce
+pub fn is_nonce_error(error: &str) -> bool {
+    error.contains("InvalidNonce")
+}
+
+pub struct SignerPool {
+    role: SignerRole,
+    accounts: Vec<PoolAccount>,
+    next: AtomicUsize,
+    low_balance_threshold: NearToken,
+}
+
+impl SignerPool {
+    /// Pool of a single account with a single key
+    pub fn single(role: SignerRole, account_id: AccountId, signer: Arc<Signer>) -> Self {
+        Self {
+            role,
+            accounts: vec![PoolAccount::new(PoolSigner { account_id, signer }, 1)],
+            next: AtomicUsize::new(0),
+            low_balance_threshold: NearToken::from_yoctonear(0),
+        }
+    }
+
+    /// Pool of the primary account and its signer, with more keys and accounts
+    ///
+    /// `extra_keys` are added to the primary account. Each of `accounts` is an account with one
+    /// of its keys; an account listed more than once gets all of its keys.
+    pub async fn new(
+        role: SignerRole,
+        primary_id: AccountId,
+        primary_signer: Arc<Signer>,
+        extra_keys: &[SecretKey],
+        accounts: &[(AccountId, SecretKey)],
+        low_balance_threshold: NearToken,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        for key in extra_keys {
+            primary_signer.add_secret_key_to_pool(key.clone()).await?;
+        }
+        let mut pool_accounts = vec![PoolAccount::new(
+            PoolSigner {
+                account_id: primary_id,
+                signer: primary_signer,
+            },
+            1 + extra_keys.len(),
+        )];
+
+        for (account_id, key) in accounts {
+            match pool_accounts
+                .iter_mut()
+                .find(|account| &account.signer.account_id == account_id)
+            {
+                Some(account) => {
+                    account
+                        .signer
+                        .signer
+                        .add_secret_key_to_pool(key.clone())
+                        .await?;
+                    account.key_count += 1;
+                }
+                None => pool_accounts.push(PoolAccount::new(
+                    PoolSigner {
+                        account_id: account_id.clone(),
+                        signer: Signer::from_secret_key(key.clone())?,
+                    },
+                    1,
+                )),
+            }
+        }
+
+        Ok(Self {
+            role,
+            accounts: pool_accounts,
+            next: AtomicUsize::new(0),
+            low_balance_threshold,
+        })
+    }
+
+    pub fn role(&self) -> SignerRole {
+        self.role
+    }
+
+    /// The primary account
+    pub fn primary(&self) -> PoolSigner {
+        self.accounts[0].signer.clone()
+    }
+
+    /// Accounts of the pool
+    pub fn account_ids(&self) -> Vec<AccountId> {
+        self.accounts
+            .iter()
+            .map(|account| account.signer.account_id.clone())
+            .collect()
+    }
+
+    /// Next account round-robin, skipping low-balance accounts while one is funded
+    fn next_index(&self) -> usize {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..self.accounts.len())
+            .map(|offset| (start + offset) % self.accounts.len())
+            .find(|&index| !self.accounts[index].low_balance.load(Ordering::Relaxed))
+            .unwrap_or(start % self.accounts.len())
+    }
+
+    async fn send_from<T, E, F, Fut>(
+        &self,
+        pick: impl Fn(&Self) -> usize,
+        mut send: F,
+    ) -> Result<(AccountId, T), E>
+    where
+        E: Display,
+        F: FnMut(PoolSigner) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut retries = 0;
+        loop {
+            let account = &self.accounts[pick(self)];
+            account.sent.fetch_add(1, Ordering::Relaxed);
+
+            match send(account.signer.clone()).await {
+                Ok(result) => return Ok((account.signer.account_id.clone(), result)),
+                Err(e) if is_nonce_error(&e.to_string()) && retries < MAX_NONCE_RETRIES => {
+                    retries += 1;
+                    account.nonce_retries.fetch_add(1, Ordering::Relaxed);
+                    log::warn!(
+                        "Transaction from {} rejected for its nonce, retrying ({}/{})",
+                        account.signer.account_id,
+                        retries,
+                        MAX_NONCE_RETRIES
+                    );
+                }
+                Err(e) => {
+                    account.failures.fetch_add(1, Ordering::Relaxed);
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// Send a transaction from the next account of the pool
+    ///
+    /// `send` builds and sends the transaction with the given account; it is called again
+    /// after an `InvalidNonce` rejection. Returns the account that sent the transaction.
+    pub async fn send<T, E, F, Fut>(&self, send: F) -> Result<(AccountId, T), E>
+    where
+        E: Display,
+        F: FnMut(PoolSigner) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.send_from(Self::next_index, send).await
+    }
+
+    /// Send a transaction from the primary account, retrying on `InvalidNonce`
+    pub async fn send_primary<T, E, F, Fut>(&self, send: F) -> Result<T, E>
+    where
+        E: Display,
+        F: FnMut(PoolSigner) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.send_from(|_| 0, send).await.map(|(_, result)| result)
+    }
+
+    pub fn health(&self) -> SignerPoolHealth {
+        SignerPoolHealth {
+            role: self.role,
+            healthy: self
+                .accounts
+                .iter()
+                .any(|account| !account.low_balance.load(Ordering::Relaxed)),
+            low_balance_threshold: self.low_balance_threshold.as_yoctonear().to_string(),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|account| SignerAccountHealth {
+                    account_id: account.signer.account_id.to_string(),
+                    keys: account.key_count,
+                    balance: account
+                        .balance
+                        .read()
+                        .ok()
+                        .and_then(|balance| *balance)
+                        .map(|balance| balance.as_yoctonear().to_string()),
+                    low_balance: account.low_balance.load(Ordering::Relaxed),
+                    sent: account.sent.load(Ordering::Relaxed),
+                    nonce_retries: account.nonce_retries.load(Ordering::Relaxed),
+                    failures: account.failures.load(Ordering::Relaxed),
+                })
+                .collect(),
+        }
+    }
+
+    /// Refresh the balances of the pool accounts
+    ///
+    /// Returns the alerts to send, one per account that crossed the threshold.
+    pub async fn check_balances(&self, network: &NetworkConfig) -> Vec<(AccountId, BalanceAlert)> {
+        let mut alerts = Vec::new();
+        for account in &self.accounts {
+            let account_id = &account.signer.account_id;
+            let balance = match Tokens::account(account_id.clone())
+                .near_balance()
+                .fetch_from(network)
+                .await
+            {
+                Ok(balance) => balance.total,
+                Err(e) => {
+                    log::warn!("Failed to fetch balance of signer {}: {}", account_id, e);
+                    continue;
+                }
+            };
+
+            if let Ok(mut stored) = account.balance.write() {
+                *stored = Some(balance);
+            }
+            let was_low = account.low_balance.load(Ordering::Relaxed);
+            if let Some(alert) = balance_alert(was_low, balance, self.low_balance_threshold) {
+                account
+                    .low_balance
+                    .store(alert == BalanceAlert::Low, Ordering::Relaxed);
+                alerts.push((account_id.clone(), alert));
+            }
+        }
+        alerts
+    }
+}
+
+/// Run the signer balance monitor
+///
+/// Sends a Telegram alert when a pool account falls below the low-balance threshold, and
+/// another once it is funded again.
+pub async fn run_signer_balance_service(state: Arc<AppState>) {
+    log::info!(
+        "Starting signer balance monitor (check interval: {} seconds)",
+        BALANCE_CHECK_INTERVAL_SECS
+    );
+
+    let mut interval = tokio::time::interval(Duration::from_secs(BALANCE_CHECK_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        for pool in [&state.relayer_pool, &state.bulk_payment_pool] {
+            for (account_id, alert) in pool.check_balances(&state.network).await {
+                let message = match alert {
+                    BalanceAlert::Low => format!(
+                        "Low balance: {} signer {} is below {}",
+                        pool.role().as_str(),
+                        account_id,
+                        pool.low_balance_threshold.exact_amount_display()
+                    ),
+                    BalanceAlert::Recovered => format!(
+                        "Balance recovered: {} signer {} is above {}",
+                        pool.role().as_str(),
+                        account_id,
+                        pool.low_balance_threshold.exact_amount_display()
+                    ),
+                };
+                log::warn!("{}", message);
+                if let Err(e) = state.telegram_client.send_message(&message).await {
+                    log::warn!("Failed to send Telegram notification: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balance_alert_only_on_crossing() {
+        let threshold = NearToken::from_near(5);
+
+        assert_eq!(
+            balance_alert(false, NearToken::from_near(4), threshold),
+            Some(BalanceAlert::Low)
+        );
+        assert_eq!(
+            balance_alert(true, NearToken::from_near(3), threshold),
+            None
+        );
+        assert_eq!(
+            balance_alert(true, NearToken::from_near(5), threshold),
+            Some(BalanceAlert::Recovered)
+        );
+        assert_eq!(
+            balance_alert(false, NearToken::from_near(10), threshold),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_nonce_error() {
+        assert!(is_nonce_error(
+            "Transaction error: InvalidTxError(InvalidNonce { tx_nonce: 5, ak_nonce: 7 })"
+        ));
+        assert!(!is_nonce_error("Transaction error: NotEnoughBalance"));
+    }
+
+    fn pool(accounts: &[&str]) -> SignerPool {
+        let signer = Signer::from_secret_key(
+            "ed25519:3tgdk2wPraJzT4nsTuf86UX41xgPNk3MHnq8epARMdBNs29AFEztAuaQ7iHddDfXG9F2RzV1XNQYgJyAyoW51UBB"
+                .parse()
+                .unwrap(),
+        )
+        .unwrap();
+        SignerPool {
+            role: SignerRole::Relayer,
+            accounts: accounts
+                .iter()
+                .map(|account_id| {
+                    PoolAccount::new(
+                        PoolSigner {
+                            account_id: account_id.parse().unwrap(),
+                            signer: signer.clone(),
+                        },
+                        1,
+                    )
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+            low_balance_threshold: NearToken::from_near(5),
+        }
+    }
+
+    #[test]
+    fn test_round_robin_skips_low_balance_accounts() {
+        let pool = pool(&["a.near", "b.near", "c.near"]);
+        let picked: Vec<usize> = (0..4).map(|_| pool.next_index()).collect();
+        assert_eq!(picked, vec![0, 1, 2, 0]);
+
+        pool.accounts[2].low_balance.store(true, Ordering::Relaxed);
+        let picked: Vec<usize> = (0..3).map(|_| pool.next_index()).collect();
+        assert_eq!(picked, vec![1, 0, 0]);
+        assert!(pool.health().healthy);
+
+        // With every account low, keep rotating rather than stop sending
+        for account in &pool.accounts {
+            account.low_balance.store(true, Ordering::Relaxed);
+        }
+        assert_eq!(pool.next_index(), 1);
+        assert!(!pool.health().healthy);
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_nonce_errors() {
+        let pool = pool(&["a.near", "b.near"]);
+        let mut calls = 0;
+
+        let result = pool
+            .send(|account| {
+                calls += 1;
+                let attempt = calls;
+                async move {
+                    if attempt < 3 {
+                        Err("InvalidTxError(InvalidNonce { tx_nonce: 1, ak_nonce: 2 })".to_string())
+                    } else {
+                        Ok(account.account_id.to_string())
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(calls, 3);
+        let (account_id, sent_by) = result.unwrap();
+        assert_eq!(account_id.as_str(), "a.near");
+        assert_eq!(sent_by, "a.near");
+        let health = pool.health();
+        assert_eq!(
+            health.accounts[0].nonce_retries + health.accounts[1].nonce_retries,
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_gives_up_on_other_errors() {
+        let pool = pool(&["a.near"]);
+        let mut calls = 0;
+
+        let result: Result<(AccountId, ()), String> = pool
+            .send(|_| {
+                calls += 1;
+                async { Err("NotEnoughBalance".to_string()) }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+        assert_eq!(pool.health().accounts[0].failures, 1);
+    }
+}
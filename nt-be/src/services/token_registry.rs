@@ -0,0 +1,664 @@
+//! Intents token and chain registry service
+//!
+//! The token lookups in `constants::intents_tokens` start from the bundled data/tokens.json.
+//! This service keeps them current without a redeploy:
+//! - Tokens are synced on a schedule from the 1Click tokens endpoint (or a local file, for
+//!   tests and offline setups) into `intents_tokens`. Tokens missing from the bundled list are
+//!   added as their own unified token, keyed by defuseAssetId: a shared ticker does not make
+//!   two tokens the same asset.
+//! - Admin overrides in `intents_token_overrides` replace a token's symbol, name or icon, or
+//!   move it to another unified token (e.g. a new USDC deployment into `usdc`). Admins edit
+//!   them through the token override endpoints, which reload the registry right away.
+//! - Chains in `intents_chains` add to or replace the bundled chain metadata; chains first seen
+//!   in a sync are added with default icons.
+//!
+//! After each sync the registry is rebuilt and installed, so overrides edited directly in the
+//! database apply on the next tick.
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::constants::intents_chains::{CHAIN_METADATA, ChainIcons, ChainMetadata, ICON_PREFIX};
+use crate::constants::intents_tokens::{
+    BaseTokenInfo, TokenDeployment, TokenRegistry, UnifiedTokenInfo, install_token_registry,
+    load_tokens_from_json,
+};
+
+/// Interval between token registry syncs (15 minutes)
+const SYNC_INTERVAL_SECS: u64 = 900;
+
+/// Where synced tokens come from
+#[derive(Debug, Clone)]
+pub enum TokenSource {
+    /// 1Click API (`{api_url}/v0/tokens`)
+    OneClick {
+        api_url: String,
+        jwt_token: Option<String>,
+    },
+    /// JSON file in the 1Click tokens format
+    File(PathBuf),
+}
+
+/// Token as listed by the 1Click tokens endpoint
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncedToken {
+    /// defuseAssetId, e.g. "nep141:eth-0xa0b8...omft.near"
+    pub asset_id: String,
+    pub decimals: u8,
+    /// Intents chain name
+    pub blockchain: String,
+    pub symbol: String,
+    #[serde(default)]
+    pub contract_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenOverride {
+    pub defuse_asset_id: String,
+    pub symbol: Option<String>,
+    pub name: Option<String>,
+    pub icon: Option<String>,
+    pub unified_asset_id: Option<String>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RegisteredChain {
+    pub chain_name: String,
+    pub name: String,
+    pub icon_dark: String,
+    pub icon_light: String,
+}
+
+/// Bridge of a token not in the bundled list, guessed from its defuseAssetId
+fn token_bridge(token: &SyncedToken) -> &'static str {
+    if token.asset_id.starts_with("nep245:v2_1.omni.hot.tg:") {
+        "hot_omni"
+    } else if token.blockchain == "near" {
+        "direct"
+    } else {
+        "poa"
+    }
+}
+
+impl SyncedToken {
+    fn to_base_token(&self) -> BaseTokenInfo {
+        let bridge = token_bridge(self).to_string();
+        let deployment = match &self.contract_address {
+            Some(address) => TokenDeployment::Fungible {
+                address: address.clone(),
+                decimals: self.decimals,
+                chain_name: self.blockchain.clone(),
+                bridge,
+                stellar_code: None,
+            },
+            None => TokenDeployment::Native {
+                kind: "native".to_string(),
+                decimals: self.decimals,
+                chain_name: self.blockchain.clone(),
+                bridge,
+            },
+        };
+
+        BaseTokenInfo {
+            defuse_asset_id: self.asset_id.clone(),
+            symbol: self.symbol.clone(),
+            name: self.symbol.clone(),
+            decimals: self.decimals,
+            icon: String::new(),
+            origin_chain_name: self.blockchain.clone(),
+            deployments: vec![deployment],
+            tags: None,
+        }
+    }
+}
+
+/// Add a base token to a unified token, creating the unified token if needed
+fn add_to_unified(
+    unified: &mut Vec<UnifiedTokenInfo>,
+    unified_asset_id: &str,
+    base: BaseTokenInfo,
+) {
+    match unified
+        .iter_mut()
+        .find(|u| u.unified_asset_id.eq_ignore_ascii_case(unified_asset_id))
+    {
+        Some(token) => token.grouped_tokens.push(base),
+        None => unified.push(UnifiedTokenInfo {
+            unified_asset_id: unified_asset_id.to_lowercase(),
+            symbol: base.symbol.clone(),
+            name: base.name.clone(),
+            icon: base.icon.clone(),
+            grouped_tokens: vec![base],
+            tags: None,
+        }),
+    }
+}
+
+/// Build the registry from the bundled tokens, synced tokens, admin overrides and chains
+pub fn build_registry(
+    bundled: Vec<UnifiedTokenInfo>,
+    synced: &[SyncedToken],
+    overrides: &[TokenOverride],
+    chains: &[RegisteredChain],
+) -> TokenRegistry {
+    let mut unified = bundled;
+    let known: HashSet<String> = unified
+        .iter()
+        .flat_map(|u| u.grouped_tokens.iter())
+        .map(|b| b.defuse_asset_id.clone())
+        .collect();
+
+    for token in synced {
+        if known.contains(&token.asset_id) {
+            // The bundled metadata is richer; only decimals are taken from the sync
+            unified
+                .iter_mut()
+                .flat_map(|u| u.grouped_tokens.iter_mut())
+                .filter(|b| b.defuse_asset_id == token.asset_id)
+                .for_each(|b| b.decimals = token.decimals);
+        } else {
+            add_to_unified(&mut unified, &token.asset_id, token.to_base_token());
+        }
+    }
+
+    for token_override in overrides {
+        let id = &token_override.defuse_asset_id;
+        for base in unified
+            .iter_mut()
+            .flat_map(|u| u.grouped_tokens.iter_mut())
+            .filter(|b| &b.defuse_asset_id == id)
+        {
+            if let Some(symbol) = &token_override.symbol {
+                base.symbol = symbol.clone();
+            }
+            if let Some(name) = &token_override.name {
+                base.name = name.clone();
+            }
+            if let Some(icon) = &token_override.icon {
+                base.icon = icon.clone();
+            }
+        }
+
+        if let Some(unified_asset_id) = &token_override.unified_asset_id {
+            let mut moved = None;
+            for token in unified.iter_mut() {
+                if let Some(index) = token
+                    .grouped_tokens
+                    .iter()
+                    .position(|b| &b.defuse_asset_id == id)
+                {
+                    moved = Some(token.grouped_tokens.remove(index));
+                }
+            }
+            unified.retain(|u| !u.grouped_tokens.is_empty());
+            if let Some(base) = moved {
+                add_to_unified(&mut unified, unified_asset_id, base);
+            }
+        }
+    }
+
+    let mut chain_metadata: HashMap<String, ChainMetadata> = CHAIN_METADATA.clone();
+    for chain in chains {
+        chain_metadata.insert(
+            chain.chain_name.to_lowercase(),
+            ChainMetadata {
+                name: chain.name.clone(),
+                icon: ChainIcons {
+                    dark: chain.icon_dark.clone(),
+                    light: chain.icon_light.clone(),
+                },
+            },
+        );
+    }
+
+    TokenRegistry::new(unified, chain_metadata)
+}
+
+/// Fetch the token list from the configured source
+pub async fn fetch_synced_tokens(
+    http_client: &reqwest::Client,
+    source: &TokenSource,
+) -> Result<Vec<SyncedToken>, Box<dyn std::error::Error + Send + Sync>> {
+    match source {
+        TokenSource::File(path) => {
+            let json = tokio::fs::read_to_string(path).await?;
+            Ok(serde_json::from_str(&json)?)
+        }
+        TokenSource::OneClick { api_url, jwt_token } => {
+            let mut request = http_client.get(format!("{}/v0/tokens", api_url));
+            if let Some(jwt_token) = jwt_token {
+                request = request.header("Authorization", format!("Bearer {}", jwt_token));
+            }
+            let response = request.send().await?.error_for_status()?;
+            Ok(response.json().await?)
+        }
+    }
+}
+
+/// Store synced tokens and register chains seen for the first time
+pub async fn store_synced_tokens(pool: &PgPool, tokens: &[SyncedToken]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    for token in tokens {
+        sqlx::query(
+            r#"
+            INSERT INTO intents_tokens (defuse_asset_id, symbol, decimals, chain_name, contract_address)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (defuse_asset_id) DO UPDATE SET
+                symbol = EXCLUDED.symbol,
+                decimals = EXCLUDED.decimals,
+                chain_name = EXCLUDED.chain_name,
+                contract_address = EXCLUDED.contract_address,
+                synced_at = NOW()
+            "#,
+        )
+        .bind(&token.asset_id)
+        .bind(&token.symbol)
+        .bind(token.decimals as i16)
+        .bind(&token.blockchain)
+        .bind(&token.contract_address)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let new_chains: HashSet<&str> = tokens
+        .iter()
+        .map(|t| t.blockchain.as_str())
+        .filter(|chain| !CHAIN_METADATA.contains_key(&chain.to_lowercase()))
+        .collect();
+    for chain in new_chains {
+        let icon = format!("{}{}.svg", ICON_PREFIX, chain.to_lowercase());
+        sqlx::query(
+            r#"
+            INSERT INTO intents_chains (chain_name, name, icon_dark, icon_light)
+            VALUES ($1, $2, $3, $3)
+            ON CONFLICT (chain_name) DO NOTHING
+            "#,
+        )
+        .bind(chain.to_lowercase())
+        .bind(chain)
+        .bind(&icon)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await
+}
+
+/// Admin overrides, by defuseAssetId
+pub async fn load_token_overrides(pool: &PgPool) -> Result<Vec<TokenOverride>, sqlx::Error> {
+    sqlx::query_as::<_, TokenOverride>(
+        r#"
+        SELECT defuse_asset_id, symbol, name, icon, unified_asset_id
+        FROM intents_token_overrides
+        ORDER BY defuse_asset_id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Create or replace the override of a token
+pub async fn save_token_override(
+    pool: &PgPool,
+    token_override: &TokenOverride,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO intents_token_overrides (defuse_asset_id, symbol, name, icon, unified_asset_id)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (defuse_asset_id) DO UPDATE SET
+            symbol = EXCLUDED.symbol,
+            name = EXCLUDED.name,
+            icon = EXCLUDED.icon,
+            unified_asset_id = EXCLUDED.unified_asset_id,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(&token_override.defuse_asset_id)
+    .bind(&token_override.symbol)
+    .bind(&token_override.name)
+    .bind(&token_override.icon)
+    .bind(&token_override.unified_asset_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Remove the override of a token. Returns whether there was one.
+pub async fn delete_token_override(
+    pool: &PgPool,
+    defuse_asset_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM intents_token_overrides WHERE defuse_asset_id = $1")
+        .bind(defuse_asset_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Build the registry from the database and install it
+pub async fn reload_token_registry(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let synced = sqlx::query_as::<_, (String, String, i16, String, Option<String>)>(
+        r#"
+        SELECT defuse_asset_id, symbol, decimals, chain_name, contract_address
+        FROM intents_tokens
+        "#,
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(
+        |(asset_id, symbol, decimals, blockchain, contract_address)| SyncedToken {
+            asset_id,
+            decimals: decimals as u8,
+            blockchain,
+            symbol,
+            contract_address,
+        },
+    )
+    .collect::<Vec<_>>();
+
+    let overrides = load_token_overrides(pool).await?;
+
+    let chains = sqlx::query_as::<_, RegisteredChain>(
+        "SELECT chain_name, name, icon_dark, icon_light FROM intents_chains",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let bundled = load_tokens_from_json().unwrap_or_else(|e| {
+        log::error!("Failed to load bundled tokens: {}", e);
+        vec![]
+    });
+    let registry = build_registry(bundled, &synced, &overrides, &chains);
+    log::debug!(
+        "Token registry reloaded: {} tokens, {} chains",
+        registry.defuse_tokens.len(),
+        registry.chains.len()
+    );
+    install_token_registry(registry);
+    Ok(())
+}
+
+/// Run the background token registry sync
+///
+/// Installs the stored registry right away, then syncs from `source` and reloads every
+/// 15 minutes.
+pub async fn run_token_registry_service(
+    pool: PgPool,
+    http_client: reqwest::Client,
+    source: TokenSource,
+) {
+    log::info!(
+        "Starting token registry sync service ({:?}, interval: {} seconds)",
+        source,
+        SYNC_INTERVAL_SECS
+    );
+
+    if let Err(e) = reload_token_registry(&pool).await {
+        log::error!("Failed to load token registry: {}", e);
+    }
+
+    let mut interval = tokio::time::interval(Duration::from_secs(SYNC_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+
+        match fetch_synced_tokens(&http_client, &source).await {
+            Ok(tokens) => {
+                if let Err(e) = store_synced_tokens(&pool, &tokens).await {
+                    log::error!("Failed to store synced tokens: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to fetch tokens for the registry: {}", e),
+        }
+
+        if let Err(e) = reload_token_registry(&pool).await {
+            log::error!("Failed to reload token registry: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC_ETH: &str = "nep141:eth-0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48.omft.near";
+
+    fn synced(asset_id: &str, symbol: &str, decimals: u8, chain: &str) -> SyncedToken {
+        SyncedToken {
+            asset_id: asset_id.to_string(),
+            decimals,
+            blockchain: chain.to_string(),
+            symbol: symbol.to_string(),
+            contract_address: Some("0x1234".to_string()),
+        }
+    }
+
+    fn bundled() -> Vec<UnifiedTokenInfo> {
+        load_tokens_from_json().unwrap()
+    }
+
+    #[test]
+    fn test_build_registry_adds_synced_tokens() {
+        let registry = build_registry(
+            bundled(),
+            &[
+                synced("nep141:base-0xnew.omft.near", "USDC", 6, "base"),
+                synced("nep141:newt.omft.near", "NEWT", 8, "newchain"),
+            ],
+            &[],
+            &[RegisteredChain {
+                chain_name: "newchain".to_string(),
+                name: "Newchain".to_string(),
+                icon_dark: format!("{}newchain.svg", ICON_PREFIX),
+                icon_light: format!("{}newchain.svg", ICON_PREFIX),
+            }],
+        );
+
+        // A token sharing a ticker is not merged into the bundled unified token
+        let usdc = &registry.tokens["usdc"];
+        assert!(
+            !usdc
+                .grouped_tokens
+                .iter()
+                .any(|b| b.defuse_asset_id == "nep141:base-0xnew.omft.near")
+        );
+        assert!(
+            usdc.grouped_tokens
+                .iter()
+                .any(|b| b.defuse_asset_id == USDC_ETH)
+        );
+        assert_eq!(
+            registry.tokens["nep141:base-0xnew.omft.near"]
+                .grouped_tokens
+                .len(),
+            1
+        );
+        // Symbol lookups keep finding the bundled token
+        assert_eq!(registry.symbol_tokens["usdc"].unified_asset_id, "usdc");
+
+        let newt = &registry.defuse_tokens["nep141:newt.omft.near"];
+        assert_eq!(newt.origin_chain_name, "newchain");
+        assert_eq!(newt.decimals, 8);
+        assert_eq!(registry.tokens["nep141:newt.omft.near"].symbol, "NEWT");
+        assert_eq!(registry.chains["newchain"].name, "Newchain");
+        // Bundled chains are kept
+        assert_eq!(registry.chains["eth"].name, "Ethereum");
+    }
+
+    #[test]
+    fn test_build_registry_applies_overrides() {
+        let registry = build_registry(
+            bundled(),
+            &[synced("nep141:newt.omft.near", "NEWT", 8, "newchain")],
+            &[
+                TokenOverride {
+                    defuse_asset_id: "nep141:newt.omft.near".to_string(),
+                    symbol: None,
+                    name: Some("Newton".to_string()),
+                    icon: Some("https://example.com/newt.png".to_string()),
+                    unified_asset_id: Some("newton".to_string()),
+                },
+                TokenOverride {
+                    defuse_asset_id: USDC_ETH.to_string(),
+                    symbol: Some("USDC.e".to_string()),
+                    name: None,
+                    icon: None,
+                    unified_asset_id: Some("usdc-eth".to_string()),
+                },
+            ],
+            &[],
+        );
+
+        let newt = &registry.tokens["newton"];
+        assert_eq!(newt.name, "Newton");
+        assert_eq!(newt.icon, "https://example.com/newt.png");
+        assert!(!registry.tokens.contains_key("nep141:newt.omft.near"));
+
+        let usdc_eth = &registry.defuse_tokens[USDC_ETH];
+        assert_eq!(usdc_eth.symbol, "USDC.e");
+        assert_eq!(usdc_eth.name, "USD Coin");
+        assert_eq!(registry.tokens["usdc-eth"].grouped_tokens.len(), 1);
+        assert!(
+            !registry.tokens["usdc"]
+                .grouped_tokens
+                .iter()
+                .any(|b| b.defuse_asset_id == USDC_ETH)
+        );
+    }
+
+    #[test]
+    fn test_build_registry_groups_tokens_by_override() {
+        let registry = build_registry(
+            bundled(),
+            &[synced("nep141:base-0xnew.omft.near", "USDC", 6, "base")],
+            &[TokenOverride {
+                defuse_asset_id: "nep141:base-0xnew.omft.near".to_string(),
+                symbol: None,
+                name: None,
+                icon: None,
+                unified_asset_id: Some("usdc".to_string()),
+            }],
+            &[],
+        );
+
+        assert!(
+            registry.tokens["usdc"]
+                .grouped_tokens
+                .iter()
+                .any(|b| b.defuse_asset_id == "nep141:base-0xnew.omft.near")
+        );
+        assert!(!registry.tokens.contains_key("nep141:base-0xnew.omft.near"));
+    }
+
+    #[sqlx::test]
+    async fn test_token_overrides_round_trip(pool: PgPool) {
+        let mut token_override = TokenOverride {
+            defuse_asset_id: USDC_ETH.to_string(),
+            symbol: Some("USDC.e".to_string()),
+            name: None,
+            icon: None,
+            unified_asset_id: None,
+        };
+        save_token_override(&pool, &token_override).await.unwrap();
+
+        token_override.symbol = None;
+        token_override.unified_asset_id = Some("usdc-eth".to_string());
+        save_token_override(&pool, &token_override).await.unwrap();
+
+        let overrides = load_token_overrides(&pool).await.unwrap();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].symbol, None);
+        assert_eq!(overrides[0].unified_asset_id.as_deref(), Some("usdc-eth"));
+
+        assert!(delete_token_override(&pool, USDC_ETH).await.unwrap());
+        assert!(!delete_token_override(&pool, USDC_ETH).await.unwrap());
+        assert!(load_token_overrides(&pool).await.unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    async fn test_saved_override_applies_on_reload(pool: PgPool) {
+        use crate::constants::intents_tokens::token_registry;
+
+        let asset_id = "nep141:override-test.omft.near";
+        store_synced_tokens(&pool, &[synced(asset_id, "OVR", 6, "eth")])
+            .await
+            .unwrap();
+        save_token_override(
+            &pool,
+            &TokenOverride {
+                defuse_asset_id: asset_id.to_string(),
+                symbol: Some("OVR.e".to_string()),
+                name: None,
+                icon: Some("https://example.com/ovr.png".to_string()),
+                unified_asset_id: Some("ovr-unified".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+
+        reload_token_registry(&pool).await.unwrap();
+
+        let registry = token_registry();
+        let token = &registry.defuse_tokens[asset_id];
+        assert_eq!(token.symbol, "OVR.e");
+        assert_eq!(token.icon, "https://example.com/ovr.png");
+        assert!(
+            registry.tokens["ovr-unified"]
+                .grouped_tokens
+                .iter()
+                .any(|b| b.defuse_asset_id == asset_id)
+        );
+        assert!(!registry.tokens.contains_key(asset_id));
+    }
+
+    #[test]
+    fn test_build_registry_takes_decimals_of_known_tokens() {
+        let registry = build_registry(bundled(), &[synced(USDC_ETH, "USDC", 18, "eth")], &[], &[]);
+
+        let usdc_eth = &registry.defuse_tokens[USDC_ETH];
+        assert_eq!(usdc_eth.decimals, 18);
+        // Bundled metadata is kept
+        assert_eq!(usdc_eth.name, "USD Coin");
+        assert!(!usdc_eth.icon.is_empty());
+    }
+
+    #[test]
+    fn test_synced_tokens_file_format() {
+        let tokens: Vec<SyncedToken> = serde_json::from_str(
+            r#"[
+                {
+                    "assetId": "nep141:wrap.near",
+                    "decimals": 24,
+                    "blockchain": "near",
+                    "symbol": "wNEAR",
+                    "price": 2.5,
+                    "priceUpdatedAt": "2026-03-01T00:00:00.000Z",
+                    "contractAddress": "wrap.near"
+                },
+                {
+                    "assetId": "nep141:btc.omft.near",
+                    "decimals": 8,
+                    "blockchain": "btc",
+                    "symbol": "BTC"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(tokens[0].contract_address.as_deref(), Some("wrap.near"));
+        assert!(matches!(
+            tokens[1].to_base_token().deployments[0],
+            TokenDeployment::Native { .. }
+        ));
+        assert_eq!(token_bridge(&tokens[0]), "direct");
+        assert_eq!(token_bridge(&tokens[1]), "poa");
+    }
+}
@@ -13,6 +13,12 @@ pub struct EnvVars {
     pub signer_key: SecretKey,
     pub signer_id: AccountId,
     pub bulk_payment_signer: SecretKey,
+    // Signer pools: more keys of the signer accounts, more relayer accounts and the
+    // balance (in NEAR) below which a pool account triggers an alert
+    pub signer_extra_keys: Vec<SecretKey>,
+    pub relayer_pool_accounts: Vec<(AccountId, SecretKey)>,
+    pub bulk_payment_signer_extra_keys: Vec<SecretKey>,
+    pub signer_low_balance_near: u128,
     pub disable_balance_monitoring: bool,
     pub disable_treasury_creation: bool,
     pub monitor_interval_seconds: u64,
@@ -31,11 +37,17 @@ pub struct EnvVars {
     pub oneclick_app_fee_bps: Option<u32>,
     pub oneclick_app_fee_recipient: Option<String>,
     pub oneclick_referral: Option<String>,
+    // Token registry sync reads this 1Click-format tokens file instead of the 1Click API
+    pub intents_tokens_file: Option<String>,
     // JWT authentication configuration
     pub jwt_secret: String,
     pub jwt_expiry_hours: u64,
+    // Payment receipts are signed with this secret (falls back to the JWT secret)
+    pub receipt_signing_secret: Option<String>,
     // CORS configuration
     pub cors_allowed_origins: Vec<String>,
+    // Accounts allowed to use the admin endpoints (platform reports, shared settings)
+    pub admin_account_ids: Vec<String>,
     // Intents Explorer API configuration
     pub intents_explorer_api_key: Option<String>,
     pub intents_explorer_api_url: String,
@@ -74,6 +86,30 @@ impl Default for EnvVars {
                 .expect("SIGNER_ID is not set")
                 .parse()
                 .unwrap(),
+            signer_extra_keys: parse_secret_keys("SIGNER_EXTRA_KEYS"),
+            // Comma-separated "account_id:ed25519:..." entries
+            relayer_pool_accounts: std::env::var("RELAYER_POOL_ACCOUNTS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|entry| {
+                    let (account_id, key) = entry
+                        .split_once(':')
+                        .expect("Invalid RELAYER_POOL_ACCOUNTS entry");
+                    (
+                        account_id
+                            .parse()
+                            .expect("Invalid RELAYER_POOL_ACCOUNTS account"),
+                        key.parse().expect("Invalid RELAYER_POOL_ACCOUNTS key"),
+                    )
+                })
+                .collect(),
+            bulk_payment_signer_extra_keys: parse_secret_keys("BULK_PAYMENT_SIGNER_EXTRA_KEYS"),
+            signer_low_balance_near: std::env::var("SIGNER_LOW_BALANCE_NEAR")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
             disable_balance_monitoring: std::env::var("DISABLE_BALANCE_MONITORING")
                 .unwrap_or_else(|_| "false".to_string())
                 .parse()
@@ -128,12 +164,18 @@ impl Default for EnvVars {
                 .ok()
                 .filter(|s| !s.is_empty())
                 .or_else(|| Some("near-treasury".to_string())),
+            intents_tokens_file: std::env::var("INTENTS_TOKENS_FILE")
+                .ok()
+                .filter(|s| !s.is_empty()),
             // JWT configuration
             jwt_secret: std::env::var("JWT_SECRET").expect("JWT_SECRET is not set"),
             jwt_expiry_hours: std::env::var("JWT_EXPIRY_HOURS")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(72), // Default: 72 hours
+            receipt_signing_secret: std::env::var("RECEIPT_SIGNING_SECRET")
+                .ok()
+                .filter(|s| !s.is_empty()),
             // CORS configuration
             cors_allowed_origins: std::env::var("CORS_ALLOWED_ORIGINS")
                 .unwrap_or_else(|_| "http://localhost:3001,http://localhost:3000".to_string())
@@ -141,6 +183,12 @@ impl Default for EnvVars {
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
                 .collect(),
+            admin_account_ids: std::env::var("ADMIN_ACCOUNT_IDS")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
             // Intents Explorer API configuration
             intents_explorer_api_key: std::env::var("INTENTS_EXPLORER_API_KEY")
                 .ok()
@@ -150,3 +198,17 @@ impl Default for EnvVars {
         }
     }
 }
+
+/// Comma-separated secret keys from an environment variable
+fn parse_secret_keys(name: &str) -> Vec<SecretKey> {
+    std::env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|key| {
+            key.parse()
+                .unwrap_or_else(|_| panic!("Invalid key in {}", name))
+        })
+        .collect()
+}
@@ -5,6 +5,9 @@
 #[cfg(test)]
 use crate::AppState;
 
+#[cfg(test)]
+use crate::services::signer_pool::{SignerPool, SignerRole};
+
 #[cfg(test)]
 use crate::utils::cache::Cache;
 
@@ -111,14 +114,21 @@ pub fn build_test_state(db_pool: sqlx::PgPool) -> AppState {
         None
     };
 
+    let signer =
+        Signer::from_secret_key(env_vars.signer_key.clone()).expect("Failed to create signer.");
+    let bulk_payment_signer = Signer::from_secret_key(env_vars.bulk_payment_signer.clone())
+        .expect("Failed to create bulk payment signer");
+
     AppState {
         cache: Cache::new(),
         telegram_client: crate::utils::telegram::TelegramClient::default(),
         http_client,
-        signer: Signer::from_secret_key(env_vars.signer_key.clone())
-            .expect("Failed to create signer."),
-        bulk_payment_signer: Signer::from_secret_key(env_vars.bulk_payment_signer.clone())
-            .expect("Failed to create bulk payment signer"),
+        relayer_pool: SignerPool::single(SignerRole::Relayer, env_vars.signer_id.clone(), signer),
+        bulk_payment_pool: SignerPool::single(
+            SignerRole::BulkPayment,
+            env_vars.bulk_payment_contract_id.clone(),
+            bulk_payment_signer,
+        ),
         signer_id: env_vars.signer_id.clone(),
         network,
         archival_network,
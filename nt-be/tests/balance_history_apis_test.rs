@@ -453,7 +453,7 @@ async fn test_csv_export_with_real_data() {
 
     // Verify CSV structure (now includes price columns)
     assert!(
-        csv_content.contains("block_height,block_time,token_id,token_symbol,counterparty,amount,balance_before,balance_after,price_usd,value_usd,transaction_hashes,receipt_id"),
+        csv_content.contains("block_height,block_time,token_id,token_symbol,counterparty,amount,balance_before,balance_after,price_usd,value_usd,transaction_hashes,receipt_id,deposit_label"),
         "CSV should have proper headers including price_usd and value_usd columns"
     );
 
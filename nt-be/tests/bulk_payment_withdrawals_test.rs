@@ -0,0 +1,75 @@
+//! Integration tests for bulk payment withdrawals to other chains
+//!
+//! Feeds the logs of a payout transaction through the same steps as the payout worker and
+//! checks which payments end up tracked on the bridge.
+
+use nt_be::handlers::bulkpayment::events::{parse_events, record_events};
+use nt_be::handlers::bulkpayment::withdrawals::record_withdrawals;
+use sqlx::PgPool;
+
+const LIST_ID: &str = "5f2b8c3e1d4a6b7c8e9f0a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d";
+const USDC_ETH: &str = "nep141:eth-0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48.omft.near";
+const PAYOUT_TX: &str = "9hV4NvU4Yk1wYzXWUh7kVfX3mRrQeSxZ5sJrz3T2aBcD";
+
+fn payment_event(event: &str, index: u32, recipient: &str, token_id: &str) -> String {
+    format!(
+        r#"EVENT_JSON:{{"standard":"bulk-payment","version":"1.0.0","event":"{}","data":{{"list_id":"{}","index":{},"recipient":"{}","token_id":"{}","amount":"2500000"}}}}"#,
+        event, LIST_ID, index, recipient, token_id
+    )
+}
+
+/// A PoA payout produces a PENDING withdrawal to the recipient's address on the origin chain;
+/// failed and NEAR-side payments are not tracked
+#[sqlx::test]
+async fn test_poa_payout_tracks_withdrawal(pool: PgPool) -> sqlx::Result<()> {
+    // Logs of a payout_batch transaction, including its on_payment_complete receipts
+    let logs = [
+        format!("Processed 3 payments for list {}, 0 remaining", LIST_ID),
+        payment_event(
+            "payment_sent",
+            0,
+            "0x71C7656EC7ab88b098defB751B7401B5f6d8976F",
+            USDC_ETH,
+        ),
+        payment_event(
+            "payment_failed",
+            1,
+            "0x2B5AD5c4795c026514f8317c7a215E218DcCD6cF",
+            USDC_ETH,
+        ),
+        payment_event("payment_sent", 2, "alice.near", "nep141:wrap.near"),
+    ];
+
+    let events = parse_events(logs.iter().map(String::as_str));
+    assert_eq!(events.len(), 3);
+    record_events(&pool, PAYOUT_TX, &events).await;
+    record_withdrawals(&pool, PAYOUT_TX, &events).await;
+    // The worker may see the same transaction again
+    record_withdrawals(&pool, PAYOUT_TX, &events).await;
+
+    let withdrawals: Vec<(i32, String, String, String, String, String)> = sqlx::query_as(
+        r#"
+        SELECT payment_index, chain, address, amount, transaction_hash, status
+        FROM bulk_payment_withdrawals
+        WHERE list_id = $1
+        ORDER BY payment_index
+        "#,
+    )
+    .bind(LIST_ID)
+    .fetch_all(&pool)
+    .await?;
+
+    assert_eq!(
+        withdrawals,
+        vec![(
+            0,
+            "eth".to_string(),
+            "0x71C7656EC7ab88b098defB751B7401B5f6d8976F".to_string(),
+            "2500000".to_string(),
+            PAYOUT_TX.to_string(),
+            "PENDING".to_string(),
+        )]
+    );
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use near_api::{NetworkConfig, RPCEndpoint, Signer};
 use nt_be::AppState;
+use nt_be::services::signer_pool::{SignerPool, SignerRole};
 use std::process::{Child, Command};
 use std::sync::Once;
 use std::time::Duration;
@@ -100,14 +101,21 @@ pub fn build_test_state(db_pool: sqlx::PgPool) -> AppState {
         None
     };
 
+    let signer =
+        Signer::from_secret_key(env_vars.signer_key.clone()).expect("Failed to create signer.");
+    let bulk_payment_signer = Signer::from_secret_key(env_vars.bulk_payment_signer.clone())
+        .expect("Failed to create bulk payment signer");
+
     AppState {
         cache: nt_be::utils::cache::Cache::new(),
         telegram_client: nt_be::utils::telegram::TelegramClient::default(),
         http_client,
-        signer: Signer::from_secret_key(env_vars.signer_key.clone())
-            .expect("Failed to create signer."),
-        bulk_payment_signer: Signer::from_secret_key(env_vars.bulk_payment_signer.clone())
-            .expect("Failed to create bulk payment signer"),
+        relayer_pool: SignerPool::single(SignerRole::Relayer, env_vars.signer_id.clone(), signer),
+        bulk_payment_pool: SignerPool::single(
+            SignerRole::BulkPayment,
+            env_vars.bulk_payment_contract_id.clone(),
+            bulk_payment_signer,
+        ),
         signer_id: env_vars.signer_id.clone(),
         network,
         archival_network,
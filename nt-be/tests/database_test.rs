@@ -40,4 +40,17 @@ async fn test_health_endpoint() {
     // Check that database is actually connected
     assert_eq!(database["connected"], true);
     assert!(database["pool_size"].as_u64().unwrap() > 0);
+
+    // Verify signer pools section
+    let signers = json["signers"]
+        .as_array()
+        .expect("signers should be an array");
+    assert_eq!(signers.len(), 2);
+    assert_eq!(signers[0]["role"], "relayer");
+    assert_eq!(signers[1]["role"], "bulk_payment");
+    assert!(
+        signers[0]["accounts"]
+            .as_array()
+            .is_some_and(|a| !a.is_empty())
+    );
 }
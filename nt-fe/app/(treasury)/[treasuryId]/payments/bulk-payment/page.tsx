@@ -27,6 +27,7 @@ import {
     generateListId,
     submitPaymentList,
     buildApproveListProposal,
+    buildAttachStorageBudgetTransaction,
     BULK_PAYMENT_CONTRACT_ID,
 } from "@/lib/bulk-payment-api";
 import { getBatchStorageDepositIsRegistered } from "@/lib/api";
@@ -38,7 +39,7 @@ export default function BulkPaymentPage() {
     const router = useRouter();
     const queryClient = useQueryClient();
     const { treasuryId: selectedTreasury } = useTreasury();
-    const { createProposal } = useNear();
+    const { createProposal, signAndSendDelegateAction } = useNear();
     const { data: policy } = useTreasuryPolicy(selectedTreasury);
 
     const [step, setStep] = useState(0);
@@ -226,32 +227,8 @@ export default function BulkPaymentPage() {
                     });
                 }
 
-                // Add storage deposits for unregistered recipients
-                const unregisteredRecipients = paymentData.filter(
-                    (payment) =>
-                        payment.isRegistered === false &&
-                        !payment.validationError,
-                );
-
-                for (const payment of unregisteredRecipients) {
-                    additionalTransactions.push({
-                        receiverId: selectedToken.address,
-                        actions: [
-                            {
-                                type: "FunctionCall",
-                                params: {
-                                    methodName: "storage_deposit",
-                                    args: {
-                                        account_id: payment.recipient,
-                                        registration_only: true,
-                                    } as any,
-                                    gas,
-                                    deposit: depositInYocto,
-                                },
-                            } as any,
-                        ],
-                    });
-                }
+                // Unregistered recipients are registered by the contract from
+                // the list's storage budget, attached once the list is submitted
             }
 
             // Create proposal (throws on failure)
@@ -308,6 +285,21 @@ export default function BulkPaymentPage() {
                     );
                 }
 
+                if (submitResult.storageBudget) {
+                    await signAndSendDelegateAction(
+                        {
+                            delegateActions: [
+                                buildAttachStorageBudgetTransaction(
+                                    listId,
+                                    submitResult.storageBudget,
+                                ),
+                            ],
+                            network: "mainnet",
+                        },
+                        selectedTreasury,
+                    );
+                }
+
                 toast.dismiss(loadingToastId);
 
                 toast.success("Bulk Payment Request submitted", {
@@ -236,6 +236,90 @@ export async function getBalanceChart(
     }
 }
 
+export interface SwapExecution {
+    detectedSwapId: number;
+    transactionHash: string;
+    settledAt: string;
+    proposalId: number | null;
+    sentTokenId: string | null;
+    sentAmount: string | null;
+    receivedTokenId: string;
+    receivedAmount: string;
+    quotedAmountOut: string | null;
+    minAmountOut: string | null;
+    slippageBps: number | null; // Realized output below the quote
+    sentUsd: number | null;
+    receivedUsd: number | null;
+    effectiveRate: number | null;
+    referenceRate: number | null; // From the USD prices of the settlement day
+    rateDeviationBps: number | null;
+    appFeeBps: number;
+    appFeeUsd: number | null;
+    totalCostUsd: number | null; // USD sent minus USD received
+}
+
+export interface SwapSummary {
+    swapCount: number;
+    quotedSwapCount: number;
+    totalSentUsd: number;
+    totalReceivedUsd: number;
+    totalAppFeesUsd: number;
+    totalCostUsd: number;
+    averageSlippageBps: number | null;
+    averageRateDeviationBps: number | null;
+}
+
+export interface SwapAnalytics {
+    swaps: SwapExecution[];
+    summary: SwapSummary;
+    monthly: (SwapSummary & { month: string })[];
+}
+
+/**
+ * Get execution quality of the treasury's swaps: quoted vs realized output,
+ * rates vs reference prices and fees, per swap and per month
+ */
+export async function getSwapAnalytics(
+    accountId: string,
+    startTime: string,
+    endTime: string,
+): Promise<SwapAnalytics | null> {
+    if (!accountId) return null;
+
+    try {
+        const queryParams = new URLSearchParams({
+            accountId,
+            startTime,
+            endTime,
+        });
+        const response = await axios.get<SwapAnalytics>(
+            `${BACKEND_API_BASE}/intents/swap-analytics?${queryParams.toString()}`,
+        );
+        return response.data;
+    } catch (error) {
+        console.error("Error getting swap analytics", error);
+        return null;
+    }
+}
+
+/**
+ * URL of the swap analytics CSV export (monthly summary, or every swap)
+ */
+export function getSwapAnalyticsCsvUrl(
+    accountId: string,
+    startTime: string,
+    endTime: string,
+    view: "monthly" | "swaps" = "monthly",
+): string {
+    const queryParams = new URLSearchParams({
+        accountId,
+        startTime,
+        endTime,
+        view,
+    });
+    return `${BACKEND_API_BASE}/intents/swap-analytics/csv?${queryParams.toString()}`;
+}
+
 export interface TokenBalance {
     accountId: string;
     tokenId: string;
@@ -282,6 +366,8 @@ export interface RecentActivity {
     transactionHashes: string[];
     receiptIds: string[];
     swap?: SwapInfo;
+    /** Label of the deposit address an incoming intents deposit was sent to */
+    depositLabel?: string;
 }
 
 export interface RecentActivityResponse {
@@ -812,6 +898,8 @@ export interface BulkPaymentListStatus {
     totalPayments: number;
     processedPayments: number;
     pendingPayments: number;
+    /** Processed payments whose transfer failed and was refunded */
+    failedPayments: number;
 }
 
 export interface BulkPaymentListStatusResponse {
@@ -824,6 +912,10 @@ export interface BulkPaymentTransaction {
     recipient: string;
     amount: string;
     blockHeight: number;
+    /** Token of the payment in a mixed-token list */
+    tokenId?: string;
+    /** Payout transaction, when its payment_sent event was recorded */
+    transactionHash?: string;
 }
 
 export interface BulkPaymentTransactionsResponse {
@@ -1115,6 +1207,172 @@ export async function searchReceipt(
     }
 }
 
+export type OutcomeStatus = "success" | "failure" | "pending";
+
+export interface ExplorerAction {
+    kind: string;
+    methodName?: string;
+    /** Function call args decoded from JSON */
+    args?: unknown;
+    /** Function call args that aren't JSON */
+    argsBase64?: string;
+    gas?: number;
+    deposit?: string;
+    details?: Record<string, unknown>;
+    delegatedActions?: ExplorerAction[];
+}
+
+export interface ExplorerReceipt {
+    receiptId: string;
+    predecessorId: string;
+    receiverId: string;
+    /** Gas and deposit refunds sent by the protocol */
+    isRefund: boolean;
+    blockHash: string | null;
+    actions: ExplorerAction[];
+    status: OutcomeStatus;
+    returnValue?: unknown;
+    failure?: unknown;
+    logs: string[];
+    gasBurnt: number;
+    tokensBurnt: string;
+    children: ExplorerReceipt[];
+}
+
+export interface ExplorerBalanceDelta {
+    accountId: string;
+    standard: "near" | "nep141" | "nep245";
+    contractId: string | null;
+    tokenId: string;
+    /** Signed amount in the token's base units */
+    delta: string;
+}
+
+export interface ExplorerTransaction {
+    transactionHash: string;
+    signerId: string;
+    receiverId: string;
+    blockHash: string;
+    blockHeight: number;
+    /** Block timestamp in nanoseconds */
+    blockTimestamp: number;
+    status: OutcomeStatus;
+    failure?: unknown;
+    actions: ExplorerAction[];
+    gasBurnt: number;
+    /** Fees burnt in yoctoNEAR, paid by the signer */
+    tokensBurnt: string;
+    receipts: ExplorerReceipt[];
+    balanceDeltas: ExplorerBalanceDelta[];
+}
+
+/**
+ * Get a transaction with its receipt tree, decoded actions, fees and balance changes.
+ * The signer is looked up from the treasury's balance changes when not given.
+ */
+export async function getExplorerTransaction(
+    txHash: string,
+    signerId?: string,
+): Promise<ExplorerTransaction | null> {
+    if (!txHash) return null;
+
+    try {
+        const url = `${BACKEND_API_BASE}/explorer/transactions/${txHash}`;
+        const response = await axios.get<ExplorerTransaction>(url, {
+            params: { signerId },
+        });
+        return response.data;
+    } catch (error) {
+        console.error(`Error getting transaction ${txHash}`, error);
+        return null;
+    }
+}
+
+export type FeePayerKind = "treasury" | "member" | "relayer" | "other";
+
+export type FeeSource =
+    | "relay"
+    | "payout"
+    | "list_submission"
+    | "relayer_proposal"
+    | "treasury_activity";
+
+export interface MonthlyFees {
+    /** "YYYY-MM" */
+    month: string;
+    payerId: string;
+    payerKind: FeePayerKind;
+    source: FeeSource;
+    daoId: string | null;
+    transactions: number;
+    gasBurnt: number;
+    /** NEAR */
+    tokensBurnt: string;
+    /** Null when a NEAR price is missing for one of the days */
+    usd: number | null;
+}
+
+export interface MonthlyFeeTotal {
+    /** "YYYY-MM" */
+    month: string;
+    transactions: number;
+    gasBurnt: number;
+    /** NEAR */
+    tokensBurnt: string;
+    usd: number | null;
+}
+
+export interface FeeReport {
+    entries: MonthlyFees[];
+    totals: MonthlyFeeTotal[];
+}
+
+/**
+ * Get the monthly gas fees burnt for a treasury, by payer (treasury, members or the
+ * platform relayer) and by what the transactions were sent for
+ */
+export async function getTreasuryFees(
+    daoId: string,
+    startTime: string,
+    endTime: string,
+): Promise<FeeReport | null> {
+    if (!daoId) return null;
+
+    try {
+        const queryParams = new URLSearchParams({ daoId, startTime, endTime });
+        const response = await axios.get<FeeReport>(
+            `${BACKEND_API_BASE}/fees/treasury?${queryParams.toString()}`,
+        );
+        return response.data;
+    } catch (error) {
+        console.error("Error getting treasury fees", error);
+        return null;
+    }
+}
+
+/**
+ * Get the monthly gas fees paid by the platform keys, by treasury and by what the
+ * transactions were sent for
+ */
+export async function getPlatformFees(
+    startTime: string,
+    endTime: string,
+    payerId?: string,
+): Promise<FeeReport | null> {
+    try {
+        const queryParams = new URLSearchParams({ startTime, endTime });
+        if (payerId) queryParams.set("payerId", payerId);
+        const response = await axios.get<FeeReport>(
+            `${BACKEND_API_BASE}/fees/platform?${queryParams.toString()}`,
+            { withCredentials: true },
+        );
+        return response.data;
+    } catch (error) {
+        console.error("Error getting platform fees", error);
+        return null;
+    }
+}
+
 export interface RelayDelegateActionResponse {
     success: boolean;
     error?: string;
@@ -1158,3 +1416,263 @@ export async function relayDelegateAction(
     );
     return response.data;
 }
+
+export type ExchangeOrderType = "limit" | "twap";
+export type ExchangeOrderStatus =
+    | "active"
+    | "paused"
+    | "completed"
+    | "cancelled"
+    | "expired";
+
+export interface ExchangeOrder {
+    id: number;
+    daoId: string;
+    createdBy: string;
+    orderType: ExchangeOrderType;
+    notes: string | null;
+    sellTokenId: string;
+    receiveTokenId: string;
+    amount: string;
+    slippageBps: number;
+    triggerPriceUsd: number | null;
+    triggerDirection: "above" | "below" | null;
+    sliceCount: number;
+    slicesCreated: number;
+    startAt: string;
+    endAt: string | null;
+    nextRunAt: string | null;
+    status: ExchangeOrderStatus;
+    createdAt: string;
+    updatedAt: string;
+}
+
+export interface ExchangeOrderSlice {
+    id: number;
+    orderId: number;
+    sliceIndex: number;
+    scheduledFor: string;
+    amountIn: string;
+    status: "pending" | "created" | "failed";
+    priceUsd: number | null;
+    depositAddress: string | null;
+    amountOut: string | null;
+    proposalId: number | null;
+    error: string | null;
+    createdAt: string;
+    updatedAt: string;
+}
+
+export interface ExchangeOrderDetails extends ExchangeOrder {
+    slices: ExchangeOrderSlice[];
+}
+
+export interface CreateExchangeOrderRequest {
+    daoId: string;
+    orderType: ExchangeOrderType;
+    notes?: string;
+    sellTokenId: string;
+    receiveTokenId: string;
+    amount: string;
+    slippageBps: number;
+    triggerPriceUsd?: number;
+    triggerDirection?: "above" | "below";
+    sliceCount?: number;
+    startAt?: string;
+    endAt?: string;
+}
+
+/**
+ * Create a limit or TWAP exchange order
+ * The backend proposes each slice as an exchange proposal when it is due
+ */
+export async function createExchangeOrder(
+    request: CreateExchangeOrderRequest,
+): Promise<ExchangeOrder> {
+    const url = `${BACKEND_API_BASE}/exchange-orders`;
+    const response = await axios.post<ExchangeOrder>(url, request, {
+        withCredentials: true,
+    });
+    return response.data;
+}
+
+/**
+ * List the exchange orders of a treasury
+ */
+export async function getExchangeOrders(
+    daoId: string,
+): Promise<ExchangeOrder[]> {
+    try {
+        const url = `${BACKEND_API_BASE}/exchange-orders`;
+        const response = await axios.get<ExchangeOrder[]>(url, {
+            params: { daoId },
+            withCredentials: true,
+        });
+        return response.data;
+    } catch (error) {
+        console.error(`Error getting exchange orders of ${daoId}`, error);
+        return [];
+    }
+}
+
+/**
+ * Get an exchange order with the proposals created for its slices
+ */
+export async function getExchangeOrder(
+    id: number,
+): Promise<ExchangeOrderDetails | null> {
+    try {
+        const url = `${BACKEND_API_BASE}/exchange-orders/${id}`;
+        const response = await axios.get<ExchangeOrderDetails>(url, {
+            withCredentials: true,
+        });
+        return response.data;
+    } catch (error) {
+        console.error(`Error getting exchange order ${id}`, error);
+        return null;
+    }
+}
+
+/**
+ * Pause, resume or cancel an exchange order
+ */
+export async function updateExchangeOrderStatus(
+    id: number,
+    status: "active" | "paused" | "cancelled",
+): Promise<ExchangeOrder> {
+    const url = `${BACKEND_API_BASE}/exchange-orders/${id}`;
+    const response = await axios.patch<ExchangeOrder>(
+        url,
+        { status },
+        { withCredentials: true },
+    );
+    return response.data;
+}
+
+export interface AllocationTarget {
+    name: string;
+    /** Symbols of the liquid tokens held in the allocation */
+    symbols: string[];
+    /** The allocation holds NEAR staked with validators */
+    staked: boolean;
+    targetPercent: number;
+    tolerancePercent: number;
+    /** Token bought when the allocation is underweight */
+    buyTokenId?: string | null;
+    /** Validator staked with when a staked allocation is underweight */
+    stakingPoolId?: string | null;
+}
+
+export interface AllocationTargets {
+    daoId: string;
+    targets: AllocationTarget[];
+    updatedBy: string;
+    updatedAt: string;
+}
+
+export interface AllocationStatus {
+    name: string;
+    targetPercent: number;
+    tolerancePercent: number;
+    currentPercent: number;
+    currentUsd: number;
+    targetUsd: number;
+    outOfBand: boolean;
+}
+
+export interface RebalancingDraft {
+    action: "exchange" | "stake" | "unstake";
+    fromAllocation: string;
+    toAllocation: string;
+    tokenId: string;
+    receiveTokenId: string | null;
+    poolId: string | null;
+    amount: string;
+    amountUsd: number;
+    note: string | null;
+    /** Exchanges: expected output and fees from their quote */
+    estimate: {
+        amountOut: string | null;
+        amountOutFormatted: string | null;
+        amountInUsd: string | null;
+        amountOutUsd: string | null;
+        costUsd: number | null;
+        timeEstimate: number | null;
+    } | null;
+    /** The proposal to submit: stakes and unstakes always, exchanges with live quotes */
+    proposal: {
+        description: string;
+        kind: any;
+        /** Exchanges of NEAR tokens: registration of the deposit address, sent alongside */
+        storageDeposit?: {
+            receiverId: string;
+            accountId: string;
+            deposit: string;
+        };
+    } | null;
+    error: string | null;
+}
+
+export interface RebalancingPlan {
+    daoId: string;
+    totalUsd: number;
+    unallocatedUsd: number;
+    needsRebalancing: boolean;
+    allocations: AllocationStatus[];
+    drafts: RebalancingDraft[];
+}
+
+/**
+ * Get the target allocation of a treasury, null when none is set
+ */
+export async function getAllocationTargets(
+    daoId: string,
+): Promise<AllocationTargets | null> {
+    try {
+        const url = `${BACKEND_API_BASE}/treasury/allocation-targets`;
+        const response = await axios.get<AllocationTargets>(url, {
+            params: { daoId },
+        });
+        return response.data;
+    } catch (error) {
+        console.error(`Error getting allocation targets of ${daoId}`, error);
+        return null;
+    }
+}
+
+/**
+ * Replace the target allocation of a treasury
+ */
+export async function setAllocationTargets(
+    daoId: string,
+    targets: AllocationTarget[],
+): Promise<AllocationTargets> {
+    const url = `${BACKEND_API_BASE}/treasury/allocation-targets`;
+    const response = await axios.put<AllocationTargets>(
+        url,
+        { daoId, targets },
+        { withCredentials: true },
+    );
+    return response.data;
+}
+
+/**
+ * Plan the exchanges and stakes that bring a treasury back to its target allocation.
+ * With liveQuotes, exchanges come with their proposals; each reserves a 1Click deposit address.
+ */
+export async function getRebalancingPlan(
+    daoId: string,
+    slippageBps?: number,
+    liveQuotes?: boolean,
+): Promise<RebalancingPlan | null> {
+    try {
+        const url = `${BACKEND_API_BASE}/treasury/rebalancing-plan`;
+        const response = await axios.get<RebalancingPlan>(url, {
+            params: { daoId, slippageBps, liveQuotes },
+        });
+        return response.data;
+    } catch (error) {
+        console.error(`Error getting rebalancing plan of ${daoId}`, error);
+        return null;
+    }
+}
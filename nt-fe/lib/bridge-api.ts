@@ -49,3 +49,54 @@ export const fetchDepositAddress = async (
         throw error;
     }
 };
+
+export interface LabeledDepositAddress {
+    id: number;
+    daoId: string;
+    chain: string;
+    address: string;
+    label: string;
+    issuedBy: string;
+    issuedAt: string;
+}
+
+/**
+ * Fetch the labeled deposit addresses issued to a treasury, most recent first
+ * @param {string} daoId - Treasury account ID
+ */
+export const fetchLabeledDepositAddresses = async (
+    daoId: string,
+): Promise<LabeledDepositAddress[]> => {
+    try {
+        const response = await axios.get(
+            `${BACKEND_API_BASE}/intents/deposit-addresses`,
+            { params: { daoId }, withCredentials: true },
+        );
+
+        return response.data || [];
+    } catch (error) {
+        console.error("Error fetching labeled deposit addresses:", error);
+        return [];
+    }
+};
+
+/**
+ * Issue the deposit address of a treasury on a chain under a purpose label.
+ * Incoming deposits on that chain are attributed to the label until another one is issued.
+ * @param {string} daoId - Treasury account ID
+ * @param {string} chainId - Chain identifier (e.g., "eth:1")
+ * @param {string} label - Purpose of the deposit (e.g., "Grant from Foundation")
+ */
+export const issueLabeledDepositAddress = async (
+    daoId: string,
+    chainId: string,
+    label: string,
+): Promise<LabeledDepositAddress> => {
+    const response = await axios.post(
+        `${BACKEND_API_BASE}/intents/deposit-addresses`,
+        { daoId, chain: chainId, label },
+        { withCredentials: true },
+    );
+
+    return response.data;
+};
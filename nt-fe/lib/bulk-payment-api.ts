@@ -1,4 +1,5 @@
 import axios from "axios";
+import type { ConnectorAction } from "@hot-labs/near-connect";
 
 // Bulk Payment Contract Configuration
 export const BULK_PAYMENT_CONTRACT_ID =
@@ -11,6 +12,16 @@ const BACKEND_API_BASE =
 // Maximum number of recipients per bulk payment import
 export const MAX_RECIPIENTS_PER_BULK_PAYMENT = 25;
 
+// List token of a mixed-token list, where every payment names its own token
+export const MIXED_TOKEN_ID = "mixed";
+
+export interface BulkPaymentInput {
+    recipient: string;
+    amount: string;
+    /** Token of this payment (mixed-token lists only) */
+    tokenId?: string;
+}
+
 /**
  * Generate a deterministic list_id (SHA-256 hash of canonical JSON)
  * Must match the backend's hash calculation
@@ -18,7 +29,7 @@ export const MAX_RECIPIENTS_PER_BULK_PAYMENT = 25;
 export async function generateListId(
     submitterId: string,
     tokenId: string,
-    payments: Array<{ recipient: string; amount: string }>,
+    payments: BulkPaymentInput[],
 ): Promise<string> {
     // Sort payments by recipient for deterministic ordering (must match API)
     const sortedPayments = [...payments].sort((a, b) =>
@@ -30,6 +41,7 @@ export async function generateListId(
         payments: sortedPayments.map((p) => ({
             amount: p.amount,
             recipient: p.recipient,
+            ...(p.tokenId ? { token_id: p.tokenId } : {}),
         })),
         submitter: submitterId,
         token_id: tokenId,
@@ -54,8 +66,17 @@ export async function submitPaymentList(params: {
     submitterId: string;
     daoContractId: string;
     tokenId: string;
-    payments: Array<{ recipient: string; amount: string }>;
-}): Promise<{ success: boolean; listId?: string; error?: string }> {
+    payments: BulkPaymentInput[];
+    fiatQuoteId?: number;
+    /** DAO proposal funding the list; the backend searches recent proposals when omitted */
+    proposalId?: number;
+}): Promise<{
+    success: boolean;
+    listId?: string;
+    error?: string;
+    /** yoctoNEAR to attach with attach_storage_budget for unregistered FT recipients */
+    storageBudget?: string;
+}> {
     try {
         const response = await axios.post(
             `${BACKEND_API_BASE}/api/bulk-payment/submit-list`,
@@ -65,6 +86,8 @@ export async function submitPaymentList(params: {
                 daoContractId: params.daoContractId,
                 tokenId: params.tokenId,
                 payments: params.payments,
+                fiatQuoteId: params.fiatQuoteId,
+                proposalId: params.proposalId,
             },
             { withCredentials: true },
         );
@@ -75,6 +98,235 @@ export async function submitPaymentList(params: {
     }
 }
 
+/**
+ * Build the transaction attaching a storage budget to a submitted list, used by
+ * payout_batch to register FT recipients that have no storage on the token.
+ * Whatever is not spent is refunded to the treasury.
+ */
+export function buildAttachStorageBudgetTransaction(
+    listId: string,
+    storageBudget: string,
+): { receiverId: string; actions: ConnectorAction[] } {
+    return {
+        receiverId: BULK_PAYMENT_CONTRACT_ID,
+        actions: [
+            {
+                type: "FunctionCall",
+                params: {
+                    methodName: "attach_storage_budget",
+                    args: { list_id: listId },
+                    gas: "30000000000000", // 30 TGas
+                    deposit: storageBudget,
+                },
+            } as ConnectorAction,
+        ],
+    };
+}
+
+export interface FiatPayment {
+    recipient: string;
+    fiatAmount: string;
+    /** Token amount in base units */
+    amount: string;
+}
+
+export interface FiatQuote {
+    id: number;
+    daoId: string;
+    tokenId: string;
+    tokenSymbol: string;
+    tokenDecimals: number;
+    currency: string;
+    /** Fiat price of one whole token */
+    rate: string;
+    priceDate: string;
+    quotedAt: string;
+    payments: FiatPayment[];
+    totalFiat: string;
+    totalAmount: string;
+    listId: string | null;
+    /** Conversion summary to attach to the proposal description */
+    report: string;
+}
+
+/**
+ * Convert fiat-denominated payments into token amounts, locking the current price
+ */
+export async function createFiatQuote(params: {
+    daoId: string;
+    tokenId: string;
+    currency?: string;
+    payments: Array<{ recipient: string; fiatAmount: string }>;
+}): Promise<FiatQuote> {
+    const response = await axios.post(
+        `${BACKEND_API_BASE}/api/bulk-payment/fiat-quote`,
+        params,
+        { withCredentials: true },
+    );
+    return response.data;
+}
+
+/**
+ * Get the fiat conversion of a submitted list, if it was fiat-denominated
+ */
+export async function getListConversion(
+    listId: string,
+): Promise<FiatQuote | null> {
+    try {
+        const response = await axios.get(
+            `${BACKEND_API_BASE}/api/bulk-payment/list/${listId}/conversion`,
+        );
+        return response.data;
+    } catch (error: any) {
+        if (error.response?.status === 404) {
+            return null;
+        }
+        throw error;
+    }
+}
+
+export interface ImportRowReport {
+    /** 1-based row number in the sheet */
+    row: number;
+    recipient: string;
+    amount: string;
+    memo: string | null;
+    /** Amount in token base units */
+    rawAmount: string | null;
+    accountExists: boolean | null;
+    storageRegistered: boolean | null;
+    errors: string[];
+    warnings: string[];
+}
+
+export interface ImportedList {
+    listId: string;
+    tokenId: string;
+    total: string;
+    rows: number[];
+    payments: BulkPaymentInput[];
+}
+
+export interface ImportReport {
+    daoId: string;
+    tokenId: string;
+    tokenSymbol: string;
+    tokenDecimals: number;
+    rows: ImportRowReport[];
+    validRows: number;
+    invalidRows: number;
+    total: string;
+    treasuryBalance: string | null;
+    sufficientBalance: boolean | null;
+    /** Valid rows split into lists of at most MAX_RECIPIENTS_PER_BULK_PAYMENT */
+    lists: ImportedList[];
+}
+
+/**
+ * Validate a CSV or XLSX sheet (recipient, amount, optional memo) and split it
+ * into bulk payment lists. Nothing is submitted.
+ */
+export async function importPaymentSheet(params: {
+    daoId: string;
+    tokenId: string;
+    file: File;
+}): Promise<ImportReport> {
+    const response = await axios.post(
+        `${BACKEND_API_BASE}/api/bulk-payment/import`,
+        params.file,
+        {
+            params: { daoId: params.daoId, tokenId: params.tokenId },
+            headers: {
+                "Content-Type": params.file.type || "application/octet-stream",
+            },
+            withCredentials: true,
+        },
+    );
+    return response.data;
+}
+
+export interface PaymentRunAmounts {
+    tokenId: string;
+    total: string;
+    paid: string;
+    pending: string;
+    failed: string;
+    cancelled: string;
+}
+
+export interface PaymentRunChunk {
+    chunkIndex: number;
+    listId: string;
+    proposalId: number | null;
+    submittedAt: string | null;
+    status: string;
+    payments: BulkPaymentInput[];
+    amounts: PaymentRunAmounts[];
+}
+
+export interface PaymentRun {
+    id: number;
+    daoId: string;
+    createdBy: string;
+    tokenId: string;
+    description: string | null;
+    paymentCount: number;
+    createdAt: string;
+    status: "Draft" | "InProgress" | "Completed" | "Cancelled";
+    amounts: PaymentRunAmounts[];
+    chunks: PaymentRunChunk[];
+}
+
+/**
+ * Create a payout run. The backend splits the payments into lists of at most
+ * 25 recipients; each chunk is then proposed and submitted on its own.
+ */
+export async function createPaymentRun(params: {
+    daoId: string;
+    tokenId: string;
+    description?: string;
+    payments: BulkPaymentInput[];
+}): Promise<PaymentRun> {
+    const response = await axios.post(
+        `${BACKEND_API_BASE}/api/bulk-payment/run`,
+        params,
+        { withCredentials: true },
+    );
+    return response.data;
+}
+
+export async function getPaymentRun(id: number): Promise<PaymentRun> {
+    const response = await axios.get(
+        `${BACKEND_API_BASE}/api/bulk-payment/run/${id}`,
+    );
+    return response.data;
+}
+
+export interface PaymentWithdrawal {
+    paymentIndex: number;
+    tokenId: string;
+    chain: string;
+    address: string;
+    amount: string;
+    transactionHash: string;
+    status: "PENDING" | "COMPLETED" | "FAILED";
+    destinationTxHash: string | null;
+    updatedAt: string;
+}
+
+/**
+ * Bridge withdrawals of a list's payments in PoA tokens (paid to addresses on
+ * the token's origin chain)
+ */
+export async function getPaymentWithdrawals(
+    listId: string,
+): Promise<PaymentWithdrawal[]> {
+    const response = await axios.get(
+        `${BACKEND_API_BASE}/api/bulk-payment/list/${listId}/withdrawals`,
+    );
+    return response.data;
+}
+
 /**
  * Build the proposal transaction for bulk payment
  *
@@ -218,6 +218,24 @@ export interface VoteCounts {
     [roleName: string]: [number, number, number];
 }
 
+export interface RoleVoteForecast {
+    role: string;
+    weightKind: string;
+    threshold: number | null;
+    approvals: number;
+    rejections: number;
+    votesRequired: number | null;
+    remainingVoters: number | null;
+}
+
+export interface ProposalForecast {
+    expiresAt: string;
+    isExpired: boolean;
+    isPassable: boolean;
+    roles: RoleVoteForecast[];
+    myVoteRequired: boolean | null;
+}
+
 export interface Proposal {
     description: string;
     id: number;
@@ -230,6 +248,8 @@ export interface Proposal {
     votes: {
         [account: string]: Vote;
     };
+    forecast?: ProposalForecast;
+    swapOrder?: SwapOrder;
 }
 
 export interface ProposalsResponse {
@@ -534,6 +554,50 @@ export async function getProposalTransaction(
     }
 }
 
+export interface ExecutionTransfer {
+    standard: "near" | "nep141" | "nep245";
+    contractId: string | null;
+    /** "near", the FT contract or the multi-token ID */
+    tokenId: string;
+    senderId: string;
+    receiverId: string;
+    amount: string;
+    receiptId: string;
+}
+
+export interface ProposalExecution {
+    daoId: string;
+    proposalId: number;
+    action: string;
+    transactionHash: string;
+    signerId: string;
+    receiptId: string;
+    blockHeight: number;
+    /** Nanoseconds */
+    blockTimestamp: number;
+    transfers: ExecutionTransfer[];
+}
+
+/**
+ * Get the execution of a finalized proposal with every transfer it made
+ */
+export async function getProposalExecution(
+    daoId: string,
+    proposalId: number,
+): Promise<ProposalExecution | null> {
+    try {
+        const url = `${BACKEND_API_BASE}/proposal/${daoId}/${proposalId}/execution`;
+        const response = await axios.get<ProposalExecution>(url);
+        return response.data;
+    } catch (error) {
+        console.error(
+            `Error getting execution of proposal ${daoId}/${proposalId}`,
+            error,
+        );
+        return null;
+    }
+}
+
 /**
  * Swap status types and API
  */
@@ -579,3 +643,53 @@ export async function getSwapStatus(
         return null;
     }
 }
+
+/**
+ * Swap order of an exchange proposal, followed by the backend until it settles
+ */
+export interface SwapOrder {
+    id: number;
+    daoId: string;
+    proposalId: number | null;
+    depositAddress: string;
+    depositMemo: string | null;
+    originAsset: string;
+    destinationAsset: string;
+    amountIn: string;
+    amountOut: string;
+    minAmountOut: string | null;
+    slippageBps: number | null;
+    deadline: string;
+    status: SwapStatus;
+    /** Amount actually received */
+    amountOutSettled: string | null;
+    refundedAmount: string | null;
+    refundReason: string | null;
+    nearTxHashes: string[];
+    detectedSwapId: number | null;
+    /** Set when the quote deadline passed without a deposit */
+    expiredAt: string | null;
+    settledAt: string | null;
+    createdAt: string;
+    updatedAt: string;
+}
+
+/**
+ * Get the swap order of an exchange proposal
+ */
+export async function getSwapOrder(
+    daoId: string,
+    proposalId: number,
+): Promise<SwapOrder | null> {
+    try {
+        const url = `${BACKEND_API_BASE}/intents/swap-orders/${daoId}/${proposalId}`;
+        const response = await axios.get<SwapOrder>(url);
+        return response.data;
+    } catch (error) {
+        console.error(
+            `Error getting swap order of proposal ${daoId}/${proposalId}`,
+            error,
+        );
+        return null;
+    }
+}
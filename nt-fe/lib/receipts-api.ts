@@ -0,0 +1,95 @@
+import axios from "axios";
+
+const BACKEND_API_BASE =
+    process.env.NEXT_PUBLIC_BACKEND_API_BASE || "http://localhost:3001";
+
+/**
+ * Payment receipt for a Transfer proposal or a bulk payment row
+ */
+export interface PaymentReceipt {
+    source: "transfer_proposal" | "bulk_payment";
+    payer: string;
+    recipient: string;
+    tokenId: string;
+    tokenSymbol: string;
+    tokenDecimals: number;
+    /** Amount in base units */
+    amount: string;
+    amountFormatted: string;
+    usdPrice: string | null;
+    usdValue: string | null;
+    priceDate: string | null;
+    transactionHash: string;
+    blockHeight: number;
+    executedAt: string;
+    proposalId: number | null;
+    listId: string | null;
+    issuedAt: string;
+}
+
+export interface SignedReceipt {
+    receipt: PaymentReceipt;
+    /** Backend signature, accepted by verifyReceipt */
+    signature: string;
+}
+
+export interface ReceiptVerification {
+    valid: boolean;
+    receipt: PaymentReceipt | null;
+    error: string | null;
+}
+
+/**
+ * Receipt of an executed Transfer proposal
+ */
+export async function getProposalReceipt(
+    daoId: string,
+    proposalId: number,
+): Promise<SignedReceipt> {
+    const response = await axios.get(
+        `${BACKEND_API_BASE}/api/receipts/proposal/${daoId}/${proposalId}`,
+    );
+    return response.data;
+}
+
+/**
+ * Receipt of a paid bulk payment
+ */
+export async function getBulkPaymentReceipt(
+    listId: string,
+    recipient: string,
+): Promise<SignedReceipt> {
+    const response = await axios.get(
+        `${BACKEND_API_BASE}/api/receipts/bulk-payment/${listId}/${encodeURIComponent(recipient)}`,
+    );
+    return response.data;
+}
+
+/**
+ * URL of the printable HTML receipt
+ */
+export function getReceiptDocumentUrl(
+    params:
+        | { daoId: string; proposalId: number }
+        | { listId: string; recipient: string },
+): string {
+    const path =
+        "proposalId" in params
+            ? `proposal/${params.daoId}/${params.proposalId}`
+            : `bulk-payment/${params.listId}/${encodeURIComponent(params.recipient)}`;
+    return `${BACKEND_API_BASE}/api/receipts/${path}?format=html`;
+}
+
+/**
+ * Check that a receipt was signed by the backend
+ */
+export async function verifyReceipt(
+    signature: string,
+    receipt?: PaymentReceipt,
+): Promise<ReceiptVerification> {
+    const response = await axios.post(
+        `${BACKEND_API_BASE}/api/receipts/verify`,
+        { signature, receipt },
+    );
+    return response.data;
+}
@@ -603,6 +603,7 @@ export const useNear = () => {
         checkAuth,
         clearError,
         signMessage,
+        signAndSendDelegateAction,
         createProposal: storeCreateProposal,
         voteProposals: storeVoteProposals,
     } = useNearStore();
@@ -700,6 +701,7 @@ export const useNear = () => {
         checkAuth,
         clearError,
         signMessage,
+        signAndSendDelegateAction,
         createProposal,
         voteProposals,
     };